@@ -2,6 +2,9 @@ const loginSection = document.getElementById("login-section");
 const twoFASection = document.getElementById("2fa-section");
 const signupSection = document.getElementById("signup-section");
 
+const forgotPasswordSection = document.getElementById("forgot-password-section");
+const resetPasswordSection = document.getElementById("reset-password-section");
+
 const signupLink = document.getElementById("signup-link");
 const twoFALoginLink = document.getElementById("2fa-login-link");
 const signupLoginLink = document.getElementById("signup-login-link");
@@ -30,6 +33,48 @@ signupLoginLink.addEventListener("click", (e) => {
     signupSection.style.display = "none";
 });
 
+function showSection(section) {
+    for (const s of [loginSection, twoFASection, signupSection, forgotPasswordSection, resetPasswordSection]) {
+        s.style.display = s === section ? "block" : "none";
+    }
+}
+
+document.getElementById("forgot-password-link").addEventListener("click", (e) => {
+    e.preventDefault();
+    showSection(forgotPasswordSection);
+});
+
+for (const id of ["forgot-password-login-link", "reset-password-login-link"]) {
+    document.getElementById(id).addEventListener("click", (e) => {
+        e.preventDefault();
+        showSection(loginSection);
+    });
+}
+
+// -----------------------------------------------------
+
+// Set by /authorize when an OpenID Connect client sent the user here to log in.
+// Only /authorize on this origin is followed so this can't be used as an open
+// redirect. Browsers read e.g. "/\evil.example" as another host, so the value
+// is resolved as a URL rather than checked as a string.
+function returnToAuthorizeRequest() {
+    const returnTo = new URLSearchParams(window.location.search).get("return_to");
+    if (returnTo === null) {
+        return false;
+    }
+    let url;
+    try {
+        url = new URL(returnTo, window.location.origin);
+    } catch {
+        return false;
+    }
+    if (url.origin !== window.location.origin || url.pathname !== "/authorize") {
+        return false;
+    }
+    window.location.assign(url.href);
+    return true;
+}
+
 // -----------------------------------------------------
 
 const loginForm = document.getElementById("login-form");
@@ -48,39 +93,97 @@ loginButton.addEventListener("click", (e) => {
             'Content-Type': 'application/json',
         },
         body: JSON.stringify({ email, password }),
-    }).then(response => {
-        if (response.status === 206) {
-            TwoFAForm.email.value = email;
-            response.json().then(data => {
-                TwoFAForm.login_attempt_id.value = data.loginAttemptId;
-            });
+    }).then(response => handleLoginResponse(response, email));
+});
 
-            loginForm.email.value = "";
-            loginForm.password.value = "";
+// Shared by password and magic link logins, which answer the same way
+function handleLoginResponse(response, email) {
+    if (response.status === 206) {
+        TwoFAForm.email.value = email;
+        response.json().then(data => {
+            TwoFAForm.login_attempt_id.value = data.loginAttemptId;
+            TwoFAForm.email_code.placeholder = data["2FAMethod"] === "totp"
+                ? "Code from your authenticator app"
+                : "Code from your email";
+        });
 
-            loginSection.style.display = "none";
-            twoFASection.style.display = "block";
-            signupSection.style.display = "none";
-            loginErrAlter.style.display = "none";
-        } else if (response.status === 200) {
-            loginForm.email.value = "";
-            loginForm.password.value = "";
-            loginErrAlter.style.display = "none";
-            alert("You have successfully logged in.");
-        } else {
-            response.json().then(data => {
-                let error_msg = data.error;
-                if (error_msg !== undefined && error_msg !== null && error_msg !== "") {
-                    loginErrAlter.innerHTML = `<span><strong>Error: </strong>${error_msg}</span>`;
-                    loginErrAlter.style.display = "block";
-                } else {
-                    loginErrAlter.style.display = "none";
-                }
-            });
+        loginForm.email.value = "";
+        loginForm.password.value = "";
+
+        loginSection.style.display = "none";
+        twoFASection.style.display = "block";
+        signupSection.style.display = "none";
+        loginErrAlter.style.display = "none";
+    } else if (response.status === 200) {
+        loginForm.email.value = "";
+        loginForm.password.value = "";
+        loginErrAlter.style.display = "none";
+        if (returnToAuthorizeRequest()) {
+            return;
         }
+        alert("You have successfully logged in.");
+    } else {
+        response.json().then(data => {
+            let error_msg = data.error;
+            if (error_msg !== undefined && error_msg !== null && error_msg !== "") {
+                loginErrAlter.innerHTML = `<span><strong>Error: </strong>${error_msg}</span>`;
+                loginErrAlter.style.display = "block";
+            } else {
+                loginErrAlter.style.display = "none";
+            }
+        });
+    }
+}
+
+document.getElementById("magic-link-link").addEventListener("click", (e) => {
+    e.preventDefault();
+
+    const email = loginForm.email.value;
+
+    fetch('/login/magic-link', {
+        method: 'POST',
+        headers: {
+            'Content-Type': 'application/json',
+        },
+        body: JSON.stringify({ email }),
+    }).then(response => {
+        response.json().then(data => {
+            if (response.ok) {
+                // Remembered for the 2FA step after the link is opened
+                localStorage.setItem("magic_link_email", email);
+                loginErrAlter.style.display = "none";
+                alert(data.message);
+            } else if (data.error) {
+                loginErrAlter.innerHTML = `<span><strong>Error: </strong>${data.error}</span>`;
+                loginErrAlter.style.display = "block";
+            }
+        });
     });
 });
 
+// Opened from the link in a magic link email. The link only works in the
+// browser it was requested from.
+const magicLinkToken = new URLSearchParams(window.location.search).get("magic_link_token");
+if (magicLinkToken !== null) {
+    const email = localStorage.getItem("magic_link_email");
+    localStorage.removeItem("magic_link_email");
+
+    fetch('/login/magic-link/callback', {
+        method: 'POST',
+        headers: {
+            'Content-Type': 'application/json',
+        },
+        body: JSON.stringify({ token: magicLinkToken }),
+    }).then(response => {
+        window.history.replaceState(null, "", "/");
+        if (response.status === 401) {
+            alert("This sign-in link is invalid, has expired, or was requested from another browser.");
+            return;
+        }
+        handleLoginResponse(response, email);
+    });
+}
+
 const signupForm = document.getElementById("signup-form");
 const signupButton = document.getElementById("signup-form-submit");
 const signupErrAlter = document.getElementById("signup-err-alert");
@@ -145,6 +248,9 @@ TwoFAButton.addEventListener("click", (e) => {
             TwoFAForm.email_code.value = "";
             TwoFAForm.login_attempt_id.value = "";
             TwoFAErrAlter.style.display = "none";
+            if (returnToAuthorizeRequest()) {
+                return;
+            }
             alert("You have successfully logged in.");
             loginSection.style.display = "block";
             twoFASection.style.display = "none";
@@ -161,4 +267,98 @@ TwoFAButton.addEventListener("click", (e) => {
             });
         }
     });
-});
\ No newline at end of file
+});
+
+const forgotPasswordForm = document.getElementById("forgot-password-form");
+const forgotPasswordButton = document.getElementById("forgot-password-form-submit");
+const forgotPasswordErrAlter = document.getElementById("forgot-password-err-alert");
+
+forgotPasswordButton.addEventListener("click", (e) => {
+    e.preventDefault();
+
+    const email = forgotPasswordForm.email.value;
+
+    fetch('/password-reset/request', {
+        method: 'POST',
+        headers: {
+            'Content-Type': 'application/json',
+        },
+        body: JSON.stringify({ email }),
+    }).then(response => {
+        response.json().then(data => {
+            if (response.ok) {
+                forgotPasswordForm.email.value = "";
+                forgotPasswordErrAlter.style.display = "none";
+                alert(data.message);
+                showSection(loginSection);
+            } else if (data.error) {
+                forgotPasswordErrAlter.innerHTML = `<span><strong>Error: </strong>${data.error}</span>`;
+                forgotPasswordErrAlter.style.display = "block";
+            }
+        });
+    });
+});
+
+const resetPasswordForm = document.getElementById("reset-password-form");
+const resetPasswordButton = document.getElementById("reset-password-form-submit");
+const resetPasswordErrAlter = document.getElementById("reset-password-err-alert");
+
+// Opened from the link in a password reset email
+const passwordResetToken = new URLSearchParams(window.location.search).get("password_reset_token");
+if (passwordResetToken !== null) {
+    resetPasswordForm.token.value = passwordResetToken;
+    showSection(resetPasswordSection);
+}
+
+resetPasswordButton.addEventListener("click", (e) => {
+    e.preventDefault();
+
+    const token = resetPasswordForm.token.value;
+    const password = resetPasswordForm.password.value;
+
+    fetch('/password-reset/confirm', {
+        method: 'POST',
+        headers: {
+            'Content-Type': 'application/json',
+        },
+        body: JSON.stringify({ token, password }),
+    }).then(response => {
+        if (response.ok) {
+            resetPasswordForm.token.value = "";
+            resetPasswordForm.password.value = "";
+            resetPasswordErrAlter.style.display = "none";
+            alert("Your password has been reset. Please log in.");
+            window.history.replaceState(null, "", "/");
+            showSection(loginSection);
+        } else {
+            response.json().then(data => {
+                let error_msg = data.error;
+                if (error_msg !== undefined && error_msg !== null && error_msg !== "") {
+                    resetPasswordErrAlter.innerHTML = `<span><strong>Error: </strong>${error_msg}</span>`;
+                    resetPasswordErrAlter.style.display = "block";
+                } else {
+                    resetPasswordErrAlter.style.display = "none";
+                }
+            });
+        }
+    });
+});
+
+// Opened from the link in a verification email
+const emailVerificationToken = new URLSearchParams(window.location.search).get("email_verification_token");
+if (emailVerificationToken !== null) {
+    fetch('/verify-email', {
+        method: 'POST',
+        headers: {
+            'Content-Type': 'application/json',
+        },
+        body: JSON.stringify({ token: emailVerificationToken }),
+    }).then(response => {
+        window.history.replaceState(null, "", "/");
+        if (response.ok) {
+            alert("Your email address has been verified.");
+        } else {
+            alert("This verification link is invalid or has expired.");
+        }
+    });
+}
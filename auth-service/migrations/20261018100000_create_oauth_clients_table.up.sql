@@ -0,0 +1,6 @@
+CREATE TABLE
+    IF NOT EXISTS oauth_clients (
+        client_id TEXT NOT NULL PRIMARY KEY,
+        client_secret_hash TEXT NOT NULL,
+        redirect_uris TEXT[] NOT NULL
+    );
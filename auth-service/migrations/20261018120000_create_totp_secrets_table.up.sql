@@ -0,0 +1,7 @@
+CREATE TABLE
+    IF NOT EXISTS totp_secrets (
+        email TEXT NOT NULL PRIMARY KEY REFERENCES users (email) ON DELETE CASCADE,
+        encrypted_secret BYTEA NOT NULL,
+        confirmed BOOLEAN NOT NULL DEFAULT FALSE,
+        last_used_step BIGINT
+    );
\ No newline at end of file
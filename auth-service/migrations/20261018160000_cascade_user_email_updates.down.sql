@@ -0,0 +1,9 @@
+ALTER TABLE totp_secrets
+    DROP CONSTRAINT totp_secrets_email_fkey,
+    ADD CONSTRAINT totp_secrets_email_fkey FOREIGN KEY (email) REFERENCES users (email) ON DELETE CASCADE;
+ALTER TABLE recovery_codes
+    DROP CONSTRAINT recovery_codes_email_fkey,
+    ADD CONSTRAINT recovery_codes_email_fkey FOREIGN KEY (email) REFERENCES users (email) ON DELETE CASCADE;
+ALTER TABLE webauthn_credentials
+    DROP CONSTRAINT webauthn_credentials_email_fkey,
+    ADD CONSTRAINT webauthn_credentials_email_fkey FOREIGN KEY (email) REFERENCES users (email) ON DELETE CASCADE;
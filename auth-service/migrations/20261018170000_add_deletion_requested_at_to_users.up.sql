@@ -0,0 +1,2 @@
+-- Set while a deleted account is in its grace period, after which it is purged
+ALTER TABLE users ADD COLUMN IF NOT EXISTS deletion_requested_at TIMESTAMPTZ;
@@ -0,0 +1,28 @@
+-- No foreign key to users: the audit trail outlives the accounts it is about
+CREATE TABLE
+    IF NOT EXISTS audit_events (
+        id BIGSERIAL PRIMARY KEY,
+        occurred_at TIMESTAMPTZ NOT NULL,
+        request_id UUID NOT NULL,
+        ip INET NOT NULL,
+        email TEXT,
+        event TEXT NOT NULL,
+        details JSONB NOT NULL
+    );
+
+CREATE INDEX IF NOT EXISTS audit_events_email_idx ON audit_events (email);
+
+-- The table is append-only
+CREATE OR REPLACE FUNCTION reject_audit_event_changes() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER audit_events_no_update_or_delete
+    BEFORE UPDATE OR DELETE ON audit_events
+    FOR EACH ROW EXECUTE FUNCTION reject_audit_event_changes();
+
+CREATE OR REPLACE TRIGGER audit_events_no_truncate
+    BEFORE TRUNCATE ON audit_events
+    FOR EACH STATEMENT EXECUTE FUNCTION reject_audit_event_changes();
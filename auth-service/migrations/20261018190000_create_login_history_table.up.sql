@@ -0,0 +1,14 @@
+CREATE TABLE
+    IF NOT EXISTS login_history (
+        id BIGSERIAL PRIMARY KEY,
+        session_id UUID NOT NULL,
+        email TEXT NOT NULL REFERENCES users (email) ON DELETE CASCADE ON UPDATE CASCADE,
+        ip INET NOT NULL,
+        ip_range TEXT NOT NULL,
+        user_agent TEXT,
+        device TEXT NOT NULL,
+        method TEXT NOT NULL,
+        logged_in_at TIMESTAMPTZ NOT NULL
+    );
+
+CREATE INDEX IF NOT EXISTS login_history_email_idx ON login_history (email);
@@ -0,0 +1,3 @@
+ALTER TABLE users DROP COLUMN IF EXISTS password_reset_required;
+ALTER TABLE users DROP COLUMN IF EXISTS locked;
+ALTER TABLE users DROP COLUMN IF EXISTS is_admin;
@@ -0,0 +1,13 @@
+CREATE TABLE
+    IF NOT EXISTS roles (
+        name TEXT PRIMARY KEY,
+        description TEXT NOT NULL DEFAULT '',
+        permissions TEXT[] NOT NULL DEFAULT '{}'
+    );
+
+CREATE TABLE
+    IF NOT EXISTS user_roles (
+        email TEXT NOT NULL REFERENCES users (email) ON DELETE CASCADE ON UPDATE CASCADE,
+        role TEXT NOT NULL REFERENCES roles (name) ON DELETE CASCADE,
+        PRIMARY KEY (email, role)
+    );
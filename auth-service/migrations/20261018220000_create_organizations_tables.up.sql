@@ -0,0 +1,15 @@
+CREATE TABLE
+    IF NOT EXISTS organizations (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL
+    );
+
+CREATE TABLE
+    IF NOT EXISTS organization_members (
+        organization_id TEXT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+        email TEXT NOT NULL REFERENCES users (email) ON DELETE CASCADE ON UPDATE CASCADE,
+        role TEXT NOT NULL CHECK (role IN ('owner', 'admin', 'member')),
+        PRIMARY KEY (organization_id, email)
+    );
+
+CREATE INDEX IF NOT EXISTS organization_members_email_idx ON organization_members (email);
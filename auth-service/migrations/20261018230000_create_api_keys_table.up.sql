@@ -0,0 +1,14 @@
+CREATE TABLE
+    IF NOT EXISTS api_keys (
+        id UUID PRIMARY KEY,
+        email TEXT NOT NULL REFERENCES users (email) ON DELETE CASCADE ON UPDATE CASCADE,
+        name TEXT NOT NULL,
+        hint TEXT NOT NULL,
+        key_hash TEXT NOT NULL UNIQUE,
+        scopes TEXT[] NOT NULL,
+        created_at TIMESTAMPTZ NOT NULL,
+        expires_at TIMESTAMPTZ NOT NULL,
+        last_used_at TIMESTAMPTZ
+    );
+
+CREATE INDEX IF NOT EXISTS api_keys_email_idx ON api_keys (email);
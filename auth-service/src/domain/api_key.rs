@@ -0,0 +1,173 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use color_eyre::eyre::{eyre, Result};
+use data_encoding::HEXLOWER;
+use lazy_static::lazy_static;
+use regex::Regex;
+use ring::digest::{digest, SHA256};
+use secrecy::{ExposeSecret, Secret};
+use uuid::Uuid;
+
+use super::Email;
+
+/// Every API key starts with this, so that secret scanners can recognize
+/// leaked keys and the Bearer path can tell keys from access tokens
+pub const API_KEY_PREFIX: &str = "ask_";
+
+const API_KEY_NAME_MAX_LENGTH: usize = 100;
+// How much of a key is kept in the clear, for users to tell their keys apart
+const API_KEY_HINT_LENGTH: usize = API_KEY_PREFIX.len() + 4;
+
+lazy_static! {
+    static ref API_KEY_REGEX: Regex = Regex::new(r"^ask_[A-Za-z0-9_-]{43}$").unwrap();
+}
+
+/// The secret of an API key, which is only shown to the user when the key is
+/// created. `key` is the prefix followed by 32 random bytes, base64url encoded
+/// without padding.
+#[derive(Clone, Debug)]
+pub struct ApiKeySecret(Secret<String>);
+
+impl ApiKeySecret {
+    pub fn parse(key: String) -> Result<Self> {
+        if API_KEY_REGEX.is_match(&key) {
+            Ok(Self(Secret::new(key)))
+        } else {
+            Err(eyre!("Invalid API key"))
+        }
+    }
+
+    // Alias to ApiKeySecret::default()
+    pub fn generate_random() -> Self {
+        ApiKeySecret::default()
+    }
+
+    /// The SHA-256 of the key, hex encoded, which is all that is stored. Keys
+    /// are random enough that a slow password hash would add nothing, and a
+    /// plain hash lets keys be looked up by it.
+    pub fn hash(&self) -> String {
+        HEXLOWER.encode(digest(&SHA256, self.0.expose_secret().as_bytes()).as_ref())
+    }
+
+    pub fn hint(&self) -> String {
+        self.0.expose_secret()[..API_KEY_HINT_LENGTH].to_owned()
+    }
+}
+
+impl Default for ApiKeySecret {
+    fn default() -> Self {
+        let mut bytes = [0u8; 32];
+        rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut bytes);
+        Self(Secret::new(format!(
+            "{}{}",
+            API_KEY_PREFIX,
+            URL_SAFE_NO_PAD.encode(bytes)
+        )))
+    }
+}
+
+impl AsRef<Secret<String>> for ApiKeySecret {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+/// A named key that scripts and CI jobs authenticate with instead of logging in
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub email: Email,
+    pub name: String,
+    /// The start of the key, e.g. `ask_x7Kq`
+    pub hint: String,
+    pub key_hash: String,
+    /// Permissions the key grants, sorted and without duplicates. A key never
+    /// grants more than the roles of its user do at the time it is used.
+    pub scopes: Vec<String>,
+    /// Unix timestamps in seconds
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub last_used_at: Option<i64>,
+}
+
+impl ApiKey {
+    pub fn new(
+        email: Email,
+        name: String,
+        mut scopes: Vec<String>,
+        secret: &ApiKeySecret,
+        ttl_days: i64,
+    ) -> Result<Self> {
+        let name = name.trim().to_owned();
+        if name.is_empty() || name.chars().count() > API_KEY_NAME_MAX_LENGTH {
+            return Err(eyre!(
+                "API key names must be 1 to {} characters",
+                API_KEY_NAME_MAX_LENGTH
+            ));
+        }
+        scopes.sort();
+        scopes.dedup();
+
+        let now = chrono::Utc::now().timestamp();
+        Ok(Self {
+            id: Uuid::new_v4(),
+            email,
+            name,
+            hint: secret.hint(),
+            key_hash: secret.hash(),
+            scopes,
+            created_at: now,
+            expires_at: now + ttl_days * 24 * 60 * 60,
+            last_used_at: None,
+        })
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.expires_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn email() -> Email {
+        Email::parse(Secret::new("test@example.com".to_owned())).unwrap()
+    }
+
+    #[test]
+    fn test_generated_keys_parse_and_hash() {
+        let secret = ApiKeySecret::generate_random();
+        let key = secret.as_ref().expose_secret().clone();
+        assert!(key.starts_with(API_KEY_PREFIX));
+
+        let parsed = ApiKeySecret::parse(key.clone()).unwrap();
+        assert_eq!(parsed.hash(), secret.hash());
+        assert_eq!(parsed.hash().len(), 64);
+        assert_ne!(ApiKeySecret::generate_random().hash(), secret.hash());
+        assert_eq!(secret.hint(), key[..8]);
+
+        assert!(ApiKeySecret::parse(key.replacen(API_KEY_PREFIX, "ghp_", 1)).is_err());
+        assert!(ApiKeySecret::parse(key[..key.len() - 1].to_owned()).is_err());
+    }
+
+    #[test]
+    fn test_new_api_key() {
+        let secret = ApiKeySecret::generate_random();
+        let scopes = vec![
+            "b:read".to_owned(),
+            "a:read".to_owned(),
+            "b:read".to_owned(),
+        ];
+        let key = ApiKey::new(email(), " CI ".to_owned(), scopes, &secret, 30).unwrap();
+
+        assert_eq!(key.name, "CI");
+        assert_eq!(key.scopes, vec!["a:read", "b:read"]);
+        assert_eq!(key.key_hash, secret.hash());
+        assert_eq!(key.expires_at - key.created_at, 30 * 24 * 60 * 60);
+        assert!(!key.is_expired(key.created_at));
+        assert!(key.is_expired(key.expires_at));
+
+        assert!(ApiKey::new(email(), " ".to_owned(), vec![], &secret, 30).is_err());
+        assert!(ApiKey::new(email(), "x".repeat(101), vec![], &secret, 30).is_err());
+    }
+}
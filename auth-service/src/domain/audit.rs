@@ -0,0 +1,196 @@
+use std::net::IpAddr;
+
+use color_eyre::eyre::Result;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{Email, OrganizationRole};
+
+/// Something security relevant that happened to an account
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AuditEvent {
+    Signup,
+    LoginSucceeded {
+        method: LoginMethod,
+    },
+    LoginFailed {
+        method: LoginMethod,
+    },
+    #[serde(rename = "2fa_code_sent")]
+    TwoFACodeSent,
+    #[serde(rename = "2fa_verified")]
+    TwoFAVerified,
+    #[serde(rename = "2fa_failed")]
+    TwoFAFailed,
+    #[serde(rename = "2fa_enabled")]
+    TwoFAEnabled {
+        method: SecondFactorMethod,
+    },
+    #[serde(rename = "2fa_disabled")]
+    TwoFADisabled,
+    /// An authenticator app secret was issued. It is only a second factor once
+    /// confirmed, which is recorded as `TwoFAEnabled`.
+    TotpEnrollmentStarted,
+    RecoveryCodesGenerated,
+    PasskeyRegistered,
+    ApiKeyCreated {
+        id: Uuid,
+        name: String,
+    },
+    ApiKeyRevoked {
+        id: Uuid,
+    },
+    Logout,
+    /// A session ended by the user, or every session when `session_id` is unset
+    SessionRevoked {
+        session_id: Option<Uuid>,
+    },
+    /// A token revoked through the OAuth revocation endpoint
+    TokenRevoked,
+    /// A refresh token was used twice, so its whole family was revoked
+    RefreshTokenReused,
+    TokenIssued {
+        client_id: String,
+    },
+    AuthorizationCodeIssued {
+        client_id: String,
+    },
+    PasswordChanged,
+    PasswordResetRequested,
+    PasswordReset,
+    VerificationEmailSent,
+    EmailVerified,
+    EmailChangeRequested,
+    EmailChanged {
+        new_email: String,
+    },
+    MagicLinkSent,
+    AccountDeleted,
+    AccountRestored,
+    AccountExported,
+    /// Users looked up through the admin API, recorded about the admin
+    AdminUsersListed {
+        search: Option<String>,
+    },
+    /// Roles and organizations created and deleted through the admin API,
+    /// recorded about the admin
+    AdminRoleCreated {
+        role: String,
+    },
+    AdminRoleDeleted {
+        role: String,
+    },
+    AdminOrganizationCreated {
+        organization: String,
+    },
+    AdminOrganizationDeleted {
+        organization: String,
+    },
+    // Changes made through the admin API, recorded about the user they were made to
+    AdminUserLocked {
+        admin: String,
+    },
+    AdminUserUnlocked {
+        admin: String,
+    },
+    AdminPasswordResetRequired {
+        admin: String,
+    },
+    #[serde(rename = "admin_2fa_required")]
+    Admin2FARequired {
+        admin: String,
+    },
+    AdminSessionsRevoked {
+        admin: String,
+    },
+    AdminUserDeleted {
+        admin: String,
+    },
+    AdminGranted {
+        admin: String,
+    },
+    AdminRevoked {
+        admin: String,
+    },
+    AdminRoleAssigned {
+        admin: String,
+        role: String,
+    },
+    AdminRoleUnassigned {
+        admin: String,
+        role: String,
+    },
+    AdminOrganizationMemberAdded {
+        admin: String,
+        organization: String,
+        role: OrganizationRole,
+    },
+    AdminOrganizationMemberRemoved {
+        admin: String,
+        organization: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoginMethod {
+    Password,
+    MagicLink,
+    Passkey,
+    /// Finished at /verify-2fa after a password or magic link
+    SecondFactor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecondFactorMethod {
+    Email,
+    AuthenticatorApp,
+}
+
+/// An audit event with the request that caused it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Unix timestamp in seconds
+    pub timestamp: i64,
+    #[serde(rename = "requestId")]
+    pub request_id: Uuid,
+    pub ip: IpAddr,
+    /// The account the event is about, if it is known
+    pub email: Option<String>,
+    #[serde(flatten)]
+    pub event: AuditEvent,
+}
+
+// Audit records are only ever appended
+#[async_trait::async_trait]
+pub trait AuditSink {
+    async fn record(&self, record: &AuditRecord) -> Result<()>;
+    /// Records about `email`, oldest first
+    async fn get_records(&self, email: &Email) -> Result<Vec<AuditRecord>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_record_json() {
+        let record = AuditRecord {
+            timestamp: 1_760_000_000,
+            request_id: Uuid::nil(),
+            ip: "127.0.0.1".parse().unwrap(),
+            email: Some("test@example.com".to_owned()),
+            event: AuditEvent::TwoFAEnabled {
+                method: SecondFactorMethod::AuthenticatorApp,
+            },
+        };
+
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["event"], "2fa_enabled");
+        assert_eq!(json["method"], "authenticator_app");
+        assert_eq!(json["requestId"], Uuid::nil().to_string());
+        assert_eq!(serde_json::from_value::<AuditRecord>(json).unwrap(), record);
+    }
+}
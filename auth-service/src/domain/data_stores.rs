@@ -1,3 +1,6 @@
+use std::time::Duration;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use color_eyre::eyre::{eyre, Context, Report, Result};
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -5,68 +8,827 @@ use secrecy::{ExposeSecret, Secret};
 use thiserror::Error;
 use uuid::Uuid;
 
-use super::User;
-use crate::domain::{Email, Password};
+use super::{
+    ApiKey, LoginRecord, Membership, OAuthClient, Organization, RateLimit, RateLimitDecision, Role,
+    Session, ThrottleKey, TotpEnrollment, TotpSecret, User, UserPage, WebauthnCredential,
+};
+use crate::domain::{Email, Password};
+
+#[async_trait::async_trait]
+pub trait UserStore: Send + Sync {
+    async fn add_user(&mut self, user: User) -> Result<(), UserStoreError>;
+    async fn get_user(&self, email: &Email) -> Result<User, UserStoreError>;
+    async fn user_exists(&self, email: &Email) -> bool;
+    async fn validate_user(
+        &self,
+        email: &Email,
+        password: &Password,
+    ) -> Result<User, UserStoreError>;
+    /// Also lifts a password reset required by an admin
+    async fn update_password(
+        &mut self,
+        email: &Email,
+        password: Password,
+    ) -> Result<(), UserStoreError>;
+    async fn set_verified(&mut self, email: &Email, verified: bool) -> Result<(), UserStoreError>;
+    async fn set_requires_2fa(
+        &mut self,
+        email: &Email,
+        requires_2fa: bool,
+    ) -> Result<(), UserStoreError>;
+    /// Moves the account of `email` to `new_email`. Fails with
+    /// `UserAlreadyExists` if another account already uses `new_email`.
+    async fn update_email(
+        &mut self,
+        email: &Email,
+        new_email: &Email,
+    ) -> Result<(), UserStoreError>;
+    /// Marks the user as pending deletion. The account is only removed by
+    /// `purge_deleted_users`, and can be brought back until then.
+    async fn delete_user(&mut self, email: &Email) -> Result<(), UserStoreError>;
+    async fn restore_user(&mut self, email: &Email) -> Result<(), UserStoreError>;
+    /// Removes the users deleted more than `grace_period` ago, returning their
+    /// addresses
+    async fn purge_deleted_users(
+        &mut self,
+        grace_period: Duration,
+    ) -> Result<Vec<Email>, UserStoreError>;
+    /// Removes the user right away, without a grace period
+    async fn remove_user(&mut self, email: &Email) -> Result<(), UserStoreError>;
+    /// Up to `limit` users ordered by email, skipping the first `offset`. With
+    /// `search`, only users whose email contains it, ignoring case.
+    async fn search_users(
+        &self,
+        search: Option<&str>,
+        offset: u64,
+        limit: u64,
+    ) -> Result<UserPage, UserStoreError>;
+    async fn set_admin(&mut self, email: &Email, admin: bool) -> Result<(), UserStoreError>;
+    async fn set_locked(&mut self, email: &Email, locked: bool) -> Result<(), UserStoreError>;
+    async fn set_password_reset_required(
+        &mut self,
+        email: &Email,
+        required: bool,
+    ) -> Result<(), UserStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum UserStoreError {
+    #[error("User already exists")]
+    UserAlreadyExists,
+    #[error("User not found")]
+    UserNotFound,
+    #[error("Invalid credentials")]
+    InvalidCredentials,
+    #[error("Unexpected error. Please try again.")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for UserStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::UserAlreadyExists, Self::UserAlreadyExists)
+                | (Self::UserNotFound, Self::UserNotFound)
+                | (Self::InvalidCredentials, Self::InvalidCredentials)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[async_trait::async_trait]
+pub trait BannedTokenStore: Send + Sync {
+    async fn add_token(&mut self, token: Secret<String>) -> Result<()>;
+    async fn get_token(&self, token: &str) -> Option<&String>;
+    async fn token_exists(&self, token: &Secret<String>) -> bool;
+}
+
+#[derive(Debug, Error)]
+pub enum BannedTokenStoreError {
+    #[error("Invalid token")]
+    InvalidToken,
+    #[error("Token already exists in banned token store")]
+    TokenAlreadyExists,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for BannedTokenStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::InvalidToken, Self::InvalidToken)
+                | (Self::TokenAlreadyExists, Self::TokenAlreadyExists)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[async_trait::async_trait]
+pub trait RefreshTokenStore: Send + Sync {
+    async fn add_token(
+        &mut self,
+        token: RefreshToken,
+        data: RefreshTokenData,
+    ) -> Result<(), RefreshTokenStoreError>;
+
+    /// Marks `token` as used and returns the data it was issued with. A token
+    /// can only be used once; presenting it again yields `TokenReused`.
+    async fn use_token(
+        &mut self,
+        token: &RefreshToken,
+    ) -> Result<RefreshTokenData, RefreshTokenStoreError>;
+
+    async fn revoke_family(&mut self, family_id: &Uuid) -> Result<(), RefreshTokenStoreError>;
+
+    /// Revokes every family issued to `email`
+    async fn revoke_user(&mut self, email: &Email) -> Result<(), RefreshTokenStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum RefreshTokenStoreError {
+    #[error("Refresh token not found")]
+    TokenNotFound,
+    #[error("Refresh token reused")]
+    TokenReused(Uuid),
+    #[error("Refresh token revoked")]
+    TokenRevoked,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for RefreshTokenStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::TokenNotFound, Self::TokenNotFound)
+                | (Self::TokenReused(_), Self::TokenReused(_))
+                | (Self::TokenRevoked, Self::TokenRevoked)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RefreshToken(Secret<String>);
+
+lazy_static! {
+    static ref RANDOM_TOKEN_REGEX: Regex = Regex::new(r"^[A-Za-z0-9_-]{43}$").unwrap();
+}
+
+// 32 random bytes, base64url encoded without padding
+fn generate_random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// `token` is 32 random bytes, base64url encoded without padding
+impl RefreshToken {
+    pub fn parse(token: String) -> Result<Self> {
+        if RANDOM_TOKEN_REGEX.is_match(&token) {
+            Ok(Self(Secret::new(token)))
+        } else {
+            Err(eyre!("Invalid refresh token"))
+        }
+    }
+
+    // Alias to RefreshToken::default()
+    pub fn generate_random() -> Self {
+        RefreshToken::default()
+    }
+}
+
+impl Default for RefreshToken {
+    fn default() -> Self {
+        Self(Secret::new(generate_random_token()))
+    }
+}
+
+impl PartialEq for RefreshToken {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.expose_secret() == other.0.expose_secret()
+    }
+}
+
+impl AsRef<Secret<String>> for RefreshToken {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+/// Every refresh token belongs to a family that starts at login. Rotating a
+/// token keeps the family, so reuse of any old token can revoke all of them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefreshTokenData {
+    pub email: Email,
+    pub family_id: Uuid,
+    /// The organization the family's session was logged in to, if any
+    pub tenant_id: Option<String>,
+}
+
+impl RefreshTokenData {
+    pub fn new(email: Email, tenant_id: Option<String>) -> Self {
+        Self {
+            email,
+            family_id: Uuid::new_v4(),
+            tenant_id,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+pub trait OAuthClientStore: Send + Sync {
+    async fn add_client(&mut self, client: OAuthClient) -> Result<(), OAuthClientStoreError>;
+    async fn get_client(&self, client_id: &str) -> Result<OAuthClient, OAuthClientStoreError>;
+    async fn validate_client(
+        &self,
+        client_id: &str,
+        client_secret: &Secret<String>,
+    ) -> Result<OAuthClient, OAuthClientStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum OAuthClientStoreError {
+    #[error("Client already exists")]
+    ClientAlreadyExists,
+    #[error("Client not found")]
+    ClientNotFound,
+    #[error("Invalid client credentials")]
+    InvalidCredentials,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for OAuthClientStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::ClientAlreadyExists, Self::ClientAlreadyExists)
+                | (Self::ClientNotFound, Self::ClientNotFound)
+                | (Self::InvalidCredentials, Self::InvalidCredentials)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[async_trait::async_trait]
+pub trait AuthorizationCodeStore: Send + Sync {
+    async fn add_code(
+        &mut self,
+        code: AuthorizationCode,
+        data: AuthorizationCodeData,
+    ) -> Result<(), AuthorizationCodeStoreError>;
+
+    /// Removes `code` from the store and returns its data. Codes are single use.
+    async fn take_code(
+        &mut self,
+        code: &AuthorizationCode,
+    ) -> Result<AuthorizationCodeData, AuthorizationCodeStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum AuthorizationCodeStoreError {
+    #[error("Authorization code not found")]
+    CodeNotFound,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for AuthorizationCodeStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::CodeNotFound, Self::CodeNotFound)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthorizationCode(Secret<String>);
+
+/// `code` is 32 random bytes, base64url encoded without padding
+impl AuthorizationCode {
+    pub fn parse(code: String) -> Result<Self> {
+        if RANDOM_TOKEN_REGEX.is_match(&code) {
+            Ok(Self(Secret::new(code)))
+        } else {
+            Err(eyre!("Invalid authorization code"))
+        }
+    }
+
+    // Alias to AuthorizationCode::default()
+    pub fn generate_random() -> Self {
+        AuthorizationCode::default()
+    }
+}
+
+impl Default for AuthorizationCode {
+    fn default() -> Self {
+        Self(Secret::new(generate_random_token()))
+    }
+}
+
+impl PartialEq for AuthorizationCode {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.expose_secret() == other.0.expose_secret()
+    }
+}
+
+impl AsRef<Secret<String>> for AuthorizationCode {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+/// Everything `/token` needs to check before exchanging an authorization code
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthorizationCodeData {
+    pub client_id: String,
+    pub redirect_uri: String,
+    pub email: Email,
+    pub scope: String,
+    pub nonce: Option<String>,
+    pub code_challenge: String,
+}
+
+#[async_trait::async_trait]
+pub trait PasswordResetTokenStore: Send + Sync {
+    async fn add_token(
+        &mut self,
+        token: PasswordResetToken,
+        email: Email,
+    ) -> Result<(), PasswordResetTokenStoreError>;
+
+    /// Removes `token` from the store and returns the email it was issued for.
+    /// Tokens are single use.
+    async fn take_token(
+        &mut self,
+        token: &PasswordResetToken,
+    ) -> Result<Email, PasswordResetTokenStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum PasswordResetTokenStoreError {
+    #[error("Password reset token not found")]
+    TokenNotFound,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for PasswordResetTokenStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::TokenNotFound, Self::TokenNotFound)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PasswordResetToken(Secret<String>);
+
+/// `token` is 32 random bytes, base64url encoded without padding
+impl PasswordResetToken {
+    pub fn parse(token: String) -> Result<Self> {
+        if RANDOM_TOKEN_REGEX.is_match(&token) {
+            Ok(Self(Secret::new(token)))
+        } else {
+            Err(eyre!("Invalid password reset token"))
+        }
+    }
+
+    // Alias to PasswordResetToken::default()
+    pub fn generate_random() -> Self {
+        PasswordResetToken::default()
+    }
+}
+
+impl Default for PasswordResetToken {
+    fn default() -> Self {
+        Self(Secret::new(generate_random_token()))
+    }
+}
+
+impl PartialEq for PasswordResetToken {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.expose_secret() == other.0.expose_secret()
+    }
+}
+
+impl AsRef<Secret<String>> for PasswordResetToken {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+#[async_trait::async_trait]
+pub trait MagicLinkTokenStore: Send + Sync {
+    async fn add_token(
+        &mut self,
+        token: MagicLinkToken,
+        email: Email,
+        nonce: MagicLinkNonce,
+    ) -> Result<(), MagicLinkTokenStoreError>;
+
+    /// Removes `token` from the store and returns the email it was issued for,
+    /// provided `nonce` is the one it was issued with. On `NonceMismatch` the
+    /// token stays valid, so that opening the link in another browser (or a
+    /// mail scanner fetching it) doesn't use it up. Tokens are single use.
+    async fn take_token(
+        &mut self,
+        token: &MagicLinkToken,
+        nonce: &MagicLinkNonce,
+    ) -> Result<Email, MagicLinkTokenStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum MagicLinkTokenStoreError {
+    #[error("Magic link token not found")]
+    TokenNotFound,
+    #[error("Magic link nonce mismatch")]
+    NonceMismatch,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for MagicLinkTokenStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::TokenNotFound, Self::TokenNotFound)
+                | (Self::NonceMismatch, Self::NonceMismatch)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MagicLinkToken(Secret<String>);
+
+/// `token` is 32 random bytes, base64url encoded without padding
+impl MagicLinkToken {
+    pub fn parse(token: String) -> Result<Self> {
+        if RANDOM_TOKEN_REGEX.is_match(&token) {
+            Ok(Self(Secret::new(token)))
+        } else {
+            Err(eyre!("Invalid magic link token"))
+        }
+    }
+
+    // Alias to MagicLinkToken::default()
+    pub fn generate_random() -> Self {
+        MagicLinkToken::default()
+    }
+}
+
+impl Default for MagicLinkToken {
+    fn default() -> Self {
+        Self(Secret::new(generate_random_token()))
+    }
+}
+
+impl PartialEq for MagicLinkToken {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.expose_secret() == other.0.expose_secret()
+    }
+}
+
+impl AsRef<Secret<String>> for MagicLinkToken {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+/// Kept in a cookie of the browser that asked for a magic link, which binds
+/// the link to that browser. `nonce` is 32 random bytes, base64url encoded
+/// without padding.
+#[derive(Debug, Clone)]
+pub struct MagicLinkNonce(Secret<String>);
+
+impl MagicLinkNonce {
+    pub fn parse(nonce: String) -> Result<Self> {
+        if RANDOM_TOKEN_REGEX.is_match(&nonce) {
+            Ok(Self(Secret::new(nonce)))
+        } else {
+            Err(eyre!("Invalid magic link nonce"))
+        }
+    }
+
+    // Alias to MagicLinkNonce::default()
+    pub fn generate_random() -> Self {
+        MagicLinkNonce::default()
+    }
+}
+
+impl Default for MagicLinkNonce {
+    fn default() -> Self {
+        Self(Secret::new(generate_random_token()))
+    }
+}
+
+impl PartialEq for MagicLinkNonce {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.expose_secret() == other.0.expose_secret()
+    }
+}
+
+impl AsRef<Secret<String>> for MagicLinkNonce {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+/// Counts failed login attempts and keeps track of lockouts
+#[async_trait::async_trait]
+pub trait LoginThrottleStore: Send + Sync {
+    /// Seconds left until `key` is no longer locked out, if it is
+    async fn get_lockout(&self, key: &ThrottleKey) -> Result<Option<u64>, LoginThrottleStoreError>;
+
+    /// Count a failure against `key` and return how many there have been since
+    /// the last reset. Failures are forgotten `window_seconds` after the last one.
+    async fn record_failure(
+        &mut self,
+        key: &ThrottleKey,
+        window_seconds: u64,
+    ) -> Result<u32, LoginThrottleStoreError>;
+
+    async fn lock_out(
+        &mut self,
+        key: &ThrottleKey,
+        seconds: u64,
+    ) -> Result<(), LoginThrottleStoreError>;
+
+    /// Forget the failures and lockout of `key`
+    async fn reset(&mut self, key: &ThrottleKey) -> Result<(), LoginThrottleStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum LoginThrottleStoreError {
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for LoginThrottleStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+/// Token buckets of the request rate limiter
+#[async_trait::async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Take a token from the bucket of `key`, which holds up to `limit`
+    async fn take_token(
+        &mut self,
+        key: &str,
+        limit: &RateLimit,
+    ) -> Result<RateLimitDecision, RateLimitStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum RateLimitStoreError {
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for RateLimitStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
 
+/// Login sessions, which live as long as their refresh tokens
 #[async_trait::async_trait]
-pub trait UserStore: Send + Sync {
-    async fn add_user(&mut self, user: User) -> Result<(), UserStoreError>;
-    async fn get_user(&self, email: &Email) -> Result<User, UserStoreError>;
-    async fn user_exists(&self, email: &Email) -> bool;
-    async fn validate_user(
+pub trait SessionStore: Send + Sync {
+    async fn add_session(&mut self, session: Session) -> Result<(), SessionStoreError>;
+    async fn get_session(&self, id: &Uuid) -> Result<Session, SessionStoreError>;
+    /// Returns the sessions of `email`, most recently used first
+    async fn get_sessions(&self, email: &Email) -> Result<Vec<Session>, SessionStoreError>;
+    /// Records that the session was used at `last_seen_at`, keeping it alive
+    async fn touch_session(
+        &mut self,
+        id: &Uuid,
+        last_seen_at: i64,
+    ) -> Result<(), SessionStoreError>;
+    async fn remove_session(&mut self, id: &Uuid) -> Result<(), SessionStoreError>;
+    async fn remove_sessions(&mut self, email: &Email) -> Result<(), SessionStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum SessionStoreError {
+    #[error("Session not found")]
+    SessionNotFound,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for SessionStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::SessionNotFound, Self::SessionNotFound)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+/// Every finished login, which outlives the session it started
+#[async_trait::async_trait]
+pub trait LoginHistoryStore: Send + Sync {
+    async fn add_login(&mut self, login: LoginRecord) -> Result<(), LoginHistoryStoreError>;
+    /// Returns the logins of `email`, most recent first
+    async fn get_logins(&self, email: &Email) -> Result<Vec<LoginRecord>, LoginHistoryStoreError>;
+    /// Whether `login` comes from a device or IP range that none of the user's
+    /// earlier logins came from. A user's first login isn't new, since there is
+    /// nothing to compare it with.
+    async fn is_new_device(&self, login: &LoginRecord) -> Result<bool, LoginHistoryStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum LoginHistoryStoreError {
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for LoginHistoryStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+/// API keys, looked up by the hash of their secret
+#[async_trait::async_trait]
+pub trait ApiKeyStore: Send + Sync {
+    async fn add_key(&mut self, key: ApiKey) -> Result<(), ApiKeyStoreError>;
+    /// Returns the keys of `email`, oldest first, including expired ones
+    async fn get_keys(&self, email: &Email) -> Result<Vec<ApiKey>, ApiKeyStoreError>;
+    async fn get_key_by_hash(&self, key_hash: &str) -> Result<ApiKey, ApiKeyStoreError>;
+    /// Fails with `KeyNotFound` unless the key belongs to `email`
+    async fn revoke_key(&mut self, email: &Email, id: &Uuid) -> Result<(), ApiKeyStoreError>;
+    async fn touch_key(&mut self, id: &Uuid, last_used_at: i64) -> Result<(), ApiKeyStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum ApiKeyStoreError {
+    #[error("API key not found")]
+    KeyNotFound,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for ApiKeyStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::KeyNotFound, Self::KeyNotFound)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+/// Organizations and their members
+#[async_trait::async_trait]
+pub trait OrganizationStore: Send + Sync {
+    /// Fails with `OrganizationAlreadyExists` if there is one with the same ID
+    async fn add_organization(
+        &mut self,
+        organization: Organization,
+    ) -> Result<(), OrganizationStoreError>;
+    /// Returns every organization, ordered by ID
+    async fn get_organizations(&self) -> Result<Vec<Organization>, OrganizationStoreError>;
+    /// Also removes its members
+    async fn delete_organization(&mut self, id: &str) -> Result<(), OrganizationStoreError>;
+    /// Adds the user to the organization, or changes their role if they are
+    /// already a member
+    async fn add_member(&mut self, membership: Membership) -> Result<(), OrganizationStoreError>;
+    async fn remove_member(
+        &mut self,
+        organization_id: &str,
+        email: &Email,
+    ) -> Result<(), OrganizationStoreError>;
+    /// Fails with `MembershipNotFound` if the user isn't a member, including
+    /// when there is no such organization
+    async fn get_membership(
         &self,
+        organization_id: &str,
         email: &Email,
-        password: &Password,
-    ) -> Result<User, UserStoreError>;
+    ) -> Result<Membership, OrganizationStoreError>;
+    /// Returns the organization's members, ordered by email
+    async fn get_members(
+        &self,
+        organization_id: &str,
+    ) -> Result<Vec<Membership>, OrganizationStoreError>;
+    /// Returns the organizations `email` is a member of, ordered by ID
+    async fn get_memberships(
+        &self,
+        email: &Email,
+    ) -> Result<Vec<Membership>, OrganizationStoreError>;
 }
 
 #[derive(Debug, Error)]
-pub enum UserStoreError {
-    #[error("User already exists")]
-    UserAlreadyExists,
-    #[error("User not found")]
-    UserNotFound,
-    #[error("Invalid credentials")]
-    InvalidCredentials,
-    #[error("Unexpected error. Please try again.")]
+pub enum OrganizationStoreError {
+    #[error("Organization already exists")]
+    OrganizationAlreadyExists,
+    #[error("Organization not found")]
+    OrganizationNotFound,
+    #[error("Membership not found")]
+    MembershipNotFound,
+    #[error("Unexpected error")]
     UnexpectedError(#[source] Report),
 }
 
-impl PartialEq for UserStoreError {
+impl PartialEq for OrganizationStoreError {
     fn eq(&self, other: &Self) -> bool {
         matches!(
             (self, other),
-            (Self::UserAlreadyExists, Self::UserAlreadyExists)
-                | (Self::UserNotFound, Self::UserNotFound)
-                | (Self::InvalidCredentials, Self::InvalidCredentials)
+            (
+                Self::OrganizationAlreadyExists,
+                Self::OrganizationAlreadyExists
+            ) | (Self::OrganizationNotFound, Self::OrganizationNotFound)
+                | (Self::MembershipNotFound, Self::MembershipNotFound)
                 | (Self::UnexpectedError(_), Self::UnexpectedError(_))
         )
     }
 }
 
+/// Roles and the users they are assigned to
 #[async_trait::async_trait]
-pub trait BannedTokenStore: Send + Sync {
-    async fn add_token(&mut self, token: Secret<String>) -> Result<()>;
-    async fn get_token(&self, token: &str) -> Option<&String>;
-    async fn token_exists(&self, token: &Secret<String>) -> bool;
+pub trait RoleStore: Send + Sync {
+    /// Fails with `RoleAlreadyExists` if there is a role with the same name
+    async fn add_role(&mut self, role: Role) -> Result<(), RoleStoreError>;
+    /// Returns every role, ordered by name
+    async fn get_roles(&self) -> Result<Vec<Role>, RoleStoreError>;
+    /// Also takes the role away from everyone who has it
+    async fn delete_role(&mut self, name: &str) -> Result<(), RoleStoreError>;
+    /// Assigning a role the user already has does nothing
+    async fn assign_role(&mut self, email: &Email, name: &str) -> Result<(), RoleStoreError>;
+    /// Unassigning a role the user doesn't have does nothing
+    async fn unassign_role(&mut self, email: &Email, name: &str) -> Result<(), RoleStoreError>;
+    /// Returns the roles of `email`, ordered by name
+    async fn get_user_roles(&self, email: &Email) -> Result<Vec<Role>, RoleStoreError>;
 }
 
 #[derive(Debug, Error)]
-pub enum BannedTokenStoreError {
-    #[error("Invalid token")]
-    InvalidToken,
-    #[error("Token already exists in banned token store")]
-    TokenAlreadyExists,
+pub enum RoleStoreError {
+    #[error("Role already exists")]
+    RoleAlreadyExists,
+    #[error("Role not found")]
+    RoleNotFound,
     #[error("Unexpected error")]
     UnexpectedError(#[source] Report),
 }
 
-impl PartialEq for BannedTokenStoreError {
+impl PartialEq for RoleStoreError {
     fn eq(&self, other: &Self) -> bool {
         matches!(
             (self, other),
-            (Self::InvalidToken, Self::InvalidToken)
-                | (Self::TokenAlreadyExists, Self::TokenAlreadyExists)
+            (Self::RoleAlreadyExists, Self::RoleAlreadyExists)
+                | (Self::RoleNotFound, Self::RoleNotFound)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+/// Per-user counter that every access token carries at issue time. Bumping it
+/// invalidates all of the user's outstanding tokens at once.
+#[async_trait::async_trait]
+pub trait TokenVersionStore: Send + Sync {
+    async fn get_token_version(&self, email: &Email) -> Result<u64, TokenVersionStoreError>;
+    /// Returns the new version
+    async fn increment_token_version(
+        &mut self,
+        email: &Email,
+    ) -> Result<u64, TokenVersionStoreError>;
+    /// Forgets the version of an address that no longer has a user, so an
+    /// account created with it later starts from the stored version
+    async fn remove_token_version(&mut self, email: &Email) -> Result<(), TokenVersionStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum TokenVersionStoreError {
+    #[error("User not found")]
+    UserNotFound,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for TokenVersionStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::UserNotFound, Self::UserNotFound)
                 | (Self::UnexpectedError(_), Self::UnexpectedError(_))
         )
     }
@@ -181,3 +943,265 @@ impl AsRef<Secret<String>> for TwoFACode {
         &self.0
     }
 }
+
+#[async_trait::async_trait]
+pub trait TotpSecretStore: Send + Sync {
+    /// Stores an unconfirmed secret for `email`, replacing any earlier
+    /// unconfirmed one. Fails with `AlreadyEnrolled` once a secret is confirmed.
+    async fn add_secret(
+        &mut self,
+        email: &Email,
+        secret: TotpSecret,
+    ) -> Result<(), TotpSecretStoreError>;
+    async fn get_secret(&self, email: &Email) -> Result<TotpEnrollment, TotpSecretStoreError>;
+    async fn confirm_secret(&mut self, email: &Email) -> Result<(), TotpSecretStoreError>;
+    /// Removes the secret of `email`, if any, turning the authenticator app off
+    async fn remove_secret(&mut self, email: &Email) -> Result<(), TotpSecretStoreError>;
+
+    /// Records that the code of time step `step` was used. Fails with
+    /// `StepAlreadyUsed` unless `step` is later than every step used before,
+    /// so that a code can't be replayed, and with `SecretNotFound` if `email`
+    /// has no secret.
+    async fn use_step(&mut self, email: &Email, step: u64) -> Result<(), TotpSecretStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum TotpSecretStoreError {
+    #[error("TOTP already enrolled")]
+    AlreadyEnrolled,
+    #[error("TOTP secret not found")]
+    SecretNotFound,
+    #[error("TOTP code already used")]
+    StepAlreadyUsed,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for TotpSecretStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::AlreadyEnrolled, Self::AlreadyEnrolled)
+                | (Self::SecretNotFound, Self::SecretNotFound)
+                | (Self::StepAlreadyUsed, Self::StepAlreadyUsed)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[async_trait::async_trait]
+pub trait RecoveryCodeStore: Send + Sync {
+    /// Replaces all recovery codes of `email`, so earlier codes stop working
+    async fn replace_codes(
+        &mut self,
+        email: &Email,
+        codes: Vec<RecoveryCode>,
+    ) -> Result<(), RecoveryCodeStoreError>;
+
+    /// Removes `code` from the codes of `email`. Codes are single use.
+    async fn use_code(
+        &mut self,
+        email: &Email,
+        code: &RecoveryCode,
+    ) -> Result<(), RecoveryCodeStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum RecoveryCodeStoreError {
+    #[error("Recovery code not found")]
+    CodeNotFound,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for RecoveryCodeStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::CodeNotFound, Self::CodeNotFound)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RecoveryCode(Secret<String>);
+
+// Lowercase letters and digits without the easily confused 0, 1, i, l and o
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+lazy_static! {
+    static ref RECOVERY_CODE_REGEX: Regex =
+        Regex::new(r"^[a-hj-km-np-z2-9]{5}-[a-hj-km-np-z2-9]{5}$").unwrap();
+}
+
+/// `code` is two groups of 5 characters, e.g. `x7kqm-2hfpz`. Parsing ignores
+/// case and surrounding whitespace, since users type these in by hand.
+impl RecoveryCode {
+    pub fn parse(code: String) -> Result<Self> {
+        let code = code.trim().to_lowercase();
+        if RECOVERY_CODE_REGEX.is_match(&code) {
+            Ok(Self(Secret::new(code)))
+        } else {
+            Err(eyre!("Invalid recovery code"))
+        }
+    }
+
+    // Alias to RecoveryCode::default()
+    pub fn generate_random() -> Self {
+        RecoveryCode::default()
+    }
+}
+
+impl Default for RecoveryCode {
+    fn default() -> Self {
+        let mut rng = rand::thread_rng();
+        let mut code: String = (0..10)
+            .map(|_| {
+                let index = rand::Rng::gen_range(&mut rng, 0..RECOVERY_CODE_ALPHABET.len());
+                RECOVERY_CODE_ALPHABET[index] as char
+            })
+            .collect();
+        code.insert(5, '-');
+        Self(Secret::new(code))
+    }
+}
+
+impl PartialEq for RecoveryCode {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.expose_secret() == other.0.expose_secret()
+    }
+}
+
+impl AsRef<Secret<String>> for RecoveryCode {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+#[async_trait::async_trait]
+pub trait WebauthnCredentialStore: Send + Sync {
+    async fn add_credential(
+        &mut self,
+        credential: WebauthnCredential,
+    ) -> Result<(), WebauthnCredentialStoreError>;
+    async fn get_credential(
+        &self,
+        credential_id: &[u8],
+    ) -> Result<WebauthnCredential, WebauthnCredentialStoreError>;
+    async fn get_credentials(
+        &self,
+        email: &Email,
+    ) -> Result<Vec<WebauthnCredential>, WebauthnCredentialStoreError>;
+    async fn update_sign_count(
+        &mut self,
+        credential_id: &[u8],
+        sign_count: u32,
+    ) -> Result<(), WebauthnCredentialStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum WebauthnCredentialStoreError {
+    #[error("Credential already exists")]
+    CredentialAlreadyExists,
+    #[error("Credential not found")]
+    CredentialNotFound,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for WebauthnCredentialStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::CredentialAlreadyExists, Self::CredentialAlreadyExists)
+                | (Self::CredentialNotFound, Self::CredentialNotFound)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[async_trait::async_trait]
+pub trait WebauthnChallengeStore: Send + Sync {
+    async fn add_challenge(
+        &mut self,
+        challenge: WebauthnChallenge,
+        data: WebauthnChallengeData,
+    ) -> Result<(), WebauthnChallengeStoreError>;
+
+    /// Removes `challenge` from the store and returns the data of its ceremony.
+    /// Challenges are single use.
+    async fn take_challenge(
+        &mut self,
+        challenge: &WebauthnChallenge,
+    ) -> Result<WebauthnChallengeData, WebauthnChallengeStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum WebauthnChallengeStoreError {
+    #[error("Challenge not found")]
+    ChallengeNotFound,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for WebauthnChallengeStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::ChallengeNotFound, Self::ChallengeNotFound)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WebauthnChallenge(Secret<String>);
+
+/// `challenge` is 32 random bytes, base64url encoded without padding
+impl WebauthnChallenge {
+    pub fn parse(challenge: String) -> Result<Self> {
+        if RANDOM_TOKEN_REGEX.is_match(&challenge) {
+            Ok(Self(Secret::new(challenge)))
+        } else {
+            Err(eyre!("Invalid WebAuthn challenge"))
+        }
+    }
+
+    // Alias to WebauthnChallenge::default()
+    pub fn generate_random() -> Self {
+        WebauthnChallenge::default()
+    }
+}
+
+impl Default for WebauthnChallenge {
+    fn default() -> Self {
+        Self(Secret::new(generate_random_token()))
+    }
+}
+
+impl PartialEq for WebauthnChallenge {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.expose_secret() == other.0.expose_secret()
+    }
+}
+
+impl AsRef<Secret<String>> for WebauthnChallenge {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+/// What a challenge was issued for. `email` is unset when signing in with a
+/// passkey without entering an email first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebauthnChallengeData {
+    pub email: Option<Email>,
+    pub ceremony: WebauthnCeremony,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WebauthnCeremony {
+    Registration,
+    Authentication,
+}
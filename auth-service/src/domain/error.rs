@@ -3,18 +3,68 @@ use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum AuthApiError {
+    #[error("Account locked")]
+    AccountLocked,
+    #[error("API key not found")]
+    ApiKeyNotFound,
+    #[error("Email not verified")]
+    EmailNotVerified,
+    /// Authenticated, but not allowed to do this
+    #[error("Forbidden")]
+    Forbidden,
     #[error("Incorrect credentials")]
     IncorrectCredentials,
     #[error("Invalid credentials")]
     InvalidCredentials,
     #[error("Invalid token")]
     InvalidToken,
+    #[error("Membership not found")]
+    MembershipNotFound,
     #[error("Missing token")]
     MissingToken,
+    #[error("Organization already exists")]
+    OrganizationAlreadyExists,
+    #[error("Organization not found")]
+    OrganizationNotFound,
+    #[error("Password reset required")]
+    PasswordResetRequired,
+    #[error("Role already exists")]
+    RoleAlreadyExists,
+    #[error("Role not found")]
+    RoleNotFound,
+    #[error("Session not found")]
+    SessionNotFound,
+    /// Locked out after too many failed attempts, for the given number of seconds
+    #[error("Too many requests")]
+    TooManyRequests(u64),
+    #[error("2FA already enabled")]
+    TwoFAAlreadyEnabled,
+    #[error("2FA not enabled")]
+    TwoFANotEnabled,
     #[error("Unauthorized")]
     Unauthorized,
     #[error("Unexpected error")]
     UnexpectedError(#[source] Report),
     #[error("User already exists")]
     UserAlreadyExists,
+    #[error("User not found")]
+    UserNotFound,
+}
+
+/// Errors returned by the OAuth 2.0 / OpenID Connect endpoints. The `Display`
+/// value is the error code defined in RFC 6749 section 5.2.
+#[derive(Debug, Error)]
+pub enum OAuthError {
+    #[error("invalid_client")]
+    InvalidClient,
+    #[error("invalid_grant")]
+    InvalidGrant,
+    #[error("invalid_request")]
+    InvalidRequest,
+    #[error("invalid_token")]
+    InvalidToken,
+    #[error("unsupported_grant_type")]
+    UnsupportedGrantType,
+    #[error("server_error")]
+    UnexpectedError(#[source] Report),
 }
@@ -0,0 +1,74 @@
+use std::net::IpAddr;
+
+use uuid::Uuid;
+
+use super::{describe_device, Email, LoginMethod};
+
+/// A finished login, kept so that users can see where their account was used
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoginRecord {
+    /// The session the login started
+    pub session_id: Uuid,
+    pub email: Email,
+    pub ip: IpAddr,
+    pub user_agent: Option<String>,
+    /// The browser and OS the login came from, which devices are told apart by
+    pub device: String,
+    pub method: LoginMethod,
+    /// Unix timestamp in seconds
+    pub logged_in_at: i64,
+}
+
+impl LoginRecord {
+    pub fn new(
+        session_id: Uuid,
+        email: Email,
+        ip: IpAddr,
+        user_agent: Option<String>,
+        method: LoginMethod,
+    ) -> Self {
+        Self {
+            session_id,
+            email,
+            ip,
+            device: describe_device(user_agent.as_deref()),
+            user_agent,
+            method,
+            logged_in_at: chrono::Utc::now().timestamp(),
+        }
+    }
+
+    pub fn ip_range(&self) -> String {
+        ip_range(self.ip)
+    }
+}
+
+// The network an address belongs to, at roughly the size ISPs hand out to one
+// customer: a /24 for IPv4 and a /48 for IPv6. A user's address moves around
+// within it, so a new range is a better sign of a new location than a new address.
+pub fn ip_range(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            format!("{}.{}.{}.0/24", a, b, c)
+        }
+        IpAddr::V6(ip) => {
+            let [a, b, c, ..] = ip.segments();
+            format!("{:x}:{:x}:{:x}::/48", a, b, c)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_range() {
+        assert_eq!(ip_range("203.0.113.42".parse().unwrap()), "203.0.113.0/24");
+        assert_eq!(
+            ip_range("2001:db8:85a3:8d3:1319:8a2e:370:7348".parse().unwrap()),
+            "2001:db8:85a3::/48"
+        );
+    }
+}
@@ -1,13 +1,35 @@
+mod api_key;
+mod audit;
 pub mod data_stores;
 pub mod email;
 pub mod email_client;
 mod error;
+mod login_history;
+mod oauth_client;
+mod organization;
 mod password;
+mod rate_limit;
+mod role;
+mod session;
+mod throttle;
+mod totp;
 mod user;
+mod webauthn;
 
+pub use api_key::*;
+pub use audit::*;
 pub use data_stores::*;
 pub use email::*;
 pub use email_client::*;
 pub use error::*;
+pub use login_history::*;
+pub use oauth_client::*;
+pub use organization::*;
 pub use password::*;
+pub use rate_limit::*;
+pub use role::*;
+pub use session::*;
+pub use throttle::*;
+pub use totp::*;
 pub use user::*;
+pub use webauthn::*;
@@ -0,0 +1,46 @@
+use secrecy::Secret;
+
+/// A relying party that may use auth-service as its OpenID Connect provider
+#[derive(Clone, Debug)]
+pub struct OAuthClient {
+    pub client_id: String,
+    pub client_secret: Secret<String>,
+    pub redirect_uris: Vec<String>,
+}
+
+impl OAuthClient {
+    pub fn new(
+        client_id: String,
+        client_secret: Secret<String>,
+        redirect_uris: Vec<String>,
+    ) -> Self {
+        Self {
+            client_id,
+            client_secret,
+            redirect_uris,
+        }
+    }
+
+    /// Redirect URIs must match a registered URI exactly
+    pub fn has_redirect_uri(&self, redirect_uri: &str) -> bool {
+        self.redirect_uris.iter().any(|uri| uri == redirect_uri)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_has_redirect_uri() {
+        let client = OAuthClient::new(
+            "client".to_owned(),
+            Secret::new("secret".to_owned()),
+            vec!["https://app.example.com/callback".to_owned()],
+        );
+
+        assert!(client.has_redirect_uri("https://app.example.com/callback"));
+        assert!(!client.has_redirect_uri("https://app.example.com/callback/"));
+        assert!(!client.has_redirect_uri("https://evil.example.com/callback"));
+    }
+}
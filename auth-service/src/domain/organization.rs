@@ -0,0 +1,98 @@
+use color_eyre::eyre::{eyre, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::Email;
+
+lazy_static! {
+    static ref ID_REGEX: Regex = Regex::new(r"^[a-z0-9][a-z0-9-]{0,62}$").unwrap();
+}
+
+/// A customer organization, the tenant its members log in to
+#[derive(Debug, Clone, PartialEq)]
+pub struct Organization {
+    /// A slug such as `acme`, which logins name as their tenant and tokens carry
+    /// as `tenant_id`
+    pub id: String,
+    pub name: String,
+}
+
+impl Organization {
+    // IDs are made of lowercase letters, digits and hyphens
+    pub fn parse(id: String, name: String) -> Result<Self> {
+        if !ID_REGEX.is_match(&id) {
+            return Err(eyre!("invalid organization ID {}", id));
+        }
+        if name.trim().is_empty() {
+            return Err(eyre!("organization name is empty"));
+        }
+
+        Ok(Self { id, name })
+    }
+}
+
+/// What a member may do within their organization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrganizationRole {
+    Owner,
+    Admin,
+    Member,
+}
+
+impl OrganizationRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Owner => "owner",
+            Self::Admin => "admin",
+            Self::Member => "member",
+        }
+    }
+
+    pub fn parse(role: &str) -> Result<Self> {
+        match role {
+            "owner" => Ok(Self::Owner),
+            "admin" => Ok(Self::Admin),
+            "member" => Ok(Self::Member),
+            _ => Err(eyre!("invalid organization role {}", role)),
+        }
+    }
+}
+
+/// A user's membership of an organization
+#[derive(Debug, Clone, PartialEq)]
+pub struct Membership {
+    pub organization_id: String,
+    pub email: Email,
+    pub role: OrganizationRole,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_organization() {
+        assert!(Organization::parse("acme".to_owned(), "Acme".to_owned()).is_ok());
+        assert!(Organization::parse("acme-eu-2".to_owned(), "Acme EU".to_owned()).is_ok());
+
+        assert!(Organization::parse("".to_owned(), "Acme".to_owned()).is_err());
+        assert!(Organization::parse("-acme".to_owned(), "Acme".to_owned()).is_err());
+        assert!(Organization::parse("Acme".to_owned(), "Acme".to_owned()).is_err());
+        assert!(Organization::parse("acme corp".to_owned(), "Acme".to_owned()).is_err());
+        assert!(Organization::parse("acme".to_owned(), " ".to_owned()).is_err());
+    }
+
+    #[test]
+    fn test_organization_role_names() {
+        for role in [
+            OrganizationRole::Owner,
+            OrganizationRole::Admin,
+            OrganizationRole::Member,
+        ] {
+            assert_eq!(OrganizationRole::parse(role.as_str()).unwrap(), role);
+        }
+        assert!(OrganizationRole::parse("guest").is_err());
+    }
+}
@@ -56,7 +56,7 @@ impl Password {
             Err(eyre!(PasswordError::MissingLowercase))
         } else if !pw_str.chars().any(|c| c.is_uppercase()) {
             Err(eyre!(PasswordError::MissingUppercase))
-        } else if !pw_str.chars().any(|c| c.is_digit(10)) {
+        } else if !pw_str.chars().any(|c| c.is_ascii_digit()) {
             Err(eyre!(PasswordError::MissingDigit))
         } else if !pw_str.chars().any(|c| !c.is_alphanumeric()) {
             Err(eyre!(PasswordError::MissingSpecialCharacter))
@@ -0,0 +1,115 @@
+/// Token bucket limit: `capacity` requests in a burst, refilled at `capacity`
+/// per `period_seconds`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub capacity: u32,
+    pub period_seconds: u64,
+}
+
+impl RateLimit {
+    pub const fn per_second(capacity: u32) -> Self {
+        Self {
+            capacity,
+            period_seconds: 1,
+        }
+    }
+
+    pub const fn per_minute(capacity: u32) -> Self {
+        Self {
+            capacity,
+            period_seconds: 60,
+        }
+    }
+
+    pub const fn per_hour(capacity: u32) -> Self {
+        Self {
+            capacity,
+            period_seconds: 60 * 60,
+        }
+    }
+
+    fn tokens_per_second(&self) -> f64 {
+        f64::from(self.capacity) / self.period_seconds as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateLimitDecision {
+    Allowed,
+    Limited { retry_after_seconds: u64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenBucket {
+    pub tokens: f64,
+    /// Unix time in milliseconds when `tokens` was last brought up to date
+    pub updated_at_ms: i64,
+}
+
+impl TokenBucket {
+    pub fn full(limit: &RateLimit, now_ms: i64) -> Self {
+        Self {
+            tokens: f64::from(limit.capacity),
+            updated_at_ms: now_ms,
+        }
+    }
+
+    /// Refill the bucket for the time passed since it was last used, then take
+    /// a token out of it if there is one
+    pub fn take(&mut self, limit: &RateLimit, now_ms: i64) -> RateLimitDecision {
+        let elapsed_seconds = (now_ms - self.updated_at_ms).max(0) as f64 / 1000.0;
+        self.tokens = (self.tokens + elapsed_seconds * limit.tokens_per_second())
+            .min(f64::from(limit.capacity));
+        self.updated_at_ms = now_ms.max(self.updated_at_ms);
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            RateLimitDecision::Allowed
+        } else {
+            // Rounded to the millisecond first so that float noise doesn't
+            // add a second
+            let wait_ms = ((1.0 - self.tokens) / limit.tokens_per_second() * 1000.0).round();
+            RateLimitDecision::Limited {
+                retry_after_seconds: (wait_ms as u64).div_ceil(1000).max(1),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_until_empty_then_refill() {
+        let limit = RateLimit::per_minute(2);
+        let mut bucket = TokenBucket::full(&limit, 0);
+
+        assert_eq!(bucket.take(&limit, 0), RateLimitDecision::Allowed);
+        assert_eq!(bucket.take(&limit, 0), RateLimitDecision::Allowed);
+        assert_eq!(
+            bucket.take(&limit, 0),
+            RateLimitDecision::Limited {
+                retry_after_seconds: 30
+            }
+        );
+
+        // One token comes back every 30 seconds
+        assert_eq!(
+            bucket.take(&limit, 20_000),
+            RateLimitDecision::Limited {
+                retry_after_seconds: 10
+            }
+        );
+        assert_eq!(bucket.take(&limit, 30_000), RateLimitDecision::Allowed);
+    }
+
+    #[test]
+    fn test_refill_stops_at_capacity() {
+        let limit = RateLimit::per_second(3);
+        let mut bucket = TokenBucket::full(&limit, 0);
+
+        assert_eq!(bucket.take(&limit, 60_000), RateLimitDecision::Allowed);
+        assert_eq!(bucket.tokens, 2.0);
+    }
+}
@@ -0,0 +1,118 @@
+use color_eyre::eyre::{eyre, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::Membership;
+
+lazy_static! {
+    // Permissions end up in the space-separated `scope` claim, so names can't
+    // contain spaces
+    static ref NAME_REGEX: Regex = Regex::new(r"^[a-z0-9][a-z0-9_.:-]{0,63}$").unwrap();
+}
+
+/// A named set of permissions that users can be given
+#[derive(Debug, Clone, PartialEq)]
+pub struct Role {
+    pub name: String,
+    pub description: String,
+    /// Sorted, without duplicates
+    pub permissions: Vec<String>,
+}
+
+impl Role {
+    // Names of roles and permissions are made of lowercase letters, digits and
+    // `_.:-`, e.g. `billing` and `invoices:read`
+    pub fn parse(name: String, description: String, mut permissions: Vec<String>) -> Result<Self> {
+        if !NAME_REGEX.is_match(&name) {
+            return Err(eyre!("invalid role name {}", name));
+        }
+        if let Some(permission) = permissions.iter().find(|p| !NAME_REGEX.is_match(p)) {
+            return Err(eyre!("invalid permission {}", permission));
+        }
+        permissions.sort();
+        permissions.dedup();
+
+        Ok(Self {
+            name,
+            description,
+            permissions,
+        })
+    }
+}
+
+/// What a user's access tokens grant: the admin API, the user's roles with the
+/// permissions that come with them, and the organization they logged in to
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UserAccess {
+    pub admin: bool,
+    pub roles: Vec<String>,
+    /// Every permission of every role, sorted and without duplicates
+    pub permissions: Vec<String>,
+    pub tenant: Option<Membership>,
+}
+
+impl UserAccess {
+    pub fn new(admin: bool, roles: &[Role]) -> Self {
+        let mut role_names: Vec<String> = roles.iter().map(|role| role.name.clone()).collect();
+        role_names.sort();
+        let mut permissions: Vec<String> = roles
+            .iter()
+            .flat_map(|role| role.permissions.iter().cloned())
+            .collect();
+        permissions.sort();
+        permissions.dedup();
+
+        Self {
+            admin,
+            roles: role_names,
+            permissions,
+            tenant: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(name: &str, permissions: &[&str]) -> Result<Role> {
+        Role::parse(
+            name.to_owned(),
+            String::new(),
+            permissions.iter().map(|p| p.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_parse_role() {
+        let billing = role(
+            "billing",
+            &["invoices:write", "invoices:read", "invoices:read"],
+        );
+        assert_eq!(
+            billing.unwrap().permissions,
+            ["invoices:read", "invoices:write"]
+        );
+
+        assert!(role("", &[]).is_err());
+        assert!(role("Billing", &[]).is_err());
+        assert!(role("billing", &["invoices read"]).is_err());
+        assert!(role(&"a".repeat(65), &[]).is_err());
+    }
+
+    #[test]
+    fn test_user_access_combines_permissions_of_roles() {
+        let roles = [
+            role("support", &["tickets:read", "users:read"]).unwrap(),
+            role("billing", &["invoices:read", "users:read"]).unwrap(),
+        ];
+
+        let access = UserAccess::new(false, &roles);
+
+        assert_eq!(access.roles, ["billing", "support"]);
+        assert_eq!(
+            access.permissions,
+            ["invoices:read", "tickets:read", "users:read"]
+        );
+    }
+}
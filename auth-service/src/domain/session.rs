@@ -0,0 +1,107 @@
+use std::net::IpAddr;
+
+use uuid::Uuid;
+
+use super::Email;
+
+/// A login on one device. Its ID is the family ID of the refresh tokens issued
+/// for it and the `sid` claim of its access tokens.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    pub id: Uuid,
+    pub email: Email,
+    pub device: String,
+    pub user_agent: Option<String>,
+    pub ip: IpAddr,
+    /// Unix timestamps in seconds
+    pub created_at: i64,
+    pub last_seen_at: i64,
+}
+
+impl Session {
+    pub fn new(id: Uuid, email: Email, ip: IpAddr, user_agent: Option<String>) -> Self {
+        let now = chrono::Utc::now().timestamp();
+        Self {
+            id,
+            email,
+            device: describe_device(user_agent.as_deref()),
+            user_agent,
+            ip,
+            created_at: now,
+            last_seen_at: now,
+        }
+    }
+}
+
+// A rough "<browser> on <OS>" for people to recognize their devices by. The
+// order of the checks matters, since most user agents also name the browsers
+// they are compatible with.
+pub fn describe_device(user_agent: Option<&str>) -> String {
+    let Some(user_agent) = user_agent.filter(|user_agent| !user_agent.is_empty()) else {
+        return "Unknown device".to_owned();
+    };
+
+    let browser = [
+        ("Edg/", "Edge"),
+        ("OPR/", "Opera"),
+        ("Firefox/", "Firefox"),
+        ("Chrome/", "Chrome"),
+        ("Safari/", "Safari"),
+        ("curl/", "curl"),
+    ]
+    .into_iter()
+    .find(|(token, _)| user_agent.contains(token))
+    .map_or("Unknown browser", |(_, browser)| browser);
+
+    let os = [
+        ("iPhone", "iOS"),
+        ("iPad", "iPadOS"),
+        ("Android", "Android"),
+        ("Windows", "Windows"),
+        ("Mac OS X", "macOS"),
+        ("CrOS", "ChromeOS"),
+        ("Linux", "Linux"),
+    ]
+    .into_iter()
+    .find(|(token, _)| user_agent.contains(token))
+    .map(|(_, os)| os);
+
+    match os {
+        Some(os) => format!("{} on {}", browser, os),
+        None => browser.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_device() {
+        let cases = [
+            (
+                "Mozilla/5.0 (X11; Linux x86_64; rv:131.0) Gecko/20100101 Firefox/131.0",
+                "Firefox on Linux",
+            ),
+            (
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/129.0.0.0 Safari/537.36 Edg/129.0.0.0",
+                "Edge on Windows",
+            ),
+            (
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 18_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/18.0 Mobile/15E148 Safari/604.1",
+                "Safari on iOS",
+            ),
+            (
+                "Mozilla/5.0 (Linux; Android 14) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/129.0.0.0 Mobile Safari/537.36",
+                "Chrome on Android",
+            ),
+            ("curl/8.5.0", "curl"),
+        ];
+        for (user_agent, device) in cases {
+            assert_eq!(describe_device(Some(user_agent)), device);
+        }
+
+        assert_eq!(describe_device(None), "Unknown device");
+        assert_eq!(describe_device(Some("")), "Unknown device");
+    }
+}
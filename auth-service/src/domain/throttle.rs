@@ -0,0 +1,100 @@
+use std::net::IpAddr;
+
+use secrecy::ExposeSecret;
+
+use super::{Email, LoginAttemptId};
+
+/// What failed login attempts are counted against. Each kind of key has its
+/// own `ThrottlePolicy`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ThrottleKey {
+    Email(Email),
+    Ip(IpAddr),
+    LoginAttempt(LoginAttemptId),
+}
+
+impl ThrottleKey {
+    pub fn policy(&self) -> ThrottlePolicy {
+        match self {
+            // Guessing passwords for one account
+            ThrottleKey::Email(_) => ThrottlePolicy {
+                free_attempts: 5,
+                base_lockout_seconds: 30,
+                max_lockout_seconds: 60 * 15,
+            },
+            // Spraying guesses across accounts. Users behind a NAT share an
+            // address, so more failures are allowed.
+            ThrottleKey::Ip(_) => ThrottlePolicy {
+                free_attempts: 20,
+                base_lockout_seconds: 30,
+                max_lockout_seconds: 60 * 15,
+            },
+            // Guessing the second factor of a single login
+            ThrottleKey::LoginAttempt(_) => ThrottlePolicy {
+                free_attempts: 2,
+                base_lockout_seconds: 5,
+                max_lockout_seconds: 60,
+            },
+        }
+    }
+
+    /// Identifies the key in a store
+    pub fn id(&self) -> String {
+        match self {
+            ThrottleKey::Email(email) => format!("email:{}", email.as_ref().expose_secret()),
+            ThrottleKey::Ip(ip) => format!("ip:{}", ip),
+            ThrottleKey::LoginAttempt(id) => {
+                format!("login_attempt:{}", id.as_ref().expose_secret())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThrottlePolicy {
+    /// Failures allowed before the key gets locked out
+    pub free_attempts: u32,
+    /// Lockout after the first failure past the free ones. It doubles with
+    /// every further failure, up to `max_lockout_seconds`.
+    pub base_lockout_seconds: u64,
+    pub max_lockout_seconds: u64,
+}
+
+impl ThrottlePolicy {
+    /// How long to lock the key out for after `failures` consecutive failures
+    pub fn lockout_seconds(&self, failures: u32) -> Option<u64> {
+        let excess = failures.checked_sub(self.free_attempts)?.checked_sub(1)?;
+        let lockout = 2u64
+            .checked_pow(excess)
+            .and_then(|factor| factor.checked_mul(self.base_lockout_seconds))
+            .unwrap_or(u64::MAX);
+        Some(lockout.min(self.max_lockout_seconds))
+    }
+
+    /// How long failures are remembered for after the last one
+    pub fn window_seconds(&self) -> u64 {
+        self.max_lockout_seconds * 4
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lockout_seconds() {
+        let policy = ThrottlePolicy {
+            free_attempts: 3,
+            base_lockout_seconds: 10,
+            max_lockout_seconds: 60,
+        };
+
+        assert_eq!(policy.lockout_seconds(0), None);
+        assert_eq!(policy.lockout_seconds(3), None);
+        assert_eq!(policy.lockout_seconds(4), Some(10));
+        assert_eq!(policy.lockout_seconds(5), Some(20));
+        assert_eq!(policy.lockout_seconds(6), Some(40));
+        assert_eq!(policy.lockout_seconds(7), Some(60));
+        assert_eq!(policy.lockout_seconds(u32::MAX), Some(60));
+    }
+}
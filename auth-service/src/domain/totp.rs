@@ -0,0 +1,202 @@
+use std::fmt;
+
+use chrono::Utc;
+use color_eyre::eyre::{eyre, Context, Result};
+use data_encoding::BASE32_NOPAD;
+use ring::hmac;
+use secrecy::{ExposeSecret, Secret};
+
+use super::{Email, TwoFACode};
+
+// Name shown next to the account in authenticator apps
+const TOTP_ISSUER: &str = "Let's Get Rusty";
+const TOTP_STEP_SECONDS: i64 = 30;
+const TOTP_DIGITS: u32 = 6;
+// 160 bits, the length recommended for HMAC-SHA1 by RFC 4226
+const TOTP_SECRET_LENGTH: usize = 20;
+
+/// Shared secret of an authenticator app. Codes follow RFC 6238 with the
+/// defaults every app supports: HMAC-SHA1, 6 digits and 30 second steps.
+pub struct TotpSecret(Secret<Vec<u8>>);
+
+impl TotpSecret {
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self(Secret::new(bytes))
+    }
+
+    pub fn parse_base32(secret: &str) -> Result<Self> {
+        let bytes = BASE32_NOPAD
+            .decode(secret.trim_end_matches('=').as_bytes())
+            .wrap_err("Invalid TOTP secret")?;
+        if bytes.is_empty() {
+            return Err(eyre!("Invalid TOTP secret"));
+        }
+        Ok(Self::from_bytes(bytes))
+    }
+
+    // Alias to TotpSecret::default()
+    pub fn generate_random() -> Self {
+        TotpSecret::default()
+    }
+
+    /// The secret as users type it into an authenticator app
+    pub fn to_base32(&self) -> String {
+        BASE32_NOPAD.encode(self.0.expose_secret())
+    }
+
+    /// Key URI that authenticator apps read from a QR code
+    pub fn otpauth_uri(&self, email: &Email) -> String {
+        let issuer = percent_encode(TOTP_ISSUER);
+        format!(
+            "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+            issuer,
+            percent_encode(email.as_ref().expose_secret()),
+            self.to_base32(),
+            issuer,
+            TOTP_DIGITS,
+            TOTP_STEP_SECONDS
+        )
+    }
+
+    /// Number of the time step that the current time falls in
+    pub fn current_step() -> u64 {
+        (Utc::now().timestamp() / TOTP_STEP_SECONDS) as u64
+    }
+
+    pub fn code_at(&self, step: u64) -> TwoFACode {
+        let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, self.0.expose_secret());
+        let tag = hmac::sign(&key, &step.to_be_bytes());
+        let digest = tag.as_ref();
+
+        // Dynamic truncation (RFC 4226 section 5.3)
+        let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+        let mut truncated = [0u8; 4];
+        truncated.copy_from_slice(&digest[offset..offset + 4]);
+        let code = (u32::from_be_bytes(truncated) & 0x7fff_ffff) % 10u32.pow(TOTP_DIGITS);
+
+        TwoFACode::parse(format!("{:06}", code)).expect("TOTP codes have 6 digits")
+    }
+
+    /// Returns the step that `code` belongs to. Codes of the steps right before
+    /// and after `step` are accepted too, to allow for clock drift.
+    pub fn verify(&self, code: &TwoFACode, step: u64) -> Option<u64> {
+        (step.saturating_sub(1)..=step + 1).find(|&candidate| self.code_at(candidate) == *code)
+    }
+}
+
+impl Default for TotpSecret {
+    fn default() -> Self {
+        let mut bytes = vec![0u8; TOTP_SECRET_LENGTH];
+        rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut bytes);
+        Self::from_bytes(bytes)
+    }
+}
+
+impl Clone for TotpSecret {
+    fn clone(&self) -> Self {
+        Self::from_bytes(self.0.expose_secret().clone())
+    }
+}
+
+impl fmt::Debug for TotpSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TotpSecret([REDACTED])")
+    }
+}
+
+impl AsRef<Secret<Vec<u8>>> for TotpSecret {
+    fn as_ref(&self) -> &Secret<Vec<u8>> {
+        &self.0
+    }
+}
+
+/// A user's authenticator app. It only counts as a second factor once the
+/// user has confirmed the enrollment by entering a code from the app.
+#[derive(Clone, Debug)]
+pub struct TotpEnrollment {
+    pub secret: TotpSecret,
+    pub confirmed: bool,
+}
+
+// Percent-encode everything but the unreserved characters of RFC 3986
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The SHA1 secret of the RFC 6238 test vectors
+    fn rfc_secret() -> TotpSecret {
+        TotpSecret::from_bytes(b"12345678901234567890".to_vec())
+    }
+
+    fn code(code: &str) -> TwoFACode {
+        TwoFACode::parse(code.to_owned()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_code_at() {
+        // RFC 6238 appendix B, truncated to 6 digits
+        let secret = rfc_secret();
+        assert_eq!(secret.code_at(59 / 30), code("287082"));
+        assert_eq!(secret.code_at(1111111109 / 30), code("081804"));
+        assert_eq!(secret.code_at(1234567890 / 30), code("005924"));
+        assert_eq!(secret.code_at(2000000000 / 30), code("279037"));
+    }
+
+    #[tokio::test]
+    async fn test_verify() {
+        let secret = rfc_secret();
+        let step = 1111111109 / 30;
+
+        assert_eq!(secret.verify(&code("081804"), step), Some(step));
+        assert_eq!(secret.verify(&code("081804"), step - 1), Some(step));
+        assert_eq!(secret.verify(&code("081804"), step + 1), Some(step));
+        assert_eq!(secret.verify(&code("081804"), step + 2), None);
+        assert_eq!(secret.verify(&code("081805"), step), None);
+    }
+
+    #[tokio::test]
+    async fn test_base32() {
+        let secret = rfc_secret();
+        assert_eq!(secret.to_base32(), "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+
+        let parsed = TotpSecret::parse_base32(&secret.to_base32()).unwrap();
+        assert_eq!(
+            parsed.as_ref().expose_secret(),
+            secret.as_ref().expose_secret()
+        );
+
+        assert!(TotpSecret::parse_base32("").is_err());
+        assert!(TotpSecret::parse_base32("not base32!").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_otpauth_uri() {
+        let email = Email::parse(Secret::new("test+totp@example.com".to_owned())).unwrap();
+        let secret = rfc_secret();
+
+        assert_eq!(
+            secret.otpauth_uri(&email),
+            "otpauth://totp/Let%27s%20Get%20Rusty:test%2Btotp%40example.com\
+             ?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=Let%27s%20Get%20Rusty\
+             &algorithm=SHA1&digits=6&period=30"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_random() {
+        let secret = TotpSecret::generate_random();
+        assert_eq!(secret.as_ref().expose_secret().len(), TOTP_SECRET_LENGTH);
+    }
+}
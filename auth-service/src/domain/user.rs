@@ -6,18 +6,42 @@ pub struct User {
     #[sqlx(rename = "password_hash")]
     pub password: Password,
     pub requires_2fa: bool,
+    pub verified: bool,
+    /// Deleted, but still within the grace period in which logging in restores it
+    pub pending_deletion: bool,
+    /// Can use the admin API
+    #[sqlx(rename = "is_admin")]
+    pub admin: bool,
+    /// Locked by an admin, which keeps the user from logging in
+    pub locked: bool,
+    /// The password can't be used to log in until it is changed
+    pub password_reset_required: bool,
 }
 
 impl User {
+    // New users have to verify their email address
     pub fn new(email: Email, password: Password, requires_2fa: bool) -> User {
         User {
             email,
-            password: password,
+            password,
             requires_2fa,
+            verified: false,
+            pending_deletion: false,
+            admin: false,
+            locked: false,
+            password_reset_required: false,
         }
     }
 }
 
+/// One page of the users matching a search
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserPage {
+    pub users: Vec<User>,
+    /// How many users match the search across all pages
+    pub total: u64,
+}
+
 #[cfg(test)]
 mod tests {
     use secrecy::{ExposeSecret, Secret};
@@ -37,6 +61,11 @@ mod tests {
 
         assert_eq!(user.email.as_ref().expose_secret(), correct_email);
         assert!(user.requires_2fa);
+        assert!(!user.verified);
+        assert!(!user.pending_deletion);
+        assert!(!user.admin);
+        assert!(!user.locked);
+        assert!(!user.password_reset_required);
         assert_eq!(user.password.as_ref().expose_secret(), correct_password);
     }
 }
@@ -0,0 +1,754 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ciborium::value::Value;
+use color_eyre::eyre::{eyre, Context, ContextCompat, Result};
+use ring::{
+    digest::{digest, SHA256},
+    signature::{UnparsedPublicKey, ECDSA_P256_SHA256_ASN1, ED25519},
+};
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+
+use super::{Email, WebauthnChallenge};
+
+// Name shown by the browser when creating a passkey
+const RELYING_PARTY_NAME: &str = "Let's Get Rusty";
+const CEREMONY_TIMEOUT_MILLISECONDS: u64 = 5 * 60 * 1000;
+const PUBLIC_KEY_CREDENTIAL_TYPE: &str = "public-key";
+
+// COSE algorithm identifiers (RFC 9053), in order of preference
+const COSE_ALGORITHM_ES256: i64 = -7;
+const COSE_ALGORITHM_EDDSA: i64 = -8;
+
+// Bits of the flags byte of authenticator data
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_ATTESTED_CREDENTIAL_DATA: u8 = 0x40;
+
+/// Public key of a passkey, in the form the signature verification expects:
+/// an uncompressed SEC1 point for ES256 and the raw 32 bytes for EdDSA.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CredentialPublicKey {
+    Es256(Vec<u8>),
+    EdDsa(Vec<u8>),
+}
+
+impl CredentialPublicKey {
+    /// `algorithm` is a COSE algorithm identifier, as returned by `algorithm()`
+    pub fn from_parts(algorithm: i64, key: Vec<u8>) -> Result<Self> {
+        match algorithm {
+            COSE_ALGORITHM_ES256 if key.len() == 65 && key[0] == 0x04 => Ok(Self::Es256(key)),
+            COSE_ALGORITHM_EDDSA if key.len() == 32 => Ok(Self::EdDsa(key)),
+            _ => Err(eyre!("Unsupported public key")),
+        }
+    }
+
+    pub fn algorithm(&self) -> i64 {
+        match self {
+            Self::Es256(_) => COSE_ALGORITHM_ES256,
+            Self::EdDsa(_) => COSE_ALGORITHM_EDDSA,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Es256(key) | Self::EdDsa(key) => key,
+        }
+    }
+
+    // Only EC2 keys on P-256 and OKP keys on Ed25519 are supported (RFC 9053 section 7)
+    fn from_cose(key: &Value) -> Result<Self> {
+        let entries = key.as_map().wrap_err("COSE key is not a map")?;
+        let get = |label: i64| {
+            entries
+                .iter()
+                .find(|(key, _)| key.as_integer().map(i128::from) == Some(label.into()))
+                .map(|(_, value)| value)
+        };
+        let get_integer = |label: i64| {
+            get(label)
+                .and_then(Value::as_integer)
+                .map(i128::from)
+                .wrap_err("Missing COSE key parameter")
+        };
+        let get_bytes = |label: i64| {
+            get(label)
+                .and_then(Value::as_bytes)
+                .filter(|bytes| bytes.len() == 32)
+                .wrap_err("Missing COSE key coordinate")
+        };
+
+        match (get_integer(1)?, get_integer(3)? as i64, get_integer(-1)?) {
+            // kty EC2, crv P-256
+            (2, COSE_ALGORITHM_ES256, 1) => {
+                let mut point = vec![0x04];
+                point.extend_from_slice(get_bytes(-2)?);
+                point.extend_from_slice(get_bytes(-3)?);
+                Ok(Self::Es256(point))
+            }
+            // kty OKP, crv Ed25519
+            (1, COSE_ALGORITHM_EDDSA, 6) => Ok(Self::EdDsa(get_bytes(-2)?.clone())),
+            _ => Err(eyre!("Unsupported COSE key")),
+        }
+    }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> Result<()> {
+        let result = match self {
+            Self::Es256(key) => {
+                UnparsedPublicKey::new(&ECDSA_P256_SHA256_ASN1, key).verify(message, signature)
+            }
+            Self::EdDsa(key) => UnparsedPublicKey::new(&ED25519, key).verify(message, signature),
+        };
+        result.map_err(|_| eyre!("Invalid signature"))
+    }
+}
+
+/// A passkey registered by a user. Users can register several of them, e.g.
+/// one per device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebauthnCredential {
+    pub credential_id: Vec<u8>,
+    pub email: Email,
+    pub public_key: CredentialPublicKey,
+    /// Signature counter of the authenticator, zero if it doesn't keep one
+    pub sign_count: u32,
+    pub name: String,
+}
+
+/// Options for `navigator.credentials.create()`, in the JSON form of
+/// `PublicKeyCredential.parseCreationOptionsFromJSON()`
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicKeyCredentialCreationOptions {
+    pub challenge: String,
+    pub rp: RelyingPartyEntity,
+    pub user: UserEntity,
+    pub pub_key_cred_params: Vec<CredentialParameters>,
+    pub timeout: u64,
+    pub exclude_credentials: Vec<CredentialDescriptor>,
+    pub authenticator_selection: AuthenticatorSelection,
+    pub attestation: String,
+}
+
+impl PublicKeyCredentialCreationOptions {
+    /// `existing` are the credentials the user already has, so that the same
+    /// authenticator isn't registered twice
+    pub fn new(
+        challenge: &WebauthnChallenge,
+        email: &Email,
+        rp_id: &str,
+        existing: &[WebauthnCredential],
+    ) -> Self {
+        let email = email.as_ref().expose_secret();
+        Self {
+            challenge: challenge.as_ref().expose_secret().to_owned(),
+            rp: RelyingPartyEntity {
+                id: rp_id.to_owned(),
+                name: RELYING_PARTY_NAME.to_owned(),
+            },
+            user: UserEntity {
+                // The user handle must not contain personal information
+                id: URL_SAFE_NO_PAD.encode(digest(&SHA256, email.as_bytes())),
+                name: email.to_owned(),
+                display_name: email.to_owned(),
+            },
+            pub_key_cred_params: [COSE_ALGORITHM_ES256, COSE_ALGORITHM_EDDSA]
+                .into_iter()
+                .map(|alg| CredentialParameters {
+                    credential_type: PUBLIC_KEY_CREDENTIAL_TYPE.to_owned(),
+                    alg,
+                })
+                .collect(),
+            timeout: CEREMONY_TIMEOUT_MILLISECONDS,
+            exclude_credentials: existing.iter().map(CredentialDescriptor::from).collect(),
+            authenticator_selection: AuthenticatorSelection {
+                resident_key: "preferred".to_owned(),
+                user_verification: "preferred".to_owned(),
+            },
+            attestation: "none".to_owned(),
+        }
+    }
+}
+
+/// Options for `navigator.credentials.get()`, in the JSON form of
+/// `PublicKeyCredential.parseRequestOptionsFromJSON()`
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicKeyCredentialRequestOptions {
+    pub challenge: String,
+    pub timeout: u64,
+    pub rp_id: String,
+    pub allow_credentials: Vec<CredentialDescriptor>,
+    pub user_verification: String,
+}
+
+impl PublicKeyCredentialRequestOptions {
+    /// With no `allowed` credentials, the browser offers the passkeys it
+    /// has stored for the site
+    pub fn new(challenge: &WebauthnChallenge, rp_id: &str, allowed: &[WebauthnCredential]) -> Self {
+        Self {
+            challenge: challenge.as_ref().expose_secret().to_owned(),
+            timeout: CEREMONY_TIMEOUT_MILLISECONDS,
+            rp_id: rp_id.to_owned(),
+            allow_credentials: allowed.iter().map(CredentialDescriptor::from).collect(),
+            user_verification: "preferred".to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RelyingPartyEntity {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UserEntity {
+    pub id: String,
+    pub name: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct CredentialParameters {
+    #[serde(rename = "type")]
+    pub credential_type: String,
+    pub alg: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct CredentialDescriptor {
+    #[serde(rename = "type")]
+    pub credential_type: String,
+    pub id: String,
+}
+
+impl From<&WebauthnCredential> for CredentialDescriptor {
+    fn from(credential: &WebauthnCredential) -> Self {
+        Self {
+            credential_type: PUBLIC_KEY_CREDENTIAL_TYPE.to_owned(),
+            id: URL_SAFE_NO_PAD.encode(&credential.credential_id),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticatorSelection {
+    pub resident_key: String,
+    pub user_verification: String,
+}
+
+/// Result of `navigator.credentials.create()`, as serialized by
+/// `PublicKeyCredential.toJSON()`. Binary values are base64url encoded.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RegistrationCredential {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub credential_type: String,
+    pub response: AttestationResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub attestation_object: String,
+}
+
+impl RegistrationCredential {
+    /// The challenge the browser signed, to look up the ceremony with
+    pub fn challenge(&self) -> Result<WebauthnChallenge> {
+        client_data_challenge(&self.response.client_data_json)
+    }
+
+    /// Checks the response to the registration ceremony started with
+    /// `challenge` and returns the new credential (WebAuthn section 7.1).
+    /// Only "none" and self attestation are accepted, since attestation
+    /// isn't requested.
+    pub fn verify(
+        &self,
+        challenge: &WebauthnChallenge,
+        rp_id: &str,
+        origin: &str,
+        email: Email,
+        name: String,
+    ) -> Result<WebauthnCredential> {
+        if self.credential_type != PUBLIC_KEY_CREDENTIAL_TYPE {
+            return Err(eyre!("Invalid credential type"));
+        }
+        let client_data_json = decode_base64url(&self.response.client_data_json)?;
+        verify_client_data(&client_data_json, "webauthn.create", challenge, origin)?;
+
+        let attestation_object = decode_base64url(&self.response.attestation_object)?;
+        let attestation_object: Value = ciborium::from_reader(attestation_object.as_slice())
+            .wrap_err("Invalid attestation object")?;
+        let format = map_get(&attestation_object, "fmt")
+            .and_then(Value::as_text)
+            .wrap_err("Missing attestation format")?;
+        let statement = map_get(&attestation_object, "attStmt")
+            .and_then(Value::as_map)
+            .wrap_err("Missing attestation statement")?;
+        let raw_authenticator_data = map_get(&attestation_object, "authData")
+            .and_then(Value::as_bytes)
+            .wrap_err("Missing authenticator data")?;
+
+        let authenticator_data = AuthenticatorData::parse(raw_authenticator_data)?;
+        authenticator_data.verify(rp_id, false)?;
+        let (credential_id, public_key) = authenticator_data
+            .attested_credential
+            .wrap_err("Missing attested credential data")?;
+        if decode_base64url(&self.id)? != credential_id {
+            return Err(eyre!("Credential ID mismatch"));
+        }
+
+        match format {
+            "none" if statement.is_empty() => {}
+            // Self attestation: signed with the new credential's own key
+            "packed" => {
+                let statement = Value::Map(statement.clone());
+                let algorithm = map_get(&statement, "alg")
+                    .and_then(Value::as_integer)
+                    .map(i128::from)
+                    .wrap_err("Missing attestation algorithm")?;
+                let signature = map_get(&statement, "sig")
+                    .and_then(Value::as_bytes)
+                    .wrap_err("Missing attestation signature")?;
+                if map_get(&statement, "x5c").is_some() {
+                    return Err(eyre!("Unsupported attestation"));
+                }
+                if algorithm != public_key.algorithm().into() {
+                    return Err(eyre!("Attestation algorithm mismatch"));
+                }
+                public_key
+                    .verify(
+                        &signed_data(raw_authenticator_data, &client_data_json),
+                        signature,
+                    )
+                    .wrap_err("Invalid attestation signature")?;
+            }
+            _ => return Err(eyre!("Unsupported attestation")),
+        }
+
+        Ok(WebauthnCredential {
+            credential_id,
+            email,
+            public_key,
+            sign_count: authenticator_data.sign_count,
+            name,
+        })
+    }
+}
+
+/// Result of `navigator.credentials.get()`, as serialized by
+/// `PublicKeyCredential.toJSON()`. Binary values are base64url encoded.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuthenticationCredential {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub credential_type: String,
+    pub response: AssertionResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AssertionResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+    pub user_handle: Option<String>,
+}
+
+impl AuthenticationCredential {
+    pub fn credential_id(&self) -> Result<Vec<u8>> {
+        decode_base64url(&self.id)
+    }
+
+    /// The challenge the browser signed, to look up the ceremony with
+    pub fn challenge(&self) -> Result<WebauthnChallenge> {
+        client_data_challenge(&self.response.client_data_json)
+    }
+
+    /// Checks the response to the authentication ceremony started with
+    /// `challenge` (WebAuthn section 7.2) and returns the new signature
+    /// counter. `require_user_verification` is set when the passkey is the
+    /// only factor, so that it proves both possession and a PIN or biometric.
+    pub fn verify(
+        &self,
+        credential: &WebauthnCredential,
+        challenge: &WebauthnChallenge,
+        rp_id: &str,
+        origin: &str,
+        require_user_verification: bool,
+    ) -> Result<u32> {
+        if self.credential_type != PUBLIC_KEY_CREDENTIAL_TYPE {
+            return Err(eyre!("Invalid credential type"));
+        }
+        if self.credential_id()? != credential.credential_id {
+            return Err(eyre!("Credential ID mismatch"));
+        }
+        let client_data_json = decode_base64url(&self.response.client_data_json)?;
+        verify_client_data(&client_data_json, "webauthn.get", challenge, origin)?;
+
+        let raw_authenticator_data = decode_base64url(&self.response.authenticator_data)?;
+        let authenticator_data = AuthenticatorData::parse(&raw_authenticator_data)?;
+        authenticator_data.verify(rp_id, require_user_verification)?;
+
+        let signature = decode_base64url(&self.response.signature)?;
+        credential.public_key.verify(
+            &signed_data(&raw_authenticator_data, &client_data_json),
+            &signature,
+        )?;
+
+        // A counter that doesn't increase hints at a cloned authenticator.
+        // Authenticators without a counter always report zero.
+        let sign_count = authenticator_data.sign_count;
+        if (sign_count != 0 || credential.sign_count != 0) && sign_count <= credential.sign_count {
+            return Err(eyre!("Signature counter did not increase"));
+        }
+
+        Ok(sign_count)
+    }
+}
+
+// The authenticator data layout of WebAuthn section 6.1
+struct AuthenticatorData {
+    rp_id_hash: Vec<u8>,
+    flags: u8,
+    sign_count: u32,
+    attested_credential: Option<(Vec<u8>, CredentialPublicKey)>,
+}
+
+impl AuthenticatorData {
+    fn parse(data: &[u8]) -> Result<Self> {
+        if data.len() < 37 {
+            return Err(eyre!("Authenticator data is too short"));
+        }
+        let flags = data[32];
+        let sign_count = u32::from_be_bytes(data[33..37].try_into()?);
+
+        let attested_credential = if flags & FLAG_ATTESTED_CREDENTIAL_DATA != 0 {
+            // The AAGUID (16 bytes) is followed by the length-prefixed credential ID
+            let length_offset = 37 + 16;
+            let length = data
+                .get(length_offset..length_offset + 2)
+                .wrap_err("Attested credential data is too short")?;
+            let id_offset = length_offset + 2;
+            let key_offset = id_offset + u16::from_be_bytes(length.try_into()?) as usize;
+            let credential_id = data
+                .get(id_offset..key_offset)
+                .wrap_err("Attested credential data is too short")?
+                .to_vec();
+            let public_key: Value =
+                ciborium::from_reader(&data[key_offset..]).wrap_err("Invalid COSE key")?;
+            Some((credential_id, CredentialPublicKey::from_cose(&public_key)?))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            rp_id_hash: data[..32].to_vec(),
+            flags,
+            sign_count,
+            attested_credential,
+        })
+    }
+
+    fn verify(&self, rp_id: &str, require_user_verification: bool) -> Result<()> {
+        if self.rp_id_hash != digest(&SHA256, rp_id.as_bytes()).as_ref() {
+            return Err(eyre!("Relying party ID mismatch"));
+        }
+        if self.flags & FLAG_USER_PRESENT == 0 {
+            return Err(eyre!("User not present"));
+        }
+        if require_user_verification && self.flags & FLAG_USER_VERIFIED == 0 {
+            return Err(eyre!("User not verified"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct CollectedClientData {
+    #[serde(rename = "type")]
+    ceremony_type: String,
+    challenge: String,
+    origin: String,
+}
+
+fn parse_client_data(client_data_json: &[u8]) -> Result<CollectedClientData> {
+    serde_json::from_slice(client_data_json).wrap_err("Invalid client data")
+}
+
+fn client_data_challenge(client_data_json: &str) -> Result<WebauthnChallenge> {
+    let client_data = parse_client_data(&decode_base64url(client_data_json)?)?;
+    WebauthnChallenge::parse(client_data.challenge)
+}
+
+fn verify_client_data(
+    client_data_json: &[u8],
+    ceremony_type: &str,
+    challenge: &WebauthnChallenge,
+    origin: &str,
+) -> Result<()> {
+    let client_data = parse_client_data(client_data_json)?;
+    if client_data.ceremony_type != ceremony_type {
+        return Err(eyre!("Invalid ceremony type"));
+    }
+    if client_data.challenge != *challenge.as_ref().expose_secret() {
+        return Err(eyre!("Challenge mismatch"));
+    }
+    if client_data.origin != origin {
+        return Err(eyre!("Origin mismatch"));
+    }
+    Ok(())
+}
+
+// Authenticators sign their data followed by the hash of the client data
+fn signed_data(authenticator_data: &[u8], client_data_json: &[u8]) -> Vec<u8> {
+    let mut data = authenticator_data.to_vec();
+    data.extend_from_slice(digest(&SHA256, client_data_json).as_ref());
+    data
+}
+
+fn map_get<'a>(map: &'a Value, key: &str) -> Option<&'a Value> {
+    map.as_map()?
+        .iter()
+        .find(|(k, _)| k.as_text() == Some(key))
+        .map(|(_, value)| value)
+}
+
+// Some clients keep the padding, which the WebAuthn JSON encoding omits
+fn decode_base64url(value: &str) -> Result<Vec<u8>> {
+    URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .wrap_err("Invalid base64url value")
+}
+
+#[cfg(test)]
+mod tests {
+    use ring::{
+        rand::SystemRandom,
+        signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING},
+    };
+    use secrecy::Secret;
+    use serde_json::json;
+
+    use super::*;
+
+    const RP_ID: &str = "localhost";
+    const ORIGIN: &str = "http://localhost:3000";
+
+    // Just enough of an authenticator to produce valid responses
+    struct Authenticator {
+        key_pair: EcdsaKeyPair,
+        credential_id: Vec<u8>,
+        sign_count: u32,
+    }
+
+    impl Authenticator {
+        fn new() -> Self {
+            let rng = SystemRandom::new();
+            let pkcs8 =
+                EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+            let key_pair =
+                EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng)
+                    .unwrap();
+            Self {
+                key_pair,
+                credential_id: vec![7; 16],
+                sign_count: 0,
+            }
+        }
+
+        fn client_data(ceremony_type: &str, challenge: &WebauthnChallenge) -> Vec<u8> {
+            json!({
+                "type": ceremony_type,
+                "challenge": challenge.as_ref().expose_secret(),
+                "origin": ORIGIN,
+            })
+            .to_string()
+            .into_bytes()
+        }
+
+        fn authenticator_data(&self, flags: u8) -> Vec<u8> {
+            let mut data = digest(&SHA256, RP_ID.as_bytes()).as_ref().to_vec();
+            data.push(flags);
+            data.extend_from_slice(&self.sign_count.to_be_bytes());
+            data
+        }
+
+        fn register(&self, challenge: &WebauthnChallenge) -> RegistrationCredential {
+            let point = self.key_pair.public_key().as_ref();
+            let cose_key = Value::Map(vec![
+                (1.into(), 2.into()),
+                (3.into(), COSE_ALGORITHM_ES256.into()),
+                ((-1).into(), 1.into()),
+                ((-2).into(), Value::Bytes(point[1..33].to_vec())),
+                ((-3).into(), Value::Bytes(point[33..].to_vec())),
+            ]);
+            let mut authenticator_data =
+                self.authenticator_data(FLAG_USER_PRESENT | FLAG_ATTESTED_CREDENTIAL_DATA);
+            authenticator_data.extend_from_slice(&[0; 16]);
+            authenticator_data.extend_from_slice(&(self.credential_id.len() as u16).to_be_bytes());
+            authenticator_data.extend_from_slice(&self.credential_id);
+            ciborium::into_writer(&cose_key, &mut authenticator_data).unwrap();
+
+            let attestation_object = Value::Map(vec![
+                ("fmt".into(), "none".into()),
+                ("attStmt".into(), Value::Map(vec![])),
+                ("authData".into(), Value::Bytes(authenticator_data)),
+            ]);
+            let mut encoded_attestation_object = Vec::new();
+            ciborium::into_writer(&attestation_object, &mut encoded_attestation_object).unwrap();
+
+            RegistrationCredential {
+                id: URL_SAFE_NO_PAD.encode(&self.credential_id),
+                credential_type: PUBLIC_KEY_CREDENTIAL_TYPE.to_owned(),
+                response: AttestationResponse {
+                    client_data_json: URL_SAFE_NO_PAD
+                        .encode(Self::client_data("webauthn.create", challenge)),
+                    attestation_object: URL_SAFE_NO_PAD.encode(encoded_attestation_object),
+                },
+            }
+        }
+
+        fn authenticate(
+            &mut self,
+            challenge: &WebauthnChallenge,
+            flags: u8,
+        ) -> AuthenticationCredential {
+            self.sign_count += 1;
+            let authenticator_data = self.authenticator_data(flags);
+            let client_data = Self::client_data("webauthn.get", challenge);
+            let signature = self
+                .key_pair
+                .sign(
+                    &SystemRandom::new(),
+                    &signed_data(&authenticator_data, &client_data),
+                )
+                .unwrap();
+
+            AuthenticationCredential {
+                id: URL_SAFE_NO_PAD.encode(&self.credential_id),
+                credential_type: PUBLIC_KEY_CREDENTIAL_TYPE.to_owned(),
+                response: AssertionResponse {
+                    client_data_json: URL_SAFE_NO_PAD.encode(client_data),
+                    authenticator_data: URL_SAFE_NO_PAD.encode(authenticator_data),
+                    signature: URL_SAFE_NO_PAD.encode(signature.as_ref()),
+                    user_handle: None,
+                },
+            }
+        }
+    }
+
+    fn email() -> Email {
+        Email::parse(Secret::new("test@example.com".to_owned())).unwrap()
+    }
+
+    fn register(authenticator: &Authenticator) -> WebauthnCredential {
+        let challenge = WebauthnChallenge::generate_random();
+        authenticator
+            .register(&challenge)
+            .verify(&challenge, RP_ID, ORIGIN, email(), "Laptop".to_owned())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_verify_registration() {
+        let authenticator = Authenticator::new();
+        let challenge = WebauthnChallenge::generate_random();
+        let response = authenticator.register(&challenge);
+        assert_eq!(response.challenge().unwrap(), challenge);
+
+        let credential = response
+            .verify(&challenge, RP_ID, ORIGIN, email(), "Laptop".to_owned())
+            .unwrap();
+        assert_eq!(credential.credential_id, authenticator.credential_id);
+        assert_eq!(
+            credential.public_key,
+            CredentialPublicKey::Es256(authenticator.key_pair.public_key().as_ref().to_vec())
+        );
+        assert_eq!(credential.sign_count, 0);
+
+        let other_challenge = WebauthnChallenge::generate_random();
+        let verify = |challenge, rp_id, origin| {
+            response.verify(challenge, rp_id, origin, email(), "Laptop".to_owned())
+        };
+        assert!(verify(&other_challenge, RP_ID, ORIGIN).is_err());
+        assert!(verify(&challenge, "example.com", ORIGIN).is_err());
+        assert!(verify(&challenge, RP_ID, "http://evil.example").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verify_authentication() {
+        let mut authenticator = Authenticator::new();
+        let mut credential = register(&authenticator);
+
+        let challenge = WebauthnChallenge::generate_random();
+        let response = authenticator.authenticate(&challenge, FLAG_USER_PRESENT);
+        assert_eq!(response.credential_id().unwrap(), credential.credential_id);
+        assert_eq!(
+            response
+                .verify(&credential, &challenge, RP_ID, ORIGIN, false)
+                .unwrap(),
+            1
+        );
+
+        // The passkey alone isn't enough without user verification
+        assert!(response
+            .verify(&credential, &challenge, RP_ID, ORIGIN, true)
+            .is_err());
+        assert!(response
+            .verify(
+                &credential,
+                &WebauthnChallenge::generate_random(),
+                RP_ID,
+                ORIGIN,
+                false
+            )
+            .is_err());
+
+        // Replaying an older counter value
+        credential.sign_count = 1;
+        assert!(response
+            .verify(&credential, &challenge, RP_ID, ORIGIN, false)
+            .is_err());
+
+        let response =
+            authenticator.authenticate(&challenge, FLAG_USER_PRESENT | FLAG_USER_VERIFIED);
+        assert_eq!(
+            response
+                .verify(&credential, &challenge, RP_ID, ORIGIN, true)
+                .unwrap(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_authentication_with_wrong_key() {
+        let mut authenticator = Authenticator::new();
+        let credential = register(&Authenticator::new());
+
+        let challenge = WebauthnChallenge::generate_random();
+        let response = authenticator.authenticate(&challenge, FLAG_USER_PRESENT);
+        assert!(response
+            .verify(&credential, &challenge, RP_ID, ORIGIN, false)
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_public_key_from_parts() {
+        let credential = register(&Authenticator::new());
+        let public_key = &credential.public_key;
+        assert_eq!(
+            CredentialPublicKey::from_parts(public_key.algorithm(), public_key.as_bytes().to_vec())
+                .unwrap(),
+            *public_key
+        );
+        assert!(CredentialPublicKey::from_parts(COSE_ALGORITHM_EDDSA, vec![0; 31]).is_err());
+        assert!(CredentialPublicKey::from_parts(-257, vec![0; 32]).is_err());
+    }
+}
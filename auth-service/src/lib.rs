@@ -1,9 +1,11 @@
-use std::error::Error;
+use std::{error::Error, net::SocketAddr};
 
 use axum::{
-    http::{Method, StatusCode},
+    extract::{connect_info::IntoMakeServiceWithConnectInfo, ConnectInfo},
+    http::{header::RETRY_AFTER, HeaderValue, Method, StatusCode},
+    middleware::{self, AddExtension},
     response::{IntoResponse, Response},
-    routing::post,
+    routing::{delete, get, post},
     serve::Serve,
     Json, Router,
 };
@@ -12,15 +14,28 @@ use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
 use sqlx::{postgres::PgPoolOptions, PgPool};
 use tower_http::{cors::CorsLayer, services::ServeDir, trace::TraceLayer};
-use tracing;
 
 pub mod domain;
 pub mod routes;
 pub mod services;
 pub mod utils;
 
-use domain::AuthApiError;
-use routes::{login, logout, signup, verify_2fa, verify_token};
+use domain::{AuthApiError, OAuthError};
+use routes::{
+    add_organization_member, assign_role, authorize, change_email, change_password,
+    confirm_email_change, confirm_password_reset, confirm_totp, create_api_key,
+    create_organization, create_role, delete_account, delete_organization, delete_role,
+    delete_user, disable_2fa, enable_2fa, enroll_totp, export_account, finish_webauthn_login,
+    finish_webauthn_registration, grant_admin, introspect, jwks, list_account_organizations,
+    list_api_keys, list_logins, list_organization_members, list_organizations, list_roles,
+    list_sessions, list_user_roles, list_users, lock_user, login, login_with_magic_link, logout,
+    openid_configuration, refresh_token, regenerate_recovery_codes, remove_organization_member,
+    request_magic_link, request_password_reset, require_password_reset, require_user_2fa,
+    resend_verification_email, revoke, revoke_admin, revoke_all_sessions, revoke_api_key,
+    revoke_session, revoke_session_from_link, revoke_user_sessions, send_2fa_code, signup,
+    start_webauthn_login, start_webauthn_registration, token, unassign_role, unlock_user, userinfo,
+    verify_2fa, verify_email, verify_token,
+};
 
 #[derive(Serialize, Deserialize)]
 pub struct ErrorResponse {
@@ -31,8 +46,29 @@ impl IntoResponse for AuthApiError {
     fn into_response(self) -> Response {
         log_error_chain(&self);
 
+        let retry_after = match self {
+            AuthApiError::TooManyRequests(seconds) => Some(seconds),
+            _ => None,
+        };
         let (status, error_message) = match self {
+            AuthApiError::AccountLocked => (StatusCode::FORBIDDEN, "Account locked"),
+            AuthApiError::ApiKeyNotFound => (StatusCode::NOT_FOUND, "API key not found"),
+            AuthApiError::EmailNotVerified => (StatusCode::FORBIDDEN, "Email not verified"),
+            AuthApiError::Forbidden => (StatusCode::FORBIDDEN, "Forbidden"),
+            AuthApiError::PasswordResetRequired => {
+                (StatusCode::FORBIDDEN, "Password reset required")
+            }
+            AuthApiError::OrganizationAlreadyExists => {
+                (StatusCode::CONFLICT, "Organization already exists")
+            }
+            AuthApiError::OrganizationNotFound => (StatusCode::NOT_FOUND, "Organization not found"),
+            AuthApiError::MembershipNotFound => (StatusCode::NOT_FOUND, "Membership not found"),
+            AuthApiError::RoleAlreadyExists => (StatusCode::CONFLICT, "Role already exists"),
+            AuthApiError::RoleNotFound => (StatusCode::NOT_FOUND, "Role not found"),
             AuthApiError::UserAlreadyExists => (StatusCode::CONFLICT, "User already exists"),
+            AuthApiError::UserNotFound => (StatusCode::NOT_FOUND, "User not found"),
+            AuthApiError::TwoFAAlreadyEnabled => (StatusCode::CONFLICT, "2FA already enabled"),
+            AuthApiError::TwoFANotEnabled => (StatusCode::CONFLICT, "2FA not enabled"),
             AuthApiError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized"),
             AuthApiError::InvalidCredentials => (StatusCode::BAD_REQUEST, "Invalid credentials"),
             AuthApiError::IncorrectCredentials => {
@@ -40,6 +76,10 @@ impl IntoResponse for AuthApiError {
             }
             AuthApiError::InvalidToken => (StatusCode::UNAUTHORIZED, "Invalid token"),
             AuthApiError::MissingToken => (StatusCode::BAD_REQUEST, "Missing token"),
+            AuthApiError::SessionNotFound => (StatusCode::NOT_FOUND, "Session not found"),
+            AuthApiError::TooManyRequests(_) => {
+                (StatusCode::TOO_MANY_REQUESTS, "Too many requests")
+            }
             AuthApiError::UnexpectedError(_) => {
                 (StatusCode::INTERNAL_SERVER_ERROR, "Unexpected error")
             }
@@ -47,6 +87,28 @@ impl IntoResponse for AuthApiError {
         let body = Json(ErrorResponse {
             error: error_message.to_string(),
         });
+        let mut response = (status, body).into_response();
+        if let Some(seconds) = retry_after {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(seconds));
+        }
+        response
+    }
+}
+
+impl IntoResponse for OAuthError {
+    fn into_response(self) -> Response {
+        log_error_chain(&self);
+
+        let status = match self {
+            OAuthError::InvalidClient | OAuthError::InvalidToken => StatusCode::UNAUTHORIZED,
+            OAuthError::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        let body = Json(ErrorResponse {
+            error: self.to_string(),
+        });
         (status, body).into_response()
     }
 }
@@ -69,43 +131,129 @@ pub mod app_state {
     use std::sync::Arc;
     use tokio::sync::RwLock;
 
-    use crate::domain::{BannedTokenStore, EmailClient, TwoFACodeStore, UserStore};
+    use crate::domain::{
+        ApiKeyStore, AuditSink, AuthorizationCodeStore, BannedTokenStore, EmailClient,
+        LoginHistoryStore, LoginThrottleStore, MagicLinkTokenStore, OAuthClientStore,
+        OrganizationStore, PasswordResetTokenStore, RateLimitStore, RecoveryCodeStore,
+        RefreshTokenStore, RoleStore, SessionStore, TokenVersionStore, TotpSecretStore,
+        TwoFACodeStore, UserStore, WebauthnChallengeStore, WebauthnCredentialStore,
+    };
 
     pub type UserStoreType = Arc<RwLock<dyn UserStore + Send + Sync>>;
     pub type BannedTokenStoreType = Arc<RwLock<dyn BannedTokenStore + Send + Sync>>;
+    pub type RefreshTokenStoreType = Arc<RwLock<dyn RefreshTokenStore + Send + Sync>>;
     pub type TwoFACodeStoreType = Arc<RwLock<dyn TwoFACodeStore + Send + Sync>>;
     pub type EmailClientType = Arc<dyn EmailClient + Send + Sync>;
+    pub type OAuthClientStoreType = Arc<RwLock<dyn OAuthClientStore + Send + Sync>>;
+    pub type AuthorizationCodeStoreType = Arc<RwLock<dyn AuthorizationCodeStore + Send + Sync>>;
+    pub type PasswordResetTokenStoreType = Arc<RwLock<dyn PasswordResetTokenStore + Send + Sync>>;
+    pub type TotpSecretStoreType = Arc<RwLock<dyn TotpSecretStore + Send + Sync>>;
+    pub type RecoveryCodeStoreType = Arc<RwLock<dyn RecoveryCodeStore + Send + Sync>>;
+    pub type WebauthnCredentialStoreType = Arc<RwLock<dyn WebauthnCredentialStore + Send + Sync>>;
+    pub type WebauthnChallengeStoreType = Arc<RwLock<dyn WebauthnChallengeStore + Send + Sync>>;
+    pub type MagicLinkTokenStoreType = Arc<RwLock<dyn MagicLinkTokenStore + Send + Sync>>;
+    pub type LoginThrottleStoreType = Arc<RwLock<dyn LoginThrottleStore + Send + Sync>>;
+    pub type RateLimitStoreType = Arc<RwLock<dyn RateLimitStore + Send + Sync>>;
+    pub type SessionStoreType = Arc<RwLock<dyn SessionStore + Send + Sync>>;
+    pub type TokenVersionStoreType = Arc<RwLock<dyn TokenVersionStore + Send + Sync>>;
+    pub type AuditSinkType = Arc<dyn AuditSink + Send + Sync>;
+    pub type LoginHistoryStoreType = Arc<RwLock<dyn LoginHistoryStore + Send + Sync>>;
+    pub type RoleStoreType = Arc<RwLock<dyn RoleStore + Send + Sync>>;
+    pub type OrganizationStoreType = Arc<RwLock<dyn OrganizationStore + Send + Sync>>;
+    pub type ApiKeyStoreType = Arc<RwLock<dyn ApiKeyStore + Send + Sync>>;
 
     #[derive(Clone)]
     pub struct AppState {
         pub user_store: UserStoreType,
         pub banned_token_store: BannedTokenStoreType,
+        pub refresh_token_store: RefreshTokenStoreType,
         pub two_fa_code_store: TwoFACodeStoreType,
         pub email_client: EmailClientType,
+        pub oauth_client_store: OAuthClientStoreType,
+        pub authorization_code_store: AuthorizationCodeStoreType,
+        pub password_reset_token_store: PasswordResetTokenStoreType,
+        pub totp_secret_store: TotpSecretStoreType,
+        pub recovery_code_store: RecoveryCodeStoreType,
+        pub webauthn_credential_store: WebauthnCredentialStoreType,
+        pub webauthn_challenge_store: WebauthnChallengeStoreType,
+        pub magic_link_token_store: MagicLinkTokenStoreType,
+        pub login_throttle_store: LoginThrottleStoreType,
+        pub rate_limit_store: RateLimitStoreType,
+        pub session_store: SessionStoreType,
+        pub token_version_store: TokenVersionStoreType,
+        pub audit_sink: AuditSinkType,
+        pub login_history_store: LoginHistoryStoreType,
+        pub role_store: RoleStoreType,
+        pub organization_store: OrganizationStoreType,
+        pub api_key_store: ApiKeyStoreType,
     }
 
     impl AppState {
+        #[allow(clippy::too_many_arguments)]
         pub fn new(
             user_store: UserStoreType,
             banned_token_store: BannedTokenStoreType,
+            refresh_token_store: RefreshTokenStoreType,
             two_fa_code_store: TwoFACodeStoreType,
             email_client: EmailClientType,
+            oauth_client_store: OAuthClientStoreType,
+            authorization_code_store: AuthorizationCodeStoreType,
+            password_reset_token_store: PasswordResetTokenStoreType,
+            totp_secret_store: TotpSecretStoreType,
+            recovery_code_store: RecoveryCodeStoreType,
+            webauthn_credential_store: WebauthnCredentialStoreType,
+            webauthn_challenge_store: WebauthnChallengeStoreType,
+            magic_link_token_store: MagicLinkTokenStoreType,
+            login_throttle_store: LoginThrottleStoreType,
+            rate_limit_store: RateLimitStoreType,
+            session_store: SessionStoreType,
+            token_version_store: TokenVersionStoreType,
+            audit_sink: AuditSinkType,
+            login_history_store: LoginHistoryStoreType,
+            role_store: RoleStoreType,
+            organization_store: OrganizationStoreType,
+            api_key_store: ApiKeyStoreType,
         ) -> Self {
             Self {
                 user_store,
                 banned_token_store,
+                refresh_token_store,
                 two_fa_code_store,
                 email_client,
+                oauth_client_store,
+                authorization_code_store,
+                password_reset_token_store,
+                totp_secret_store,
+                recovery_code_store,
+                webauthn_credential_store,
+                webauthn_challenge_store,
+                magic_link_token_store,
+                login_throttle_store,
+                rate_limit_store,
+                session_store,
+                token_version_store,
+                audit_sink,
+                login_history_store,
+                role_store,
+                organization_store,
+                api_key_store,
             }
         }
     }
 }
 use app_state::AppState;
 
-use crate::utils::tracing::{make_span_with_request_id, on_request, on_response};
+use crate::utils::{
+    admin::require_admin,
+    rate_limit::rate_limit,
+    tracing::{assign_request_id, make_span_with_request_id, on_request, on_response},
+};
 
 pub struct Application {
-    server: Serve<Router, Router>,
+    server: Serve<
+        IntoMakeServiceWithConnectInfo<Router, SocketAddr>,
+        AddExtension<Router, ConnectInfo<SocketAddr>>,
+    >,
     pub address: String,
 }
 
@@ -117,16 +265,105 @@ impl Application {
         ];
 
         let cors = CorsLayer::new()
-            .allow_methods([Method::GET, Method::POST])
+            .allow_methods([Method::GET, Method::POST, Method::DELETE])
             .allow_credentials(true)
             .allow_origin(allowed_origins);
 
+        // Every route of the admin API needs an admin's access token
+        let admin_router = Router::new()
+            .route(
+                "/organizations",
+                get(list_organizations).post(create_organization),
+            )
+            .route("/organizations/:id", delete(delete_organization))
+            .route(
+                "/organizations/:id/members",
+                get(list_organization_members).post(add_organization_member),
+            )
+            .route(
+                "/organizations/:id/members/:email",
+                delete(remove_organization_member),
+            )
+            .route("/roles", get(list_roles).post(create_role))
+            .route("/roles/:name", delete(delete_role))
+            .route("/users", get(list_users))
+            .route("/users/:email", delete(delete_user))
+            .route("/users/:email/2fa", post(require_user_2fa))
+            .route(
+                "/users/:email/admin",
+                post(grant_admin).delete(revoke_admin),
+            )
+            .route("/users/:email/lock", post(lock_user))
+            .route("/users/:email/password-reset", post(require_password_reset))
+            .route(
+                "/users/:email/roles",
+                get(list_user_roles).post(assign_role),
+            )
+            .route("/users/:email/roles/:role", delete(unassign_role))
+            .route("/users/:email/sessions", delete(revoke_user_sessions))
+            .route("/users/:email/unlock", post(unlock_user))
+            .route_layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                require_admin,
+            ));
+
         let router = Router::new()
+            .route("/2fa/code", post(send_2fa_code))
+            .route("/2fa/disable", post(disable_2fa))
+            .route("/2fa/enable", post(enable_2fa))
+            .route("/2fa/recovery-codes", post(regenerate_recovery_codes))
+            .route("/2fa/totp/confirm", post(confirm_totp))
+            .route("/2fa/totp/enroll", post(enroll_totp))
+            .route("/.well-known/jwks.json", get(jwks))
+            .route(
+                "/.well-known/openid-configuration",
+                get(openid_configuration),
+            )
+            .route("/account", delete(delete_account))
+            .route("/account/api-keys", get(list_api_keys).post(create_api_key))
+            .route("/account/api-keys/:id", delete(revoke_api_key))
+            .route("/account/email", post(change_email))
+            .route("/account/email/confirm", post(confirm_email_change))
+            .route("/account/export", get(export_account))
+            .route("/account/logins", get(list_logins))
+            .route("/account/organizations", get(list_account_organizations))
+            .route("/account/password", post(change_password))
+            .nest("/admin", admin_router)
+            .route("/authorize", get(authorize))
             .route("/login", post(login))
+            .route("/login/magic-link", post(request_magic_link))
+            .route("/login/magic-link/callback", post(login_with_magic_link))
             .route("/logout", post(logout))
+            .route("/oauth/introspect", post(introspect))
+            .route("/oauth/revoke", post(revoke))
+            .route("/password-reset/confirm", post(confirm_password_reset))
+            .route("/password-reset/request", post(request_password_reset))
+            .route("/sessions", get(list_sessions).delete(revoke_all_sessions))
+            .route("/sessions/:id", delete(revoke_session))
+            .route("/sessions/revoke", post(revoke_session_from_link))
             .route("/signup", post(signup))
+            .route("/token", post(token))
+            .route("/token/refresh", post(refresh_token))
+            .route("/userinfo", get(userinfo))
             .route("/verify-2fa", post(verify_2fa))
+            .route("/verify-email", post(verify_email))
+            .route("/verify-email/resend", post(resend_verification_email))
             .route("/verify-token", post(verify_token))
+            .route("/webauthn/login/finish", post(finish_webauthn_login))
+            .route("/webauthn/login/start", post(start_webauthn_login))
+            .route(
+                "/webauthn/register/finish",
+                post(finish_webauthn_registration),
+            )
+            .route(
+                "/webauthn/register/start",
+                post(start_webauthn_registration),
+            )
+            // Only matched routes are limited, not the static assets
+            .route_layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                rate_limit,
+            ))
             .nest_service("/", ServeDir::new("assets"))
             .with_state(app_state)
             .layer(cors)
@@ -135,11 +372,16 @@ impl Application {
                     .make_span_with(make_span_with_request_id)
                     .on_request(on_request)
                     .on_response(on_response),
-            );
+            )
+            .layer(middleware::from_fn(assign_request_id));
 
         let listener = tokio::net::TcpListener::bind(address).await?;
         let address = listener.local_addr()?.to_string();
-        let server = axum::serve(listener, router);
+        // Handlers see the client address, e.g. for login throttling
+        let server = axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        );
 
         Ok(Application { server, address })
     }
@@ -5,19 +5,43 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use auth_service::{
-    app_state::AppState,
+    app_state::{AppState, AuditSinkType},
     domain::Email,
     get_postgres_pool, get_redis_client,
     services::{
         data_stores::{
+            postgres_api_key_store::PostgresApiKeyStore,
+            postgres_login_history_store::PostgresLoginHistoryStore,
+            postgres_oauth_client_store::PostgresOAuthClientStore,
+            postgres_organization_store::PostgresOrganizationStore,
+            postgres_recovery_code_store::PostgresRecoveryCodeStore,
+            postgres_role_store::PostgresRoleStore,
+            postgres_token_version_store::PostgresTokenVersionStore,
+            postgres_totp_secret_store::PostgresTotpSecretStore,
             postgres_user_store::PostgresUserStore,
+            postgres_webauthn_credential_store::PostgresWebauthnCredentialStore,
+            redis_authorization_code_store::RedisAuthorizationCodeStore,
             redis_banned_token_store::RedisBannedTokenStore,
-            redis_two_fa_code_store::RedisTwoFACodeStore,
+            redis_login_throttle_store::RedisLoginThrottleStore,
+            redis_magic_link_token_store::RedisMagicLinkTokenStore,
+            redis_password_reset_token_store::RedisPasswordResetTokenStore,
+            redis_rate_limit_store::RedisRateLimitStore,
+            redis_refresh_token_store::RedisRefreshTokenStore,
+            redis_session_store::RedisSessionStore, redis_two_fa_code_store::RedisTwoFACodeStore,
+            redis_webauthn_challenge_store::RedisWebauthnChallengeStore,
         },
+        json_lines_audit_sink::JsonLinesAuditSink,
+        postgres_audit_sink::PostgresAuditSink,
         postmark_email_client::PostmarkEmailClient,
     },
     utils::{
-        constants::{prod, DATABASE_URL, REDIS_HOSTNAME},
+        account_deletion::{grace_period, spawn_account_purger},
+        constants::{
+            prod, ACCOUNT_DELETION_GRACE_PERIOD_DAYS, AUDIT_LOG_FILE, DATABASE_URL, REDIS_HOSTNAME,
+            TOTP_ENCRYPTION_KEY,
+        },
+        encryption::EncryptionKey,
+        keyring::KEYRING,
         tracing::init_tracing,
         POSTMARK_AUTH_TOKEN,
     },
@@ -28,21 +52,92 @@ use auth_service::{
 async fn main() {
     color_eyre::install().expect("Failed to install color_eyre");
     init_tracing().expect("Failed to initialize tracing");
+    // Fail on startup rather than on the first login if the keys can't be loaded
+    lazy_static::initialize(&KEYRING);
 
     let pg_pool = configure_postgres().await;
-    let user_store = Arc::new(RwLock::new(PostgresUserStore::new(pg_pool)));
+    let oauth_client_store = Arc::new(RwLock::new(PostgresOAuthClientStore::new(pg_pool.clone())));
+    let totp_encryption_key =
+        EncryptionKey::parse(&TOTP_ENCRYPTION_KEY).expect("Invalid TOTP_ENCRYPTION_KEY");
+    let user_store = Arc::new(RwLock::new(PostgresUserStore::new(
+        pg_pool.clone(),
+        totp_encryption_key.clone(),
+    )));
+    let recovery_code_store =
+        Arc::new(RwLock::new(PostgresRecoveryCodeStore::new(pg_pool.clone())));
+    let webauthn_credential_store = Arc::new(RwLock::new(PostgresWebauthnCredentialStore::new(
+        pg_pool.clone(),
+    )));
+    let totp_secret_store = Arc::new(RwLock::new(PostgresTotpSecretStore::new(
+        pg_pool.clone(),
+        totp_encryption_key,
+    )));
+
+    let audit_sink = configure_audit_sink(pg_pool.clone()).await;
+    let login_history_store =
+        Arc::new(RwLock::new(PostgresLoginHistoryStore::new(pg_pool.clone())));
+    let role_store = Arc::new(RwLock::new(PostgresRoleStore::new(pg_pool.clone())));
+    let organization_store = Arc::new(RwLock::new(PostgresOrganizationStore::new(pg_pool.clone())));
+    let api_key_store = Arc::new(RwLock::new(PostgresApiKeyStore::new(pg_pool.clone())));
 
     let redis_conn = Arc::new(RwLock::new(configure_redis()));
     let banned_token_store = Arc::new(RwLock::new(RedisBannedTokenStore::new(redis_conn.clone())));
+    let refresh_token_store =
+        Arc::new(RwLock::new(RedisRefreshTokenStore::new(redis_conn.clone())));
     let two_fa_code_store = Arc::new(RwLock::new(RedisTwoFACodeStore::new(redis_conn.clone())));
+    let authorization_code_store = Arc::new(RwLock::new(RedisAuthorizationCodeStore::new(
+        redis_conn.clone(),
+    )));
+    let password_reset_token_store = Arc::new(RwLock::new(RedisPasswordResetTokenStore::new(
+        redis_conn.clone(),
+    )));
+
+    let webauthn_challenge_store = Arc::new(RwLock::new(RedisWebauthnChallengeStore::new(
+        redis_conn.clone(),
+    )));
+    let magic_link_token_store = Arc::new(RwLock::new(RedisMagicLinkTokenStore::new(
+        redis_conn.clone(),
+    )));
+    let login_throttle_store = Arc::new(RwLock::new(RedisLoginThrottleStore::new(
+        redis_conn.clone(),
+    )));
+    let rate_limit_store = Arc::new(RwLock::new(RedisRateLimitStore::new(redis_conn.clone())));
+    let session_store = Arc::new(RwLock::new(RedisSessionStore::new(redis_conn.clone())));
+    let token_version_store = Arc::new(RwLock::new(PostgresTokenVersionStore::new(
+        pg_pool, redis_conn,
+    )));
 
     let email_client = Arc::new(configure_postmark_email_client());
 
+    spawn_account_purger(
+        user_store.clone(),
+        token_version_store.clone(),
+        grace_period(*ACCOUNT_DELETION_GRACE_PERIOD_DAYS),
+    );
+
     let app_state = AppState::new(
         user_store,
         banned_token_store,
+        refresh_token_store,
         two_fa_code_store,
         email_client,
+        oauth_client_store,
+        authorization_code_store,
+        password_reset_token_store,
+        totp_secret_store,
+        recovery_code_store,
+        webauthn_credential_store,
+        webauthn_challenge_store,
+        magic_link_token_store,
+        login_throttle_store,
+        rate_limit_store,
+        session_store,
+        token_version_store,
+        audit_sink,
+        login_history_store,
+        role_store,
+        organization_store,
+        api_key_store,
     );
 
     let app = Application::build(app_state, prod::APP_ADDRESS)
@@ -67,6 +162,17 @@ async fn configure_postgres() -> PgPool {
     pg_pool
 }
 
+async fn configure_audit_sink(pg_pool: PgPool) -> AuditSinkType {
+    match AUDIT_LOG_FILE.as_deref() {
+        Some(path) => Arc::new(
+            JsonLinesAuditSink::open(path)
+                .await
+                .expect("Failed to open AUDIT_LOG_FILE"),
+        ),
+        None => Arc::new(PostgresAuditSink::new(pg_pool)),
+    }
+}
+
 fn configure_redis() -> redis::Connection {
     get_redis_client(REDIS_HOSTNAME.to_owned())
         .expect("Failed to get Redis client")
@@ -0,0 +1,62 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_extra::extract::CookieJar;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuthApiError, Membership, OrganizationRole},
+    utils::auth::authenticate_user,
+};
+
+// List the organizations the user belongs to, which they can log in to by
+// passing one's ID as `tenant` to /login
+#[tracing::instrument(name = "List Account Organizations", skip_all)]
+pub async fn list_account_organizations(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let email = authenticate_user(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+
+    let organizations = state
+        .organization_store
+        .read()
+        .await
+        .get_memberships(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?
+        .into_iter()
+        .map(MembershipResponse::from)
+        .collect();
+
+    Ok((
+        StatusCode::OK,
+        Json(AccountOrganizationsResponse { organizations }),
+    ))
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct AccountOrganizationsResponse {
+    pub organizations: Vec<MembershipResponse>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct MembershipResponse {
+    /// The organization's ID
+    pub id: String,
+    pub role: OrganizationRole,
+}
+
+impl From<Membership> for MembershipResponse {
+    fn from(membership: Membership) -> Self {
+        Self {
+            id: membership.organization_id,
+            role: membership.role,
+        }
+    }
+}
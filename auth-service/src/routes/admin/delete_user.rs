@@ -0,0 +1,58 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension,
+};
+use secrecy::ExposeSecret;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError},
+    routes::admin::existing_user,
+    utils::{admin::AdminUser, audit::AuditContext, auth::revoke_all_user_tokens},
+};
+
+// Delete the user right away. Unlike a deletion by the user themselves there is
+// no grace period, so logging in can't bring the account back.
+#[tracing::instrument(name = "Admin Delete User", skip_all)]
+pub async fn delete_user(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Extension(admin): Extension<AdminUser>,
+    Path(email): Path<String>,
+) -> Result<StatusCode, AuthApiError> {
+    let email = existing_user(&state, email).await?;
+
+    // Sessions and refresh tokens are kept outside of the users table
+    revoke_all_user_tokens(
+        &email,
+        state.token_version_store.clone(),
+        state.refresh_token_store.clone(),
+        state.session_store.clone(),
+    )
+    .await
+    .map_err(AuthApiError::UnexpectedError)?;
+    state
+        .user_store
+        .write()
+        .await
+        .remove_user(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    // Otherwise the cached version would carry over to a new account with the
+    // same address
+    state
+        .token_version_store
+        .write()
+        .await
+        .remove_token_version(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    let event = AuditEvent::AdminUserDeleted {
+        admin: admin.email.as_ref().expose_secret().to_owned(),
+    };
+    audit.record(&state.audit_sink, Some(&email), event).await;
+
+    Ok(StatusCode::OK)
+}
@@ -0,0 +1,75 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension,
+};
+use secrecy::ExposeSecret;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError},
+    routes::admin::existing_user,
+    utils::{admin::AdminUser, audit::AuditContext, auth::revoke_all_user_tokens},
+};
+
+// Give the user the admin API. Their tokens carry the admin claim from their
+// next refresh or login.
+#[tracing::instrument(name = "Admin Grant Admin", skip_all)]
+pub async fn grant_admin(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Extension(admin): Extension<AdminUser>,
+    Path(email): Path<String>,
+) -> Result<StatusCode, AuthApiError> {
+    let email = existing_user(&state, email).await?;
+
+    state
+        .user_store
+        .write()
+        .await
+        .set_admin(&email, true)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    let event = AuditEvent::AdminGranted {
+        admin: admin.email.as_ref().expose_secret().to_owned(),
+    };
+    audit.record(&state.audit_sink, Some(&email), event).await;
+
+    Ok(StatusCode::OK)
+}
+
+// Take the admin API away from the user, ending their sessions since their
+// access tokens still carry the admin claim
+#[tracing::instrument(name = "Admin Revoke Admin", skip_all)]
+pub async fn revoke_admin(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Extension(admin): Extension<AdminUser>,
+    Path(email): Path<String>,
+) -> Result<StatusCode, AuthApiError> {
+    let email = existing_user(&state, email).await?;
+
+    state
+        .user_store
+        .write()
+        .await
+        .set_admin(&email, false)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    revoke_all_user_tokens(
+        &email,
+        state.token_version_store.clone(),
+        state.refresh_token_store.clone(),
+        state.session_store.clone(),
+    )
+    .await
+    .map_err(AuthApiError::UnexpectedError)?;
+
+    let event = AuditEvent::AdminRevoked {
+        admin: admin.email.as_ref().expose_secret().to_owned(),
+    };
+    audit.record(&state.audit_sink, Some(&email), event).await;
+
+    Ok(StatusCode::OK)
+}
@@ -0,0 +1,105 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, User},
+    utils::{admin::AdminUser, audit::AuditContext},
+};
+
+const DEFAULT_PER_PAGE: u64 = 50;
+const MAX_PER_PAGE: u64 = 100;
+
+// List the users a page at a time, optionally only those whose email contains `search`
+#[tracing::instrument(name = "Admin List Users", skip_all)]
+pub async fn list_users(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Extension(admin): Extension<AdminUser>,
+    Query(query): Query<ListUsersQuery>,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let search = query.search.filter(|search| !search.is_empty());
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query
+        .per_page
+        .unwrap_or(DEFAULT_PER_PAGE)
+        .clamp(1, MAX_PER_PAGE);
+
+    let result = state
+        .user_store
+        .read()
+        .await
+        .search_users(search.as_deref(), (page - 1) * per_page, per_page)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    let event = AuditEvent::AdminUsersListed { search };
+    audit
+        .record(&state.audit_sink, Some(&admin.email), event)
+        .await;
+
+    let response = ListUsersResponse {
+        users: result
+            .users
+            .into_iter()
+            .map(AdminUserResponse::from)
+            .collect(),
+        page,
+        per_page,
+        total: result.total,
+    };
+    Ok((StatusCode::OK, Json(response)))
+}
+
+#[derive(Deserialize)]
+pub struct ListUsersQuery {
+    pub search: Option<String>,
+    /// Starts at 1
+    pub page: Option<u64>,
+    #[serde(rename = "perPage")]
+    pub per_page: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct ListUsersResponse {
+    pub users: Vec<AdminUserResponse>,
+    pub page: u64,
+    #[serde(rename = "perPage")]
+    pub per_page: u64,
+    /// How many users match the search across all pages
+    pub total: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct AdminUserResponse {
+    pub email: String,
+    pub verified: bool,
+    #[serde(rename = "requires2FA")]
+    pub requires_2fa: bool,
+    #[serde(rename = "pendingDeletion")]
+    pub pending_deletion: bool,
+    pub admin: bool,
+    pub locked: bool,
+    #[serde(rename = "passwordResetRequired")]
+    pub password_reset_required: bool,
+}
+
+impl From<User> for AdminUserResponse {
+    fn from(user: User) -> Self {
+        Self {
+            email: user.email.as_ref().expose_secret().to_owned(),
+            verified: user.verified,
+            requires_2fa: user.requires_2fa,
+            pending_deletion: user.pending_deletion,
+            admin: user.admin,
+            locked: user.locked,
+            password_reset_required: user.password_reset_required,
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension,
+};
+use secrecy::ExposeSecret;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError},
+    routes::admin::existing_user,
+    utils::{admin::AdminUser, audit::AuditContext, auth::revoke_all_user_tokens},
+};
+
+// Keep the user from logging in until they are unlocked, ending their sessions
+#[tracing::instrument(name = "Admin Lock User", skip_all)]
+pub async fn lock_user(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Extension(admin): Extension<AdminUser>,
+    Path(email): Path<String>,
+) -> Result<StatusCode, AuthApiError> {
+    let email = existing_user(&state, email).await?;
+
+    state
+        .user_store
+        .write()
+        .await
+        .set_locked(&email, true)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    revoke_all_user_tokens(
+        &email,
+        state.token_version_store.clone(),
+        state.refresh_token_store.clone(),
+        state.session_store.clone(),
+    )
+    .await
+    .map_err(AuthApiError::UnexpectedError)?;
+
+    let event = AuditEvent::AdminUserLocked {
+        admin: admin.email.as_ref().expose_secret().to_owned(),
+    };
+    audit.record(&state.audit_sink, Some(&email), event).await;
+
+    Ok(StatusCode::OK)
+}
+
+#[tracing::instrument(name = "Admin Unlock User", skip_all)]
+pub async fn unlock_user(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Extension(admin): Extension<AdminUser>,
+    Path(email): Path<String>,
+) -> Result<StatusCode, AuthApiError> {
+    let email = existing_user(&state, email).await?;
+
+    state
+        .user_store
+        .write()
+        .await
+        .set_locked(&email, false)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    let event = AuditEvent::AdminUserUnlocked {
+        admin: admin.email.as_ref().expose_secret().to_owned(),
+    };
+    audit.record(&state.audit_sink, Some(&email), event).await;
+
+    Ok(StatusCode::OK)
+}
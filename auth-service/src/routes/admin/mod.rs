@@ -0,0 +1,40 @@
+use secrecy::Secret;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuthApiError, Email},
+};
+
+mod delete_user;
+mod grant_admin;
+mod list_users;
+mod lock_user;
+mod organization_members;
+mod organizations;
+mod require_2fa;
+mod require_password_reset;
+mod revoke_user_sessions;
+mod roles;
+mod user_roles;
+
+pub use delete_user::*;
+pub use grant_admin::*;
+pub use list_users::*;
+pub use lock_user::*;
+pub use organization_members::*;
+pub use organizations::*;
+pub use require_2fa::*;
+pub use require_password_reset::*;
+pub use revoke_user_sessions::*;
+pub use roles::*;
+pub use user_roles::*;
+
+/// Parses the email address in an admin route's path, checking that it belongs
+/// to an account
+pub(crate) async fn existing_user(state: &AppState, email: String) -> Result<Email, AuthApiError> {
+    let email = Email::parse(Secret::new(email)).map_err(|_| AuthApiError::UserNotFound)?;
+    if !state.user_store.read().await.user_exists(&email).await {
+        return Err(AuthApiError::UserNotFound);
+    }
+    Ok(email)
+}
@@ -0,0 +1,135 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, Membership, OrganizationRole, OrganizationStoreError},
+    routes::admin::existing_user,
+    utils::{admin::AdminUser, audit::AuditContext},
+};
+
+#[tracing::instrument(name = "Admin List Organization Members", skip_all)]
+pub async fn list_organization_members(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let members = state
+        .organization_store
+        .read()
+        .await
+        .get_members(&id)
+        .await
+        .map_err(|e| match e {
+            OrganizationStoreError::OrganizationNotFound => AuthApiError::OrganizationNotFound,
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?
+        .into_iter()
+        .map(OrganizationMemberResponse::from)
+        .collect();
+
+    Ok((
+        StatusCode::OK,
+        Json(ListOrganizationMembersResponse { members }),
+    ))
+}
+
+// Add a user to an organization, or change their role in it
+#[tracing::instrument(name = "Admin Add Organization Member", skip_all)]
+pub async fn add_organization_member(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Extension(admin): Extension<AdminUser>,
+    Path(id): Path<String>,
+    Json(request): Json<AddOrganizationMemberRequest>,
+) -> Result<StatusCode, AuthApiError> {
+    let email = existing_user(&state, request.email).await?;
+
+    let membership = Membership {
+        organization_id: id.clone(),
+        email: email.clone(),
+        role: request.role,
+    };
+    state
+        .organization_store
+        .write()
+        .await
+        .add_member(membership)
+        .await
+        .map_err(|e| match e {
+            OrganizationStoreError::OrganizationNotFound => AuthApiError::OrganizationNotFound,
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?;
+
+    let event = AuditEvent::AdminOrganizationMemberAdded {
+        admin: admin.email.as_ref().expose_secret().to_owned(),
+        organization: id,
+        role: request.role,
+    };
+    audit.record(&state.audit_sink, Some(&email), event).await;
+
+    Ok(StatusCode::OK)
+}
+
+// Remove a user from an organization. Their sessions logged in to it can't be
+// refreshed afterwards.
+#[tracing::instrument(name = "Admin Remove Organization Member", skip_all)]
+pub async fn remove_organization_member(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Extension(admin): Extension<AdminUser>,
+    Path((id, email)): Path<(String, String)>,
+) -> Result<StatusCode, AuthApiError> {
+    let email = existing_user(&state, email).await?;
+
+    state
+        .organization_store
+        .write()
+        .await
+        .remove_member(&id, &email)
+        .await
+        .map_err(|e| match e {
+            OrganizationStoreError::OrganizationNotFound => AuthApiError::OrganizationNotFound,
+            OrganizationStoreError::MembershipNotFound => AuthApiError::MembershipNotFound,
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?;
+
+    let event = AuditEvent::AdminOrganizationMemberRemoved {
+        admin: admin.email.as_ref().expose_secret().to_owned(),
+        organization: id,
+    };
+    audit.record(&state.audit_sink, Some(&email), event).await;
+
+    Ok(StatusCode::OK)
+}
+
+#[derive(Deserialize)]
+pub struct AddOrganizationMemberRequest {
+    pub email: String,
+    pub role: OrganizationRole,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct ListOrganizationMembersResponse {
+    pub members: Vec<OrganizationMemberResponse>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct OrganizationMemberResponse {
+    pub email: String,
+    pub role: OrganizationRole,
+}
+
+impl From<Membership> for OrganizationMemberResponse {
+    fn from(membership: Membership) -> Self {
+        Self {
+            email: membership.email.as_ref().expose_secret().to_owned(),
+            role: membership.role,
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, Organization, OrganizationStoreError},
+    utils::{admin::AdminUser, audit::AuditContext},
+};
+
+#[tracing::instrument(name = "Admin List Organizations", skip_all)]
+pub async fn list_organizations(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let organizations = state
+        .organization_store
+        .read()
+        .await
+        .get_organizations()
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?
+        .into_iter()
+        .map(OrganizationResponse::from)
+        .collect();
+
+    Ok((
+        StatusCode::OK,
+        Json(ListOrganizationsResponse { organizations }),
+    ))
+}
+
+#[tracing::instrument(name = "Admin Create Organization", skip_all)]
+pub async fn create_organization(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Extension(admin): Extension<AdminUser>,
+    Json(request): Json<CreateOrganizationRequest>,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let organization = Organization::parse(request.id, request.name)
+        .map_err(|_| AuthApiError::InvalidCredentials)?;
+
+    state
+        .organization_store
+        .write()
+        .await
+        .add_organization(organization.clone())
+        .await
+        .map_err(|e| match e {
+            OrganizationStoreError::OrganizationAlreadyExists => {
+                AuthApiError::OrganizationAlreadyExists
+            }
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?;
+
+    let event = AuditEvent::AdminOrganizationCreated {
+        organization: organization.id.clone(),
+    };
+    audit
+        .record(&state.audit_sink, Some(&admin.email), event)
+        .await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(OrganizationResponse::from(organization)),
+    ))
+}
+
+// Delete an organization with its memberships. Sessions logged in to it can't
+// be refreshed afterwards.
+#[tracing::instrument(name = "Admin Delete Organization", skip_all)]
+pub async fn delete_organization(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Extension(admin): Extension<AdminUser>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AuthApiError> {
+    state
+        .organization_store
+        .write()
+        .await
+        .delete_organization(&id)
+        .await
+        .map_err(|e| match e {
+            OrganizationStoreError::OrganizationNotFound => AuthApiError::OrganizationNotFound,
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?;
+
+    let event = AuditEvent::AdminOrganizationDeleted { organization: id };
+    audit
+        .record(&state.audit_sink, Some(&admin.email), event)
+        .await;
+
+    Ok(StatusCode::OK)
+}
+
+#[derive(Deserialize)]
+pub struct CreateOrganizationRequest {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct ListOrganizationsResponse {
+    pub organizations: Vec<OrganizationResponse>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct OrganizationResponse {
+    pub id: String,
+    pub name: String,
+}
+
+impl From<Organization> for OrganizationResponse {
+    fn from(organization: Organization) -> Self {
+        Self {
+            id: organization.id,
+            name: organization.name,
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension,
+};
+use secrecy::ExposeSecret;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError},
+    routes::{admin::existing_user, confirmed_totp_secret, send_2fa_change_notice},
+    utils::{admin::AdminUser, audit::AuditContext, auth::revoke_all_user_tokens},
+};
+
+// Turn on emailed 2FA codes for a user who has no second factor, ending the
+// sessions they started without one
+#[tracing::instrument(name = "Admin Require 2FA", skip_all)]
+pub async fn require_user_2fa(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Extension(admin): Extension<AdminUser>,
+    Path(email): Path<String>,
+) -> Result<StatusCode, AuthApiError> {
+    let email = existing_user(&state, email).await?;
+
+    let user = state
+        .user_store
+        .read()
+        .await
+        .get_user(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    if user.requires_2fa || confirmed_totp_secret(&state, &email).await?.is_some() {
+        return Err(AuthApiError::TwoFAAlreadyEnabled);
+    }
+
+    state
+        .user_store
+        .write()
+        .await
+        .set_requires_2fa(&email, true)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    revoke_all_user_tokens(
+        &email,
+        state.token_version_store.clone(),
+        state.refresh_token_store.clone(),
+        state.session_store.clone(),
+    )
+    .await
+    .map_err(AuthApiError::UnexpectedError)?;
+
+    send_2fa_change_notice(&state, &email, true).await;
+    let event = AuditEvent::Admin2FARequired {
+        admin: admin.email.as_ref().expose_secret().to_owned(),
+    };
+    audit.record(&state.audit_sink, Some(&email), event).await;
+
+    Ok(StatusCode::OK)
+}
@@ -0,0 +1,55 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension,
+};
+use secrecy::ExposeSecret;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError},
+    routes::{admin::existing_user, send_password_reset_link},
+    utils::{admin::AdminUser, audit::AuditContext, auth::revoke_all_user_tokens},
+};
+
+// Stop the user's password from working until they choose a new one, e.g. when
+// it may have leaked. Their sessions end and they are emailed a reset link.
+#[tracing::instrument(name = "Admin Require Password Reset", skip_all)]
+pub async fn require_password_reset(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Extension(admin): Extension<AdminUser>,
+    Path(email): Path<String>,
+) -> Result<StatusCode, AuthApiError> {
+    let email = existing_user(&state, email).await?;
+
+    state
+        .user_store
+        .write()
+        .await
+        .set_password_reset_required(&email, true)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    revoke_all_user_tokens(
+        &email,
+        state.token_version_store.clone(),
+        state.refresh_token_store.clone(),
+        state.session_store.clone(),
+    )
+    .await
+    .map_err(AuthApiError::UnexpectedError)?;
+
+    send_password_reset_link(
+        &state,
+        &email,
+        "An administrator asked you to choose a new password, so your current one no longer works.",
+    )
+    .await?;
+
+    let event = AuditEvent::AdminPasswordResetRequired {
+        admin: admin.email.as_ref().expose_secret().to_owned(),
+    };
+    audit.record(&state.audit_sink, Some(&email), event).await;
+
+    Ok(StatusCode::OK)
+}
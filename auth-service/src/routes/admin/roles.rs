@@ -0,0 +1,123 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, Role, RoleStoreError},
+    utils::{admin::AdminUser, audit::AuditContext},
+};
+
+#[tracing::instrument(name = "Admin List Roles", skip_all)]
+pub async fn list_roles(State(state): State<AppState>) -> Result<impl IntoResponse, AuthApiError> {
+    let roles = state
+        .role_store
+        .read()
+        .await
+        .get_roles()
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    Ok((StatusCode::OK, Json(RolesResponse::from(roles))))
+}
+
+#[tracing::instrument(name = "Admin Create Role", skip_all)]
+pub async fn create_role(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Extension(admin): Extension<AdminUser>,
+    Json(request): Json<CreateRoleRequest>,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let role = Role::parse(request.name, request.description, request.permissions)
+        .map_err(|_| AuthApiError::InvalidCredentials)?;
+
+    state
+        .role_store
+        .write()
+        .await
+        .add_role(role.clone())
+        .await
+        .map_err(|e| match e {
+            RoleStoreError::RoleAlreadyExists => AuthApiError::RoleAlreadyExists,
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?;
+
+    let event = AuditEvent::AdminRoleCreated {
+        role: role.name.clone(),
+    };
+    audit
+        .record(&state.audit_sink, Some(&admin.email), event)
+        .await;
+
+    Ok((StatusCode::CREATED, Json(RoleResponse::from(role))))
+}
+
+// Delete a role, taking it away from every user who has it
+#[tracing::instrument(name = "Admin Delete Role", skip_all)]
+pub async fn delete_role(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Extension(admin): Extension<AdminUser>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, AuthApiError> {
+    state
+        .role_store
+        .write()
+        .await
+        .delete_role(&name)
+        .await
+        .map_err(|e| match e {
+            RoleStoreError::RoleNotFound => AuthApiError::RoleNotFound,
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?;
+
+    let event = AuditEvent::AdminRoleDeleted { role: name };
+    audit
+        .record(&state.audit_sink, Some(&admin.email), event)
+        .await;
+
+    Ok(StatusCode::OK)
+}
+
+#[derive(Deserialize)]
+pub struct CreateRoleRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct RolesResponse {
+    pub roles: Vec<RoleResponse>,
+}
+
+impl From<Vec<Role>> for RolesResponse {
+    fn from(roles: Vec<Role>) -> Self {
+        Self {
+            roles: roles.into_iter().map(RoleResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct RoleResponse {
+    pub name: String,
+    pub description: String,
+    pub permissions: Vec<String>,
+}
+
+impl From<Role> for RoleResponse {
+    fn from(role: Role) -> Self {
+        Self {
+            name: role.name,
+            description: role.description,
+            permissions: role.permissions,
+        }
+    }
+}
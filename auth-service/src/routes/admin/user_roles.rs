@@ -0,0 +1,99 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use secrecy::ExposeSecret;
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, RoleStoreError},
+    routes::admin::{existing_user, RolesResponse},
+    utils::{admin::AdminUser, audit::AuditContext},
+};
+
+#[tracing::instrument(name = "Admin List User Roles", skip_all)]
+pub async fn list_user_roles(
+    State(state): State<AppState>,
+    Path(email): Path<String>,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let email = existing_user(&state, email).await?;
+
+    let roles = state
+        .role_store
+        .read()
+        .await
+        .get_user_roles(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    Ok((StatusCode::OK, Json(RolesResponse::from(roles))))
+}
+
+// Give the user a role. Like taking one away, it reaches their access tokens
+// when they are next refreshed.
+#[tracing::instrument(name = "Admin Assign Role", skip_all)]
+pub async fn assign_role(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Extension(admin): Extension<AdminUser>,
+    Path(email): Path<String>,
+    Json(request): Json<AssignRoleRequest>,
+) -> Result<StatusCode, AuthApiError> {
+    let email = existing_user(&state, email).await?;
+
+    state
+        .role_store
+        .write()
+        .await
+        .assign_role(&email, &request.role)
+        .await
+        .map_err(|e| match e {
+            RoleStoreError::RoleNotFound => AuthApiError::RoleNotFound,
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?;
+
+    let event = AuditEvent::AdminRoleAssigned {
+        admin: admin.email.as_ref().expose_secret().to_owned(),
+        role: request.role,
+    };
+    audit.record(&state.audit_sink, Some(&email), event).await;
+
+    Ok(StatusCode::OK)
+}
+
+#[tracing::instrument(name = "Admin Unassign Role", skip_all)]
+pub async fn unassign_role(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Extension(admin): Extension<AdminUser>,
+    Path((email, role)): Path<(String, String)>,
+) -> Result<StatusCode, AuthApiError> {
+    let email = existing_user(&state, email).await?;
+
+    state
+        .role_store
+        .write()
+        .await
+        .unassign_role(&email, &role)
+        .await
+        .map_err(|e| match e {
+            RoleStoreError::RoleNotFound => AuthApiError::RoleNotFound,
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?;
+
+    let event = AuditEvent::AdminRoleUnassigned {
+        admin: admin.email.as_ref().expose_secret().to_owned(),
+        role,
+    };
+    audit.record(&state.audit_sink, Some(&email), event).await;
+
+    Ok(StatusCode::OK)
+}
+
+#[derive(Deserialize)]
+pub struct AssignRoleRequest {
+    pub role: String,
+}
@@ -0,0 +1,211 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use axum_extra::extract::CookieJar;
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    domain::{ApiKey, ApiKeySecret, ApiKeyStoreError, AuditEvent, AuthApiError},
+    routes::reauthenticate,
+    utils::{
+        audit::AuditContext,
+        auth::{authenticate_user, get_user_access},
+        client_ip::ClientInfo,
+    },
+};
+
+// Longer-lived keys have to be asked for
+const DEFAULT_API_KEY_TTL_DAYS: i64 = 30;
+const MAX_API_KEY_TTL_DAYS: i64 = 365;
+
+#[tracing::instrument(name = "List API Keys", skip_all)]
+pub async fn list_api_keys(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let email = authenticate_user(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+
+    let api_keys = state
+        .api_key_store
+        .read()
+        .await
+        .get_keys(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?
+        .into_iter()
+        .map(ApiKeyResponse::from)
+        .collect();
+
+    Ok((StatusCode::OK, Json(ApiKeysResponse { api_keys })))
+}
+
+// Mint a key for scripts and CI jobs. The key itself is only ever in this
+// response; the scope can't go beyond the permissions of the user's roles. A key
+// outlives the session, so the user's password is checked again first.
+#[tracing::instrument(name = "Create API Key", skip_all)]
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    client: ClientInfo,
+    jar: CookieJar,
+    Json(request): Json<CreateApiKeyRequest>,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let email = authenticate_user(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+
+    let ttl_days = request.expires_in_days.unwrap_or(DEFAULT_API_KEY_TTL_DAYS);
+    if !(1..=MAX_API_KEY_TTL_DAYS).contains(&ttl_days) {
+        return Err(AuthApiError::InvalidCredentials);
+    }
+
+    reauthenticate(&state, &email, request.password, &client).await?;
+
+    let user = state
+        .user_store
+        .read()
+        .await
+        .get_user(&email)
+        .await
+        .map_err(|_| AuthApiError::InvalidToken)?;
+    let access = get_user_access(&user, None, state.role_store.clone())
+        .await
+        .map_err(AuthApiError::UnexpectedError)?;
+    let scopes: Vec<String> = request
+        .scope
+        .as_deref()
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_owned)
+        .collect();
+    if !scopes
+        .iter()
+        .all(|scope| access.permissions.contains(scope))
+    {
+        return Err(AuthApiError::Forbidden);
+    }
+
+    let secret = ApiKeySecret::generate_random();
+    let api_key = ApiKey::new(email.clone(), request.name, scopes, &secret, ttl_days)
+        .map_err(|_| AuthApiError::InvalidCredentials)?;
+    state
+        .api_key_store
+        .write()
+        .await
+        .add_key(api_key.clone())
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    let event = AuditEvent::ApiKeyCreated {
+        id: api_key.id,
+        name: api_key.name.clone(),
+    };
+    audit.record(&state.audit_sink, Some(&email), event).await;
+
+    let response = CreateApiKeyResponse {
+        key: secret.as_ref().expose_secret().to_owned(),
+        api_key: ApiKeyResponse::from(api_key),
+    };
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+#[tracing::instrument(name = "Revoke API Key", skip_all)]
+pub async fn revoke_api_key(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(id): Path<String>,
+    jar: CookieJar,
+) -> Result<StatusCode, AuthApiError> {
+    let email = authenticate_user(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+
+    let id = Uuid::parse_str(&id).map_err(|_| AuthApiError::ApiKeyNotFound)?;
+    state
+        .api_key_store
+        .write()
+        .await
+        .revoke_key(&email, &id)
+        .await
+        .map_err(|e| match e {
+            ApiKeyStoreError::KeyNotFound => AuthApiError::ApiKeyNotFound,
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?;
+
+    let event = AuditEvent::ApiKeyRevoked { id };
+    audit.record(&state.audit_sink, Some(&email), event).await;
+
+    Ok(StatusCode::OK)
+}
+
+#[derive(Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub password: Secret<String>,
+    /// Space-separated permissions
+    pub scope: Option<String>,
+    #[serde(rename = "expiresInDays")]
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct CreateApiKeyResponse {
+    pub key: String,
+    #[serde(flatten)]
+    pub api_key: ApiKeyResponse,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct ApiKeysResponse {
+    #[serde(rename = "apiKeys")]
+    pub api_keys: Vec<ApiKeyResponse>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct ApiKeyResponse {
+    pub id: String,
+    pub name: String,
+    /// The start of the key, for telling keys apart
+    pub hint: String,
+    pub scope: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: i64,
+    #[serde(rename = "lastUsedAt")]
+    pub last_used_at: Option<i64>,
+}
+
+impl From<ApiKey> for ApiKeyResponse {
+    fn from(api_key: ApiKey) -> Self {
+        Self {
+            id: api_key.id.to_string(),
+            name: api_key.name,
+            hint: api_key.hint,
+            scope: api_key.scopes.join(" "),
+            created_at: api_key.created_at,
+            expires_at: api_key.expires_at,
+            last_used_at: api_key.last_used_at,
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use axum::{
+    extract::{OriginalUri, Query, State},
+    response::Redirect,
+};
+use axum_extra::extract::CookieJar;
+use reqwest::Url;
+use secrecy::{ExposeSecret, Secret};
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuditEvent, AuthorizationCode, AuthorizationCodeData, Email, OAuthClientStoreError,
+        OAuthError,
+    },
+    utils::{
+        audit::AuditContext,
+        auth::validate_token,
+        constants::{JWT_COOKIE_NAME, OIDC_ISSUER},
+    },
+};
+
+#[tracing::instrument(name = "Authorize", skip_all)]
+pub async fn authorize(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    jar: CookieJar,
+    OriginalUri(original_uri): OriginalUri,
+    Query(request): Query<AuthorizeRequest>,
+) -> Result<Redirect, OAuthError> {
+    let client = match state
+        .oauth_client_store
+        .read()
+        .await
+        .get_client(&request.client_id)
+        .await
+    {
+        Ok(client) => client,
+        Err(OAuthClientStoreError::ClientNotFound) => return Err(OAuthError::InvalidClient),
+        Err(e) => return Err(OAuthError::UnexpectedError(e.into())),
+    };
+
+    // Until the redirect URI is known to belong to the client, errors must not be
+    // sent to it (RFC 6749 section 4.1.2.1)
+    if !client.has_redirect_uri(&request.redirect_uri) {
+        return Err(OAuthError::InvalidRequest);
+    }
+    let mut redirect_uri =
+        Url::parse(&request.redirect_uri).map_err(|_| OAuthError::InvalidRequest)?;
+
+    if let Some(error) = request.validation_error() {
+        redirect_uri.query_pairs_mut().append_pair("error", error);
+        return Ok(redirect_with_state(redirect_uri, request.state.as_deref()));
+    }
+
+    let email = match logged_in_user(&state, &jar).await {
+        Some(email) => email,
+        None => {
+            // Send the user to the login page, which comes back here once they're logged in
+            let return_to = original_uri
+                .path_and_query()
+                .map(|path_and_query| path_and_query.as_str())
+                .unwrap_or("/authorize");
+            let mut login_url = Url::parse(&format!("{}/", *OIDC_ISSUER))
+                .map_err(|e| OAuthError::UnexpectedError(e.into()))?;
+            login_url
+                .query_pairs_mut()
+                .append_pair("return_to", return_to);
+            return Ok(Redirect::to(login_url.as_str()));
+        }
+    };
+
+    audit
+        .record(
+            &state.audit_sink,
+            Some(&email),
+            AuditEvent::AuthorizationCodeIssued {
+                client_id: client.client_id.clone(),
+            },
+        )
+        .await;
+
+    let code = AuthorizationCode::generate_random();
+    let data = AuthorizationCodeData {
+        client_id: client.client_id,
+        redirect_uri: request.redirect_uri,
+        email,
+        scope: request.scope,
+        nonce: request.nonce,
+        code_challenge: request.code_challenge.unwrap_or_default(),
+    };
+    state
+        .authorization_code_store
+        .write()
+        .await
+        .add_code(code.clone(), data)
+        .await
+        .map_err(|e| OAuthError::UnexpectedError(e.into()))?;
+
+    redirect_uri
+        .query_pairs_mut()
+        .append_pair("code", code.as_ref().expose_secret());
+    Ok(redirect_with_state(redirect_uri, request.state.as_deref()))
+}
+
+#[tracing::instrument(name = "Logged In User", skip_all)]
+async fn logged_in_user(state: &AppState, jar: &CookieJar) -> Option<Email> {
+    let cookie = jar.get(JWT_COOKIE_NAME)?;
+    let claims = validate_token(
+        cookie.value(),
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await
+    .ok()?;
+    Email::parse(Secret::new(claims.sub)).ok()
+}
+
+fn redirect_with_state(mut redirect_uri: Url, state: Option<&str>) -> Redirect {
+    if let Some(state) = state {
+        redirect_uri.query_pairs_mut().append_pair("state", state);
+    }
+    Redirect::to(redirect_uri.as_str())
+}
+
+#[derive(Deserialize)]
+pub struct AuthorizeRequest {
+    pub response_type: String,
+    pub client_id: String,
+    pub redirect_uri: String,
+    pub scope: String,
+    pub state: Option<String>,
+    pub nonce: Option<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+}
+
+impl AuthorizeRequest {
+    // The error code to send back to the client, if the request can't be served
+    fn validation_error(&self) -> Option<&'static str> {
+        if self.response_type != "code" {
+            return Some("unsupported_response_type");
+        }
+        if !self.scope.split(' ').any(|scope| scope == "openid") {
+            return Some("invalid_scope");
+        }
+        // PKCE is required for every client, confidential or not
+        if self
+            .code_challenge
+            .as_deref()
+            .unwrap_or_default()
+            .is_empty()
+            || self.code_challenge_method.as_deref() != Some("S256")
+        {
+            return Some("invalid_request");
+        }
+        None
+    }
+}
@@ -0,0 +1,113 @@
+use axum::{extract::State, http::StatusCode, Json};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::Result;
+use secrecy::{ExposeSecret, Secret};
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, Email},
+    routes::reauthenticate,
+    utils::{
+        audit::AuditContext,
+        auth::{authenticate_user, generate_email_change_token},
+        client_ip::ClientInfo,
+        constants::OIDC_ISSUER,
+    },
+};
+
+// Start moving the account to a new email address, which takes effect once the
+// link sent there is opened
+#[tracing::instrument(name = "Change Email", skip_all)]
+pub async fn change_email(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    client: ClientInfo,
+    jar: CookieJar,
+    Json(request): Json<ChangeEmailRequest>,
+) -> Result<StatusCode, AuthApiError> {
+    let email = authenticate_user(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+
+    let new_email =
+        Email::parse(request.new_email).map_err(|_| AuthApiError::InvalidCredentials)?;
+    if new_email == email {
+        return Err(AuthApiError::InvalidCredentials);
+    }
+
+    reauthenticate(&state, &email, request.password, &client).await?;
+
+    if state.user_store.read().await.user_exists(&new_email).await {
+        return Err(AuthApiError::UserAlreadyExists);
+    }
+
+    // The current address hears about the change too, in case the session was stolen
+    let subject = "Your Let's Get Rusty email address is being changed";
+    let content = format!(
+        "A change of your account's email address to {} was requested. \
+         If this wasn't you, reset your password right away.",
+        new_email.as_ref().expose_secret()
+    );
+    if let Err(e) = state
+        .email_client
+        .send_email(&email, subject, &content)
+        .await
+    {
+        tracing::error!("failed to send email change notice: {:?}", e);
+    }
+
+    send_email_change_confirmation(&state, &email, &new_email)
+        .await
+        .map_err(AuthApiError::UnexpectedError)?;
+
+    audit
+        .record(
+            &state.audit_sink,
+            Some(&email),
+            AuditEvent::EmailChangeRequested,
+        )
+        .await;
+
+    Ok(StatusCode::OK)
+}
+
+// Email a link to `new_email` that moves the account of `email` there
+#[tracing::instrument(name = "Send Email Change Confirmation", skip_all)]
+async fn send_email_change_confirmation(
+    state: &AppState,
+    email: &Email,
+    new_email: &Email,
+) -> Result<()> {
+    let token_version = state
+        .token_version_store
+        .read()
+        .await
+        .get_token_version(email)
+        .await?;
+    let token = generate_email_change_token(email, new_email, token_version)?;
+
+    // The issuer is the public URL of the login UI, which finishes the change
+    let subject = "Confirm your new Let's Get Rusty email address";
+    let content = format!(
+        "Use this link to confirm your new email address: {}/?email_change_token={}\n\
+         The link expires in 24 hours.",
+        *OIDC_ISSUER, token
+    );
+
+    state
+        .email_client
+        .send_email(new_email, subject, &content)
+        .await
+}
+
+#[derive(Deserialize)]
+pub struct ChangeEmailRequest {
+    #[serde(rename = "newEmail")]
+    pub new_email: Secret<String>,
+    pub password: Secret<String>,
+}
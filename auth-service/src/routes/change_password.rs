@@ -0,0 +1,118 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_extra::extract::CookieJar;
+use secrecy::Secret;
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, Email, Password, ThrottleKey},
+    routes::tenant_membership,
+    utils::{
+        audit::AuditContext,
+        auth::{authenticate_claims, restart_session},
+        client_ip::ClientInfo,
+        throttle::{check_lockout, record_failed_attempts, reset_failed_attempts},
+    },
+};
+
+#[tracing::instrument(name = "Change Password", skip_all)]
+pub async fn change_password(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    client: ClientInfo,
+    jar: CookieJar,
+    Json(request): Json<ChangePasswordRequest>,
+) -> Result<(CookieJar, impl IntoResponse), AuthApiError> {
+    let claims = authenticate_claims(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+    let email = Email::parse(Secret::new(claims.sub)).map_err(|_| AuthApiError::InvalidToken)?;
+    let tenant = tenant_membership(&state, &email, claims.tenant_id.as_deref()).await?;
+
+    let new_password = Password::parse(request.new_password, false)
+        .map_err(|_| AuthApiError::InvalidCredentials)?;
+
+    reauthenticate(&state, &email, request.current_password, &client).await?;
+
+    state
+        .user_store
+        .write()
+        .await
+        .update_password(&email, new_password)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    // Anyone who knew the old password is logged out
+    let (auth_cookie, refresh_cookie) = restart_session(&state, &email, tenant, client)
+        .await
+        .map_err(AuthApiError::UnexpectedError)?;
+
+    let subject = "Your Let's Get Rusty password was changed";
+    let content = "The password of your account was just changed. \
+                   If this wasn't you, reset your password right away.";
+    if let Err(e) = state
+        .email_client
+        .send_email(&email, subject, content)
+        .await
+    {
+        tracing::error!("failed to send password change notice: {:?}", e);
+    }
+    audit
+        .record(&state.audit_sink, Some(&email), AuditEvent::PasswordChanged)
+        .await;
+
+    let updated_jar = jar.add(auth_cookie).add(refresh_cookie);
+
+    Ok((updated_jar, StatusCode::OK))
+}
+
+/// Checks the current password of a logged-in user before a sensitive change
+/// to their account, so that a stolen session alone isn't enough. Wrong
+/// passwords count towards the login lockout.
+#[tracing::instrument(name = "Reauthenticate", skip_all)]
+pub(crate) async fn reauthenticate(
+    state: &AppState,
+    email: &Email,
+    password: Secret<String>,
+    client: &ClientInfo,
+) -> Result<(), AuthApiError> {
+    let password =
+        Password::parse(password, false).map_err(|_| AuthApiError::InvalidCredentials)?;
+
+    let throttle_keys = [
+        ThrottleKey::Email(email.clone()),
+        ThrottleKey::Ip(client.ip),
+    ];
+    check_lockout(&state.login_throttle_store, &throttle_keys).await?;
+
+    let result = state
+        .user_store
+        .read()
+        .await
+        .validate_user(email, &password)
+        .await;
+    if result.is_err() {
+        record_failed_attempts(&state.login_throttle_store, &throttle_keys).await?;
+        return Err(AuthApiError::IncorrectCredentials);
+    }
+
+    // Only the account's count is cleared. Clearing the address's too would let
+    // anyone with an account wipe the failures of guesses against other accounts.
+    reset_failed_attempts(
+        &state.login_throttle_store,
+        &[ThrottleKey::Email(email.clone())],
+    )
+    .await
+}
+
+#[derive(Deserialize)]
+pub struct ChangePasswordRequest {
+    #[serde(rename = "currentPassword")]
+    pub current_password: Secret<String>,
+    #[serde(rename = "newPassword")]
+    pub new_password: Secret<String>,
+}
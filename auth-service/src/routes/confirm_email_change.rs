@@ -0,0 +1,86 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_extra::extract::{cookie::Cookie, CookieJar};
+use secrecy::ExposeSecret;
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, UserStoreError},
+    utils::{
+        audit::AuditContext,
+        auth::{revoke_all_user_tokens, validate_email_change_token},
+        constants::{JWT_COOKIE_NAME, REFRESH_TOKEN_COOKIE_NAME},
+    },
+};
+
+// Move the account to the address an email change link was sent to. The user
+// is logged out everywhere and logs back in with the new address.
+#[tracing::instrument(name = "Confirm Email Change", skip_all)]
+pub async fn confirm_email_change(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    jar: CookieJar,
+    Json(request): Json<ConfirmEmailChangeRequest>,
+) -> Result<(CookieJar, impl IntoResponse), AuthApiError> {
+    let (email, new_email) =
+        validate_email_change_token(&request.token, state.token_version_store.clone())
+            .await
+            .map_err(|_| AuthApiError::InvalidToken)?;
+
+    // The address may have been taken since the link was sent
+    if state.user_store.read().await.user_exists(&new_email).await {
+        return Err(AuthApiError::UserAlreadyExists);
+    }
+
+    // Sessions and refresh tokens are kept by email address, so they are revoked
+    // before the account moves. This also uses up the link.
+    revoke_all_user_tokens(
+        &email,
+        state.token_version_store.clone(),
+        state.refresh_token_store.clone(),
+        state.session_store.clone(),
+    )
+    .await
+    .map_err(AuthApiError::UnexpectedError)?;
+
+    {
+        let mut user_store = state.user_store.write().await;
+        user_store
+            .update_email(&email, &new_email)
+            .await
+            .map_err(|e| match e {
+                UserStoreError::UserAlreadyExists => AuthApiError::UserAlreadyExists,
+                e => AuthApiError::UnexpectedError(e.into()),
+            })?;
+        // Opening the link proved ownership of the new address
+        user_store
+            .set_verified(&new_email, true)
+            .await
+            .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    }
+    // The version moved with the account, so the old address's cached one is stale
+    state
+        .token_version_store
+        .write()
+        .await
+        .remove_token_version(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    // Recorded under the old address, which is the one the history was kept under
+    let event = AuditEvent::EmailChanged {
+        new_email: new_email.as_ref().expose_secret().to_owned(),
+    };
+    audit.record(&state.audit_sink, Some(&email), event).await;
+
+    let updated_jar = jar
+        .remove(Cookie::build(JWT_COOKIE_NAME).path("/"))
+        .remove(Cookie::build(REFRESH_TOKEN_COOKIE_NAME).path("/"));
+
+    Ok((updated_jar, StatusCode::OK))
+}
+
+#[derive(Deserialize)]
+pub struct ConfirmEmailChangeRequest {
+    pub token: String,
+}
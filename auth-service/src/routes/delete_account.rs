@@ -0,0 +1,119 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_extra::extract::{cookie::Cookie, CookieJar};
+use secrecy::Secret;
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, Email},
+    routes::reauthenticate,
+    utils::{
+        audit::AuditContext,
+        auth::{authenticate_user, revoke_all_user_tokens},
+        client_ip::ClientInfo,
+        constants::{
+            ACCOUNT_DELETION_GRACE_PERIOD_DAYS, JWT_COOKIE_NAME, REFRESH_TOKEN_COOKIE_NAME,
+        },
+    },
+};
+
+// Delete the account of the logged-in user. It is only marked for deletion and
+// purged once the grace period is over, unless the user logs in again first.
+#[tracing::instrument(name = "Delete Account", skip_all)]
+pub async fn delete_account(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    client: ClientInfo,
+    jar: CookieJar,
+    Json(request): Json<DeleteAccountRequest>,
+) -> Result<(CookieJar, impl IntoResponse), AuthApiError> {
+    let email = authenticate_user(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+
+    reauthenticate(&state, &email, request.password, &client).await?;
+
+    state
+        .user_store
+        .write()
+        .await
+        .delete_user(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    revoke_all_user_tokens(
+        &email,
+        state.token_version_store.clone(),
+        state.refresh_token_store.clone(),
+        state.session_store.clone(),
+    )
+    .await
+    .map_err(AuthApiError::UnexpectedError)?;
+
+    let subject = "Your Let's Get Rusty account will be deleted";
+    let content = format!(
+        "Your account was deleted and will be removed for good in {} days. \
+         To keep it, log in before then.",
+        *ACCOUNT_DELETION_GRACE_PERIOD_DAYS
+    );
+    if let Err(e) = state
+        .email_client
+        .send_email(&email, subject, &content)
+        .await
+    {
+        tracing::error!("failed to send account deletion notice: {:?}", e);
+    }
+    audit
+        .record(&state.audit_sink, Some(&email), AuditEvent::AccountDeleted)
+        .await;
+
+    let updated_jar = jar
+        .remove(Cookie::build(JWT_COOKIE_NAME).path("/"))
+        .remove(Cookie::build(REFRESH_TOKEN_COOKIE_NAME).path("/"));
+
+    Ok((updated_jar, StatusCode::OK))
+}
+
+/// Restores the account of `email` if it is pending deletion. Called whenever
+/// a login finishes, since logging in is how a deletion is cancelled.
+#[tracing::instrument(name = "Cancel Account Deletion", skip_all)]
+pub(crate) async fn cancel_account_deletion(
+    state: &AppState,
+    audit: &AuditContext,
+    email: &Email,
+) -> Result<(), AuthApiError> {
+    let mut user_store = state.user_store.write().await;
+    let user = user_store
+        .get_user(email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    if !user.pending_deletion {
+        return Ok(());
+    }
+
+    user_store
+        .restore_user(email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    drop(user_store);
+
+    let subject = "Your Let's Get Rusty account will not be deleted";
+    let content = "You logged in, so the deletion of your account was cancelled.";
+    if let Err(e) = state.email_client.send_email(email, subject, content).await {
+        tracing::error!("failed to send account restoration notice: {:?}", e);
+    }
+    audit
+        .record(&state.audit_sink, Some(email), AuditEvent::AccountRestored)
+        .await;
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+pub struct DeleteAccountRequest {
+    pub password: Secret<String>,
+}
@@ -0,0 +1,119 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_extra::extract::CookieJar;
+use secrecy::Secret;
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, ThrottleKey, TwoFACode},
+    routes::{
+        check_emailed_2fa_code, confirmed_totp_secret, reauthenticate, send_2fa_change_notice,
+        verify_totp_code,
+    },
+    utils::{
+        audit::AuditContext,
+        auth::authenticate_user,
+        client_ip::ClientInfo,
+        throttle::{check_lockout, record_failed_attempts, reset_failed_attempts},
+    },
+};
+
+// Turn off every second factor that is sent or generated as a code: emailed
+// codes, the authenticator app and its recovery codes. The code must come from
+// the authenticator app if one is confirmed, otherwise from /2fa/code.
+#[tracing::instrument(name = "Disable 2FA", skip_all)]
+pub async fn disable_2fa(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    client: ClientInfo,
+    jar: CookieJar,
+    Json(request): Json<Disable2FARequest>,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let email = authenticate_user(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+
+    let code = TwoFACode::parse(request.code).map_err(|_| AuthApiError::InvalidCredentials)?;
+
+    reauthenticate(&state, &email, request.password, &client).await?;
+
+    let user = state
+        .user_store
+        .read()
+        .await
+        .get_user(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    let totp_secret = confirmed_totp_secret(&state, &email).await?;
+    if !user.requires_2fa && totp_secret.is_none() {
+        return Err(AuthApiError::TwoFANotEnabled);
+    }
+
+    let throttle_keys = [
+        ThrottleKey::Email(email.clone()),
+        ThrottleKey::Ip(client.ip),
+    ];
+    check_lockout(&state.login_throttle_store, &throttle_keys).await?;
+    let result = match totp_secret {
+        Some(secret) => verify_totp_code(&state, &email, &secret, &code).await,
+        None => check_emailed_2fa_code(&state, &email, &code).await,
+    };
+    match result {
+        // Only the account's count is cleared, as after a login
+        Ok(()) => {
+            reset_failed_attempts(
+                &state.login_throttle_store,
+                &[ThrottleKey::Email(email.clone())],
+            )
+            .await?
+        }
+        Err(AuthApiError::IncorrectCredentials) => {
+            audit
+                .record(&state.audit_sink, Some(&email), AuditEvent::TwoFAFailed)
+                .await;
+            record_failed_attempts(&state.login_throttle_store, &throttle_keys).await?;
+            return Err(AuthApiError::IncorrectCredentials);
+        }
+        Err(e) => return Err(e),
+    }
+
+    state
+        .user_store
+        .write()
+        .await
+        .set_requires_2fa(&email, false)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    state
+        .totp_secret_store
+        .write()
+        .await
+        .remove_secret(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    state
+        .recovery_code_store
+        .write()
+        .await
+        .replace_codes(&email, Vec::new())
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    send_2fa_change_notice(&state, &email, false).await;
+    audit
+        .record(&state.audit_sink, Some(&email), AuditEvent::TwoFADisabled)
+        .await;
+
+    Ok(StatusCode::OK)
+}
+
+#[derive(Deserialize)]
+pub struct Disable2FARequest {
+    pub password: Secret<String>,
+    #[serde(rename = "2FACode")]
+    pub code: String,
+}
@@ -0,0 +1,124 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_extra::extract::CookieJar;
+use secrecy::Secret;
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, Email, SecondFactorMethod, ThrottleKey, TwoFACode},
+    routes::{check_emailed_2fa_code, confirmed_totp_secret, tenant_membership},
+    utils::{
+        audit::AuditContext,
+        auth::{authenticate_claims, restart_session},
+        client_ip::ClientInfo,
+        throttle::{check_lockout, record_failed_attempts, reset_failed_attempts},
+    },
+};
+
+// Turn on emailed 2FA codes, using a code from /2fa/code to prove the user
+// receives them
+#[tracing::instrument(name = "Enable 2FA", skip_all)]
+pub async fn enable_2fa(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    client: ClientInfo,
+    jar: CookieJar,
+    Json(request): Json<Enable2FARequest>,
+) -> Result<(CookieJar, impl IntoResponse), AuthApiError> {
+    let claims = authenticate_claims(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+    let email = Email::parse(Secret::new(claims.sub)).map_err(|_| AuthApiError::InvalidToken)?;
+    let tenant = tenant_membership(&state, &email, claims.tenant_id.as_deref()).await?;
+
+    let code = TwoFACode::parse(request.code).map_err(|_| AuthApiError::InvalidCredentials)?;
+
+    let user = state
+        .user_store
+        .read()
+        .await
+        .get_user(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    if user.requires_2fa || confirmed_totp_secret(&state, &email).await?.is_some() {
+        return Err(AuthApiError::TwoFAAlreadyEnabled);
+    }
+
+    let throttle_keys = [
+        ThrottleKey::Email(email.clone()),
+        ThrottleKey::Ip(client.ip),
+    ];
+    check_lockout(&state.login_throttle_store, &throttle_keys).await?;
+    match check_emailed_2fa_code(&state, &email, &code).await {
+        // Only the account's count is cleared, as after a login
+        Ok(()) => {
+            reset_failed_attempts(
+                &state.login_throttle_store,
+                &[ThrottleKey::Email(email.clone())],
+            )
+            .await?
+        }
+        Err(AuthApiError::IncorrectCredentials) => {
+            audit
+                .record(&state.audit_sink, Some(&email), AuditEvent::TwoFAFailed)
+                .await;
+            record_failed_attempts(&state.login_throttle_store, &throttle_keys).await?;
+            return Err(AuthApiError::IncorrectCredentials);
+        }
+        Err(e) => return Err(e),
+    }
+
+    state
+        .user_store
+        .write()
+        .await
+        .set_requires_2fa(&email, true)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    send_2fa_change_notice(&state, &email, true).await;
+    let event = AuditEvent::TwoFAEnabled {
+        method: SecondFactorMethod::Email,
+    };
+    audit.record(&state.audit_sink, Some(&email), event).await;
+
+    // Sessions elsewhere were started without the second factor
+    let (auth_cookie, refresh_cookie) = restart_session(&state, &email, tenant, client)
+        .await
+        .map_err(AuthApiError::UnexpectedError)?;
+    let updated_jar = jar.add(auth_cookie).add(refresh_cookie);
+
+    Ok((updated_jar, StatusCode::OK))
+}
+
+/// Tells the user their 2FA setting changed, in case it wasn't them. A failure
+/// to send is logged rather than undoing the change.
+pub(crate) async fn send_2fa_change_notice(state: &AppState, email: &Email, enabled: bool) {
+    let (subject, content) = if enabled {
+        (
+            "Two-factor authentication was turned on",
+            "Two-factor authentication was just turned on for your account. \
+             If this wasn't you, reset your password right away.",
+        )
+    } else {
+        (
+            "Two-factor authentication was turned off",
+            "Two-factor authentication was just turned off for your account. \
+             If this wasn't you, reset your password and turn it back on right away.",
+        )
+    };
+
+    if let Err(e) = state.email_client.send_email(email, subject, content).await {
+        tracing::error!("failed to send 2FA change notice: {:?}", e);
+    }
+}
+
+#[derive(Deserialize)]
+pub struct Enable2FARequest {
+    #[serde(rename = "2FACode")]
+    pub code: String,
+}
@@ -0,0 +1,183 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_extra::extract::CookieJar;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuditRecord, AuthApiError, Email},
+    routes::{
+        confirmed_totp_secret, ApiKeyResponse, LoginHistoryEntry, MembershipResponse,
+        SessionResponse, TwoFAMethod, WebauthnCredentialResponse,
+    },
+    utils::{audit::AuditContext, auth::authenticate_claims},
+};
+
+// Everything stored about the logged-in user, for them to download. Secrets
+// such as the password hash, TOTP secret and recovery codes are left out.
+#[tracing::instrument(name = "Export Account", skip_all)]
+pub async fn export_account(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let claims = authenticate_claims(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+    let email = Email::parse(Secret::new(claims.sub)).map_err(|_| AuthApiError::InvalidToken)?;
+
+    let user = state
+        .user_store
+        .read()
+        .await
+        .get_user(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    let two_fa_method = if confirmed_totp_secret(&state, &email).await?.is_some() {
+        Some(TwoFAMethod::Totp)
+    } else if user.requires_2fa {
+        Some(TwoFAMethod::Email)
+    } else {
+        None
+    };
+
+    let passkeys = state
+        .webauthn_credential_store
+        .read()
+        .await
+        .get_credentials(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?
+        .into_iter()
+        .map(|credential| WebauthnCredentialResponse {
+            credential_id: URL_SAFE_NO_PAD.encode(&credential.credential_id),
+            name: credential.name,
+        })
+        .collect();
+
+    let sessions = state
+        .session_store
+        .read()
+        .await
+        .get_sessions(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?
+        .into_iter()
+        .map(|session| SessionResponse::new(session, claims.sid.as_deref()))
+        .collect();
+
+    let logins = state
+        .login_history_store
+        .read()
+        .await
+        .get_logins(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?
+        .into_iter()
+        .map(|login| LoginHistoryEntry::new(login, claims.sid.as_deref()))
+        .collect();
+
+    let roles = state
+        .role_store
+        .read()
+        .await
+        .get_user_roles(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?
+        .into_iter()
+        .map(|role| role.name)
+        .collect();
+
+    let organizations = state
+        .organization_store
+        .read()
+        .await
+        .get_memberships(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?
+        .into_iter()
+        .map(MembershipResponse::from)
+        .collect();
+
+    let api_keys = state
+        .api_key_store
+        .read()
+        .await
+        .get_keys(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?
+        .into_iter()
+        .map(ApiKeyResponse::from)
+        .collect();
+
+    let audit_events = state
+        .audit_sink
+        .get_records(&email)
+        .await
+        .map_err(AuthApiError::UnexpectedError)?;
+
+    let response = AccountExportResponse {
+        user: UserExport {
+            email: email.as_ref().expose_secret().to_owned(),
+            verified: user.verified,
+            requires_2fa: user.requires_2fa,
+            pending_deletion: user.pending_deletion,
+            admin: user.admin,
+            locked: user.locked,
+            password_reset_required: user.password_reset_required,
+            roles,
+        },
+        two_fa_method,
+        passkeys,
+        sessions,
+        logins,
+        organizations,
+        api_keys,
+        audit_events,
+    };
+
+    audit
+        .record(&state.audit_sink, Some(&email), AuditEvent::AccountExported)
+        .await;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct AccountExportResponse {
+    pub user: UserExport,
+    /// Where login codes come from, or `null` if the account has no 2FA
+    #[serde(rename = "twoFAMethod")]
+    pub two_fa_method: Option<TwoFAMethod>,
+    pub passkeys: Vec<WebauthnCredentialResponse>,
+    pub sessions: Vec<SessionResponse>,
+    pub logins: Vec<LoginHistoryEntry>,
+    pub organizations: Vec<MembershipResponse>,
+    /// Without the keys themselves, which aren't stored
+    #[serde(rename = "apiKeys")]
+    pub api_keys: Vec<ApiKeyResponse>,
+    /// The account's audit log, oldest first
+    #[serde(rename = "auditEvents")]
+    pub audit_events: Vec<AuditRecord>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct UserExport {
+    pub email: String,
+    pub verified: bool,
+    #[serde(rename = "requires2FA")]
+    pub requires_2fa: bool,
+    #[serde(rename = "pendingDeletion")]
+    pub pending_deletion: bool,
+    pub admin: bool,
+    pub locked: bool,
+    #[serde(rename = "passwordResetRequired")]
+    pub password_reset_required: bool,
+    pub roles: Vec<String>,
+}
@@ -0,0 +1,72 @@
+use axum::{extract::State, http::HeaderMap, Form, Json};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::OAuthError,
+    utils::auth::{authenticate_client, validate_oauth_token},
+};
+
+#[tracing::instrument(name = "Introspect Token", skip_all)]
+pub async fn introspect(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Form(request): Form<IntrospectRequest>,
+) -> Result<Json<IntrospectResponse>, OAuthError> {
+    authenticate_client(
+        &headers,
+        request.client_id,
+        request.client_secret,
+        state.oauth_client_store.clone(),
+    )
+    .await?;
+
+    // Expired, banned, malformed, and unknown tokens are all just inactive (RFC 7662 section 2.2)
+    let response = match validate_oauth_token(
+        &request.token,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await
+    {
+        Ok(claims) => IntrospectResponse {
+            active: true,
+            sub: Some(claims.sub),
+            exp: Some(claims.exp),
+            iat: claims.iat,
+            scope: claims.scope,
+            jti: claims.jti,
+            token_type: Some("Bearer".to_owned()),
+        },
+        Err(_) => IntrospectResponse::default(),
+    };
+
+    Ok(Json(response))
+}
+
+#[derive(Deserialize)]
+pub struct IntrospectRequest {
+    pub token: String,
+    pub token_type_hint: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<Secret<String>>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct IntrospectResponse {
+    pub active: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exp: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iat: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
+}
@@ -0,0 +1,9 @@
+use axum::Json;
+use jsonwebtoken::jwk::JwkSet;
+
+use crate::utils::keyring::KEYRING;
+
+#[tracing::instrument(name = "JWKS", skip_all)]
+pub async fn jwks() -> Json<JwkSet> {
+    Json(KEYRING.jwks())
+}
@@ -1,95 +1,213 @@
 use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
-use axum_extra::extract::CookieJar;
+use axum_extra::extract::{cookie::Cookie, CookieJar};
 use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     app_state::AppState,
-    domain::{AuthApiError, Email, LoginAttemptId, Password, TwoFACode},
-    utils::auth::generate_auth_cookie,
+    domain::{
+        AuditEvent, AuthApiError, Email, LoginAttemptId, LoginMethod, LoginRecord, Membership,
+        OrganizationStoreError, Password, ThrottleKey, TotpSecretStoreError, TwoFACode, User,
+    },
+    routes::{cancel_account_deletion, record_login},
+    utils::{
+        audit::AuditContext,
+        auth::{generate_pending_2fa_cookie, start_session},
+        client_ip::ClientInfo,
+        constants::REQUIRE_VERIFIED_EMAIL,
+        throttle::{check_lockout, record_failed_attempts, reset_failed_attempts},
+    },
 };
 
 #[tracing::instrument(name = "Login", skip_all)]
 pub async fn login(
     State(state): State<AppState>,
+    audit: AuditContext,
+    client: ClientInfo,
     jar: CookieJar,
     Json(request): Json<LoginRequest>,
 ) -> Result<(CookieJar, impl IntoResponse), AuthApiError> {
-    println!("---------------> [login] 1. ");
     let email = Email::parse(Secret::new(request.email.clone()))
         .map_err(|_| AuthApiError::InvalidCredentials)?;
-    println!("---------------> [login] 2. email: {email:?}");
 
     let password = Password::parse(request.password.clone(), false)
         .map_err(|_| AuthApiError::InvalidCredentials)?;
-    println!("---------------> [login] 3. password: {password:?}");
+
+    // Failed attempts are counted per account and per client address
+    let throttle_keys = [
+        ThrottleKey::Email(email.clone()),
+        ThrottleKey::Ip(client.ip),
+    ];
+    if let Err(e) = check_lockout(&state.login_throttle_store, &throttle_keys).await {
+        let event = AuditEvent::LoginFailed {
+            method: LoginMethod::Password,
+        };
+        audit.record(&state.audit_sink, Some(&email), event).await;
+        return Err(e);
+    }
 
     let user = {
         let user_store = state.user_store.read().await;
-        match user_store.validate_user(&email, &password).await {
-            Ok(user) => user,
-            Err(_) => {
-                return Err(AuthApiError::IncorrectCredentials);
-            }
+        user_store.validate_user(&email, &password).await
+    };
+    let user = match user {
+        Ok(user) => user,
+        Err(_) => {
+            let event = AuditEvent::LoginFailed {
+                method: LoginMethod::Password,
+            };
+            audit.record(&state.audit_sink, Some(&email), event).await;
+            record_failed_attempts(&state.login_throttle_store, &throttle_keys).await?;
+            return Err(AuthApiError::IncorrectCredentials);
         }
     };
-    println!("---------------> [login] 4. user: {user:?}");
 
-    match user.requires_2fa {
-        true => handle_2fa(&email, &state, jar).await,
-        false => handle_no_2fa(&user.email, jar).await,
+    if *REQUIRE_VERIFIED_EMAIL && !user.verified {
+        return Err(AuthApiError::EmailNotVerified);
+    }
+    // Set by an admin when the password may be known to someone else
+    if user.password_reset_required {
+        return Err(AuthApiError::PasswordResetRequired);
+    }
+    // Checked before asking for a second factor, and again once it is given
+    let tenant_id = request.tenant.filter(|tenant| !tenant.is_empty());
+    tenant_membership(&state, &email, tenant_id.as_deref()).await?;
+
+    complete_login(
+        &user,
+        tenant_id.as_deref(),
+        LoginMethod::Password,
+        &state,
+        &audit,
+        client,
+        jar,
+    )
+    .await
+}
+
+/// Looks up the user's membership of the organization they are logging in to,
+/// if they named one. Users can only log in to organizations they belong to.
+#[tracing::instrument(name = "Tenant Membership", skip_all)]
+pub(crate) async fn tenant_membership(
+    state: &AppState,
+    email: &Email,
+    tenant_id: Option<&str>,
+) -> Result<Option<Membership>, AuthApiError> {
+    let Some(tenant_id) = tenant_id else {
+        return Ok(None);
+    };
+
+    match state
+        .organization_store
+        .read()
+        .await
+        .get_membership(tenant_id, email)
+        .await
+    {
+        Ok(membership) => Ok(Some(membership)),
+        Err(OrganizationStoreError::MembershipNotFound) => Err(AuthApiError::Forbidden),
+        Err(e) => Err(AuthApiError::UnexpectedError(e.into())),
+    }
+}
+
+/// Finishes a login for a user who has proven the first factor, either asking
+/// for the second factor or issuing the session cookies. `tenant_id` is the
+/// organization being logged in to, if any.
+#[tracing::instrument(name = "Complete Login", skip_all)]
+pub(crate) async fn complete_login(
+    user: &User,
+    tenant_id: Option<&str>,
+    method: LoginMethod,
+    state: &AppState,
+    audit: &AuditContext,
+    client: ClientInfo,
+    jar: CookieJar,
+) -> Result<(CookieJar, (StatusCode, Json<LoginResponse>)), AuthApiError> {
+    if user.locked {
+        return Err(AuthApiError::AccountLocked);
+    }
+
+    // A confirmed authenticator app is a second factor even without `requires_2fa`
+    let totp_enabled = match state
+        .totp_secret_store
+        .read()
+        .await
+        .get_secret(&user.email)
+        .await
+    {
+        Ok(enrollment) => enrollment.confirmed,
+        Err(TotpSecretStoreError::SecretNotFound) => false,
+        Err(e) => return Err(AuthApiError::UnexpectedError(e.into())),
+    };
+
+    match (totp_enabled, user.requires_2fa) {
+        (true, _) => handle_2fa(&user.email, tenant_id, TwoFAMethod::Totp, state, audit, jar).await,
+        (false, true) => {
+            handle_2fa(
+                &user.email,
+                tenant_id,
+                TwoFAMethod::Email,
+                state,
+                audit,
+                jar,
+            )
+            .await
+        }
+        (false, false) => {
+            handle_no_2fa(&user.email, tenant_id, method, state, audit, client, jar).await
+        }
     }
 }
 
 #[tracing::instrument(name = "Handle 2FA", skip_all)]
 async fn handle_2fa(
     email: &Email,
+    tenant_id: Option<&str>,
+    method: TwoFAMethod,
     state: &AppState,
+    audit: &AuditContext,
     jar: CookieJar,
 ) -> Result<(CookieJar, (StatusCode, Json<LoginResponse>)), AuthApiError> {
-    println!("\t---------------> [handle_2fa] 1.");
     let login_attempt_id = LoginAttemptId::generate_random();
-    println!("\t---------------> [handle_2fa] 2.");
     let two_fa_code = TwoFACode::generate_random();
     {
         let mut two_fa_code_store = state.two_fa_code_store.write().await;
-        let _ = two_fa_code_store
+        two_fa_code_store
             .add_code(email.clone(), login_attempt_id.clone(), two_fa_code.clone())
             .await
-            .map_err(|e| AuthApiError::UnexpectedError(e.into()));
+            .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
     }
-    println!("\t---------------> [handle_2fa] 3.");
 
-    let subject = "Your Let's Get Rusty 2FA Code";
-    println!("\t---------------> [handle_2fa] 4.");
-    let content = format!(
-        "Your 2FA code is: {}",
-        &two_fa_code.as_ref().expose_secret()
-    );
-    println!("\t---------------> [handle_2fa] 5.");
+    // Authenticator app codes aren't sent anywhere
+    if method == TwoFAMethod::Email {
+        let subject = "Your Let's Get Rusty 2FA Code";
+        let content = format!(
+            "Your 2FA code is: {}",
+            &two_fa_code.as_ref().expose_secret()
+        );
 
-    if let Err(e) = state
-        .email_client
-        .send_email(&email, subject, &content)
-        .await
-    {
-        println!("%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%");
-        println!("%%%%%%%%%%%%%%% Err: {e:?}");
-        println!("%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%");
-        return Err(AuthApiError::UnexpectedError(e.into()));
+        if let Err(e) = state
+            .email_client
+            .send_email(email, subject, &content)
+            .await
+        {
+            return Err(AuthApiError::UnexpectedError(e));
+        }
+        audit
+            .record(&state.audit_sink, Some(email), AuditEvent::TwoFACodeSent)
+            .await;
     }
-    println!("\t---------------> [handle_2fa] 6.");
 
     let response = Json(LoginResponse::TwoFactorAuth(TwoFactorAuthResponse {
         message: "2FA required".to_string(),
         login_attempt_id: login_attempt_id.as_ref().expose_secret().to_string(),
+        method,
     }));
-    println!("\t---------------> [handle_2fa] 7.");
 
-    let auth_cookie = generate_auth_cookie(email).map_err(|e| AuthApiError::UnexpectedError(e))?;
-    println!("\t---------------> [handle_2fa] 8.");
-    let updated_jar = jar.add(auth_cookie);
-    println!("\t---------------> [handle_2fa] 9.");
+    // The auth cookie is only issued once /verify-2fa checks the second factor
+    let pending_cookie =
+        generate_pending_2fa_cookie(email, tenant_id).map_err(AuthApiError::UnexpectedError)?;
+    let updated_jar = jar.add(pending_cookie);
 
     Ok((updated_jar, (StatusCode::PARTIAL_CONTENT, response)))
 }
@@ -97,13 +215,24 @@ async fn handle_2fa(
 #[tracing::instrument(name = "Handle No 2FA", skip_all)]
 async fn handle_no_2fa(
     email: &Email,
+    tenant_id: Option<&str>,
+    method: LoginMethod,
+    state: &AppState,
+    audit: &AuditContext,
+    client: ClientInfo,
     jar: CookieJar,
 ) -> Result<(CookieJar, (StatusCode, Json<LoginResponse>)), AuthApiError> {
-    println!("\t---------------> [handle_no_2fa] 1.");
-    let auth_cookie = generate_auth_cookie(email).map_err(AuthApiError::UnexpectedError)?;
-    println!("\t---------------> [handle_no_2fa] 2.");
-    let updated_jar = jar.add(auth_cookie);
-    println!("\t---------------> [handle_no_2fa] 3.");
+    // A finished login clears the account's failed attempts. Logins that still
+    // need a second factor don't, since wrong guesses at /verify-2fa count
+    // against the account too.
+    reset_failed_attempts(
+        &state.login_throttle_store,
+        &[ThrottleKey::Email(email.clone())],
+    )
+    .await?;
+    let (auth_cookie, refresh_cookie) =
+        finish_login(email, tenant_id, method, state, audit, client).await?;
+    let updated_jar = jar.add(auth_cookie).add(refresh_cookie);
 
     Ok((
         updated_jar,
@@ -111,10 +240,70 @@ async fn handle_no_2fa(
     ))
 }
 
+/// Starts a session for a user who has proven every factor their account
+/// needs and records the login, returning the auth and refresh cookies
+#[tracing::instrument(name = "Finish Login", skip_all)]
+pub(crate) async fn finish_login(
+    email: &Email,
+    tenant_id: Option<&str>,
+    method: LoginMethod,
+    state: &AppState,
+    audit: &AuditContext,
+    client: ClientInfo,
+) -> Result<(Cookie<'static>, Cookie<'static>), AuthApiError> {
+    // The account may have been locked since the first factor was checked
+    let user = state
+        .user_store
+        .read()
+        .await
+        .get_user(email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    if user.locked {
+        return Err(AuthApiError::AccountLocked);
+    }
+    let tenant = tenant_membership(state, email, tenant_id).await?;
+
+    cancel_account_deletion(state, audit, email).await?;
+
+    let (session_id, auth_cookie, refresh_cookie) = start_session(
+        &user,
+        tenant,
+        client.clone(),
+        state.session_store.clone(),
+        state.refresh_token_store.clone(),
+        state.token_version_store.clone(),
+        state.role_store.clone(),
+    )
+    .await
+    .map_err(AuthApiError::UnexpectedError)?;
+
+    let login = LoginRecord::new(
+        session_id,
+        email.clone(),
+        client.ip,
+        client.user_agent,
+        method,
+    );
+    record_login(state, login).await?;
+
+    audit
+        .record(
+            &state.audit_sink,
+            Some(email),
+            AuditEvent::LoginSucceeded { method },
+        )
+        .await;
+
+    Ok((auth_cookie, refresh_cookie))
+}
+
 #[derive(Deserialize)]
 pub struct LoginRequest {
     pub email: String,
     pub password: Secret<String>,
+    /// The ID of an organization to log in to
+    pub tenant: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq)]
@@ -129,4 +318,14 @@ pub struct TwoFactorAuthResponse {
     pub message: String,
     #[serde(rename = "loginAttemptId")]
     pub login_attempt_id: String,
+    #[serde(rename = "2FAMethod")]
+    pub method: TwoFAMethod,
+}
+
+/// Where the user gets the code for the second factor from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TwoFAMethod {
+    Email,
+    Totp,
 }
@@ -0,0 +1,132 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_extra::extract::CookieJar;
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuthApiError, Email, LoginMethod, LoginRecord},
+    utils::{
+        auth::{authenticate_claims, generate_session_revocation_token},
+        constants::OIDC_ISSUER,
+    },
+};
+
+// List the user's logins, most recent first
+#[tracing::instrument(name = "List Logins", skip_all)]
+pub async fn list_logins(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let claims = authenticate_claims(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+    let email = Email::parse(Secret::new(claims.sub)).map_err(|_| AuthApiError::InvalidToken)?;
+
+    let logins = state
+        .login_history_store
+        .read()
+        .await
+        .get_logins(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?
+        .into_iter()
+        .map(|login| LoginHistoryEntry::new(login, claims.sid.as_deref()))
+        .collect();
+
+    Ok((StatusCode::OK, Json(LoginHistoryResponse { logins })))
+}
+
+/// Adds a finished login to the user's history. If it came from a device or
+/// network the user hasn't logged in from before, they are emailed a link that
+/// ends its session.
+#[tracing::instrument(name = "Record Login", skip_all)]
+pub(crate) async fn record_login(state: &AppState, login: LoginRecord) -> Result<(), AuthApiError> {
+    let is_new_device = state
+        .login_history_store
+        .read()
+        .await
+        .is_new_device(&login)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    if is_new_device {
+        send_new_device_notice(state, &login).await;
+    }
+
+    state
+        .login_history_store
+        .write()
+        .await
+        .add_login(login)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))
+}
+
+async fn send_new_device_notice(state: &AppState, login: &LoginRecord) {
+    let token = match generate_session_revocation_token(&login.email, &login.session_id) {
+        Ok(token) => token,
+        Err(e) => {
+            tracing::error!("failed to generate session revocation token: {:?}", e);
+            return;
+        }
+    };
+    let logged_in_at = chrono::DateTime::from_timestamp(login.logged_in_at, 0)
+        .map(|time| time.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default();
+
+    // The issuer is the public URL of the login UI, which posts the token to /sessions/revoke
+    let subject = "New sign-in to your Let's Get Rusty account";
+    let content = format!(
+        "Your account was signed in to from {} at {} on {}.\n\
+         If this wasn't you, use this link to log that device out: {}/?revoke_session_token={}\n\
+         Then reset your password right away.",
+        login.device, login.ip, logged_in_at, *OIDC_ISSUER, token
+    );
+    if let Err(e) = state
+        .email_client
+        .send_email(&login.email, subject, &content)
+        .await
+    {
+        tracing::error!("failed to send new sign-in notice: {:?}", e);
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct LoginHistoryResponse {
+    pub logins: Vec<LoginHistoryEntry>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct LoginHistoryEntry {
+    #[serde(rename = "sessionId")]
+    pub session_id: String,
+    pub device: String,
+    #[serde(rename = "userAgent")]
+    pub user_agent: Option<String>,
+    pub ip: String,
+    pub method: LoginMethod,
+    #[serde(rename = "loggedInAt")]
+    pub logged_in_at: i64,
+    /// Whether this login started the session the request was made with
+    pub current: bool,
+}
+
+impl LoginHistoryEntry {
+    pub(crate) fn new(login: LoginRecord, current_session_id: Option<&str>) -> Self {
+        let session_id = login.session_id.to_string();
+        Self {
+            current: current_session_id == Some(session_id.as_str()),
+            session_id,
+            device: login.device,
+            user_agent: login.user_agent,
+            ip: login.ip.to_string(),
+            method: login.method,
+            logged_in_at: login.logged_in_at,
+        }
+    }
+}
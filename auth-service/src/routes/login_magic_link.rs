@@ -0,0 +1,102 @@
+use axum::{extract::State, http::StatusCode, Json};
+use axum_extra::extract::{
+    cookie::{Cookie, SameSite},
+    CookieJar,
+};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, Email, MagicLinkNonce, MagicLinkToken},
+    utils::{
+        audit::AuditContext,
+        constants::{MAGIC_LINK_NONCE_COOKIE_NAME, OIDC_ISSUER},
+    },
+};
+
+// The nonce cookie is only sent to the magic link routes
+pub(crate) const MAGIC_LINK_COOKIE_PATH: &str = "/login/magic-link";
+
+#[tracing::instrument(name = "Request Magic Link", skip_all)]
+pub async fn request_magic_link(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    jar: CookieJar,
+    Json(request): Json<MagicLinkRequest>,
+) -> Result<(CookieJar, (StatusCode, Json<MagicLinkResponse>)), AuthApiError> {
+    let email =
+        Email::parse(Secret::new(request.email)).map_err(|_| AuthApiError::InvalidCredentials)?;
+
+    // Links are bound to this browser through the nonce cookie. A browser that
+    // asks for several links keeps its nonce so that all of them stay usable.
+    let nonce = jar
+        .get(MAGIC_LINK_NONCE_COOKIE_NAME)
+        .and_then(|cookie| MagicLinkNonce::parse(cookie.value().to_owned()).ok())
+        .unwrap_or_default();
+    let jar = jar.add(create_magic_link_nonce_cookie(&nonce));
+
+    // Respond the same way whether or not the user exists so that this route
+    // can't be used to find out which emails have an account
+    let response = Json(MagicLinkResponse {
+        message: "If an account exists for this email, a sign-in link has been sent".to_owned(),
+    });
+
+    if !state.user_store.read().await.user_exists(&email).await {
+        return Ok((jar, (StatusCode::OK, response)));
+    }
+
+    let token = MagicLinkToken::generate_random();
+    state
+        .magic_link_token_store
+        .write()
+        .await
+        .add_token(token.clone(), email.clone(), nonce)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    // The issuer is the public URL of the login UI, which finishes the login
+    let subject = "Your Let's Get Rusty sign-in link";
+    let content = format!(
+        "Use this link to sign in: {}/?magic_link_token={}\n\
+         The link expires in 15 minutes and only works in the browser you requested it from. \
+         If you didn't ask to sign in, you can ignore this email.",
+        *OIDC_ISSUER,
+        token.as_ref().expose_secret()
+    );
+
+    if let Err(e) = state
+        .email_client
+        .send_email(&email, subject, &content)
+        .await
+    {
+        // A failure here must not reveal that the account exists
+        tracing::error!("failed to send magic link email: {:?}", e);
+    }
+    audit
+        .record(&state.audit_sink, Some(&email), AuditEvent::MagicLinkSent)
+        .await;
+
+    Ok((jar, (StatusCode::OK, response)))
+}
+
+fn create_magic_link_nonce_cookie(nonce: &MagicLinkNonce) -> Cookie<'static> {
+    Cookie::build((
+        MAGIC_LINK_NONCE_COOKIE_NAME,
+        nonce.as_ref().expose_secret().to_owned(),
+    ))
+    .path(MAGIC_LINK_COOKIE_PATH)
+    .http_only(true)
+    .same_site(SameSite::Strict)
+    .build()
+}
+
+#[derive(Deserialize)]
+pub struct MagicLinkRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct MagicLinkResponse {
+    pub message: String,
+}
@@ -0,0 +1,89 @@
+use axum::{extract::State, http::StatusCode, Json};
+use axum_extra::extract::{cookie::Cookie, CookieJar};
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuditEvent, AuthApiError, LoginMethod, MagicLinkNonce, MagicLinkToken,
+        MagicLinkTokenStoreError,
+    },
+    routes::{complete_login, LoginResponse, MAGIC_LINK_COOKIE_PATH},
+    utils::{audit::AuditContext, client_ip::ClientInfo, constants::MAGIC_LINK_NONCE_COOKIE_NAME},
+};
+
+#[tracing::instrument(name = "Login With Magic Link", skip_all)]
+pub async fn login_with_magic_link(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    client: ClientInfo,
+    jar: CookieJar,
+    Json(request): Json<MagicLinkCallbackRequest>,
+) -> Result<(CookieJar, (StatusCode, Json<LoginResponse>)), AuthApiError> {
+    let token = MagicLinkToken::parse(request.token).map_err(|_| AuthApiError::InvalidToken)?;
+
+    // Without the nonce cookie this isn't the browser that asked for the link
+    let nonce = jar
+        .get(MAGIC_LINK_NONCE_COOKIE_NAME)
+        .and_then(|cookie| MagicLinkNonce::parse(cookie.value().to_owned()).ok())
+        .ok_or(AuthApiError::InvalidToken)?;
+
+    let email = match state
+        .magic_link_token_store
+        .write()
+        .await
+        .take_token(&token, &nonce)
+        .await
+    {
+        Ok(email) => email,
+        Err(MagicLinkTokenStoreError::TokenNotFound | MagicLinkTokenStoreError::NonceMismatch) => {
+            let event = AuditEvent::LoginFailed {
+                method: LoginMethod::MagicLink,
+            };
+            audit.record(&state.audit_sink, None, event).await;
+            return Err(AuthApiError::InvalidToken);
+        }
+        Err(e) => return Err(AuthApiError::UnexpectedError(e.into())),
+    };
+
+    let user = state
+        .user_store
+        .read()
+        .await
+        .get_user(&email)
+        .await
+        .map_err(|_| AuthApiError::InvalidToken)?;
+
+    // Following the link proves that the user owns the email address
+    if !user.verified {
+        state
+            .user_store
+            .write()
+            .await
+            .set_verified(&email, true)
+            .await
+            .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    }
+
+    let jar = jar.remove(
+        Cookie::build(MAGIC_LINK_NONCE_COOKIE_NAME)
+            .path(MAGIC_LINK_COOKIE_PATH)
+            .build(),
+    );
+
+    complete_login(
+        &user,
+        None,
+        LoginMethod::MagicLink,
+        &state,
+        &audit,
+        client,
+        jar,
+    )
+    .await
+}
+
+#[derive(Deserialize)]
+pub struct MagicLinkCallbackRequest {
+    pub token: String,
+}
@@ -1,31 +1,66 @@
 use axum::{extract::State, http::StatusCode, response::IntoResponse};
 use axum_extra::extract::{cookie::Cookie, CookieJar};
 use secrecy::Secret;
+use uuid::Uuid;
 
-use crate::utils::auth::validate_token;
-use crate::utils::constants::JWT_COOKIE_NAME;
+use crate::domain::{AuditEvent, Email, RefreshToken};
+use crate::utils::audit::AuditContext;
+use crate::utils::auth::{end_session, revoke_refresh_token, validate_token};
+use crate::utils::constants::{JWT_COOKIE_NAME, REFRESH_TOKEN_COOKIE_NAME};
 use crate::{app_state::AppState, domain::AuthApiError};
 
 pub async fn logout(
     State(state): State<AppState>,
+    audit: AuditContext,
     jar: CookieJar,
 ) -> Result<(CookieJar, impl IntoResponse), AuthApiError> {
     let cookie = jar.get(JWT_COOKIE_NAME).ok_or(AuthApiError::MissingToken)?;
     let token = cookie.value().to_owned();
 
-    validate_token(&token, state.banned_token_store.clone())
-        .await
-        .map_err(|_| AuthApiError::InvalidToken)?;
+    let claims = validate_token(
+        &token,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await
+    .map_err(|_| AuthApiError::InvalidToken)?;
 
     {
         let mut banned_token_store = state.banned_token_store.write().await;
         banned_token_store
             .add_token(Secret::new(token))
             .await
-            .map_err(|e| AuthApiError::UnexpectedError(e))?;
+            .map_err(AuthApiError::UnexpectedError)?;
+    }
+
+    if let Some(session_id) = claims.sid.and_then(|sid| Uuid::parse_str(&sid).ok()) {
+        end_session(
+            &session_id,
+            state.session_store.clone(),
+            state.refresh_token_store.clone(),
+        )
+        .await
+        .map_err(AuthApiError::UnexpectedError)?;
     }
 
-    let updated_jar = jar.remove(Cookie::from(JWT_COOKIE_NAME));
+    // Revoke the whole refresh token family so that the session can't be refreshed
+    if let Some(cookie) = jar.get(REFRESH_TOKEN_COOKIE_NAME) {
+        if let Ok(refresh_token) = RefreshToken::parse(cookie.value().to_owned()) {
+            revoke_refresh_token(&refresh_token, state.refresh_token_store.clone())
+                .await
+                .map_err(AuthApiError::UnexpectedError)?;
+        }
+    }
+
+    let email = Email::parse(Secret::new(claims.sub)).ok();
+    audit
+        .record(&state.audit_sink, email.as_ref(), AuditEvent::Logout)
+        .await;
+
+    let updated_jar = jar
+        .remove(Cookie::from(JWT_COOKIE_NAME))
+        .remove(Cookie::from(REFRESH_TOKEN_COOKIE_NAME));
 
     Ok((updated_jar, StatusCode::OK.into_response()))
 }
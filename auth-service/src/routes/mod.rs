@@ -1,11 +1,85 @@
+mod account_organizations;
+mod admin;
+mod api_keys;
+mod authorize;
+mod change_email;
+mod change_password;
+mod confirm_email_change;
+mod delete_account;
+mod disable_2fa;
+mod enable_2fa;
+mod export_account;
+mod introspect;
+mod jwks;
 mod login;
-mod logout; 
-mod signup; 
-mod verify_2fa; 
+mod login_history;
+mod login_magic_link;
+mod login_magic_link_callback;
+mod logout;
+mod openid_configuration;
+mod password_reset_confirm;
+mod password_reset_request;
+mod recovery_codes;
+mod refresh_token;
+mod resend_verification_email;
+mod revoke;
+mod revoke_all_sessions;
+mod revoke_session;
+mod revoke_session_link;
+mod send_2fa_code;
+mod sessions;
+mod signup;
+mod token;
+mod totp_confirm;
+mod totp_enroll;
+mod userinfo;
+mod verify_2fa;
+mod verify_email;
 mod verify_token;
+mod webauthn_login_finish;
+mod webauthn_login_start;
+mod webauthn_register_finish;
+mod webauthn_register_start;
 
+pub use account_organizations::*;
+pub use admin::*;
+pub use api_keys::*;
+pub use authorize::*;
+pub use change_email::*;
+pub use change_password::*;
+pub use confirm_email_change::*;
+pub use delete_account::*;
+pub use disable_2fa::*;
+pub use enable_2fa::*;
+pub use export_account::*;
+pub use introspect::*;
+pub use jwks::*;
 pub use login::*;
+pub use login_history::*;
+pub use login_magic_link::*;
+pub use login_magic_link_callback::*;
 pub use logout::*;
+pub use openid_configuration::*;
+pub use password_reset_confirm::*;
+pub use password_reset_request::*;
+pub use recovery_codes::*;
+pub use refresh_token::*;
+pub use resend_verification_email::*;
+pub use revoke::*;
+pub use revoke_all_sessions::*;
+pub use revoke_session::*;
+pub use revoke_session_link::*;
+pub use send_2fa_code::*;
+pub use sessions::*;
 pub use signup::*;
-pub use verify_2fa::*; 
+pub use token::*;
+pub use totp_confirm::*;
+pub use totp_enroll::*;
+pub use userinfo::*;
+pub use verify_2fa::*;
+pub use verify_email::*;
 pub use verify_token::*;
+pub use webauthn_login_finish::*;
+pub use webauthn_login_start::*;
+pub use webauthn_register_finish::*;
+pub use webauthn_register_start::*;
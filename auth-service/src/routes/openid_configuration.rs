@@ -0,0 +1,44 @@
+use axum::Json;
+use jsonwebtoken::Algorithm;
+use serde::Serialize;
+
+use crate::utils::{auth::OIDC_SCOPES, constants::OIDC_ISSUER, keyring::KEYRING};
+
+#[tracing::instrument(name = "OpenID Configuration", skip_all)]
+pub async fn openid_configuration() -> Json<OpenIdConfiguration> {
+    let issuer = OIDC_ISSUER.to_owned();
+
+    Json(OpenIdConfiguration {
+        authorization_endpoint: format!("{}/authorize", issuer),
+        token_endpoint: format!("{}/token", issuer),
+        userinfo_endpoint: format!("{}/userinfo", issuer),
+        jwks_uri: format!("{}/.well-known/jwks.json", issuer),
+        issuer,
+        response_types_supported: vec!["code"],
+        grant_types_supported: vec!["authorization_code"],
+        subject_types_supported: vec!["public"],
+        id_token_signing_alg_values_supported: vec![KEYRING.signing_key().algorithm()],
+        scopes_supported: OIDC_SCOPES.to_vec(),
+        token_endpoint_auth_methods_supported: vec!["client_secret_basic", "client_secret_post"],
+        code_challenge_methods_supported: vec!["S256"],
+        claims_supported: vec!["sub", "iss", "aud", "exp", "iat", "nonce", "email"],
+    })
+}
+
+/// OpenID Provider Metadata (OpenID Connect Discovery 1.0 section 3)
+#[derive(Debug, Serialize)]
+pub struct OpenIdConfiguration {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: String,
+    pub jwks_uri: String,
+    pub response_types_supported: Vec<&'static str>,
+    pub grant_types_supported: Vec<&'static str>,
+    pub subject_types_supported: Vec<&'static str>,
+    pub id_token_signing_alg_values_supported: Vec<Algorithm>,
+    pub scopes_supported: Vec<&'static str>,
+    pub token_endpoint_auth_methods_supported: Vec<&'static str>,
+    pub code_challenge_methods_supported: Vec<&'static str>,
+    pub claims_supported: Vec<&'static str>,
+}
@@ -0,0 +1,65 @@
+use axum::{extract::State, http::StatusCode, Json};
+use secrecy::Secret;
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuditEvent, AuthApiError, Password, PasswordResetToken, PasswordResetTokenStoreError,
+    },
+    utils::{audit::AuditContext, auth::revoke_all_user_tokens},
+};
+
+#[tracing::instrument(name = "Confirm Password Reset", skip_all)]
+pub async fn confirm_password_reset(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(request): Json<PasswordResetConfirmRequest>,
+) -> Result<StatusCode, AuthApiError> {
+    // Check the new password first so that a rejected password doesn't use up the token
+    let password =
+        Password::parse(request.password, false).map_err(|_| AuthApiError::InvalidCredentials)?;
+
+    let token = PasswordResetToken::parse(request.token).map_err(|_| AuthApiError::InvalidToken)?;
+
+    let email = match state
+        .password_reset_token_store
+        .write()
+        .await
+        .take_token(&token)
+        .await
+    {
+        Ok(email) => email,
+        Err(PasswordResetTokenStoreError::TokenNotFound) => return Err(AuthApiError::InvalidToken),
+        Err(e) => return Err(AuthApiError::UnexpectedError(e.into())),
+    };
+
+    state
+        .user_store
+        .write()
+        .await
+        .update_password(&email, password)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    revoke_all_user_tokens(
+        &email,
+        state.token_version_store.clone(),
+        state.refresh_token_store.clone(),
+        state.session_store.clone(),
+    )
+    .await
+    .map_err(AuthApiError::UnexpectedError)?;
+
+    audit
+        .record(&state.audit_sink, Some(&email), AuditEvent::PasswordReset)
+        .await;
+
+    Ok(StatusCode::OK)
+}
+
+#[derive(Deserialize)]
+pub struct PasswordResetConfirmRequest {
+    pub token: String,
+    pub password: Secret<String>,
+}
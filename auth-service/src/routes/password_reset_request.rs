@@ -0,0 +1,95 @@
+use axum::{extract::State, http::StatusCode, Json};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, Email, PasswordResetToken},
+    utils::{audit::AuditContext, constants::OIDC_ISSUER},
+};
+
+#[tracing::instrument(name = "Request Password Reset", skip_all)]
+pub async fn request_password_reset(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(request): Json<PasswordResetRequest>,
+) -> Result<(StatusCode, Json<PasswordResetRequestResponse>), AuthApiError> {
+    let email =
+        Email::parse(Secret::new(request.email)).map_err(|_| AuthApiError::InvalidCredentials)?;
+
+    // Respond the same way whether or not the user exists so that this route
+    // can't be used to find out which emails have an account
+    let response = Json(PasswordResetRequestResponse {
+        message: "If an account exists for this email, a password reset link has been sent"
+            .to_owned(),
+    });
+
+    if !state.user_store.read().await.user_exists(&email).await {
+        return Ok((StatusCode::OK, response));
+    }
+
+    send_password_reset_link(
+        &state,
+        &email,
+        "If you didn't ask to reset your password, you can ignore this email.",
+    )
+    .await?;
+
+    audit
+        .record(
+            &state.audit_sink,
+            Some(&email),
+            AuditEvent::PasswordResetRequested,
+        )
+        .await;
+
+    Ok((StatusCode::OK, response))
+}
+
+/// Emails `email` a link for choosing a new password, ending with `note`.
+/// Failing to send it is only logged, so that callers can respond the same way
+/// whether or not the account exists.
+pub(crate) async fn send_password_reset_link(
+    state: &AppState,
+    email: &Email,
+    note: &str,
+) -> Result<(), AuthApiError> {
+    let token = PasswordResetToken::generate_random();
+    state
+        .password_reset_token_store
+        .write()
+        .await
+        .add_token(token.clone(), email.clone())
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    // The issuer is the public URL of the login UI, which finishes the reset
+    let subject = "Reset your Let's Get Rusty password";
+    let content = format!(
+        "Use this link to choose a new password: {}/?password_reset_token={}\n\
+         The link expires in 30 minutes. {}",
+        *OIDC_ISSUER,
+        token.as_ref().expose_secret(),
+        note
+    );
+
+    if let Err(e) = state
+        .email_client
+        .send_email(email, subject, &content)
+        .await
+    {
+        tracing::error!("failed to send password reset email: {:?}", e);
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+pub struct PasswordResetRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct PasswordResetRequestResponse {
+    pub message: String,
+}
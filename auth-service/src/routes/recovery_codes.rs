@@ -0,0 +1,73 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_extra::extract::CookieJar;
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, Email, RecoveryCode},
+    utils::{audit::AuditContext, auth::authenticate_user},
+};
+
+const RECOVERY_CODE_COUNT: usize = 10;
+
+// Generate a new set of recovery codes, invalidating the previous ones
+#[tracing::instrument(name = "Regenerate Recovery Codes", skip_all)]
+pub async fn regenerate_recovery_codes(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let email = authenticate_user(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+
+    let recovery_codes = issue_recovery_codes(&state, &email).await?;
+    audit
+        .record(
+            &state.audit_sink,
+            Some(&email),
+            AuditEvent::RecoveryCodesGenerated,
+        )
+        .await;
+
+    Ok((
+        StatusCode::OK,
+        Json(RecoveryCodesResponse { recovery_codes }),
+    ))
+}
+
+// Replace the recovery codes of `email` and return the new codes. Only hashes
+// are stored, so this is the one time the codes can be shown to the user.
+pub(crate) async fn issue_recovery_codes(
+    state: &AppState,
+    email: &Email,
+) -> Result<Vec<String>, AuthApiError> {
+    let codes: Vec<RecoveryCode> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| RecoveryCode::generate_random())
+        .collect();
+    let plaintext_codes = codes
+        .iter()
+        .map(|code| code.as_ref().expose_secret().to_owned())
+        .collect();
+
+    state
+        .recovery_code_store
+        .write()
+        .await
+        .replace_codes(email, codes)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    Ok(plaintext_codes)
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct RecoveryCodesResponse {
+    #[serde(rename = "recoveryCodes")]
+    pub recovery_codes: Vec<String>,
+}
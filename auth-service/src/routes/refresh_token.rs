@@ -0,0 +1,127 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use axum_extra::extract::CookieJar;
+use chrono::Utc;
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuditEvent, AuthApiError, RefreshToken, RefreshTokenStoreError, SessionStoreError,
+        UserStoreError,
+    },
+    routes::tenant_membership,
+    utils::{
+        audit::AuditContext,
+        auth::{generate_auth_cookie, generate_refresh_cookie, get_user_access},
+        constants::REFRESH_TOKEN_COOKIE_NAME,
+    },
+};
+
+#[tracing::instrument(name = "Refresh Token", skip_all)]
+pub async fn refresh_token(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    jar: CookieJar,
+) -> Result<(CookieJar, impl IntoResponse), AuthApiError> {
+    let cookie = jar
+        .get(REFRESH_TOKEN_COOKIE_NAME)
+        .ok_or(AuthApiError::MissingToken)?;
+
+    let token =
+        RefreshToken::parse(cookie.value().to_owned()).map_err(|_| AuthApiError::InvalidToken)?;
+
+    let result = state
+        .refresh_token_store
+        .write()
+        .await
+        .use_token(&token)
+        .await;
+    let data = match result {
+        Ok(data) => data,
+        Err(RefreshTokenStoreError::TokenReused(family_id)) => {
+            // A used token came back, so the family has leaked. Revoke all of it.
+            tracing::warn!("refresh token reused, revoking family {}", family_id);
+            state
+                .refresh_token_store
+                .write()
+                .await
+                .revoke_family(&family_id)
+                .await
+                .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+            // The family shares its ID with the session it was issued for
+            let session = state
+                .session_store
+                .read()
+                .await
+                .get_session(&family_id)
+                .await;
+            let email = session.ok().map(|session| session.email);
+            audit
+                .record(
+                    &state.audit_sink,
+                    email.as_ref(),
+                    AuditEvent::RefreshTokenReused,
+                )
+                .await;
+            return Err(AuthApiError::InvalidToken);
+        }
+        Err(RefreshTokenStoreError::UnexpectedError(e)) => {
+            return Err(AuthApiError::UnexpectedError(e));
+        }
+        Err(_) => return Err(AuthApiError::InvalidToken),
+    };
+
+    // The session may have been ended from another device. Refreshing keeps it
+    // alive otherwise.
+    let session_id = data.family_id;
+    match state
+        .session_store
+        .write()
+        .await
+        .touch_session(&session_id, Utc::now().timestamp())
+        .await
+    {
+        Ok(()) => {}
+        Err(SessionStoreError::SessionNotFound) => return Err(AuthApiError::InvalidToken),
+        Err(e) => return Err(AuthApiError::UnexpectedError(e.into())),
+    }
+
+    let token_version = state
+        .token_version_store
+        .read()
+        .await
+        .get_token_version(&data.email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    // Whether the user is an admin and their roles are looked up again, so that
+    // changes to them reach the session's next access token
+    let user = state
+        .user_store
+        .read()
+        .await
+        .get_user(&data.email)
+        .await
+        .map_err(|e| match e {
+            UserStoreError::UserNotFound => AuthApiError::InvalidToken,
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?;
+    // So is their membership of the organization the session is logged in to
+    let tenant = tenant_membership(&state, &data.email, data.tenant_id.as_deref())
+        .await
+        .map_err(|e| match e {
+            AuthApiError::Forbidden => AuthApiError::InvalidToken,
+            e => e,
+        })?;
+    let access = get_user_access(&user, tenant, state.role_store.clone())
+        .await
+        .map_err(AuthApiError::UnexpectedError)?;
+    let auth_cookie = generate_auth_cookie(&data.email, &session_id, token_version, &access)
+        .map_err(AuthApiError::UnexpectedError)?;
+    let refresh_cookie = generate_refresh_cookie(data, state.refresh_token_store.clone())
+        .await
+        .map_err(AuthApiError::UnexpectedError)?;
+
+    let updated_jar = jar.add(auth_cookie).add(refresh_cookie);
+
+    Ok((updated_jar, StatusCode::OK.into_response()))
+}
@@ -0,0 +1,54 @@
+use axum::{extract::State, http::StatusCode, Json};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, Email},
+    routes::send_verification_email,
+    utils::audit::AuditContext,
+};
+
+#[tracing::instrument(name = "Resend Verification Email", skip_all)]
+pub async fn resend_verification_email(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(request): Json<ResendVerificationEmailRequest>,
+) -> Result<(StatusCode, Json<ResendVerificationEmailResponse>), AuthApiError> {
+    let email =
+        Email::parse(Secret::new(request.email)).map_err(|_| AuthApiError::InvalidCredentials)?;
+
+    // Respond the same way whether or not the user exists or is already verified
+    let response = Json(ResendVerificationEmailResponse {
+        message: "If this email has an unverified account, a verification link has been sent"
+            .to_owned(),
+    });
+
+    let user = state.user_store.read().await.get_user(&email).await;
+    if let Ok(user) = user {
+        if !user.verified {
+            if let Err(e) = send_verification_email(&state, &email).await {
+                tracing::error!("failed to send verification email: {:?}", e);
+            }
+            audit
+                .record(
+                    &state.audit_sink,
+                    Some(&email),
+                    AuditEvent::VerificationEmailSent,
+                )
+                .await;
+        }
+    }
+
+    Ok((StatusCode::OK, response))
+}
+
+#[derive(Deserialize)]
+pub struct ResendVerificationEmailRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ResendVerificationEmailResponse {
+    pub message: String,
+}
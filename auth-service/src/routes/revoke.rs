@@ -0,0 +1,68 @@
+use axum::{extract::State, http::HeaderMap, http::StatusCode, Form};
+use secrecy::Secret;
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, Email, OAuthError, RefreshToken},
+    utils::{
+        audit::AuditContext,
+        auth::{authenticate_client, revoke_refresh_token, validate_oauth_token},
+    },
+};
+
+#[tracing::instrument(name = "Revoke Token", skip_all)]
+pub async fn revoke(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    headers: HeaderMap,
+    Form(request): Form<RevokeRequest>,
+) -> Result<StatusCode, OAuthError> {
+    authenticate_client(
+        &headers,
+        request.client_id,
+        request.client_secret,
+        state.oauth_client_store.clone(),
+    )
+    .await?;
+
+    // Refresh tokens are opaque, so anything that parses as one is treated as one.
+    // Invalid or already revoked tokens still get a 200 (RFC 7009 section 2.2).
+    if let Ok(refresh_token) = RefreshToken::parse(request.token.clone()) {
+        revoke_refresh_token(&refresh_token, state.refresh_token_store.clone())
+            .await
+            .map_err(OAuthError::UnexpectedError)?;
+        audit
+            .record(&state.audit_sink, None, AuditEvent::TokenRevoked)
+            .await;
+    } else if let Ok(claims) = validate_oauth_token(
+        &request.token,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await
+    {
+        state
+            .banned_token_store
+            .write()
+            .await
+            .add_token(Secret::new(request.token))
+            .await
+            .map_err(OAuthError::UnexpectedError)?;
+        let email = Email::parse(Secret::new(claims.sub)).ok();
+        audit
+            .record(&state.audit_sink, email.as_ref(), AuditEvent::TokenRevoked)
+            .await;
+    }
+
+    Ok(StatusCode::OK)
+}
+
+#[derive(Deserialize)]
+pub struct RevokeRequest {
+    pub token: String,
+    pub token_type_hint: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<Secret<String>>,
+}
@@ -0,0 +1,51 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use axum_extra::extract::{cookie::Cookie, CookieJar};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError},
+    utils::{
+        audit::AuditContext,
+        auth::{authenticate_user, revoke_all_user_tokens},
+        constants::{JWT_COOKIE_NAME, REFRESH_TOKEN_COOKIE_NAME},
+    },
+};
+
+// Log the user out everywhere, including on this device
+#[tracing::instrument(name = "Revoke All Sessions", skip_all)]
+pub async fn revoke_all_sessions(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    jar: CookieJar,
+) -> Result<(CookieJar, impl IntoResponse), AuthApiError> {
+    let email = authenticate_user(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+
+    revoke_all_user_tokens(
+        &email,
+        state.token_version_store.clone(),
+        state.refresh_token_store.clone(),
+        state.session_store.clone(),
+    )
+    .await
+    .map_err(AuthApiError::UnexpectedError)?;
+
+    audit
+        .record(
+            &state.audit_sink,
+            Some(&email),
+            AuditEvent::SessionRevoked { session_id: None },
+        )
+        .await;
+
+    let updated_jar = jar
+        .remove(Cookie::build(JWT_COOKIE_NAME).path("/"))
+        .remove(Cookie::build(REFRESH_TOKEN_COOKIE_NAME).path("/"));
+
+    Ok((updated_jar, StatusCode::OK))
+}
@@ -0,0 +1,82 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use axum_extra::extract::{cookie::Cookie, CookieJar};
+use secrecy::Secret;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, Email, SessionStoreError},
+    utils::{
+        audit::AuditContext,
+        auth::{authenticate_claims, end_session},
+        constants::{JWT_COOKIE_NAME, REFRESH_TOKEN_COOKIE_NAME},
+    },
+};
+
+// Log the user out on one of their devices
+#[tracing::instrument(name = "Revoke Session", skip_all)]
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(session_id): Path<String>,
+    jar: CookieJar,
+) -> Result<(CookieJar, impl IntoResponse), AuthApiError> {
+    let claims = authenticate_claims(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+    let email = Email::parse(Secret::new(claims.sub)).map_err(|_| AuthApiError::InvalidToken)?;
+
+    let session_id = Uuid::parse_str(&session_id).map_err(|_| AuthApiError::SessionNotFound)?;
+
+    // Other users' sessions look the same as ones that don't exist
+    let session = state
+        .session_store
+        .read()
+        .await
+        .get_session(&session_id)
+        .await
+        .map_err(|e| match e {
+            SessionStoreError::SessionNotFound => AuthApiError::SessionNotFound,
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?;
+    if session.email != email {
+        return Err(AuthApiError::SessionNotFound);
+    }
+
+    end_session(
+        &session_id,
+        state.session_store.clone(),
+        state.refresh_token_store.clone(),
+    )
+    .await
+    .map_err(AuthApiError::UnexpectedError)?;
+
+    audit
+        .record(
+            &state.audit_sink,
+            Some(&email),
+            AuditEvent::SessionRevoked {
+                session_id: Some(session_id),
+            },
+        )
+        .await;
+
+    // The cookies were set for every path, so removing them has to say so
+    // rather than default to this route's
+    let updated_jar = if claims.sid == Some(session_id.to_string()) {
+        jar.remove(Cookie::build(JWT_COOKIE_NAME).path("/"))
+            .remove(Cookie::build(REFRESH_TOKEN_COOKIE_NAME).path("/"))
+    } else {
+        jar
+    };
+
+    Ok((updated_jar, StatusCode::OK))
+}
@@ -0,0 +1,64 @@
+use axum::{extract::State, http::StatusCode, Json};
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, SessionStoreError},
+    utils::{
+        audit::AuditContext,
+        auth::{end_session, validate_session_revocation_token},
+    },
+};
+
+// Log out the session named in the link of a new sign-in notice. The link works
+// without being logged in, since the user may not be anywhere else.
+#[tracing::instrument(name = "Revoke Session From Link", skip_all)]
+pub async fn revoke_session_from_link(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(request): Json<RevokeSessionLinkRequest>,
+) -> Result<StatusCode, AuthApiError> {
+    let (email, session_id) = validate_session_revocation_token(&request.token)
+        .map_err(|_| AuthApiError::InvalidToken)?;
+
+    // A session that already ended has nothing left to revoke
+    let session = match state
+        .session_store
+        .read()
+        .await
+        .get_session(&session_id)
+        .await
+    {
+        Ok(session) => session,
+        Err(SessionStoreError::SessionNotFound) => return Ok(StatusCode::OK),
+        Err(e) => return Err(AuthApiError::UnexpectedError(e.into())),
+    };
+    if session.email != email {
+        return Err(AuthApiError::InvalidToken);
+    }
+
+    end_session(
+        &session_id,
+        state.session_store.clone(),
+        state.refresh_token_store.clone(),
+    )
+    .await
+    .map_err(AuthApiError::UnexpectedError)?;
+
+    audit
+        .record(
+            &state.audit_sink,
+            Some(&email),
+            AuditEvent::SessionRevoked {
+                session_id: Some(session_id),
+            },
+        )
+        .await;
+
+    Ok(StatusCode::OK)
+}
+
+#[derive(Deserialize)]
+pub struct RevokeSessionLinkRequest {
+    pub token: String,
+}
@@ -0,0 +1,82 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use axum_extra::extract::CookieJar;
+use secrecy::ExposeSecret;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, Email, LoginAttemptId, TwoFACode, TwoFACodeStoreError},
+    utils::{audit::AuditContext, auth::authenticate_user},
+};
+
+// Email a 2FA code to the logged-in user, to be entered when turning 2FA on or off
+#[tracing::instrument(name = "Send 2FA Code", skip_all)]
+pub async fn send_2fa_code(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let email = authenticate_user(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+
+    // There is no login attempt, but the store keeps codes per attempt
+    let two_fa_code = TwoFACode::generate_random();
+    state
+        .two_fa_code_store
+        .write()
+        .await
+        .add_code(
+            email.clone(),
+            LoginAttemptId::generate_random(),
+            two_fa_code.clone(),
+        )
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    let subject = "Your Let's Get Rusty 2FA Code";
+    let content = format!("Your 2FA code is: {}", two_fa_code.as_ref().expose_secret());
+    state
+        .email_client
+        .send_email(&email, subject, &content)
+        .await
+        .map_err(AuthApiError::UnexpectedError)?;
+    audit
+        .record(&state.audit_sink, Some(&email), AuditEvent::TwoFACodeSent)
+        .await;
+
+    Ok(StatusCode::OK)
+}
+
+/// Checks `code` against the last code emailed to `email`, which can only be
+/// used once
+pub(crate) async fn check_emailed_2fa_code(
+    state: &AppState,
+    email: &Email,
+    code: &TwoFACode,
+) -> Result<(), AuthApiError> {
+    let (_, emailed_code) = state
+        .two_fa_code_store
+        .read()
+        .await
+        .get_code(email)
+        .await
+        .map_err(|e| match e {
+            TwoFACodeStoreError::LoginAttemptIdNotFound => AuthApiError::IncorrectCredentials,
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?;
+    if code != &emailed_code {
+        return Err(AuthApiError::IncorrectCredentials);
+    }
+
+    state
+        .two_fa_code_store
+        .write()
+        .await
+        .remove_code(email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))
+}
@@ -0,0 +1,76 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_extra::extract::CookieJar;
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuthApiError, Email, Session},
+    utils::auth::authenticate_claims,
+};
+
+// List the devices the user is logged in on
+#[tracing::instrument(name = "List Sessions", skip_all)]
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let claims = authenticate_claims(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+    let email = Email::parse(Secret::new(claims.sub)).map_err(|_| AuthApiError::InvalidToken)?;
+
+    let sessions = state
+        .session_store
+        .read()
+        .await
+        .get_sessions(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    let sessions = sessions
+        .into_iter()
+        .map(|session| SessionResponse::new(session, claims.sid.as_deref()))
+        .collect();
+
+    Ok((StatusCode::OK, Json(SessionsResponse { sessions })))
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct SessionsResponse {
+    pub sessions: Vec<SessionResponse>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct SessionResponse {
+    pub id: String,
+    pub device: String,
+    #[serde(rename = "userAgent")]
+    pub user_agent: Option<String>,
+    pub ip: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+    #[serde(rename = "lastSeenAt")]
+    pub last_seen_at: i64,
+    /// Whether this is the session the request was made with
+    pub current: bool,
+}
+
+impl SessionResponse {
+    pub(crate) fn new(session: Session, current_session_id: Option<&str>) -> Self {
+        let id = session.id.to_string();
+        Self {
+            current: current_session_id == Some(id.as_str()),
+            id,
+            device: session.device,
+            user_agent: session.user_agent,
+            ip: session.ip.to_string(),
+            created_at: session.created_at,
+            last_seen_at: session.last_seen_at,
+        }
+    }
+}
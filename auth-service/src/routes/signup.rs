@@ -4,32 +4,43 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     app_state::AppState,
-    domain::{AuthApiError, Email, Password, User, UserStoreError},
+    domain::{AuditEvent, AuthApiError, Email, Password, User, UserStoreError},
+    routes::send_verification_email,
+    utils::audit::AuditContext,
 };
 
 #[tracing::instrument(name = "Signup", skip_all)]
 pub async fn signup(
     State(state): State<AppState>,
+    audit: AuditContext,
     Json(request): Json<SignupRequest>,
 ) -> Result<impl IntoResponse, AuthApiError> {
     let email = Email::parse(request.email.into()).map_err(|_| AuthApiError::InvalidCredentials)?;
     let password =
         Password::parse(request.password, false).map_err(|_| AuthApiError::InvalidCredentials)?;
 
-    let user = User::new(email, password, request.requires_2fa);
+    let user = User::new(email.clone(), password, request.requires_2fa);
     let result = state.user_store.write().await.add_user(user).await;
     match result {
-        Ok(_) => Ok((
-            StatusCode::CREATED,
-            Json(SignupResponse {
-                message: "User created successfully.".to_string(),
-            }),
-        )),
-        Err(UserStoreError::UserAlreadyExists) => Err(AuthApiError::UserAlreadyExists),
-        Err(e) => {
-            eprintln!("Error adding user: {}", e);
-            Err(AuthApiError::UnexpectedError(e.into()))
+        Ok(_) => {
+            audit
+                .record(&state.audit_sink, Some(&email), AuditEvent::Signup)
+                .await;
+
+            // The account exists either way; the user can ask for another email
+            if let Err(e) = send_verification_email(&state, &email).await {
+                tracing::error!("failed to send verification email: {:?}", e);
+            }
+
+            Ok((
+                StatusCode::CREATED,
+                Json(SignupResponse {
+                    message: "User created successfully.".to_string(),
+                }),
+            ))
         }
+        Err(UserStoreError::UserAlreadyExists) => Err(AuthApiError::UserAlreadyExists),
+        Err(e) => Err(AuthApiError::UnexpectedError(e.into())),
     }
 }
 
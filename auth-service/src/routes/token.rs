@@ -0,0 +1,144 @@
+use axum::{
+    extract::State,
+    http::{header::CACHE_CONTROL, HeaderMap},
+    response::IntoResponse,
+    Form, Json,
+};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthorizationCode, AuthorizationCodeStoreError, OAuthError, UserAccess},
+    utils::{
+        audit::AuditContext,
+        auth::{
+            authenticate_client, generate_id_token, generate_scoped_auth_token, get_user_access,
+            verify_code_challenge, OIDC_SCOPES, TOKEN_TTL_SECONDS,
+        },
+    },
+};
+
+#[tracing::instrument(name = "Token", skip_all)]
+pub async fn token(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    headers: HeaderMap,
+    Form(request): Form<TokenRequest>,
+) -> Result<impl IntoResponse, OAuthError> {
+    let client = authenticate_client(
+        &headers,
+        request.client_id,
+        request.client_secret,
+        state.oauth_client_store.clone(),
+    )
+    .await?;
+
+    if request.grant_type != "authorization_code" {
+        return Err(OAuthError::UnsupportedGrantType);
+    }
+
+    let code = request
+        .code
+        .and_then(|code| AuthorizationCode::parse(code).ok())
+        .ok_or(OAuthError::InvalidGrant)?;
+
+    let data = match state
+        .authorization_code_store
+        .write()
+        .await
+        .take_code(&code)
+        .await
+    {
+        Ok(data) => data,
+        Err(AuthorizationCodeStoreError::CodeNotFound) => return Err(OAuthError::InvalidGrant),
+        Err(e) => return Err(OAuthError::UnexpectedError(e.into())),
+    };
+
+    if data.client_id != client.client_id
+        || request.redirect_uri.as_deref() != Some(data.redirect_uri.as_str())
+    {
+        return Err(OAuthError::InvalidGrant);
+    }
+
+    let code_verifier = request.code_verifier.unwrap_or_default();
+    if !verify_code_challenge(&code_verifier, &data.code_challenge) {
+        return Err(OAuthError::InvalidGrant);
+    }
+
+    let user = state
+        .user_store
+        .read()
+        .await
+        .get_user(&data.email)
+        .await
+        .map_err(|_| OAuthError::InvalidGrant)?;
+    let access = get_user_access(&user, None, state.role_store.clone())
+        .await
+        .map_err(OAuthError::UnexpectedError)?;
+    let scope = granted_scope(&data.scope, &access);
+
+    let token_version = state
+        .token_version_store
+        .read()
+        .await
+        .get_token_version(&data.email)
+        .await
+        .map_err(|e| OAuthError::UnexpectedError(e.into()))?;
+    let access_token = generate_scoped_auth_token(&data.email, &scope, token_version)
+        .map_err(OAuthError::UnexpectedError)?;
+    let id_token = generate_id_token(&data.email, &client.client_id, data.nonce)
+        .map_err(OAuthError::UnexpectedError)?;
+
+    audit
+        .record(
+            &state.audit_sink,
+            Some(&data.email),
+            AuditEvent::TokenIssued {
+                client_id: client.client_id,
+            },
+        )
+        .await;
+
+    let response = TokenResponse {
+        access_token,
+        token_type: "Bearer".to_owned(),
+        expires_in: TOKEN_TTL_SECONDS,
+        id_token,
+        scope,
+    };
+
+    Ok(([(CACHE_CONTROL, "no-store")], Json(response)))
+}
+
+// The access token's `scope` claim is trusted for permissions, so a client only
+// gets the OpenID Connect scopes and the permissions the user actually has out
+// of those it asked for at /authorize
+fn granted_scope(requested: &str, access: &UserAccess) -> String {
+    requested
+        .split_whitespace()
+        .filter(|scope| {
+            OIDC_SCOPES.contains(scope) || access.permissions.iter().any(|p| p == scope)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[derive(Deserialize)]
+pub struct TokenRequest {
+    pub grant_type: String,
+    pub code: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub code_verifier: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<Secret<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    pub id_token: String,
+    pub scope: String,
+}
@@ -0,0 +1,100 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_extra::extract::CookieJar;
+use secrecy::Secret;
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuditEvent, AuthApiError, Email, SecondFactorMethod, TotpSecretStoreError, TwoFACode,
+    },
+    routes::{
+        issue_recovery_codes, send_2fa_change_notice, tenant_membership, verify_totp_code,
+        RecoveryCodesResponse,
+    },
+    utils::{
+        audit::AuditContext,
+        auth::{authenticate_claims, restart_session},
+        client_ip::ClientInfo,
+    },
+};
+
+#[tracing::instrument(name = "Confirm TOTP", skip_all)]
+pub async fn confirm_totp(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    client: ClientInfo,
+    jar: CookieJar,
+    Json(request): Json<TotpConfirmRequest>,
+) -> Result<(CookieJar, impl IntoResponse), AuthApiError> {
+    let claims = authenticate_claims(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+    let email = Email::parse(Secret::new(claims.sub)).map_err(|_| AuthApiError::InvalidToken)?;
+    let tenant = tenant_membership(&state, &email, claims.tenant_id.as_deref()).await?;
+
+    let code = TwoFACode::parse(request.code).map_err(|_| AuthApiError::InvalidCredentials)?;
+
+    let enrollment = state
+        .totp_secret_store
+        .read()
+        .await
+        .get_secret(&email)
+        .await
+        .map_err(|e| match e {
+            TotpSecretStoreError::SecretNotFound => AuthApiError::IncorrectCredentials,
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?;
+    if enrollment.confirmed {
+        return Err(AuthApiError::TwoFAAlreadyEnabled);
+    }
+
+    if let Err(e) = verify_totp_code(&state, &email, &enrollment.secret, &code).await {
+        if let AuthApiError::IncorrectCredentials = e {
+            audit
+                .record(&state.audit_sink, Some(&email), AuditEvent::TwoFAFailed)
+                .await;
+        }
+        return Err(e);
+    }
+
+    state
+        .totp_secret_store
+        .write()
+        .await
+        .confirm_secret(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    // Recovery codes keep the account accessible if the authenticator app is lost
+    let recovery_codes = issue_recovery_codes(&state, &email).await?;
+
+    send_2fa_change_notice(&state, &email, true).await;
+    let event = AuditEvent::TwoFAEnabled {
+        method: SecondFactorMethod::AuthenticatorApp,
+    };
+    audit.record(&state.audit_sink, Some(&email), event).await;
+
+    // Sessions elsewhere were started without the second factor
+    let (auth_cookie, refresh_cookie) = restart_session(&state, &email, tenant, client)
+        .await
+        .map_err(AuthApiError::UnexpectedError)?;
+    let updated_jar = jar.add(auth_cookie).add(refresh_cookie);
+
+    Ok((
+        updated_jar,
+        (
+            StatusCode::OK,
+            Json(RecoveryCodesResponse { recovery_codes }),
+        ),
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct TotpConfirmRequest {
+    pub code: String,
+}
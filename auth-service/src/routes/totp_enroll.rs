@@ -0,0 +1,60 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_extra::extract::CookieJar;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, TotpSecret, TotpSecretStoreError},
+    utils::{audit::AuditContext, auth::authenticate_user},
+};
+
+// Start enrolling an authenticator app. The secret only becomes a second factor
+// once a code generated from it is sent to /2fa/totp/confirm.
+#[tracing::instrument(name = "Enroll TOTP", skip_all)]
+pub async fn enroll_totp(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let email = authenticate_user(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+
+    let secret = TotpSecret::generate_random();
+    let response = TotpEnrollResponse {
+        secret: secret.to_base32(),
+        otpauth_uri: secret.otpauth_uri(&email),
+    };
+
+    state
+        .totp_secret_store
+        .write()
+        .await
+        .add_secret(&email, secret)
+        .await
+        .map_err(|e| match e {
+            TotpSecretStoreError::AlreadyEnrolled => AuthApiError::TwoFAAlreadyEnabled,
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?;
+
+    audit
+        .record(
+            &state.audit_sink,
+            Some(&email),
+            AuditEvent::TotpEnrollmentStarted,
+        )
+        .await;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct TotpEnrollResponse {
+    pub secret: String,
+    #[serde(rename = "otpauthUri")]
+    pub otpauth_uri: String,
+}
@@ -0,0 +1,42 @@
+use axum::{extract::State, http::HeaderMap, Json};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{Email, OAuthError},
+    utils::auth::{authenticate_oauth_principal, bearer_token},
+};
+
+#[tracing::instrument(name = "Userinfo", skip_all)]
+pub async fn userinfo(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<UserinfoResponse>, OAuthError> {
+    let token = bearer_token(&headers).ok_or(OAuthError::InvalidToken)?;
+
+    let principal = authenticate_oauth_principal(token, &state)
+        .await
+        .map_err(|_| OAuthError::InvalidToken)?;
+
+    let email =
+        Email::parse(Secret::new(principal.sub.clone())).map_err(|_| OAuthError::InvalidToken)?;
+    let user = state
+        .user_store
+        .read()
+        .await
+        .get_user(&email)
+        .await
+        .map_err(|_| OAuthError::InvalidToken)?;
+
+    Ok(Json(UserinfoResponse {
+        sub: principal.sub,
+        email: user.email.as_ref().expose_secret().to_owned(),
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserinfoResponse {
+    pub sub: String,
+    pub email: String,
+}
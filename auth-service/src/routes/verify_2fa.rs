@@ -5,13 +5,28 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     app_state::AppState,
-    domain::{AuthApiError, Email, LoginAttemptId, TwoFACode},
-    utils::auth::generate_auth_cookie,
+    domain::{
+        AuditEvent, AuthApiError, AuthenticationCredential, Email, LoginAttemptId, LoginMethod,
+        RecoveryCode, RecoveryCodeStoreError, ThrottleKey, TotpSecret, TotpSecretStoreError,
+        TwoFACode,
+    },
+    routes::{finish_login, verify_passkey},
+    utils::{
+        audit::AuditContext,
+        auth::{pending_2fa_removal_cookie, validate_pending_2fa_token},
+        client_ip::ClientInfo,
+        constants::PENDING_2FA_COOKIE_NAME,
+        throttle::{
+            check_lockout, record_failed_attempt, record_failed_attempts, reset_failed_attempts,
+        },
+    },
 };
 
 #[tracing::instrument(name = "Verify 2FA", skip_all)]
 pub async fn verify_2fa(
     State(state): State<AppState>,
+    audit: AuditContext,
+    client: ClientInfo,
     jar: CookieJar,
     Json(request): Json<Verify2FARequest>,
 ) -> Result<(CookieJar, impl IntoResponse), AuthApiError> {
@@ -21,39 +36,192 @@ pub async fn verify_2fa(
     let login_attempt_id = LoginAttemptId::parse(request.login_attempt_id)
         .map_err(|_| AuthApiError::InvalidCredentials)?;
 
-    let code = TwoFACode::parse(request.code).map_err(|_| AuthApiError::InvalidCredentials)?;
+    // A recovery code can be entered in place of the usual code, and a
+    // passkey can be used instead of either
+    let second_factor = match (request.passkey, request.code) {
+        (Some(passkey), _) => SecondFactor::Passkey(passkey),
+        (None, Some(code)) => match TwoFACode::parse(code.clone()) {
+            Ok(code) => SecondFactor::Code(code),
+            Err(_) => RecoveryCode::parse(code)
+                .map(SecondFactor::RecoveryCode)
+                .map_err(|_| AuthApiError::InvalidCredentials)?,
+        },
+        (None, None) => return Err(AuthApiError::InvalidCredentials),
+    };
 
-    let (correct_login_attempt_id, correct_code) = {
-        let mut two_fa_code_store = state.two_fa_code_store.write().await;
+    // Only the browser that got through the first factor can continue
+    let pending_cookie = jar
+        .get(PENDING_2FA_COOKIE_NAME)
+        .ok_or(AuthApiError::MissingToken)?;
+    let (pending_email, tenant_id) = validate_pending_2fa_token(pending_cookie.value())
+        .map_err(|_| AuthApiError::InvalidToken)?;
+    if pending_email != email {
+        return Err(AuthApiError::IncorrectCredentials);
+    }
 
-        let correct_values = two_fa_code_store
-            .get_code(&email)
-            .await
-            .map_err(|_| AuthApiError::IncorrectCredentials)?;
+    let throttle_keys = [
+        ThrottleKey::Email(email.clone()),
+        ThrottleKey::Ip(client.ip),
+    ];
+    check_lockout(&state.login_throttle_store, &throttle_keys).await?;
 
-        let _: () = two_fa_code_store
-            .remove_code(&email)
-            .await
-            .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    let (correct_login_attempt_id, correct_code) = state
+        .two_fa_code_store
+        .read()
+        .await
+        .get_code(&email)
+        .await
+        .map_err(|_| AuthApiError::IncorrectCredentials)?;
 
-        correct_values
+    let result = if login_attempt_id == correct_login_attempt_id {
+        verify_second_factor(&state, &email, second_factor, &correct_code).await
+    } else {
+        Err(AuthApiError::IncorrectCredentials)
     };
 
-    if login_attempt_id != correct_login_attempt_id || code != correct_code {
-        return Err(AuthApiError::IncorrectCredentials);
+    // Wrong guesses count against the pending login attempt, whose code is
+    // thrown away after too many, as well as against the account and client
+    match result {
+        Ok(()) => {}
+        Err(AuthApiError::IncorrectCredentials) => {
+            audit
+                .record(&state.audit_sink, Some(&email), AuditEvent::TwoFAFailed)
+                .await;
+            let attempt_key = ThrottleKey::LoginAttempt(correct_login_attempt_id);
+            let failures = record_failed_attempt(&state.login_throttle_store, &attempt_key).await?;
+            if attempt_key.policy().lockout_seconds(failures).is_some() {
+                remove_two_fa_code(&state, &email).await?;
+            }
+            record_failed_attempts(&state.login_throttle_store, &throttle_keys).await?;
+            return Err(AuthApiError::IncorrectCredentials);
+        }
+        Err(e) => return Err(e),
     }
 
-    let auth_cookie = generate_auth_cookie(&email).map_err(|e| AuthApiError::UnexpectedError(e))?;
-    let updated_jar = jar.add(auth_cookie);
+    audit
+        .record(&state.audit_sink, Some(&email), AuditEvent::TwoFAVerified)
+        .await;
+    remove_two_fa_code(&state, &email).await?;
+    reset_failed_attempts(
+        &state.login_throttle_store,
+        &[
+            ThrottleKey::Email(email.clone()),
+            ThrottleKey::LoginAttempt(login_attempt_id),
+        ],
+    )
+    .await?;
+
+    let (auth_cookie, refresh_cookie) = finish_login(
+        &email,
+        tenant_id.as_deref(),
+        LoginMethod::SecondFactor,
+        &state,
+        &audit,
+        client,
+    )
+    .await?;
+    let updated_jar = jar
+        .remove(pending_2fa_removal_cookie())
+        .add(auth_cookie)
+        .add(refresh_cookie);
 
     Ok((updated_jar, (StatusCode::OK.into_response())))
 }
 
+// Check the second factor the user entered for the pending login
+async fn verify_second_factor(
+    state: &AppState,
+    email: &Email,
+    second_factor: SecondFactor,
+    correct_code: &TwoFACode,
+) -> Result<(), AuthApiError> {
+    match second_factor {
+        SecondFactor::Code(code) => {
+            // Users with a confirmed authenticator app enter its code instead of an emailed one
+            match confirmed_totp_secret(state, email).await? {
+                Some(secret) => verify_totp_code(state, email, &secret, &code).await?,
+                None if &code != correct_code => return Err(AuthApiError::IncorrectCredentials),
+                None => {}
+            }
+        }
+        SecondFactor::RecoveryCode(code) => state
+            .recovery_code_store
+            .write()
+            .await
+            .use_code(email, &code)
+            .await
+            .map_err(|e| match e {
+                RecoveryCodeStoreError::CodeNotFound => AuthApiError::IncorrectCredentials,
+                e => AuthApiError::UnexpectedError(e.into()),
+            })?,
+        // The password was the first factor, so user presence is enough
+        SecondFactor::Passkey(passkey) => {
+            verify_passkey(state, &passkey, Some(email), false).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn remove_two_fa_code(state: &AppState, email: &Email) -> Result<(), AuthApiError> {
+    state
+        .two_fa_code_store
+        .write()
+        .await
+        .remove_code(email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))
+}
+
+enum SecondFactor {
+    Code(TwoFACode),
+    RecoveryCode(RecoveryCode),
+    Passkey(AuthenticationCredential),
+}
+
+/// Returns the authenticator app secret of `email` if the app has been confirmed
+pub(crate) async fn confirmed_totp_secret(
+    state: &AppState,
+    email: &Email,
+) -> Result<Option<TotpSecret>, AuthApiError> {
+    match state.totp_secret_store.read().await.get_secret(email).await {
+        Ok(enrollment) if enrollment.confirmed => Ok(Some(enrollment.secret)),
+        Ok(_) | Err(TotpSecretStoreError::SecretNotFound) => Ok(None),
+        Err(e) => Err(AuthApiError::UnexpectedError(e.into())),
+    }
+}
+
+// Check `code` against the current time step and record the step it matched,
+// so that the same code can't be used twice
+pub(crate) async fn verify_totp_code(
+    state: &AppState,
+    email: &Email,
+    secret: &TotpSecret,
+    code: &TwoFACode,
+) -> Result<(), AuthApiError> {
+    let step = secret
+        .verify(code, TotpSecret::current_step())
+        .ok_or(AuthApiError::IncorrectCredentials)?;
+
+    state
+        .totp_secret_store
+        .write()
+        .await
+        .use_step(email, step)
+        .await
+        .map_err(|e| match e {
+            TotpSecretStoreError::StepAlreadyUsed => AuthApiError::IncorrectCredentials,
+            e => AuthApiError::UnexpectedError(e.into()),
+        })
+}
+
 #[derive(Debug, Deserialize, Serialize, PartialEq)]
 pub struct Verify2FARequest {
     pub email: String,
     #[serde(rename = "loginAttemptId")]
     pub login_attempt_id: String,
     #[serde(rename = "2FACode")]
-    pub code: String,
+    pub code: Option<String>,
+    /// Response to a challenge from /webauthn/login/start
+    pub passkey: Option<AuthenticationCredential>,
 }
@@ -0,0 +1,61 @@
+use axum::{extract::State, http::StatusCode, Json};
+use color_eyre::eyre::Result;
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuditEvent, AuthApiError, Email},
+    utils::{
+        audit::AuditContext,
+        auth::{generate_email_verification_token, validate_email_verification_token},
+        constants::OIDC_ISSUER,
+    },
+};
+
+#[tracing::instrument(name = "Verify Email", skip_all)]
+pub async fn verify_email(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(request): Json<VerifyEmailRequest>,
+) -> Result<StatusCode, AuthApiError> {
+    let email = validate_email_verification_token(&request.token)
+        .map_err(|_| AuthApiError::InvalidToken)?;
+
+    state
+        .user_store
+        .write()
+        .await
+        .set_verified(&email, true)
+        .await
+        .map_err(|_| AuthApiError::InvalidToken)?;
+
+    audit
+        .record(&state.audit_sink, Some(&email), AuditEvent::EmailVerified)
+        .await;
+
+    Ok(StatusCode::OK)
+}
+
+// Email a link that verifies ownership of `email`
+#[tracing::instrument(name = "Send Verification Email", skip_all)]
+pub(crate) async fn send_verification_email(state: &AppState, email: &Email) -> Result<()> {
+    let token = generate_email_verification_token(email)?;
+
+    // The issuer is the public URL of the login UI, which finishes the verification
+    let subject = "Verify your Let's Get Rusty email address";
+    let content = format!(
+        "Use this link to verify your email address: {}/?email_verification_token={}\n\
+         The link expires in 24 hours.",
+        *OIDC_ISSUER, token
+    );
+
+    state
+        .email_client
+        .send_email(email, subject, &content)
+        .await
+}
+
+#[derive(Deserialize)]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
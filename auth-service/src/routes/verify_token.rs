@@ -1,20 +1,61 @@
-use axum::{extract::State, http::StatusCode, Json};
+use axum::{extract::State, Json};
 use serde::Deserialize;
 
-use crate::{app_state::AppState, domain::AuthApiError, utils::auth::validate_token};
+use crate::{
+    app_state::AppState,
+    domain::AuthApiError,
+    utils::auth::{authenticate_principal, Principal},
+};
 
+// Check that an access token or API key is valid and, if the request asks for
+// them, that it was issued for a tenant and carries a role and every permission
+// of a space-separated scope. Returns who the credential belongs to.
 #[tracing::instrument(name = "Verify Token", skip_all)]
 pub async fn verify_token(
     State(state): State<AppState>,
     Json(request): Json<VerifyTokenRequest>,
-) -> Result<StatusCode, AuthApiError> {
-    match validate_token(&request.token, state.banned_token_store.clone()).await {
-        Ok(_) => Ok(StatusCode::OK),
-        Err(_) => Err(AuthApiError::InvalidToken),
+) -> Result<Json<Principal>, AuthApiError> {
+    let principal = authenticate_principal(&request.token, &state)
+        .await
+        .map_err(|_| AuthApiError::InvalidToken)?;
+
+    if let Some(tenant) = &request.tenant {
+        if principal.tenant_id.as_ref() != Some(tenant) {
+            return Err(AuthApiError::Forbidden);
+        }
+    }
+    if let Some(role) = &request.role {
+        if !principal.roles.contains(role) {
+            return Err(AuthApiError::Forbidden);
+        }
+    }
+    if let Some(scope) = &request.scope {
+        if !has_scope(&principal, scope) {
+            return Err(AuthApiError::Forbidden);
+        }
     }
+
+    Ok(Json(principal))
+}
+
+fn has_scope(principal: &Principal, scope: &str) -> bool {
+    let granted: Vec<&str> = principal
+        .scope
+        .as_deref()
+        .map(|scope| scope.split_whitespace().collect())
+        .unwrap_or_default();
+    scope
+        .split_whitespace()
+        .all(|permission| granted.contains(&permission))
 }
 
 #[derive(Deserialize)]
 pub struct VerifyTokenRequest {
     token: String,
+    /// The ID of the organization the token must be issued for
+    tenant: Option<String>,
+    /// A role the token must carry
+    role: Option<String>,
+    /// Space-separated permissions the token must all carry
+    scope: Option<String>,
 }
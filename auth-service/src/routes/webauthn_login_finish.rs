@@ -0,0 +1,129 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_extra::extract::CookieJar;
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuditEvent, AuthApiError, AuthenticationCredential, Email, LoginMethod, WebauthnCeremony,
+        WebauthnChallengeStoreError, WebauthnCredentialStoreError,
+    },
+    routes::finish_login,
+    utils::{
+        audit::AuditContext,
+        client_ip::ClientInfo,
+        constants::{REQUIRE_VERIFIED_EMAIL, WEBAUTHN_ORIGIN, WEBAUTHN_RP_ID},
+    },
+};
+
+// A passkey with user verification is both something the user has and
+// something they know or are, so it replaces the password and any second factor
+#[tracing::instrument(name = "Finish WebAuthn login", skip_all)]
+pub async fn finish_webauthn_login(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    client: ClientInfo,
+    jar: CookieJar,
+    Json(credential): Json<AuthenticationCredential>,
+) -> Result<(CookieJar, impl IntoResponse), AuthApiError> {
+    let email = match verify_passkey(&state, &credential, None, true).await {
+        Ok(email) => email,
+        Err(e) => {
+            if let AuthApiError::IncorrectCredentials = e {
+                let event = AuditEvent::LoginFailed {
+                    method: LoginMethod::Passkey,
+                };
+                audit.record(&state.audit_sink, None, event).await;
+            }
+            return Err(e);
+        }
+    };
+
+    let user = state
+        .user_store
+        .read()
+        .await
+        .get_user(&email)
+        .await
+        .map_err(|_| AuthApiError::IncorrectCredentials)?;
+
+    if *REQUIRE_VERIFIED_EMAIL && !user.verified {
+        return Err(AuthApiError::EmailNotVerified);
+    }
+
+    let (auth_cookie, refresh_cookie) =
+        finish_login(&email, None, LoginMethod::Passkey, &state, &audit, client).await?;
+    let updated_jar = jar.add(auth_cookie).add(refresh_cookie);
+
+    Ok((updated_jar, StatusCode::OK))
+}
+
+// Check the response to a challenge from /webauthn/login/start and return the
+// email of the passkey's owner. When `email` is set, the passkey must be theirs.
+pub(crate) async fn verify_passkey(
+    state: &AppState,
+    credential: &AuthenticationCredential,
+    email: Option<&Email>,
+    require_user_verification: bool,
+) -> Result<Email, AuthApiError> {
+    let challenge = credential
+        .challenge()
+        .map_err(|_| AuthApiError::InvalidCredentials)?;
+    let credential_id = credential
+        .credential_id()
+        .map_err(|_| AuthApiError::InvalidCredentials)?;
+
+    let challenge_data = state
+        .webauthn_challenge_store
+        .write()
+        .await
+        .take_challenge(&challenge)
+        .await
+        .map_err(|e| match e {
+            WebauthnChallengeStoreError::ChallengeNotFound => AuthApiError::IncorrectCredentials,
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?;
+    if challenge_data.ceremony != WebauthnCeremony::Authentication {
+        return Err(AuthApiError::IncorrectCredentials);
+    }
+
+    let stored_credential = state
+        .webauthn_credential_store
+        .read()
+        .await
+        .get_credential(&credential_id)
+        .await
+        .map_err(|e| match e {
+            WebauthnCredentialStoreError::CredentialNotFound => AuthApiError::IncorrectCredentials,
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?;
+
+    // A challenge issued for one user can't be answered with another user's passkey
+    let expected_emails = [challenge_data.email.as_ref(), email];
+    if expected_emails
+        .into_iter()
+        .flatten()
+        .any(|email| *email != stored_credential.email)
+    {
+        return Err(AuthApiError::IncorrectCredentials);
+    }
+
+    let sign_count = credential
+        .verify(
+            &stored_credential,
+            &challenge,
+            &WEBAUTHN_RP_ID,
+            &WEBAUTHN_ORIGIN,
+            require_user_verification,
+        )
+        .map_err(|_| AuthApiError::IncorrectCredentials)?;
+
+    state
+        .webauthn_credential_store
+        .write()
+        .await
+        .update_sign_count(&credential_id, sign_count)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    Ok(stored_credential.email)
+}
@@ -0,0 +1,63 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuthApiError, Email, PublicKeyCredentialRequestOptions, WebauthnCeremony,
+        WebauthnChallenge, WebauthnChallengeData,
+    },
+    utils::constants::WEBAUTHN_RP_ID,
+};
+
+// Start signing in with a passkey, either instead of a password or as the
+// second factor after /login. Without an email the browser offers any passkey
+// it has for the site.
+#[tracing::instrument(name = "Start WebAuthn login", skip_all)]
+pub async fn start_webauthn_login(
+    State(state): State<AppState>,
+    Json(request): Json<WebauthnLoginStartRequest>,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let email = request
+        .email
+        .map(|email| Email::parse(Secret::new(email)))
+        .transpose()
+        .map_err(|_| AuthApiError::InvalidCredentials)?;
+
+    let allowed_credentials = match &email {
+        Some(email) => state
+            .webauthn_credential_store
+            .read()
+            .await
+            .get_credentials(email)
+            .await
+            .map_err(|e| AuthApiError::UnexpectedError(e.into()))?,
+        None => vec![],
+    };
+
+    let challenge = WebauthnChallenge::generate_random();
+    let options =
+        PublicKeyCredentialRequestOptions::new(&challenge, &WEBAUTHN_RP_ID, &allowed_credentials);
+
+    state
+        .webauthn_challenge_store
+        .write()
+        .await
+        .add_challenge(
+            challenge,
+            WebauthnChallengeData {
+                email,
+                ceremony: WebauthnCeremony::Authentication,
+            },
+        )
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    Ok((StatusCode::OK, Json(options)))
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct WebauthnLoginStartRequest {
+    pub email: Option<String>,
+}
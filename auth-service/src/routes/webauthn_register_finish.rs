@@ -0,0 +1,116 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_extra::extract::CookieJar;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuditEvent, AuthApiError, RegistrationCredential, WebauthnCeremony,
+        WebauthnChallengeStoreError, WebauthnCredentialStoreError,
+    },
+    utils::{
+        audit::AuditContext,
+        auth::authenticate_user,
+        constants::{WEBAUTHN_ORIGIN, WEBAUTHN_RP_ID},
+    },
+};
+
+const DEFAULT_CREDENTIAL_NAME: &str = "Passkey";
+const MAX_CREDENTIAL_NAME_LENGTH: usize = 64;
+
+#[tracing::instrument(name = "Finish WebAuthn registration", skip_all)]
+pub async fn finish_webauthn_registration(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    jar: CookieJar,
+    Json(request): Json<WebauthnRegisterFinishRequest>,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let email = authenticate_user(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+
+    let name = match request.name.as_deref().map(str::trim) {
+        None | Some("") => DEFAULT_CREDENTIAL_NAME.to_owned(),
+        Some(name) if name.chars().count() <= MAX_CREDENTIAL_NAME_LENGTH => name.to_owned(),
+        Some(_) => return Err(AuthApiError::InvalidCredentials),
+    };
+
+    let challenge = request
+        .credential
+        .challenge()
+        .map_err(|_| AuthApiError::InvalidCredentials)?;
+    let challenge_data = state
+        .webauthn_challenge_store
+        .write()
+        .await
+        .take_challenge(&challenge)
+        .await
+        .map_err(|e| match e {
+            WebauthnChallengeStoreError::ChallengeNotFound => AuthApiError::InvalidCredentials,
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?;
+
+    // The challenge must have been issued to this user for a registration
+    if challenge_data.ceremony != WebauthnCeremony::Registration
+        || challenge_data.email.as_ref() != Some(&email)
+    {
+        return Err(AuthApiError::InvalidCredentials);
+    }
+
+    let credential = request
+        .credential
+        .verify(
+            &challenge,
+            &WEBAUTHN_RP_ID,
+            &WEBAUTHN_ORIGIN,
+            email.clone(),
+            name,
+        )
+        .map_err(|_| AuthApiError::InvalidCredentials)?;
+    let response = WebauthnCredentialResponse {
+        credential_id: URL_SAFE_NO_PAD.encode(&credential.credential_id),
+        name: credential.name.clone(),
+    };
+
+    state
+        .webauthn_credential_store
+        .write()
+        .await
+        .add_credential(credential)
+        .await
+        .map_err(|e| match e {
+            WebauthnCredentialStoreError::CredentialAlreadyExists => {
+                AuthApiError::InvalidCredentials
+            }
+            e => AuthApiError::UnexpectedError(e.into()),
+        })?;
+
+    audit
+        .record(
+            &state.audit_sink,
+            Some(&email),
+            AuditEvent::PasskeyRegistered,
+        )
+        .await;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct WebauthnRegisterFinishRequest {
+    /// Lets users tell their passkeys apart, e.g. "Work laptop"
+    pub name: Option<String>,
+    pub credential: RegistrationCredential,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct WebauthnCredentialResponse {
+    #[serde(rename = "credentialId")]
+    pub credential_id: String,
+    pub name: String,
+}
@@ -0,0 +1,59 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_extra::extract::CookieJar;
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuthApiError, PublicKeyCredentialCreationOptions, WebauthnCeremony, WebauthnChallenge,
+        WebauthnChallengeData,
+    },
+    utils::{auth::authenticate_user, constants::WEBAUTHN_RP_ID},
+};
+
+// Start registering a passkey for the logged in user. The options are passed
+// to `navigator.credentials.create()` and its result to /webauthn/register/finish.
+#[tracing::instrument(name = "Start WebAuthn registration", skip_all)]
+pub async fn start_webauthn_registration(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, AuthApiError> {
+    let email = authenticate_user(
+        &jar,
+        state.banned_token_store.clone(),
+        state.session_store.clone(),
+        state.token_version_store.clone(),
+    )
+    .await?;
+
+    let existing_credentials = state
+        .webauthn_credential_store
+        .read()
+        .await
+        .get_credentials(&email)
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    let challenge = WebauthnChallenge::generate_random();
+    let options = PublicKeyCredentialCreationOptions::new(
+        &challenge,
+        &email,
+        &WEBAUTHN_RP_ID,
+        &existing_credentials,
+    );
+
+    state
+        .webauthn_challenge_store
+        .write()
+        .await
+        .add_challenge(
+            challenge,
+            WebauthnChallengeData {
+                email: Some(email),
+                ceremony: WebauthnCeremony::Registration,
+            },
+        )
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+
+    Ok((StatusCode::OK, Json(options)))
+}
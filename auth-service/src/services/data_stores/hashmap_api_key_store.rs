@@ -0,0 +1,126 @@
+use uuid::Uuid;
+
+use crate::domain::{ApiKey, ApiKeyStore, ApiKeyStoreError, Email};
+
+#[derive(Default)]
+pub struct HashmapApiKeyStore {
+    // Oldest first
+    keys: Vec<ApiKey>,
+}
+
+#[async_trait::async_trait]
+impl ApiKeyStore for HashmapApiKeyStore {
+    async fn add_key(&mut self, key: ApiKey) -> Result<(), ApiKeyStoreError> {
+        self.keys.push(key);
+        Ok(())
+    }
+
+    async fn get_keys(&self, email: &Email) -> Result<Vec<ApiKey>, ApiKeyStoreError> {
+        Ok(self
+            .keys
+            .iter()
+            .filter(|key| &key.email == email)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_key_by_hash(&self, key_hash: &str) -> Result<ApiKey, ApiKeyStoreError> {
+        self.keys
+            .iter()
+            .find(|key| key.key_hash == key_hash)
+            .cloned()
+            .ok_or(ApiKeyStoreError::KeyNotFound)
+    }
+
+    async fn revoke_key(&mut self, email: &Email, id: &Uuid) -> Result<(), ApiKeyStoreError> {
+        let index = self
+            .keys
+            .iter()
+            .position(|key| &key.id == id && &key.email == email)
+            .ok_or(ApiKeyStoreError::KeyNotFound)?;
+        self.keys.remove(index);
+        Ok(())
+    }
+
+    async fn touch_key(&mut self, id: &Uuid, last_used_at: i64) -> Result<(), ApiKeyStoreError> {
+        let key = self
+            .keys
+            .iter_mut()
+            .find(|key| &key.id == id)
+            .ok_or(ApiKeyStoreError::KeyNotFound)?;
+        key.last_used_at = Some(last_used_at);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+
+    use crate::domain::ApiKeySecret;
+
+    use super::*;
+
+    fn api_key(email: &Email, name: &str) -> (ApiKeySecret, ApiKey) {
+        let secret = ApiKeySecret::generate_random();
+        let key = ApiKey::new(email.clone(), name.to_owned(), vec![], &secret, 30).unwrap();
+        (secret, key)
+    }
+
+    #[tokio::test]
+    async fn test_add_and_get_keys() {
+        let mut store = HashmapApiKeyStore::default();
+        let email = Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let other_email = Email::parse(Secret::new("other@example.com".to_owned())).unwrap();
+        let (secret, ci_key) = api_key(&email, "CI");
+        let (_, deploy_key) = api_key(&email, "Deploy");
+        let (_, other_key) = api_key(&other_email, "CI");
+
+        for key in [&ci_key, &deploy_key, &other_key] {
+            assert_eq!(store.add_key(key.clone()).await, Ok(()));
+        }
+
+        assert_eq!(
+            store.get_keys(&email).await.unwrap(),
+            vec![ci_key.clone(), deploy_key]
+        );
+        assert_eq!(store.get_key_by_hash(&secret.hash()).await, Ok(ci_key));
+        assert_eq!(
+            store
+                .get_key_by_hash(&ApiKeySecret::generate_random().hash())
+                .await,
+            Err(ApiKeyStoreError::KeyNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_revoke_and_touch_key() {
+        let mut store = HashmapApiKeyStore::default();
+        let email = Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let other_email = Email::parse(Secret::new("other@example.com".to_owned())).unwrap();
+        let (secret, key) = api_key(&email, "CI");
+        let _ = store.add_key(key.clone()).await;
+
+        assert_eq!(store.touch_key(&key.id, 1_700_000_000).await, Ok(()));
+        assert_eq!(
+            store
+                .get_key_by_hash(&secret.hash())
+                .await
+                .unwrap()
+                .last_used_at,
+            Some(1_700_000_000)
+        );
+
+        // Users can only revoke their own keys
+        assert_eq!(
+            store.revoke_key(&other_email, &key.id).await,
+            Err(ApiKeyStoreError::KeyNotFound)
+        );
+        assert_eq!(store.revoke_key(&email, &key.id).await, Ok(()));
+        assert_eq!(
+            store.revoke_key(&email, &key.id).await,
+            Err(ApiKeyStoreError::KeyNotFound)
+        );
+        assert_eq!(store.get_keys(&email).await.unwrap(), vec![]);
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use secrecy::ExposeSecret;
+
+use crate::domain::{
+    AuthorizationCode, AuthorizationCodeData, AuthorizationCodeStore, AuthorizationCodeStoreError,
+};
+
+#[derive(Default)]
+pub struct HashmapAuthorizationCodeStore {
+    codes: HashMap<String, AuthorizationCodeData>,
+}
+
+#[async_trait::async_trait]
+impl AuthorizationCodeStore for HashmapAuthorizationCodeStore {
+    async fn add_code(
+        &mut self,
+        code: AuthorizationCode,
+        data: AuthorizationCodeData,
+    ) -> Result<(), AuthorizationCodeStoreError> {
+        self.codes
+            .insert(code.as_ref().expose_secret().to_owned(), data);
+        Ok(())
+    }
+
+    async fn take_code(
+        &mut self,
+        code: &AuthorizationCode,
+    ) -> Result<AuthorizationCodeData, AuthorizationCodeStoreError> {
+        self.codes
+            .remove(code.as_ref().expose_secret())
+            .ok_or(AuthorizationCodeStoreError::CodeNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+
+    use super::*;
+    use crate::domain::Email;
+
+    fn data() -> AuthorizationCodeData {
+        AuthorizationCodeData {
+            client_id: "client".to_owned(),
+            redirect_uri: "https://app.example.com/callback".to_owned(),
+            email: Email::parse(Secret::new("test@example.com".to_owned())).unwrap(),
+            scope: "openid".to_owned(),
+            nonce: Some("nonce".to_owned()),
+            code_challenge: "challenge".to_owned(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_take_code() {
+        let mut store = HashmapAuthorizationCodeStore::default();
+        let code = AuthorizationCode::generate_random();
+        store.add_code(code.clone(), data()).await.unwrap();
+
+        assert_eq!(store.take_code(&code).await, Ok(data()));
+    }
+
+    #[tokio::test]
+    async fn test_take_code_is_single_use() {
+        let mut store = HashmapAuthorizationCodeStore::default();
+        let code = AuthorizationCode::generate_random();
+        store.add_code(code.clone(), data()).await.unwrap();
+        store.take_code(&code).await.unwrap();
+
+        assert_eq!(
+            store.take_code(&code).await,
+            Err(AuthorizationCodeStoreError::CodeNotFound)
+        );
+    }
+}
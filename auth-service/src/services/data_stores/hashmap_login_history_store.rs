@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use crate::domain::{Email, LoginHistoryStore, LoginHistoryStoreError, LoginRecord};
+
+#[derive(Default)]
+pub struct HashmapLoginHistoryStore {
+    // Oldest first
+    logins: HashMap<Email, Vec<LoginRecord>>,
+}
+
+#[async_trait::async_trait]
+impl LoginHistoryStore for HashmapLoginHistoryStore {
+    async fn add_login(&mut self, login: LoginRecord) -> Result<(), LoginHistoryStoreError> {
+        self.logins
+            .entry(login.email.clone())
+            .or_default()
+            .push(login);
+        Ok(())
+    }
+
+    async fn get_logins(&self, email: &Email) -> Result<Vec<LoginRecord>, LoginHistoryStoreError> {
+        Ok(self
+            .logins
+            .get(email)
+            .map(|logins| logins.iter().rev().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn is_new_device(&self, login: &LoginRecord) -> Result<bool, LoginHistoryStoreError> {
+        let Some(logins) = self.logins.get(&login.email) else {
+            return Ok(false);
+        };
+        let known_device = logins.iter().any(|earlier| earlier.device == login.device);
+        let known_ip_range = logins
+            .iter()
+            .any(|earlier| earlier.ip_range() == login.ip_range());
+        Ok(!(known_device && known_ip_range))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::domain::LoginMethod;
+
+    const FIREFOX_ON_LINUX: &str =
+        "Mozilla/5.0 (X11; Linux x86_64; rv:131.0) Gecko/20100101 Firefox/131.0";
+    const SAFARI_ON_IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_6 like Mac OS X) \
+        AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.6 Mobile/15E148 Safari/604.1";
+
+    fn login(email: &Email, ip: &str, user_agent: &str) -> LoginRecord {
+        LoginRecord::new(
+            Uuid::new_v4(),
+            email.clone(),
+            ip.parse().unwrap(),
+            Some(user_agent.to_owned()),
+            LoginMethod::Password,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_get_logins_most_recent_first() {
+        let mut store = HashmapLoginHistoryStore::default();
+        let email = Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let first = login(&email, "203.0.113.1", FIREFOX_ON_LINUX);
+        let second = login(&email, "203.0.113.2", FIREFOX_ON_LINUX);
+
+        store.add_login(first.clone()).await.unwrap();
+        store.add_login(second.clone()).await.unwrap();
+
+        assert_eq!(store.get_logins(&email).await.unwrap(), vec![second, first]);
+    }
+
+    #[tokio::test]
+    async fn test_is_new_device() {
+        let mut store = HashmapLoginHistoryStore::default();
+        let email = Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+
+        // There is nothing to compare the first login with
+        let first = login(&email, "203.0.113.1", FIREFOX_ON_LINUX);
+        assert!(!store.is_new_device(&first).await.unwrap());
+        store.add_login(first).await.unwrap();
+
+        let same_network = login(&email, "203.0.113.99", FIREFOX_ON_LINUX);
+        assert!(!store.is_new_device(&same_network).await.unwrap());
+
+        let other_network = login(&email, "198.51.100.1", FIREFOX_ON_LINUX);
+        assert!(store.is_new_device(&other_network).await.unwrap());
+
+        let other_device = login(&email, "203.0.113.1", SAFARI_ON_IPHONE);
+        assert!(store.is_new_device(&other_device).await.unwrap());
+    }
+}
@@ -0,0 +1,87 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crate::domain::{LoginThrottleStore, LoginThrottleStoreError, ThrottleKey};
+
+#[derive(Default)]
+pub struct HashmapLoginThrottleStore {
+    failures: HashMap<String, (u32, Instant)>,
+    lockouts: HashMap<String, Instant>,
+}
+
+#[async_trait::async_trait]
+impl LoginThrottleStore for HashmapLoginThrottleStore {
+    async fn get_lockout(&self, key: &ThrottleKey) -> Result<Option<u64>, LoginThrottleStoreError> {
+        Ok(self.lockouts.get(&key.id()).and_then(|locked_until| {
+            let remaining = locked_until.checked_duration_since(Instant::now())?;
+            // Round up so that a lockout never reads as 0 seconds
+            Some(remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0))
+        }))
+    }
+
+    async fn record_failure(
+        &mut self,
+        key: &ThrottleKey,
+        window_seconds: u64,
+    ) -> Result<u32, LoginThrottleStoreError> {
+        let now = Instant::now();
+        let expires_at = now + Duration::from_secs(window_seconds);
+        let entry = self.failures.entry(key.id()).or_insert((0, expires_at));
+        if entry.1 <= now {
+            entry.0 = 0;
+        }
+        *entry = (entry.0 + 1, expires_at);
+        Ok(entry.0)
+    }
+
+    async fn lock_out(
+        &mut self,
+        key: &ThrottleKey,
+        seconds: u64,
+    ) -> Result<(), LoginThrottleStoreError> {
+        self.lockouts
+            .insert(key.id(), Instant::now() + Duration::from_secs(seconds));
+        Ok(())
+    }
+
+    async fn reset(&mut self, key: &ThrottleKey) -> Result<(), LoginThrottleStoreError> {
+        self.failures.remove(&key.id());
+        self.lockouts.remove(&key.id());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record_failure_and_lock_out() {
+        let mut store = HashmapLoginThrottleStore::default();
+        let key = ThrottleKey::Ip(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        assert_eq!(store.record_failure(&key, 60).await, Ok(1));
+        assert_eq!(store.record_failure(&key, 60).await, Ok(2));
+        assert_eq!(store.get_lockout(&key).await, Ok(None));
+
+        store.lock_out(&key, 30).await.unwrap();
+        assert_eq!(store.get_lockout(&key).await, Ok(Some(30)));
+
+        store.reset(&key).await.unwrap();
+        assert_eq!(store.get_lockout(&key).await, Ok(None));
+        assert_eq!(store.record_failure(&key, 60).await, Ok(1));
+    }
+
+    #[tokio::test]
+    async fn test_failures_are_forgotten_after_window() {
+        let mut store = HashmapLoginThrottleStore::default();
+        let key = ThrottleKey::Ip(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        assert_eq!(store.record_failure(&key, 0).await, Ok(1));
+        assert_eq!(store.record_failure(&key, 0).await, Ok(1));
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use secrecy::ExposeSecret;
+
+use crate::domain::{
+    Email, MagicLinkNonce, MagicLinkToken, MagicLinkTokenStore, MagicLinkTokenStoreError,
+};
+
+#[derive(Default)]
+pub struct HashmapMagicLinkTokenStore {
+    tokens: HashMap<String, (Email, MagicLinkNonce)>,
+}
+
+#[async_trait::async_trait]
+impl MagicLinkTokenStore for HashmapMagicLinkTokenStore {
+    async fn add_token(
+        &mut self,
+        token: MagicLinkToken,
+        email: Email,
+        nonce: MagicLinkNonce,
+    ) -> Result<(), MagicLinkTokenStoreError> {
+        self.tokens
+            .insert(token.as_ref().expose_secret().to_owned(), (email, nonce));
+        Ok(())
+    }
+
+    async fn take_token(
+        &mut self,
+        token: &MagicLinkToken,
+        nonce: &MagicLinkNonce,
+    ) -> Result<Email, MagicLinkTokenStoreError> {
+        let key = token.as_ref().expose_secret();
+        let (_, expected_nonce) = self
+            .tokens
+            .get(key)
+            .ok_or(MagicLinkTokenStoreError::TokenNotFound)?;
+        if expected_nonce != nonce {
+            return Err(MagicLinkTokenStoreError::NonceMismatch);
+        }
+
+        self.tokens
+            .remove(key)
+            .map(|(email, _)| email)
+            .ok_or(MagicLinkTokenStoreError::TokenNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_take_token() {
+        let mut store = HashmapMagicLinkTokenStore::default();
+        let email = Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let token = MagicLinkToken::generate_random();
+        let nonce = MagicLinkNonce::generate_random();
+        store
+            .add_token(token.clone(), email.clone(), nonce.clone())
+            .await
+            .unwrap();
+
+        // A wrong nonce leaves the token usable
+        assert_eq!(
+            store
+                .take_token(&token, &MagicLinkNonce::generate_random())
+                .await,
+            Err(MagicLinkTokenStoreError::NonceMismatch)
+        );
+        assert_eq!(store.take_token(&token, &nonce).await, Ok(email));
+        assert_eq!(
+            store.take_token(&token, &nonce).await,
+            Err(MagicLinkTokenStoreError::TokenNotFound)
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use secrecy::{ExposeSecret, Secret};
+
+use crate::domain::{OAuthClient, OAuthClientStore, OAuthClientStoreError};
+
+#[derive(Default)]
+pub struct HashmapOAuthClientStore {
+    clients: HashMap<String, OAuthClient>,
+}
+
+#[async_trait::async_trait]
+impl OAuthClientStore for HashmapOAuthClientStore {
+    async fn add_client(&mut self, client: OAuthClient) -> Result<(), OAuthClientStoreError> {
+        if self.clients.contains_key(&client.client_id) {
+            return Err(OAuthClientStoreError::ClientAlreadyExists);
+        }
+        self.clients.insert(client.client_id.clone(), client);
+        Ok(())
+    }
+
+    async fn get_client(&self, client_id: &str) -> Result<OAuthClient, OAuthClientStoreError> {
+        self.clients
+            .get(client_id)
+            .cloned()
+            .ok_or(OAuthClientStoreError::ClientNotFound)
+    }
+
+    async fn validate_client(
+        &self,
+        client_id: &str,
+        client_secret: &Secret<String>,
+    ) -> Result<OAuthClient, OAuthClientStoreError> {
+        let client = self.get_client(client_id).await?;
+
+        if client.client_secret.expose_secret() == client_secret.expose_secret() {
+            Ok(client)
+        } else {
+            Err(OAuthClientStoreError::InvalidCredentials)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> OAuthClient {
+        OAuthClient::new(
+            "client".to_owned(),
+            Secret::new("secret".to_owned()),
+            vec!["https://app.example.com/callback".to_owned()],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_add_client() {
+        let mut store = HashmapOAuthClientStore::default();
+        assert_eq!(store.add_client(client()).await, Ok(()));
+        assert_eq!(
+            store.add_client(client()).await,
+            Err(OAuthClientStoreError::ClientAlreadyExists)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_client() {
+        let mut store = HashmapOAuthClientStore::default();
+        store.add_client(client()).await.unwrap();
+
+        let stored = store.get_client("client").await.unwrap();
+        assert_eq!(stored.redirect_uris, client().redirect_uris);
+        assert_eq!(
+            store.get_client("unknown").await.unwrap_err(),
+            OAuthClientStoreError::ClientNotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_client() {
+        let mut store = HashmapOAuthClientStore::default();
+        store.add_client(client()).await.unwrap();
+
+        assert!(store
+            .validate_client("client", &Secret::new("secret".to_owned()))
+            .await
+            .is_ok());
+        assert_eq!(
+            store
+                .validate_client("client", &Secret::new("wrong".to_owned()))
+                .await
+                .unwrap_err(),
+            OAuthClientStoreError::InvalidCredentials
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+
+use secrecy::ExposeSecret;
+
+use crate::domain::{Email, Membership, Organization, OrganizationStore, OrganizationStoreError};
+
+#[derive(Default)]
+pub struct HashmapOrganizationStore {
+    organizations: BTreeMap<String, Organization>,
+    /// Members of each organization, by email
+    members: BTreeMap<String, BTreeMap<String, Membership>>,
+}
+
+#[async_trait::async_trait]
+impl OrganizationStore for HashmapOrganizationStore {
+    async fn add_organization(
+        &mut self,
+        organization: Organization,
+    ) -> Result<(), OrganizationStoreError> {
+        if self.organizations.contains_key(&organization.id) {
+            return Err(OrganizationStoreError::OrganizationAlreadyExists);
+        }
+        self.members
+            .insert(organization.id.clone(), BTreeMap::new());
+        self.organizations
+            .insert(organization.id.clone(), organization);
+        Ok(())
+    }
+
+    async fn get_organizations(&self) -> Result<Vec<Organization>, OrganizationStoreError> {
+        Ok(self.organizations.values().cloned().collect())
+    }
+
+    async fn delete_organization(&mut self, id: &str) -> Result<(), OrganizationStoreError> {
+        self.organizations
+            .remove(id)
+            .ok_or(OrganizationStoreError::OrganizationNotFound)?;
+        self.members.remove(id);
+        Ok(())
+    }
+
+    async fn add_member(&mut self, membership: Membership) -> Result<(), OrganizationStoreError> {
+        let members = self
+            .members
+            .get_mut(&membership.organization_id)
+            .ok_or(OrganizationStoreError::OrganizationNotFound)?;
+        members.insert(
+            membership.email.as_ref().expose_secret().to_owned(),
+            membership,
+        );
+        Ok(())
+    }
+
+    async fn remove_member(
+        &mut self,
+        organization_id: &str,
+        email: &Email,
+    ) -> Result<(), OrganizationStoreError> {
+        self.members
+            .get_mut(organization_id)
+            .ok_or(OrganizationStoreError::OrganizationNotFound)?
+            .remove(email.as_ref().expose_secret())
+            .ok_or(OrganizationStoreError::MembershipNotFound)?;
+        Ok(())
+    }
+
+    async fn get_membership(
+        &self,
+        organization_id: &str,
+        email: &Email,
+    ) -> Result<Membership, OrganizationStoreError> {
+        self.members
+            .get(organization_id)
+            .and_then(|members| members.get(email.as_ref().expose_secret()))
+            .cloned()
+            .ok_or(OrganizationStoreError::MembershipNotFound)
+    }
+
+    async fn get_members(
+        &self,
+        organization_id: &str,
+    ) -> Result<Vec<Membership>, OrganizationStoreError> {
+        Ok(self
+            .members
+            .get(organization_id)
+            .ok_or(OrganizationStoreError::OrganizationNotFound)?
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    async fn get_memberships(
+        &self,
+        email: &Email,
+    ) -> Result<Vec<Membership>, OrganizationStoreError> {
+        Ok(self
+            .members
+            .values()
+            .filter_map(|members| members.get(email.as_ref().expose_secret()))
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+
+    use crate::domain::OrganizationRole;
+
+    use super::*;
+
+    fn organization(id: &str) -> Organization {
+        Organization::parse(id.to_owned(), id.to_uppercase()).unwrap()
+    }
+
+    fn membership(organization_id: &str, email: &Email, role: OrganizationRole) -> Membership {
+        Membership {
+            organization_id: organization_id.to_owned(),
+            email: email.clone(),
+            role,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_add_and_delete_organization() {
+        let mut store = HashmapOrganizationStore::default();
+
+        assert_eq!(store.add_organization(organization("globex")).await, Ok(()));
+        assert_eq!(store.add_organization(organization("acme")).await, Ok(()));
+        assert_eq!(
+            store.add_organization(organization("acme")).await,
+            Err(OrganizationStoreError::OrganizationAlreadyExists)
+        );
+        assert_eq!(
+            store.get_organizations().await.unwrap(),
+            vec![organization("acme"), organization("globex")]
+        );
+
+        assert_eq!(store.delete_organization("acme").await, Ok(()));
+        assert_eq!(
+            store.delete_organization("acme").await,
+            Err(OrganizationStoreError::OrganizationNotFound)
+        );
+        assert_eq!(
+            store.get_organizations().await.unwrap(),
+            vec![organization("globex")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_add_and_remove_members() {
+        let mut store = HashmapOrganizationStore::default();
+        let email = Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let _ = store.add_organization(organization("acme")).await;
+        let _ = store.add_organization(organization("globex")).await;
+
+        assert_eq!(
+            store
+                .add_member(membership("initech", &email, OrganizationRole::Member))
+                .await,
+            Err(OrganizationStoreError::OrganizationNotFound)
+        );
+        let acme_member = membership("acme", &email, OrganizationRole::Member);
+        let globex_owner = membership("globex", &email, OrganizationRole::Owner);
+        assert_eq!(store.add_member(acme_member.clone()).await, Ok(()));
+        assert_eq!(store.add_member(globex_owner.clone()).await, Ok(()));
+        assert_eq!(
+            store.get_memberships(&email).await.unwrap(),
+            vec![acme_member.clone(), globex_owner.clone()]
+        );
+
+        // Adding a member again changes their role
+        let acme_admin = membership("acme", &email, OrganizationRole::Admin);
+        assert_eq!(store.add_member(acme_admin.clone()).await, Ok(()));
+        assert_eq!(store.get_membership("acme", &email).await, Ok(acme_admin));
+
+        assert_eq!(store.remove_member("acme", &email).await, Ok(()));
+        assert_eq!(
+            store.remove_member("acme", &email).await,
+            Err(OrganizationStoreError::MembershipNotFound)
+        );
+        assert_eq!(
+            store.get_membership("acme", &email).await,
+            Err(OrganizationStoreError::MembershipNotFound)
+        );
+        assert_eq!(store.get_members("acme").await.unwrap(), vec![]);
+
+        // Deleting an organization removes its members
+        let _ = store.delete_organization("globex").await;
+        assert_eq!(store.get_memberships(&email).await.unwrap(), vec![]);
+        assert_eq!(
+            store.get_members("globex").await,
+            Err(OrganizationStoreError::OrganizationNotFound)
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use crate::domain::{
+    RateLimit, RateLimitDecision, RateLimitStore, RateLimitStoreError, TokenBucket,
+};
+
+#[derive(Default)]
+pub struct HashmapRateLimitStore {
+    buckets: HashMap<String, TokenBucket>,
+}
+
+#[async_trait::async_trait]
+impl RateLimitStore for HashmapRateLimitStore {
+    async fn take_token(
+        &mut self,
+        key: &str,
+        limit: &RateLimit,
+    ) -> Result<RateLimitDecision, RateLimitStoreError> {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let bucket = self
+            .buckets
+            .entry(key.to_owned())
+            .or_insert_with(|| TokenBucket::full(limit, now_ms));
+        Ok(bucket.take(limit, now_ms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_take_token() {
+        let mut store = HashmapRateLimitStore::default();
+        let limit = RateLimit::per_hour(2);
+
+        for _ in 0..2 {
+            let result = store.take_token("/signup:ip:127.0.0.1", &limit).await;
+            assert_eq!(result, Ok(RateLimitDecision::Allowed));
+        }
+        let result = store.take_token("/signup:ip:127.0.0.1", &limit).await;
+        assert!(matches!(
+            result,
+            Ok(RateLimitDecision::Limited {
+                retry_after_seconds
+            }) if retry_after_seconds > 1000
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_buckets_are_per_key() {
+        let mut store = HashmapRateLimitStore::default();
+        let limit = RateLimit::per_hour(1);
+
+        let result = store.take_token("/signup:ip:127.0.0.1", &limit).await;
+        assert_eq!(result, Ok(RateLimitDecision::Allowed));
+        let result = store.take_token("/signup:ip:127.0.0.2", &limit).await;
+        assert_eq!(result, Ok(RateLimitDecision::Allowed));
+        let result = store.take_token("/login:ip:127.0.0.1", &limit).await;
+        assert_eq!(result, Ok(RateLimitDecision::Allowed));
+    }
+}
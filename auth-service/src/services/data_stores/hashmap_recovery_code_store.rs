@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use crate::domain::{Email, RecoveryCode, RecoveryCodeStore, RecoveryCodeStoreError};
+
+#[derive(Default)]
+pub struct HashmapRecoveryCodeStore {
+    codes: HashMap<Email, Vec<RecoveryCode>>,
+}
+
+#[async_trait::async_trait]
+impl RecoveryCodeStore for HashmapRecoveryCodeStore {
+    async fn replace_codes(
+        &mut self,
+        email: &Email,
+        codes: Vec<RecoveryCode>,
+    ) -> Result<(), RecoveryCodeStoreError> {
+        self.codes.insert(email.clone(), codes);
+        Ok(())
+    }
+
+    async fn use_code(
+        &mut self,
+        email: &Email,
+        code: &RecoveryCode,
+    ) -> Result<(), RecoveryCodeStoreError> {
+        let codes = self
+            .codes
+            .get_mut(email)
+            .ok_or(RecoveryCodeStoreError::CodeNotFound)?;
+        let index = codes
+            .iter()
+            .position(|stored| stored == code)
+            .ok_or(RecoveryCodeStoreError::CodeNotFound)?;
+        codes.remove(index);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_use_code_is_single_use() {
+        let mut store = HashmapRecoveryCodeStore::default();
+        let email = Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let code = RecoveryCode::generate_random();
+        store
+            .replace_codes(&email, vec![code.clone(), RecoveryCode::generate_random()])
+            .await
+            .unwrap();
+
+        assert_eq!(store.use_code(&email, &code).await, Ok(()));
+        assert_eq!(
+            store.use_code(&email, &code).await,
+            Err(RecoveryCodeStoreError::CodeNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replace_codes() {
+        let mut store = HashmapRecoveryCodeStore::default();
+        let email = Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let old_code = RecoveryCode::generate_random();
+        let new_code = RecoveryCode::generate_random();
+        store
+            .replace_codes(&email, vec![old_code.clone()])
+            .await
+            .unwrap();
+        store
+            .replace_codes(&email, vec![new_code.clone()])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.use_code(&email, &old_code).await,
+            Err(RecoveryCodeStoreError::CodeNotFound)
+        );
+        assert_eq!(store.use_code(&email, &new_code).await, Ok(()));
+    }
+}
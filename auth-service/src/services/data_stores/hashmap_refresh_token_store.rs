@@ -0,0 +1,128 @@
+use std::collections::{HashMap, HashSet};
+
+use secrecy::ExposeSecret;
+use uuid::Uuid;
+
+use crate::domain::{
+    Email, RefreshToken, RefreshTokenData, RefreshTokenStore, RefreshTokenStoreError,
+};
+
+#[derive(Default)]
+pub struct HashmapRefreshTokenStore {
+    tokens: HashMap<String, (RefreshTokenData, bool)>,
+    revoked_families: HashSet<Uuid>,
+}
+
+#[async_trait::async_trait]
+impl RefreshTokenStore for HashmapRefreshTokenStore {
+    async fn add_token(
+        &mut self,
+        token: RefreshToken,
+        data: RefreshTokenData,
+    ) -> Result<(), RefreshTokenStoreError> {
+        self.tokens
+            .insert(token.as_ref().expose_secret().to_owned(), (data, false));
+        Ok(())
+    }
+
+    async fn use_token(
+        &mut self,
+        token: &RefreshToken,
+    ) -> Result<RefreshTokenData, RefreshTokenStoreError> {
+        let (data, used) = self
+            .tokens
+            .get_mut(token.as_ref().expose_secret())
+            .ok_or(RefreshTokenStoreError::TokenNotFound)?;
+
+        if self.revoked_families.contains(&data.family_id) {
+            return Err(RefreshTokenStoreError::TokenRevoked);
+        }
+
+        if *used {
+            return Err(RefreshTokenStoreError::TokenReused(data.family_id));
+        }
+
+        *used = true;
+        Ok(data.clone())
+    }
+
+    async fn revoke_family(&mut self, family_id: &Uuid) -> Result<(), RefreshTokenStoreError> {
+        self.revoked_families.insert(*family_id);
+        Ok(())
+    }
+
+    async fn revoke_user(&mut self, email: &Email) -> Result<(), RefreshTokenStoreError> {
+        let family_ids = self
+            .tokens
+            .values()
+            .filter(|(data, _)| &data.email == email)
+            .map(|(data, _)| data.family_id);
+        self.revoked_families.extend(family_ids);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+
+    use super::*;
+
+    async fn add_token() -> (HashmapRefreshTokenStore, RefreshToken, RefreshTokenData) {
+        let mut store = HashmapRefreshTokenStore::default();
+
+        let email = Email::parse(Secret::new("test@example.com".to_string())).unwrap();
+        let token = RefreshToken::generate_random();
+        let data = RefreshTokenData::new(email, None);
+
+        let result = store.add_token(token.clone(), data.clone()).await;
+        assert!(result.is_ok());
+
+        (store, token, data)
+    }
+
+    #[tokio::test]
+    async fn test_use_token() {
+        let (mut store, token, data) = add_token().await;
+        let result = store.use_token(&token).await;
+        assert_eq!(result, Ok(data));
+    }
+
+    #[tokio::test]
+    async fn test_use_token_twice_is_reuse() {
+        let (mut store, token, data) = add_token().await;
+        let _ = store.use_token(&token).await;
+        let result = store.use_token(&token).await;
+        assert_eq!(
+            result,
+            Err(RefreshTokenStoreError::TokenReused(data.family_id))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_revoke_family() {
+        let (mut store, token, data) = add_token().await;
+        let result = store.revoke_family(&data.family_id).await;
+        assert!(result.is_ok());
+
+        let result = store.use_token(&token).await;
+        assert_eq!(result, Err(RefreshTokenStoreError::TokenRevoked));
+    }
+
+    #[tokio::test]
+    async fn test_revoke_user() {
+        let (mut store, token, data) = add_token().await;
+        let result = store.revoke_user(&data.email).await;
+        assert!(result.is_ok());
+
+        let result = store.use_token(&token).await;
+        assert_eq!(result, Err(RefreshTokenStoreError::TokenRevoked));
+    }
+
+    #[tokio::test]
+    async fn test_use_unknown_token() {
+        let mut store = HashmapRefreshTokenStore::default();
+        let result = store.use_token(&RefreshToken::generate_random()).await;
+        assert_eq!(result, Err(RefreshTokenStoreError::TokenNotFound));
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::domain::{Email, Role, RoleStore, RoleStoreError};
+
+#[derive(Default)]
+pub struct HashmapRoleStore {
+    roles: BTreeMap<String, Role>,
+    assignments: HashMap<Email, BTreeSet<String>>,
+}
+
+#[async_trait::async_trait]
+impl RoleStore for HashmapRoleStore {
+    async fn add_role(&mut self, role: Role) -> Result<(), RoleStoreError> {
+        if self.roles.contains_key(&role.name) {
+            return Err(RoleStoreError::RoleAlreadyExists);
+        }
+        self.roles.insert(role.name.clone(), role);
+        Ok(())
+    }
+
+    async fn get_roles(&self) -> Result<Vec<Role>, RoleStoreError> {
+        Ok(self.roles.values().cloned().collect())
+    }
+
+    async fn delete_role(&mut self, name: &str) -> Result<(), RoleStoreError> {
+        self.roles
+            .remove(name)
+            .ok_or(RoleStoreError::RoleNotFound)?;
+        for roles in self.assignments.values_mut() {
+            roles.remove(name);
+        }
+        Ok(())
+    }
+
+    async fn assign_role(&mut self, email: &Email, name: &str) -> Result<(), RoleStoreError> {
+        if !self.roles.contains_key(name) {
+            return Err(RoleStoreError::RoleNotFound);
+        }
+        self.assignments
+            .entry(email.clone())
+            .or_default()
+            .insert(name.to_owned());
+        Ok(())
+    }
+
+    async fn unassign_role(&mut self, email: &Email, name: &str) -> Result<(), RoleStoreError> {
+        if !self.roles.contains_key(name) {
+            return Err(RoleStoreError::RoleNotFound);
+        }
+        if let Some(roles) = self.assignments.get_mut(email) {
+            roles.remove(name);
+        }
+        Ok(())
+    }
+
+    async fn get_user_roles(&self, email: &Email) -> Result<Vec<Role>, RoleStoreError> {
+        Ok(self
+            .assignments
+            .get(email)
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|name| self.roles.get(name).cloned())
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+
+    use super::*;
+
+    fn role(name: &str) -> Role {
+        Role::parse(
+            name.to_owned(),
+            String::new(),
+            vec![format!("{}:read", name)],
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_add_and_delete_role() {
+        let mut store = HashmapRoleStore::default();
+
+        assert_eq!(store.add_role(role("support")).await, Ok(()));
+        assert_eq!(store.add_role(role("billing")).await, Ok(()));
+        assert_eq!(
+            store.add_role(role("billing")).await,
+            Err(RoleStoreError::RoleAlreadyExists)
+        );
+        assert_eq!(
+            store.get_roles().await.unwrap(),
+            vec![role("billing"), role("support")]
+        );
+
+        assert_eq!(store.delete_role("billing").await, Ok(()));
+        assert_eq!(
+            store.delete_role("billing").await,
+            Err(RoleStoreError::RoleNotFound)
+        );
+        assert_eq!(store.get_roles().await.unwrap(), vec![role("support")]);
+    }
+
+    #[tokio::test]
+    async fn test_assign_and_unassign_role() {
+        let mut store = HashmapRoleStore::default();
+        let email = Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let _ = store.add_role(role("support")).await;
+        let _ = store.add_role(role("billing")).await;
+
+        assert_eq!(
+            store.assign_role(&email, "unknown").await,
+            Err(RoleStoreError::RoleNotFound)
+        );
+        assert_eq!(store.assign_role(&email, "support").await, Ok(()));
+        assert_eq!(store.assign_role(&email, "billing").await, Ok(()));
+        assert_eq!(store.assign_role(&email, "billing").await, Ok(()));
+        assert_eq!(
+            store.get_user_roles(&email).await.unwrap(),
+            vec![role("billing"), role("support")]
+        );
+
+        assert_eq!(store.unassign_role(&email, "support").await, Ok(()));
+        assert_eq!(
+            store.get_user_roles(&email).await.unwrap(),
+            vec![role("billing")]
+        );
+
+        // Deleting a role takes it away from its users
+        let _ = store.delete_role("billing").await;
+        assert_eq!(store.get_user_roles(&email).await.unwrap(), vec![]);
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::domain::{Email, Session, SessionStore, SessionStoreError};
+
+#[derive(Default)]
+pub struct HashmapSessionStore {
+    sessions: HashMap<Uuid, Session>,
+}
+
+#[async_trait::async_trait]
+impl SessionStore for HashmapSessionStore {
+    async fn add_session(&mut self, session: Session) -> Result<(), SessionStoreError> {
+        self.sessions.insert(session.id, session);
+        Ok(())
+    }
+
+    async fn get_session(&self, id: &Uuid) -> Result<Session, SessionStoreError> {
+        self.sessions
+            .get(id)
+            .cloned()
+            .ok_or(SessionStoreError::SessionNotFound)
+    }
+
+    async fn get_sessions(&self, email: &Email) -> Result<Vec<Session>, SessionStoreError> {
+        let mut sessions: Vec<Session> = self
+            .sessions
+            .values()
+            .filter(|session| &session.email == email)
+            .cloned()
+            .collect();
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.last_seen_at));
+        Ok(sessions)
+    }
+
+    async fn touch_session(
+        &mut self,
+        id: &Uuid,
+        last_seen_at: i64,
+    ) -> Result<(), SessionStoreError> {
+        let session = self
+            .sessions
+            .get_mut(id)
+            .ok_or(SessionStoreError::SessionNotFound)?;
+        session.last_seen_at = last_seen_at;
+        Ok(())
+    }
+
+    async fn remove_session(&mut self, id: &Uuid) -> Result<(), SessionStoreError> {
+        self.sessions
+            .remove(id)
+            .map(|_| ())
+            .ok_or(SessionStoreError::SessionNotFound)
+    }
+
+    async fn remove_sessions(&mut self, email: &Email) -> Result<(), SessionStoreError> {
+        self.sessions.retain(|_, session| &session.email != email);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use secrecy::Secret;
+
+    use super::*;
+
+    fn new_session(email: &str) -> Session {
+        let email = Email::parse(Secret::new(email.to_owned())).unwrap();
+        Session::new(
+            Uuid::new_v4(),
+            email,
+            IpAddr::V4(Ipv4Addr::LOCALHOST),
+            Some("curl/8.5.0".to_owned()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_get_sessions() {
+        let mut store = HashmapSessionStore::default();
+        let mut older = new_session("test@example.com");
+        older.last_seen_at -= 60;
+        let newer = new_session("test@example.com");
+        let other = new_session("other@example.com");
+        for session in [older.clone(), newer.clone(), other] {
+            store.add_session(session).await.unwrap();
+        }
+
+        let sessions = store.get_sessions(&newer.email).await.unwrap();
+        assert_eq!(sessions, vec![newer, older]);
+    }
+
+    #[tokio::test]
+    async fn test_touch_and_remove_session() {
+        let mut store = HashmapSessionStore::default();
+        let session = new_session("test@example.com");
+        store.add_session(session.clone()).await.unwrap();
+
+        store
+            .touch_session(&session.id, session.last_seen_at + 60)
+            .await
+            .unwrap();
+        let stored = store.get_session(&session.id).await.unwrap();
+        assert_eq!(stored.last_seen_at, session.last_seen_at + 60);
+
+        store.remove_session(&session.id).await.unwrap();
+        assert_eq!(
+            store.get_session(&session.id).await,
+            Err(SessionStoreError::SessionNotFound)
+        );
+        assert_eq!(
+            store.touch_session(&session.id, 0).await,
+            Err(SessionStoreError::SessionNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_remove_sessions() {
+        let mut store = HashmapSessionStore::default();
+        let session = new_session("test@example.com");
+        let other = new_session("other@example.com");
+        store.add_session(session.clone()).await.unwrap();
+        store.add_session(other.clone()).await.unwrap();
+
+        store.remove_sessions(&session.email).await.unwrap();
+
+        assert!(store.get_sessions(&session.email).await.unwrap().is_empty());
+        assert_eq!(store.get_session(&other.id).await, Ok(other));
+    }
+}
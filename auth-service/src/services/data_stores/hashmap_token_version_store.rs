@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use crate::domain::{Email, TokenVersionStore, TokenVersionStoreError};
+
+#[derive(Default)]
+pub struct HashmapTokenVersionStore {
+    versions: HashMap<Email, u64>,
+}
+
+#[async_trait::async_trait]
+impl TokenVersionStore for HashmapTokenVersionStore {
+    async fn get_token_version(&self, email: &Email) -> Result<u64, TokenVersionStoreError> {
+        Ok(self.versions.get(email).copied().unwrap_or_default())
+    }
+
+    async fn increment_token_version(
+        &mut self,
+        email: &Email,
+    ) -> Result<u64, TokenVersionStoreError> {
+        let version = self.versions.entry(email.clone()).or_default();
+        *version += 1;
+        Ok(*version)
+    }
+
+    async fn remove_token_version(&mut self, email: &Email) -> Result<(), TokenVersionStoreError> {
+        self.versions.remove(email);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_increment_token_version() {
+        let mut store = HashmapTokenVersionStore::default();
+        let email = Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let other = Email::parse(Secret::new("other@example.com".to_owned())).unwrap();
+        assert_eq!(store.get_token_version(&email).await, Ok(0));
+
+        assert_eq!(store.increment_token_version(&email).await, Ok(1));
+        assert_eq!(store.increment_token_version(&email).await, Ok(2));
+        assert_eq!(store.get_token_version(&email).await, Ok(2));
+        assert_eq!(store.get_token_version(&other).await, Ok(0));
+
+        assert_eq!(store.remove_token_version(&email).await, Ok(()));
+        assert_eq!(store.get_token_version(&email).await, Ok(0));
+    }
+}
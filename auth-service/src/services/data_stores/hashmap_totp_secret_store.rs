@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use crate::domain::{Email, TotpEnrollment, TotpSecret, TotpSecretStore, TotpSecretStoreError};
+
+#[derive(Default)]
+pub struct HashmapTotpSecretStore {
+    secrets: HashMap<Email, (TotpEnrollment, Option<u64>)>,
+}
+
+#[async_trait::async_trait]
+impl TotpSecretStore for HashmapTotpSecretStore {
+    async fn add_secret(
+        &mut self,
+        email: &Email,
+        secret: TotpSecret,
+    ) -> Result<(), TotpSecretStoreError> {
+        if let Some((enrollment, _)) = self.secrets.get(email) {
+            if enrollment.confirmed {
+                return Err(TotpSecretStoreError::AlreadyEnrolled);
+            }
+        }
+
+        let enrollment = TotpEnrollment {
+            secret,
+            confirmed: false,
+        };
+        self.secrets.insert(email.clone(), (enrollment, None));
+        Ok(())
+    }
+
+    async fn get_secret(&self, email: &Email) -> Result<TotpEnrollment, TotpSecretStoreError> {
+        self.secrets
+            .get(email)
+            .map(|(enrollment, _)| enrollment.clone())
+            .ok_or(TotpSecretStoreError::SecretNotFound)
+    }
+
+    async fn confirm_secret(&mut self, email: &Email) -> Result<(), TotpSecretStoreError> {
+        let (enrollment, _) = self
+            .secrets
+            .get_mut(email)
+            .ok_or(TotpSecretStoreError::SecretNotFound)?;
+        enrollment.confirmed = true;
+        Ok(())
+    }
+
+    async fn remove_secret(&mut self, email: &Email) -> Result<(), TotpSecretStoreError> {
+        self.secrets.remove(email);
+        Ok(())
+    }
+
+    async fn use_step(&mut self, email: &Email, step: u64) -> Result<(), TotpSecretStoreError> {
+        let (_, last_used_step) = self
+            .secrets
+            .get_mut(email)
+            .ok_or(TotpSecretStoreError::SecretNotFound)?;
+        if last_used_step.is_some_and(|last_used_step| step <= last_used_step) {
+            return Err(TotpSecretStoreError::StepAlreadyUsed);
+        }
+        *last_used_step = Some(step);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_add_secret_after_confirmation() {
+        let mut store = HashmapTotpSecretStore::default();
+        let email = Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+
+        // Enrollment can be restarted until it is confirmed
+        store
+            .add_secret(&email, TotpSecret::generate_random())
+            .await
+            .unwrap();
+        store
+            .add_secret(&email, TotpSecret::generate_random())
+            .await
+            .unwrap();
+        assert!(!store.get_secret(&email).await.unwrap().confirmed);
+
+        store.confirm_secret(&email).await.unwrap();
+        assert!(store.get_secret(&email).await.unwrap().confirmed);
+        assert_eq!(
+            store
+                .add_secret(&email, TotpSecret::generate_random())
+                .await,
+            Err(TotpSecretStoreError::AlreadyEnrolled)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_use_step() {
+        let mut store = HashmapTotpSecretStore::default();
+        let email = Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        assert_eq!(
+            store.use_step(&email, 10).await,
+            Err(TotpSecretStoreError::SecretNotFound)
+        );
+
+        store
+            .add_secret(&email, TotpSecret::generate_random())
+            .await
+            .unwrap();
+        assert_eq!(store.use_step(&email, 10).await, Ok(()));
+        assert_eq!(
+            store.use_step(&email, 10).await,
+            Err(TotpSecretStoreError::StepAlreadyUsed)
+        );
+        assert_eq!(
+            store.use_step(&email, 9).await,
+            Err(TotpSecretStoreError::StepAlreadyUsed)
+        );
+        assert_eq!(store.use_step(&email, 11).await, Ok(()));
+    }
+}
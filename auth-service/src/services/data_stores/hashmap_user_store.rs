@@ -1,12 +1,16 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
 
 use secrecy::ExposeSecret;
 
-use crate::domain::{Email, Password, User, UserStore, UserStoreError};
+use crate::domain::{Email, Password, User, UserPage, UserStore, UserStoreError};
 
 #[derive(Default, Debug)]
 pub struct HashmapUserStore {
     users: HashMap<Email, User>,
+    deletion_requests: HashMap<Email, SystemTime>,
 }
 
 #[async_trait::async_trait]
@@ -49,7 +53,176 @@ impl UserStore for HashmapUserStore {
     }
 
     async fn user_exists(&self, email: &Email) -> bool {
-        self.users.contains_key(&email)
+        self.users.contains_key(email)
+    }
+
+    async fn update_password(
+        &mut self,
+        email: &Email,
+        password: Password,
+    ) -> Result<(), UserStoreError> {
+        let user = self
+            .users
+            .get_mut(email)
+            .ok_or(UserStoreError::UserNotFound)?;
+        user.password = password;
+        user.password_reset_required = false;
+        Ok(())
+    }
+
+    async fn set_verified(&mut self, email: &Email, verified: bool) -> Result<(), UserStoreError> {
+        let user = self
+            .users
+            .get_mut(email)
+            .ok_or(UserStoreError::UserNotFound)?;
+        user.verified = verified;
+        Ok(())
+    }
+
+    async fn set_requires_2fa(
+        &mut self,
+        email: &Email,
+        requires_2fa: bool,
+    ) -> Result<(), UserStoreError> {
+        let user = self
+            .users
+            .get_mut(email)
+            .ok_or(UserStoreError::UserNotFound)?;
+        user.requires_2fa = requires_2fa;
+        Ok(())
+    }
+
+    async fn update_email(
+        &mut self,
+        email: &Email,
+        new_email: &Email,
+    ) -> Result<(), UserStoreError> {
+        if self.user_exists(new_email).await {
+            return Err(UserStoreError::UserAlreadyExists);
+        }
+        let mut user = self
+            .users
+            .remove(email)
+            .ok_or(UserStoreError::UserNotFound)?;
+        user.email = new_email.clone();
+        self.users.insert(new_email.clone(), user);
+        Ok(())
+    }
+
+    async fn delete_user(&mut self, email: &Email) -> Result<(), UserStoreError> {
+        let user = self
+            .users
+            .get_mut(email)
+            .ok_or(UserStoreError::UserNotFound)?;
+        user.pending_deletion = true;
+        self.deletion_requests
+            .entry(email.clone())
+            .or_insert_with(SystemTime::now);
+        Ok(())
+    }
+
+    async fn restore_user(&mut self, email: &Email) -> Result<(), UserStoreError> {
+        let user = self
+            .users
+            .get_mut(email)
+            .ok_or(UserStoreError::UserNotFound)?;
+        user.pending_deletion = false;
+        self.deletion_requests.remove(email);
+        Ok(())
+    }
+
+    async fn purge_deleted_users(
+        &mut self,
+        grace_period: Duration,
+    ) -> Result<Vec<Email>, UserStoreError> {
+        let expired: Vec<Email> = self
+            .deletion_requests
+            .iter()
+            .filter(|(_, requested_at)| requested_at.elapsed().unwrap_or_default() >= grace_period)
+            .map(|(email, _)| email.clone())
+            .collect();
+        for email in &expired {
+            self.deletion_requests.remove(email);
+            self.users.remove(email);
+        }
+        Ok(expired)
+    }
+
+    async fn remove_user(&mut self, email: &Email) -> Result<(), UserStoreError> {
+        self.users
+            .remove(email)
+            .ok_or(UserStoreError::UserNotFound)?;
+        self.deletion_requests.remove(email);
+        Ok(())
+    }
+
+    async fn search_users(
+        &self,
+        search: Option<&str>,
+        offset: u64,
+        limit: u64,
+    ) -> Result<UserPage, UserStoreError> {
+        let search = search.map(str::to_lowercase);
+        let mut users: Vec<&User> = self
+            .users
+            .values()
+            .filter(|user| match &search {
+                Some(search) => user
+                    .email
+                    .as_ref()
+                    .expose_secret()
+                    .to_lowercase()
+                    .contains(search.as_str()),
+                None => true,
+            })
+            .collect();
+        users.sort_by(|a, b| {
+            a.email
+                .as_ref()
+                .expose_secret()
+                .cmp(b.email.as_ref().expose_secret())
+        });
+
+        Ok(UserPage {
+            total: users.len() as u64,
+            users: users
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .cloned()
+                .collect(),
+        })
+    }
+
+    async fn set_admin(&mut self, email: &Email, admin: bool) -> Result<(), UserStoreError> {
+        let user = self
+            .users
+            .get_mut(email)
+            .ok_or(UserStoreError::UserNotFound)?;
+        user.admin = admin;
+        Ok(())
+    }
+
+    async fn set_locked(&mut self, email: &Email, locked: bool) -> Result<(), UserStoreError> {
+        let user = self
+            .users
+            .get_mut(email)
+            .ok_or(UserStoreError::UserNotFound)?;
+        user.locked = locked;
+        Ok(())
+    }
+
+    async fn set_password_reset_required(
+        &mut self,
+        email: &Email,
+        required: bool,
+    ) -> Result<(), UserStoreError> {
+        let user = self
+            .users
+            .get_mut(email)
+            .ok_or(UserStoreError::UserNotFound)?;
+        user.password_reset_required = required;
+        Ok(())
     }
 }
 
@@ -103,4 +276,181 @@ mod tests {
             .await;
         assert_eq!(valid_result, Ok(correct));
     }
+
+    #[tokio::test]
+    async fn test_update_password() {
+        let user1 = user1();
+        let email = user1.email.clone();
+        let new_password =
+            Password::parse(Secret::new("NewPaSSword@123!".to_string()), false).unwrap();
+
+        let mut user_store = HashmapUserStore::default();
+        let _ = user_store.add_user(user1).await;
+
+        let result = user_store
+            .update_password(&email, new_password.clone())
+            .await;
+        assert_eq!(result, Ok(()));
+
+        let valid_result = user_store.validate_user(&email, &new_password).await;
+        assert!(valid_result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_set_verified() {
+        let user1 = user1();
+        let email = user1.email.clone();
+
+        let mut user_store = HashmapUserStore::default();
+        let _ = user_store.add_user(user1).await;
+
+        let result = user_store.set_verified(&email, true).await;
+        assert_eq!(result, Ok(()));
+        assert!(user_store.get_user(&email).await.unwrap().verified);
+    }
+
+    #[tokio::test]
+    async fn test_set_requires_2fa() {
+        let user1 = user1();
+        let email = user1.email.clone();
+
+        let mut user_store = HashmapUserStore::default();
+        let _ = user_store.add_user(user1).await;
+
+        let result = user_store.set_requires_2fa(&email, false).await;
+        assert_eq!(result, Ok(()));
+        assert!(!user_store.get_user(&email).await.unwrap().requires_2fa);
+    }
+
+    #[tokio::test]
+    async fn test_delete_restore_and_purge_user() {
+        let email = user1().email;
+
+        let mut user_store = HashmapUserStore::default();
+        let _ = user_store.add_user(user1()).await;
+
+        assert_eq!(user_store.delete_user(&email).await, Ok(()));
+        assert!(user_store.get_user(&email).await.unwrap().pending_deletion);
+        assert_eq!(
+            user_store
+                .purge_deleted_users(Duration::from_secs(3600))
+                .await,
+            Ok(vec![])
+        );
+
+        assert_eq!(user_store.restore_user(&email).await, Ok(()));
+        assert!(!user_store.get_user(&email).await.unwrap().pending_deletion);
+        assert_eq!(
+            user_store.purge_deleted_users(Duration::ZERO).await,
+            Ok(vec![])
+        );
+
+        let _ = user_store.delete_user(&email).await;
+        assert_eq!(
+            user_store.purge_deleted_users(Duration::ZERO).await,
+            Ok(vec![email.clone()])
+        );
+        assert_eq!(
+            user_store.get_user(&email).await,
+            Err(UserStoreError::UserNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_remove_user() {
+        let email = user1().email;
+
+        let mut user_store = HashmapUserStore::default();
+        let _ = user_store.add_user(user1()).await;
+
+        assert_eq!(user_store.remove_user(&email).await, Ok(()));
+        assert!(!user_store.user_exists(&email).await);
+        assert_eq!(
+            user_store.remove_user(&email).await,
+            Err(UserStoreError::UserNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_users() {
+        let mut user_store = HashmapUserStore::default();
+        for address in ["carol@example.com", "alice@example.com", "bob@test.com"] {
+            let email = Email::parse(Secret::new(address.to_string())).unwrap();
+            let password = Password::parse(Secret::new("PaSSword@123!".to_string()), false);
+            let _ = user_store
+                .add_user(User::new(email, password.unwrap(), false))
+                .await;
+        }
+        let emails = |page: UserPage| {
+            page.users
+                .iter()
+                .map(|user| user.email.as_ref().expose_secret().to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        let page = user_store.search_users(None, 1, 10).await.unwrap();
+        assert_eq!(page.total, 3);
+        assert_eq!(emails(page), ["bob@test.com", "carol@example.com"]);
+
+        let page = user_store
+            .search_users(Some("EXAMPLE"), 0, 1)
+            .await
+            .unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(emails(page), ["alice@example.com"]);
+    }
+
+    #[tokio::test]
+    async fn test_password_reset_required_until_password_updated() {
+        let email = user1().email;
+        let new_password =
+            Password::parse(Secret::new("NewPaSSword@123!".to_string()), false).unwrap();
+
+        let mut user_store = HashmapUserStore::default();
+        let _ = user_store.add_user(user1()).await;
+
+        let result = user_store.set_password_reset_required(&email, true).await;
+        assert_eq!(result, Ok(()));
+        assert!(
+            user_store
+                .get_user(&email)
+                .await
+                .unwrap()
+                .password_reset_required
+        );
+
+        let _ = user_store.update_password(&email, new_password).await;
+        assert!(
+            !user_store
+                .get_user(&email)
+                .await
+                .unwrap()
+                .password_reset_required
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_email() {
+        let email = user1().email;
+        let new_email = Email::parse(Secret::new("new@example.com".to_string())).unwrap();
+
+        let mut user_store = HashmapUserStore::default();
+        let _ = user_store.add_user(user1()).await;
+
+        let result = user_store.update_email(&email, &new_email).await;
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            user_store.get_user(&email).await,
+            Err(UserStoreError::UserNotFound)
+        );
+        assert_eq!(
+            user_store.get_user(&new_email).await.unwrap().email,
+            new_email
+        );
+
+        // Addresses of other accounts can't be taken over
+        let _ = user_store.add_user(user1()).await;
+        let result = user_store.update_email(&email, &new_email).await;
+        assert_eq!(result, Err(UserStoreError::UserAlreadyExists));
+    }
 }
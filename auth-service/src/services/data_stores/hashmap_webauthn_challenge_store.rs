@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use secrecy::ExposeSecret;
+
+use crate::domain::{
+    WebauthnChallenge, WebauthnChallengeData, WebauthnChallengeStore, WebauthnChallengeStoreError,
+};
+
+#[derive(Default)]
+pub struct HashmapWebauthnChallengeStore {
+    challenges: HashMap<String, WebauthnChallengeData>,
+}
+
+#[async_trait::async_trait]
+impl WebauthnChallengeStore for HashmapWebauthnChallengeStore {
+    async fn add_challenge(
+        &mut self,
+        challenge: WebauthnChallenge,
+        data: WebauthnChallengeData,
+    ) -> Result<(), WebauthnChallengeStoreError> {
+        self.challenges
+            .insert(challenge.as_ref().expose_secret().to_owned(), data);
+        Ok(())
+    }
+
+    async fn take_challenge(
+        &mut self,
+        challenge: &WebauthnChallenge,
+    ) -> Result<WebauthnChallengeData, WebauthnChallengeStoreError> {
+        self.challenges
+            .remove(challenge.as_ref().expose_secret())
+            .ok_or(WebauthnChallengeStoreError::ChallengeNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::WebauthnCeremony;
+
+    #[tokio::test]
+    async fn test_take_challenge_is_single_use() {
+        let mut store = HashmapWebauthnChallengeStore::default();
+        let challenge = WebauthnChallenge::generate_random();
+        let data = WebauthnChallengeData {
+            email: None,
+            ceremony: WebauthnCeremony::Authentication,
+        };
+        store
+            .add_challenge(challenge.clone(), data.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(store.take_challenge(&challenge).await, Ok(data));
+        assert_eq!(
+            store.take_challenge(&challenge).await,
+            Err(WebauthnChallengeStoreError::ChallengeNotFound)
+        );
+    }
+}
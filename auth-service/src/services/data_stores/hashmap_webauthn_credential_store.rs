@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use crate::domain::{
+    Email, WebauthnCredential, WebauthnCredentialStore, WebauthnCredentialStoreError,
+};
+
+#[derive(Default)]
+pub struct HashmapWebauthnCredentialStore {
+    credentials: HashMap<Vec<u8>, WebauthnCredential>,
+}
+
+#[async_trait::async_trait]
+impl WebauthnCredentialStore for HashmapWebauthnCredentialStore {
+    async fn add_credential(
+        &mut self,
+        credential: WebauthnCredential,
+    ) -> Result<(), WebauthnCredentialStoreError> {
+        if self.credentials.contains_key(&credential.credential_id) {
+            return Err(WebauthnCredentialStoreError::CredentialAlreadyExists);
+        }
+        self.credentials
+            .insert(credential.credential_id.clone(), credential);
+        Ok(())
+    }
+
+    async fn get_credential(
+        &self,
+        credential_id: &[u8],
+    ) -> Result<WebauthnCredential, WebauthnCredentialStoreError> {
+        self.credentials
+            .get(credential_id)
+            .cloned()
+            .ok_or(WebauthnCredentialStoreError::CredentialNotFound)
+    }
+
+    async fn get_credentials(
+        &self,
+        email: &Email,
+    ) -> Result<Vec<WebauthnCredential>, WebauthnCredentialStoreError> {
+        Ok(self
+            .credentials
+            .values()
+            .filter(|credential| credential.email == *email)
+            .cloned()
+            .collect())
+    }
+
+    async fn update_sign_count(
+        &mut self,
+        credential_id: &[u8],
+        sign_count: u32,
+    ) -> Result<(), WebauthnCredentialStoreError> {
+        let credential = self
+            .credentials
+            .get_mut(credential_id)
+            .ok_or(WebauthnCredentialStoreError::CredentialNotFound)?;
+        credential.sign_count = sign_count;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+
+    use super::*;
+    use crate::domain::CredentialPublicKey;
+
+    fn credential(credential_id: u8, email: &Email) -> WebauthnCredential {
+        WebauthnCredential {
+            credential_id: vec![credential_id; 16],
+            email: email.clone(),
+            public_key: CredentialPublicKey::EdDsa(vec![credential_id; 32]),
+            sign_count: 0,
+            name: "Passkey".to_owned(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_several_credentials_per_user() {
+        let mut store = HashmapWebauthnCredentialStore::default();
+        let email = Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let other_email = Email::parse(Secret::new("other@example.com".to_owned())).unwrap();
+
+        store.add_credential(credential(1, &email)).await.unwrap();
+        store.add_credential(credential(2, &email)).await.unwrap();
+        store
+            .add_credential(credential(3, &other_email))
+            .await
+            .unwrap();
+        assert_eq!(
+            store.add_credential(credential(1, &other_email)).await,
+            Err(WebauthnCredentialStoreError::CredentialAlreadyExists)
+        );
+
+        let mut credential_ids: Vec<_> = store
+            .get_credentials(&email)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|credential| credential.credential_id)
+            .collect();
+        credential_ids.sort();
+        assert_eq!(credential_ids, vec![vec![1; 16], vec![2; 16]]);
+    }
+
+    #[tokio::test]
+    async fn test_update_sign_count() {
+        let mut store = HashmapWebauthnCredentialStore::default();
+        let email = Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        assert_eq!(
+            store.update_sign_count(&[1; 16], 5).await,
+            Err(WebauthnCredentialStoreError::CredentialNotFound)
+        );
+
+        store.add_credential(credential(1, &email)).await.unwrap();
+        store.update_sign_count(&[1; 16], 5).await.unwrap();
+        assert_eq!(store.get_credential(&[1; 16]).await.unwrap().sign_count, 5);
+    }
+}
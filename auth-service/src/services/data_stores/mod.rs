@@ -1,10 +1,61 @@
+pub mod hashmap_api_key_store;
+pub mod hashmap_authorization_code_store;
+pub mod hashmap_login_history_store;
+pub mod hashmap_login_throttle_store;
+pub mod hashmap_magic_link_token_store;
+pub mod hashmap_oauth_client_store;
+pub mod hashmap_organization_store;
+pub mod hashmap_password_reset_token_store;
+pub mod hashmap_rate_limit_store;
+pub mod hashmap_recovery_code_store;
+pub mod hashmap_refresh_token_store;
+pub mod hashmap_role_store;
+pub mod hashmap_session_store;
+pub mod hashmap_token_version_store;
+pub mod hashmap_totp_secret_store;
 pub mod hashmap_two_fa_code_store;
 pub mod hashmap_user_store;
+pub mod hashmap_webauthn_challenge_store;
+pub mod hashmap_webauthn_credential_store;
 pub mod hashset_banned_token_store;
+pub mod postgres_api_key_store;
+pub mod postgres_login_history_store;
+pub mod postgres_oauth_client_store;
+pub mod postgres_organization_store;
+pub mod postgres_recovery_code_store;
+pub mod postgres_role_store;
+pub mod postgres_token_version_store;
+pub mod postgres_totp_secret_store;
 pub mod postgres_user_store;
+pub mod postgres_webauthn_credential_store;
+pub mod redis_authorization_code_store;
 pub mod redis_banned_token_store;
+pub mod redis_login_throttle_store;
+pub mod redis_magic_link_token_store;
+pub mod redis_password_reset_token_store;
+pub mod redis_rate_limit_store;
+pub mod redis_refresh_token_store;
+pub mod redis_session_store;
 pub mod redis_two_fa_code_store;
+pub mod redis_webauthn_challenge_store;
 
+pub use hashmap_api_key_store::*;
+pub use hashmap_authorization_code_store::*;
+pub use hashmap_login_history_store::*;
+pub use hashmap_login_throttle_store::*;
+pub use hashmap_magic_link_token_store::*;
+pub use hashmap_oauth_client_store::*;
+pub use hashmap_organization_store::*;
+pub use hashmap_password_reset_token_store::*;
+pub use hashmap_rate_limit_store::*;
+pub use hashmap_recovery_code_store::*;
+pub use hashmap_refresh_token_store::*;
+pub use hashmap_role_store::*;
+pub use hashmap_session_store::*;
+pub use hashmap_token_version_store::*;
+pub use hashmap_totp_secret_store::*;
 pub use hashmap_two_fa_code_store::*;
 pub use hashmap_user_store::*;
+pub use hashmap_webauthn_challenge_store::*;
+pub use hashmap_webauthn_credential_store::*;
 pub use hashset_banned_token_store::*;
@@ -0,0 +1,167 @@
+use color_eyre::eyre::Report;
+use secrecy::{ExposeSecret, Secret};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::{ApiKey, ApiKeyStore, ApiKeyStoreError, Email};
+
+pub struct PostgresApiKeyStore {
+    pool: PgPool,
+}
+
+impl PostgresApiKeyStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+struct ApiKeyRow {
+    id: String,
+    email: String,
+    name: String,
+    hint: String,
+    key_hash: String,
+    scopes: Vec<String>,
+    created_at: i64,
+    expires_at: i64,
+    last_used_at: Option<i64>,
+}
+
+impl TryFrom<ApiKeyRow> for ApiKey {
+    type Error = Report;
+
+    fn try_from(row: ApiKeyRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id.parse()?,
+            email: Email::parse(Secret::new(row.email))?,
+            name: row.name,
+            hint: row.hint,
+            key_hash: row.key_hash,
+            scopes: row.scopes,
+            created_at: row.created_at,
+            expires_at: row.expires_at,
+            last_used_at: row.last_used_at,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiKeyStore for PostgresApiKeyStore {
+    #[tracing::instrument(name = "Adding API key to PostgreSQL", skip_all)]
+    async fn add_key(&mut self, key: ApiKey) -> Result<(), ApiKeyStoreError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO api_keys
+                (id, email, name, hint, key_hash, scopes, created_at, expires_at)
+            VALUES ($1::TEXT::UUID, $2, $3, $4, $5, $6, to_timestamp($7), to_timestamp($8))
+            "#,
+            key.id.to_string(),
+            key.email.as_ref().expose_secret(),
+            key.name,
+            key.hint,
+            key.key_hash,
+            &key.scopes,
+            key.created_at as f64,
+            key.expires_at as f64
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ApiKeyStoreError::UnexpectedError(e.into()))?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Retrieving API keys from PostgreSQL", skip_all)]
+    async fn get_keys(&self, email: &Email) -> Result<Vec<ApiKey>, ApiKeyStoreError> {
+        sqlx::query_as!(
+            ApiKeyRow,
+            r#"
+            SELECT
+                id::TEXT AS "id!",
+                email,
+                name,
+                hint,
+                key_hash,
+                scopes,
+                EXTRACT(EPOCH FROM created_at)::BIGINT AS "created_at!",
+                EXTRACT(EPOCH FROM expires_at)::BIGINT AS "expires_at!",
+                EXTRACT(EPOCH FROM last_used_at)::BIGINT AS last_used_at
+            FROM api_keys
+            WHERE email = $1
+            ORDER BY created_at, id
+            "#,
+            email.as_ref().expose_secret()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiKeyStoreError::UnexpectedError(e.into()))?
+        .into_iter()
+        .map(ApiKey::try_from)
+        .collect::<Result<_, _>>()
+        .map_err(ApiKeyStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(name = "Retrieving API key from PostgreSQL", skip_all)]
+    async fn get_key_by_hash(&self, key_hash: &str) -> Result<ApiKey, ApiKeyStoreError> {
+        let row = sqlx::query_as!(
+            ApiKeyRow,
+            r#"
+            SELECT
+                id::TEXT AS "id!",
+                email,
+                name,
+                hint,
+                key_hash,
+                scopes,
+                EXTRACT(EPOCH FROM created_at)::BIGINT AS "created_at!",
+                EXTRACT(EPOCH FROM expires_at)::BIGINT AS "expires_at!",
+                EXTRACT(EPOCH FROM last_used_at)::BIGINT AS last_used_at
+            FROM api_keys
+            WHERE key_hash = $1
+            "#,
+            key_hash
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiKeyStoreError::UnexpectedError(e.into()))?
+        .ok_or(ApiKeyStoreError::KeyNotFound)?;
+
+        ApiKey::try_from(row).map_err(ApiKeyStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(name = "Revoking API key in PostgreSQL", skip_all)]
+    async fn revoke_key(&mut self, email: &Email, id: &Uuid) -> Result<(), ApiKeyStoreError> {
+        let result = sqlx::query!(
+            "DELETE FROM api_keys WHERE id = $1::TEXT::UUID AND email = $2",
+            id.to_string(),
+            email.as_ref().expose_secret()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ApiKeyStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(ApiKeyStoreError::KeyNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Updating API key last use in PostgreSQL", skip_all)]
+    async fn touch_key(&mut self, id: &Uuid, last_used_at: i64) -> Result<(), ApiKeyStoreError> {
+        let result = sqlx::query!(
+            "UPDATE api_keys SET last_used_at = to_timestamp($2) WHERE id = $1::TEXT::UUID",
+            id.to_string(),
+            last_used_at as f64
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ApiKeyStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(ApiKeyStoreError::KeyNotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+use color_eyre::eyre::Report;
+use secrecy::{ExposeSecret, Secret};
+use sqlx::PgPool;
+
+use crate::domain::{Email, LoginHistoryStore, LoginHistoryStoreError, LoginMethod, LoginRecord};
+
+pub struct PostgresLoginHistoryStore {
+    pool: PgPool,
+}
+
+impl PostgresLoginHistoryStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+struct LoginRow {
+    session_id: String,
+    email: String,
+    ip: String,
+    user_agent: Option<String>,
+    device: String,
+    method: String,
+    logged_in_at: i64,
+}
+
+impl TryFrom<LoginRow> for LoginRecord {
+    type Error = Report;
+
+    fn try_from(row: LoginRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            session_id: row.session_id.parse()?,
+            email: Email::parse(Secret::new(row.email))?,
+            ip: row.ip.parse()?,
+            user_agent: row.user_agent,
+            device: row.device,
+            method: serde_json::from_value(serde_json::Value::String(row.method))?,
+            logged_in_at: row.logged_in_at,
+        })
+    }
+}
+
+// Methods are stored the way they appear in the API
+fn method_name(method: LoginMethod) -> Result<String, Report> {
+    match serde_json::to_value(method)? {
+        serde_json::Value::String(name) => Ok(name),
+        _ => unreachable!("login methods serialize to strings"),
+    }
+}
+
+#[async_trait::async_trait]
+impl LoginHistoryStore for PostgresLoginHistoryStore {
+    #[tracing::instrument(name = "Adding login to PostgreSQL", skip_all)]
+    async fn add_login(&mut self, login: LoginRecord) -> Result<(), LoginHistoryStoreError> {
+        let method = method_name(login.method).map_err(LoginHistoryStoreError::UnexpectedError)?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO login_history
+                (session_id, email, ip, ip_range, user_agent, device, method, logged_in_at)
+            VALUES ($1::TEXT::UUID, $2, $3::TEXT::INET, $4, $5, $6, $7, to_timestamp($8))
+            "#,
+            login.session_id.to_string(),
+            login.email.as_ref().expose_secret(),
+            login.ip.to_string(),
+            login.ip_range(),
+            login.user_agent,
+            login.device,
+            method,
+            login.logged_in_at as f64
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| LoginHistoryStoreError::UnexpectedError(e.into()))?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Retrieving logins from PostgreSQL", skip_all)]
+    async fn get_logins(&self, email: &Email) -> Result<Vec<LoginRecord>, LoginHistoryStoreError> {
+        sqlx::query_as!(
+            LoginRow,
+            r#"
+            SELECT
+                session_id::TEXT AS "session_id!",
+                email,
+                host(ip) AS "ip!",
+                user_agent,
+                device,
+                method,
+                EXTRACT(EPOCH FROM logged_in_at)::BIGINT AS "logged_in_at!"
+            FROM login_history
+            WHERE email = $1
+            ORDER BY id DESC
+            "#,
+            email.as_ref().expose_secret()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| LoginHistoryStoreError::UnexpectedError(e.into()))?
+        .into_iter()
+        .map(LoginRecord::try_from)
+        .collect::<Result<_, _>>()
+        .map_err(LoginHistoryStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(name = "Checking login device in PostgreSQL", skip_all)]
+    async fn is_new_device(&self, login: &LoginRecord) -> Result<bool, LoginHistoryStoreError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) > 0 AS "has_logins!",
+                COALESCE(BOOL_OR(device = $2), FALSE) AS "known_device!",
+                COALESCE(BOOL_OR(ip_range = $3), FALSE) AS "known_ip_range!"
+            FROM login_history
+            WHERE email = $1
+            "#,
+            login.email.as_ref().expose_secret(),
+            login.device,
+            login.ip_range()
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| LoginHistoryStoreError::UnexpectedError(e.into()))?;
+
+        Ok(row.has_logins && !(row.known_device && row.known_ip_range))
+    }
+}
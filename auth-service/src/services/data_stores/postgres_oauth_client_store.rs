@@ -0,0 +1,95 @@
+use secrecy::{ExposeSecret, Secret};
+use sqlx::PgPool;
+
+use crate::domain::{OAuthClient, OAuthClientStore, OAuthClientStoreError};
+
+use super::postgres_user_store::{compute_password_hash, verify_password_hash};
+
+pub struct PostgresOAuthClientStore {
+    pool: PgPool,
+}
+
+impl PostgresOAuthClientStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+struct OAuthClientRow {
+    client_id: String,
+    client_secret_hash: String,
+    redirect_uris: Vec<String>,
+}
+
+impl From<OAuthClientRow> for OAuthClient {
+    fn from(row: OAuthClientRow) -> Self {
+        OAuthClient::new(
+            row.client_id,
+            Secret::new(row.client_secret_hash),
+            row.redirect_uris,
+        )
+    }
+}
+
+#[async_trait::async_trait]
+impl OAuthClientStore for PostgresOAuthClientStore {
+    #[tracing::instrument(name = "Adding OAuth client to PostgreSQL", skip_all)]
+    async fn add_client(&mut self, client: OAuthClient) -> Result<(), OAuthClientStoreError> {
+        let client_secret_hash = compute_password_hash(client.client_secret)
+            .await
+            .map_err(OAuthClientStoreError::UnexpectedError)?;
+
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO oauth_clients (client_id, client_secret_hash, redirect_uris)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (client_id) DO NOTHING
+            "#,
+            client.client_id,
+            client_secret_hash.expose_secret(),
+            &client.redirect_uris
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OAuthClientStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OAuthClientStoreError::ClientAlreadyExists);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Retrieving OAuth client from PostgreSQL", skip_all)]
+    async fn get_client(&self, client_id: &str) -> Result<OAuthClient, OAuthClientStoreError> {
+        sqlx::query_as!(
+            OAuthClientRow,
+            r#"
+            SELECT client_id, client_secret_hash, redirect_uris
+            FROM oauth_clients
+            WHERE client_id = $1
+            "#,
+            client_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| OAuthClientStoreError::UnexpectedError(e.into()))?
+        .map(OAuthClient::from)
+        .ok_or(OAuthClientStoreError::ClientNotFound)
+    }
+
+    #[tracing::instrument(name = "Validating OAuth client credentials in PostgreSQL", skip_all)]
+    async fn validate_client(
+        &self,
+        client_id: &str,
+        client_secret: &Secret<String>,
+    ) -> Result<OAuthClient, OAuthClientStoreError> {
+        let client = self.get_client(client_id).await?;
+
+        verify_password_hash(client.client_secret.clone(), client_secret.clone())
+            .await
+            .map_err(|_| OAuthClientStoreError::InvalidCredentials)?;
+
+        Ok(client)
+    }
+}
@@ -0,0 +1,227 @@
+use color_eyre::eyre::Report;
+use secrecy::{ExposeSecret, Secret};
+use sqlx::PgPool;
+
+use crate::domain::{
+    Email, Membership, Organization, OrganizationRole, OrganizationStore, OrganizationStoreError,
+};
+
+pub struct PostgresOrganizationStore {
+    pool: PgPool,
+}
+
+impl PostgresOrganizationStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    async fn organization_exists(&self, id: &str) -> Result<bool, OrganizationStoreError> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM organizations WHERE id = $1) AS "exists!""#,
+            id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| OrganizationStoreError::UnexpectedError(e.into()))
+    }
+}
+
+struct MembershipRow {
+    organization_id: String,
+    email: String,
+    role: String,
+}
+
+impl TryFrom<MembershipRow> for Membership {
+    type Error = Report;
+
+    fn try_from(row: MembershipRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            organization_id: row.organization_id,
+            email: Email::parse(Secret::new(row.email))?,
+            role: OrganizationRole::parse(&row.role)?,
+        })
+    }
+}
+
+fn to_memberships(rows: Vec<MembershipRow>) -> Result<Vec<Membership>, OrganizationStoreError> {
+    rows.into_iter()
+        .map(Membership::try_from)
+        .collect::<Result<_, _>>()
+        .map_err(OrganizationStoreError::UnexpectedError)
+}
+
+#[async_trait::async_trait]
+impl OrganizationStore for PostgresOrganizationStore {
+    #[tracing::instrument(name = "Adding organization to PostgreSQL", skip_all)]
+    async fn add_organization(
+        &mut self,
+        organization: Organization,
+    ) -> Result<(), OrganizationStoreError> {
+        sqlx::query!(
+            "INSERT INTO organizations (id, name) VALUES ($1, $2)",
+            organization.id,
+            organization.name
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            Some(db_error) if db_error.is_unique_violation() => {
+                OrganizationStoreError::OrganizationAlreadyExists
+            }
+            _ => OrganizationStoreError::UnexpectedError(e.into()),
+        })?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Retrieving organizations from PostgreSQL", skip_all)]
+    async fn get_organizations(&self) -> Result<Vec<Organization>, OrganizationStoreError> {
+        sqlx::query_as!(
+            Organization,
+            "SELECT id, name FROM organizations ORDER BY id"
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| OrganizationStoreError::UnexpectedError(e.into()))
+    }
+
+    #[tracing::instrument(name = "Deleting organization from PostgreSQL", skip_all)]
+    async fn delete_organization(&mut self, id: &str) -> Result<(), OrganizationStoreError> {
+        // Memberships go with the organization through their foreign key
+        let result = sqlx::query!("DELETE FROM organizations WHERE id = $1", id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| OrganizationStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OrganizationStoreError::OrganizationNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Adding organization member to PostgreSQL", skip_all)]
+    async fn add_member(&mut self, membership: Membership) -> Result<(), OrganizationStoreError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO organization_members (organization_id, email, role)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (organization_id, email) DO UPDATE SET role = EXCLUDED.role
+            "#,
+            membership.organization_id,
+            membership.email.as_ref().expose_secret(),
+            membership.role.as_str()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            Some(db_error)
+                if db_error.constraint() == Some("organization_members_organization_id_fkey") =>
+            {
+                OrganizationStoreError::OrganizationNotFound
+            }
+            _ => OrganizationStoreError::UnexpectedError(e.into()),
+        })?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Removing organization member from PostgreSQL", skip_all)]
+    async fn remove_member(
+        &mut self,
+        organization_id: &str,
+        email: &Email,
+    ) -> Result<(), OrganizationStoreError> {
+        if !self.organization_exists(organization_id).await? {
+            return Err(OrganizationStoreError::OrganizationNotFound);
+        }
+
+        let result = sqlx::query!(
+            "DELETE FROM organization_members WHERE organization_id = $1 AND email = $2",
+            organization_id,
+            email.as_ref().expose_secret()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| OrganizationStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(OrganizationStoreError::MembershipNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Retrieving organization membership from PostgreSQL", skip_all)]
+    async fn get_membership(
+        &self,
+        organization_id: &str,
+        email: &Email,
+    ) -> Result<Membership, OrganizationStoreError> {
+        let row = sqlx::query_as!(
+            MembershipRow,
+            r#"
+            SELECT organization_id, email, role
+            FROM organization_members
+            WHERE organization_id = $1 AND email = $2
+            "#,
+            organization_id,
+            email.as_ref().expose_secret()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| OrganizationStoreError::UnexpectedError(e.into()))?
+        .ok_or(OrganizationStoreError::MembershipNotFound)?;
+
+        Membership::try_from(row).map_err(OrganizationStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(name = "Retrieving organization members from PostgreSQL", skip_all)]
+    async fn get_members(
+        &self,
+        organization_id: &str,
+    ) -> Result<Vec<Membership>, OrganizationStoreError> {
+        if !self.organization_exists(organization_id).await? {
+            return Err(OrganizationStoreError::OrganizationNotFound);
+        }
+
+        let rows = sqlx::query_as!(
+            MembershipRow,
+            r#"
+            SELECT organization_id, email, role
+            FROM organization_members
+            WHERE organization_id = $1
+            ORDER BY email
+            "#,
+            organization_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| OrganizationStoreError::UnexpectedError(e.into()))?;
+
+        to_memberships(rows)
+    }
+
+    #[tracing::instrument(name = "Retrieving user's organizations from PostgreSQL", skip_all)]
+    async fn get_memberships(
+        &self,
+        email: &Email,
+    ) -> Result<Vec<Membership>, OrganizationStoreError> {
+        let rows = sqlx::query_as!(
+            MembershipRow,
+            r#"
+            SELECT organization_id, email, role
+            FROM organization_members
+            WHERE email = $1
+            ORDER BY organization_id
+            "#,
+            email.as_ref().expose_secret()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| OrganizationStoreError::UnexpectedError(e.into()))?;
+
+        to_memberships(rows)
+    }
+}
@@ -0,0 +1,130 @@
+use secrecy::ExposeSecret;
+use sqlx::PgPool;
+
+use crate::domain::{Email, Role, RoleStore, RoleStoreError};
+
+pub struct PostgresRoleStore {
+    pool: PgPool,
+}
+
+impl PostgresRoleStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    async fn role_exists(&self, name: &str) -> Result<bool, RoleStoreError> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM roles WHERE name = $1) AS "exists!""#,
+            name
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| RoleStoreError::UnexpectedError(e.into()))
+    }
+}
+
+#[async_trait::async_trait]
+impl RoleStore for PostgresRoleStore {
+    #[tracing::instrument(name = "Adding role to PostgreSQL", skip_all)]
+    async fn add_role(&mut self, role: Role) -> Result<(), RoleStoreError> {
+        sqlx::query!(
+            "INSERT INTO roles (name, description, permissions) VALUES ($1, $2, $3)",
+            role.name,
+            role.description,
+            &role.permissions
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            Some(db_error) if db_error.is_unique_violation() => RoleStoreError::RoleAlreadyExists,
+            _ => RoleStoreError::UnexpectedError(e.into()),
+        })?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Retrieving roles from PostgreSQL", skip_all)]
+    async fn get_roles(&self) -> Result<Vec<Role>, RoleStoreError> {
+        sqlx::query_as!(
+            Role,
+            "SELECT name, description, permissions FROM roles ORDER BY name"
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RoleStoreError::UnexpectedError(e.into()))
+    }
+
+    #[tracing::instrument(name = "Deleting role from PostgreSQL", skip_all)]
+    async fn delete_role(&mut self, name: &str) -> Result<(), RoleStoreError> {
+        // Assignments go with the role through their foreign key
+        let result = sqlx::query!("DELETE FROM roles WHERE name = $1", name)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| RoleStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(RoleStoreError::RoleNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Assigning role in PostgreSQL", skip_all)]
+    async fn assign_role(&mut self, email: &Email, name: &str) -> Result<(), RoleStoreError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO user_roles (email, role)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            email.as_ref().expose_secret(),
+            name
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            Some(db_error) if db_error.constraint() == Some("user_roles_role_fkey") => {
+                RoleStoreError::RoleNotFound
+            }
+            _ => RoleStoreError::UnexpectedError(e.into()),
+        })?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Unassigning role in PostgreSQL", skip_all)]
+    async fn unassign_role(&mut self, email: &Email, name: &str) -> Result<(), RoleStoreError> {
+        if !self.role_exists(name).await? {
+            return Err(RoleStoreError::RoleNotFound);
+        }
+
+        sqlx::query!(
+            "DELETE FROM user_roles WHERE email = $1 AND role = $2",
+            email.as_ref().expose_secret(),
+            name
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RoleStoreError::UnexpectedError(e.into()))?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Retrieving user roles from PostgreSQL", skip_all)]
+    async fn get_user_roles(&self, email: &Email) -> Result<Vec<Role>, RoleStoreError> {
+        sqlx::query_as!(
+            Role,
+            r#"
+            SELECT roles.name, roles.description, roles.permissions
+            FROM roles
+            JOIN user_roles ON user_roles.role = roles.name
+            WHERE user_roles.email = $1
+            ORDER BY roles.name
+            "#,
+            email.as_ref().expose_secret()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RoleStoreError::UnexpectedError(e.into()))
+    }
+}
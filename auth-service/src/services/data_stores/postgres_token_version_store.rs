@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::Context;
+use redis::{Commands, Connection, ExistenceCheck, SetExpiry, SetOptions};
+use secrecy::ExposeSecret;
+use sqlx::PgPool;
+use tokio::sync::RwLock;
+
+use crate::domain::{Email, TokenVersionStore, TokenVersionStoreError};
+
+/// Versions live in the `users` table. Every access token is checked against
+/// them, so they are cached in Redis to keep the database out of the hot path.
+pub struct PostgresTokenVersionStore {
+    pool: PgPool,
+    cache: Arc<RwLock<Connection>>,
+}
+
+impl PostgresTokenVersionStore {
+    pub fn new(pool: PgPool, cache: Arc<RwLock<Connection>>) -> Self {
+        Self { pool, cache }
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenVersionStore for PostgresTokenVersionStore {
+    #[tracing::instrument(name = "Retrieving token version", skip_all)]
+    async fn get_token_version(&self, email: &Email) -> Result<u64, TokenVersionStoreError> {
+        let key = make_token_version_key(email);
+        let cached: Option<u64> = self
+            .cache
+            .write()
+            .await
+            .get(&key)
+            .wrap_err("failed to get token version from Redis")
+            .map_err(TokenVersionStoreError::UnexpectedError)?;
+        if let Some(version) = cached {
+            return Ok(version);
+        }
+
+        let version = sqlx::query_scalar!(
+            r#"
+            SELECT token_version
+            FROM users
+            WHERE email = $1
+            "#,
+            email.as_ref().expose_secret()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| TokenVersionStoreError::UnexpectedError(e.into()))?
+        .ok_or(TokenVersionStoreError::UserNotFound)?;
+        let version = to_u64(version)?;
+
+        // Only fill an empty cache: if the version was bumped since it was read
+        // above, the bump has already cached the newer one
+        let options = SetOptions::default()
+            .conditional_set(ExistenceCheck::NX)
+            .with_expiration(SetExpiry::EX(TOKEN_VERSION_CACHE_TTL_SECONDS));
+        let _: () = self
+            .cache
+            .write()
+            .await
+            .set_options(&key, version, options)
+            .wrap_err("failed to cache token version in Redis")
+            .map_err(TokenVersionStoreError::UnexpectedError)?;
+
+        Ok(version)
+    }
+
+    #[tracing::instrument(name = "Incrementing token version in PostgreSQL", skip_all)]
+    async fn increment_token_version(
+        &mut self,
+        email: &Email,
+    ) -> Result<u64, TokenVersionStoreError> {
+        let version = sqlx::query_scalar!(
+            r#"
+            UPDATE users
+            SET token_version = token_version + 1
+            WHERE email = $1
+            RETURNING token_version
+            "#,
+            email.as_ref().expose_secret()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| TokenVersionStoreError::UnexpectedError(e.into()))?
+        .ok_or(TokenVersionStoreError::UserNotFound)?;
+        let version = to_u64(version)?;
+
+        let _: () = self
+            .cache
+            .write()
+            .await
+            .set_ex(
+                make_token_version_key(email),
+                version,
+                TOKEN_VERSION_CACHE_TTL_SECONDS as u64,
+            )
+            .wrap_err("failed to cache token version in Redis")
+            .map_err(TokenVersionStoreError::UnexpectedError)?;
+
+        Ok(version)
+    }
+
+    #[tracing::instrument(name = "Removing token version from Redis", skip_all)]
+    async fn remove_token_version(&mut self, email: &Email) -> Result<(), TokenVersionStoreError> {
+        // The version itself went with the user's row
+        let _: () = self
+            .cache
+            .write()
+            .await
+            .del(make_token_version_key(email))
+            .wrap_err("failed to delete token version from Redis")
+            .map_err(TokenVersionStoreError::UnexpectedError)?;
+
+        Ok(())
+    }
+}
+
+fn to_u64(version: i32) -> Result<u64, TokenVersionStoreError> {
+    version
+        .try_into()
+        .wrap_err("token version is negative")
+        .map_err(TokenVersionStoreError::UnexpectedError)
+}
+
+const TOKEN_VERSION_CACHE_TTL_SECONDS: usize = 60 * 60; // 1 hour
+const TOKEN_VERSION_KEY_PREFIX: &str = "token_version:";
+
+#[tracing::instrument(name = "Make Token Version Key", skip_all)]
+fn make_token_version_key(email: &Email) -> String {
+    format!(
+        "{}{}",
+        TOKEN_VERSION_KEY_PREFIX,
+        email.as_ref().expose_secret()
+    )
+}
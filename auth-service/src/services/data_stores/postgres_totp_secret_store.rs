@@ -0,0 +1,215 @@
+use color_eyre::eyre::{Context, Result};
+use secrecy::ExposeSecret;
+use sqlx::{PgConnection, PgPool};
+
+use crate::{
+    domain::{Email, TotpEnrollment, TotpSecret, TotpSecretStore, TotpSecretStoreError},
+    utils::encryption::EncryptionKey,
+};
+
+/// Secrets are encrypted with `encryption_key`, using the email as associated
+/// data, so a database dump alone doesn't allow generating codes.
+pub struct PostgresTotpSecretStore {
+    pool: PgPool,
+    encryption_key: EncryptionKey,
+}
+
+impl PostgresTotpSecretStore {
+    pub fn new(pool: PgPool, encryption_key: EncryptionKey) -> Self {
+        Self {
+            pool,
+            encryption_key,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TotpSecretStore for PostgresTotpSecretStore {
+    #[tracing::instrument(name = "Adding TOTP secret to PostgreSQL", skip_all)]
+    async fn add_secret(
+        &mut self,
+        email: &Email,
+        secret: TotpSecret,
+    ) -> Result<(), TotpSecretStoreError> {
+        let email = email.as_ref().expose_secret();
+        let encrypted_secret = self
+            .encryption_key
+            .encrypt(secret.as_ref().expose_secret(), email.as_bytes())
+            .map_err(TotpSecretStoreError::UnexpectedError)?;
+
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO totp_secrets (email, encrypted_secret)
+            VALUES ($1, $2)
+            ON CONFLICT (email) DO UPDATE
+            SET encrypted_secret = EXCLUDED.encrypted_secret, last_used_step = NULL
+            WHERE totp_secrets.confirmed = FALSE
+            "#,
+            email,
+            encrypted_secret
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| TotpSecretStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(TotpSecretStoreError::AlreadyEnrolled);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Retrieving TOTP secret from PostgreSQL", skip_all)]
+    async fn get_secret(&self, email: &Email) -> Result<TotpEnrollment, TotpSecretStoreError> {
+        let email = email.as_ref().expose_secret();
+        let row = sqlx::query!(
+            r#"
+            SELECT encrypted_secret, confirmed
+            FROM totp_secrets
+            WHERE email = $1
+            "#,
+            email
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| TotpSecretStoreError::UnexpectedError(e.into()))?
+        .ok_or(TotpSecretStoreError::SecretNotFound)?;
+
+        let secret = self
+            .encryption_key
+            .decrypt(&row.encrypted_secret, email.as_bytes())
+            .map_err(TotpSecretStoreError::UnexpectedError)?;
+
+        Ok(TotpEnrollment {
+            secret: TotpSecret::from_bytes(secret),
+            confirmed: row.confirmed,
+        })
+    }
+
+    #[tracing::instrument(name = "Confirming TOTP secret in PostgreSQL", skip_all)]
+    async fn confirm_secret(&mut self, email: &Email) -> Result<(), TotpSecretStoreError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE totp_secrets
+            SET confirmed = TRUE
+            WHERE email = $1
+            "#,
+            email.as_ref().expose_secret()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| TotpSecretStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(TotpSecretStoreError::SecretNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Removing TOTP secret from PostgreSQL", skip_all)]
+    async fn remove_secret(&mut self, email: &Email) -> Result<(), TotpSecretStoreError> {
+        sqlx::query!(
+            r#"
+            DELETE FROM totp_secrets
+            WHERE email = $1
+            "#,
+            email.as_ref().expose_secret()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| TotpSecretStoreError::UnexpectedError(e.into()))?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Recording used TOTP step in PostgreSQL", skip_all)]
+    async fn use_step(&mut self, email: &Email, step: u64) -> Result<(), TotpSecretStoreError> {
+        let step = i64::try_from(step)
+            .wrap_err("TOTP step out of range")
+            .map_err(TotpSecretStoreError::UnexpectedError)?;
+
+        // A single conditional update, so that concurrent requests can't both use a step
+        let result = sqlx::query!(
+            r#"
+            UPDATE totp_secrets
+            SET last_used_step = $2
+            WHERE email = $1 AND (last_used_step IS NULL OR last_used_step < $2)
+            "#,
+            email.as_ref().expose_secret(),
+            step
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| TotpSecretStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            // Tell a missing secret apart from a replayed code
+            let exists = sqlx::query_scalar!(
+                r#"SELECT EXISTS (SELECT 1 FROM totp_secrets WHERE email = $1) AS "exists!""#,
+                email.as_ref().expose_secret()
+            )
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| TotpSecretStoreError::UnexpectedError(e.into()))?;
+            return Err(if exists {
+                TotpSecretStoreError::StepAlreadyUsed
+            } else {
+                TotpSecretStoreError::SecretNotFound
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Re-encrypts the secret of a user whose email address changed from `email` to
+/// `new_email` for the new address. The user store calls this in the same
+/// transaction that moves the account, so the secret is never left bound to an
+/// address it no longer belongs to. Users without a secret are left alone.
+#[tracing::instrument(name = "Re-encrypting TOTP secret in PostgreSQL", skip_all)]
+pub(crate) async fn rebind_secret(
+    connection: &mut PgConnection,
+    encryption_key: &EncryptionKey,
+    email: &Email,
+    new_email: &Email,
+) -> Result<()> {
+    // The row has already followed the user through its foreign key, but the
+    // secret is still bound to the old address
+    let new_email = new_email.as_ref().expose_secret();
+    let Some(row) = sqlx::query!(
+        r#"
+        SELECT encrypted_secret
+        FROM totp_secrets
+        WHERE email = $1
+        "#,
+        new_email
+    )
+    .fetch_optional(&mut *connection)
+    .await
+    .wrap_err("failed to get TOTP secret")?
+    else {
+        return Ok(());
+    };
+
+    let secret = encryption_key.decrypt(
+        &row.encrypted_secret,
+        email.as_ref().expose_secret().as_bytes(),
+    )?;
+    let encrypted_secret = encryption_key.encrypt(&secret, new_email.as_bytes())?;
+
+    sqlx::query!(
+        r#"
+        UPDATE totp_secrets
+        SET encrypted_secret = $2
+        WHERE email = $1
+        "#,
+        new_email,
+        encrypted_secret
+    )
+    .execute(&mut *connection)
+    .await
+    .wrap_err("failed to update TOTP secret")?;
+
+    Ok(())
+}
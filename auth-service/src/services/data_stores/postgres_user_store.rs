@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use argon2::{
     password_hash::SaltString, Algorithm, Argon2, Params, PasswordHash, PasswordHasher,
     PasswordVerifier, Version,
@@ -6,18 +8,28 @@ use color_eyre::eyre::{eyre, Context, Result};
 use secrecy::{ExposeSecret, Secret};
 use sqlx::{postgres::PgRow, FromRow, PgPool, Row};
 
-use crate::domain::{
-    data_stores::{UserStore, UserStoreError},
-    Email, Password, User,
+use crate::{
+    domain::{
+        data_stores::{UserStore, UserStoreError},
+        Email, Password, User, UserPage,
+    },
+    services::data_stores::postgres_totp_secret_store::rebind_secret,
+    utils::encryption::EncryptionKey,
 };
 
+/// The TOTP encryption key is needed to move an account to a new email address,
+/// since TOTP secrets are bound to the address they were encrypted for.
 pub struct PostgresUserStore {
     pool: PgPool,
+    totp_encryption_key: EncryptionKey,
 }
 
 impl PostgresUserStore {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, totp_encryption_key: EncryptionKey) -> Self {
+        Self {
+            pool,
+            totp_encryption_key,
+        }
     }
 }
 
@@ -25,6 +37,11 @@ pub struct UserRow {
     email: String,
     password_hash: String,
     requires_2fa: bool,
+    verified: bool,
+    pending_deletion: bool,
+    is_admin: bool,
+    locked: bool,
+    password_reset_required: bool,
 }
 
 impl From<UserRow> for User {
@@ -33,6 +50,11 @@ impl From<UserRow> for User {
             email: Email::parse(Secret::new(row.email)).unwrap(),
             password: Password::parse(Secret::new(row.password_hash), true).unwrap(),
             requires_2fa: row.requires_2fa,
+            verified: row.verified,
+            pending_deletion: row.pending_deletion,
+            admin: row.is_admin,
+            locked: row.locked,
+            password_reset_required: row.password_reset_required,
         }
     }
 }
@@ -45,6 +67,11 @@ impl<'r> FromRow<'r, PgRow> for User {
             password: Password::parse(Secret::new(row.try_get("password_hash")?), true)
                 .map_err(|_| sqlx::Error::RowNotFound)?,
             requires_2fa: row.try_get("requires_2fa")?,
+            verified: row.try_get("verified")?,
+            pending_deletion: row.try_get("pending_deletion")?,
+            admin: row.try_get("is_admin")?,
+            locked: row.try_get("locked")?,
+            password_reset_required: row.try_get("password_reset_required")?,
         })
     }
 }
@@ -62,10 +89,14 @@ impl UserStore for PostgresUserStore {
             .map_err(UserStoreError::UnexpectedError)?;
 
         sqlx::query!(
-            "INSERT INTO users (email, password_hash, requires_2fa) VALUES ($1, $2, $3)",
+            r#"
+            INSERT INTO users (email, password_hash, requires_2fa, verified)
+            VALUES ($1, $2, $3, $4)
+            "#,
             user.email.as_ref().expose_secret(),
             password_hash.expose_secret(),
-            user.requires_2fa
+            user.requires_2fa,
+            user.verified
         )
         .execute(&self.pool)
         .await
@@ -78,12 +109,21 @@ impl UserStore for PostgresUserStore {
     async fn get_user(&self, email: &Email) -> Result<User, UserStoreError> {
         let user_row = sqlx::query_as!(
             UserRow,
-            "SELECT email, password_hash, requires_2fa FROM users WHERE email = $1",
+            r#"
+            SELECT email, password_hash, requires_2fa, verified,
+                deletion_requested_at IS NOT NULL AS "pending_deletion!",
+                is_admin, locked, password_reset_required
+            FROM users
+            WHERE email = $1
+            "#,
             email.as_ref().expose_secret()
         )
         .fetch_one(&self.pool)
         .await
-        .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))?;
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => UserStoreError::UserNotFound,
+            e => UserStoreError::UnexpectedError(eyre!(e)),
+        })?;
 
         Ok(User::from(user_row))
     }
@@ -117,10 +157,309 @@ impl UserStore for PostgresUserStore {
 
         Ok(user)
     }
+
+    #[tracing::instrument(name = "Updating user password in PostgreSQL", skip_all)]
+    async fn update_password(
+        &mut self,
+        email: &Email,
+        password: Password,
+    ) -> Result<(), UserStoreError> {
+        let password_hash = compute_password_hash(password.as_ref().clone())
+            .await
+            .map_err(UserStoreError::UnexpectedError)?;
+
+        let result = sqlx::query!(
+            "UPDATE users SET password_hash = $1, password_reset_required = FALSE WHERE email = $2",
+            password_hash.expose_secret(),
+            email.as_ref().expose_secret()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Setting user verified flag in PostgreSQL", skip_all)]
+    async fn set_verified(&mut self, email: &Email, verified: bool) -> Result<(), UserStoreError> {
+        let result = sqlx::query!(
+            "UPDATE users SET verified = $1 WHERE email = $2",
+            verified,
+            email.as_ref().expose_secret()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Setting user requires 2FA flag in PostgreSQL", skip_all)]
+    async fn set_requires_2fa(
+        &mut self,
+        email: &Email,
+        requires_2fa: bool,
+    ) -> Result<(), UserStoreError> {
+        let result = sqlx::query!(
+            "UPDATE users SET requires_2fa = $1 WHERE email = $2",
+            requires_2fa,
+            email.as_ref().expose_secret()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Updating user email in PostgreSQL", skip_all)]
+    async fn update_email(
+        &mut self,
+        email: &Email,
+        new_email: &Email,
+    ) -> Result<(), UserStoreError> {
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        // Rows in other tables follow through their foreign keys
+        let result = sqlx::query!(
+            "UPDATE users SET email = $1 WHERE email = $2",
+            new_email.as_ref().expose_secret(),
+            email.as_ref().expose_secret()
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            Some(db_error) if db_error.is_unique_violation() => UserStoreError::UserAlreadyExists,
+            _ => UserStoreError::UnexpectedError(e.into()),
+        })?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        // In the same transaction, so that a failure can't leave the user with a
+        // secret that can no longer be decrypted
+        rebind_secret(
+            &mut transaction,
+            &self.totp_encryption_key,
+            email,
+            new_email,
+        )
+        .await
+        .map_err(UserStoreError::UnexpectedError)?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Marking user for deletion in PostgreSQL", skip_all)]
+    async fn delete_user(&mut self, email: &Email) -> Result<(), UserStoreError> {
+        // Deleting again doesn't extend the grace period
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET deletion_requested_at = COALESCE(deletion_requested_at, NOW())
+            WHERE email = $1
+            "#,
+            email.as_ref().expose_secret()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Restoring deleted user in PostgreSQL", skip_all)]
+    async fn restore_user(&mut self, email: &Email) -> Result<(), UserStoreError> {
+        let result = sqlx::query!(
+            "UPDATE users SET deletion_requested_at = NULL WHERE email = $1",
+            email.as_ref().expose_secret()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Purging deleted users from PostgreSQL", skip_all)]
+    async fn purge_deleted_users(
+        &mut self,
+        grace_period: Duration,
+    ) -> Result<Vec<Email>, UserStoreError> {
+        // Rows in other tables go with the user through their foreign keys
+        let emails = sqlx::query_scalar!(
+            r#"
+            DELETE FROM users
+            WHERE deletion_requested_at <= NOW() - make_interval(secs => $1)
+            RETURNING email
+            "#,
+            grace_period.as_secs_f64()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        emails
+            .into_iter()
+            .map(|email| Email::parse(Secret::new(email)).map_err(UserStoreError::UnexpectedError))
+            .collect()
+    }
+
+    #[tracing::instrument(name = "Removing user from PostgreSQL", skip_all)]
+    async fn remove_user(&mut self, email: &Email) -> Result<(), UserStoreError> {
+        // Rows in other tables go with the user through their foreign keys
+        let result = sqlx::query!(
+            "DELETE FROM users WHERE email = $1",
+            email.as_ref().expose_secret()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Searching users in PostgreSQL", skip_all)]
+    async fn search_users(
+        &self,
+        search: Option<&str>,
+        offset: u64,
+        limit: u64,
+    ) -> Result<UserPage, UserStoreError> {
+        // strpos rather than LIKE, so that the search has no wildcards
+        let rows = sqlx::query_as!(
+            UserRow,
+            r#"
+            SELECT email, password_hash, requires_2fa, verified,
+                deletion_requested_at IS NOT NULL AS "pending_deletion!",
+                is_admin, locked, password_reset_required
+            FROM users
+            WHERE $1::TEXT IS NULL OR strpos(lower(email), lower($1)) > 0
+            ORDER BY email
+            OFFSET $2
+            LIMIT $3
+            "#,
+            search,
+            offset as i64,
+            limit as i64
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        let total = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "total!"
+            FROM users
+            WHERE $1::TEXT IS NULL OR strpos(lower(email), lower($1)) > 0
+            "#,
+            search
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        Ok(UserPage {
+            users: rows.into_iter().map(User::from).collect(),
+            total: total as u64,
+        })
+    }
+
+    #[tracing::instrument(name = "Setting user admin flag in PostgreSQL", skip_all)]
+    async fn set_admin(&mut self, email: &Email, admin: bool) -> Result<(), UserStoreError> {
+        let result = sqlx::query!(
+            "UPDATE users SET is_admin = $1 WHERE email = $2",
+            admin,
+            email.as_ref().expose_secret()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Setting user locked flag in PostgreSQL", skip_all)]
+    async fn set_locked(&mut self, email: &Email, locked: bool) -> Result<(), UserStoreError> {
+        let result = sqlx::query!(
+            "UPDATE users SET locked = $1 WHERE email = $2",
+            locked,
+            email.as_ref().expose_secret()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Setting user password reset flag in PostgreSQL", skip_all)]
+    async fn set_password_reset_required(
+        &mut self,
+        email: &Email,
+        required: bool,
+    ) -> Result<(), UserStoreError> {
+        let result = sqlx::query!(
+            "UPDATE users SET password_reset_required = $1 WHERE email = $2",
+            required,
+            email.as_ref().expose_secret()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| UserStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        Ok(())
+    }
 }
 
 #[tracing::instrument(name = "Verify password hash", skip_all)]
-async fn verify_password_hash(
+pub async fn verify_password_hash(
     expected_password_hash: Secret<String>,
     password_candidate: Secret<String>,
 ) -> Result<()> {
@@ -129,7 +468,7 @@ async fn verify_password_hash(
     let result = tokio::task::spawn_blocking(move || {
         current_span.in_scope(|| {
             let expected_password_hash: PasswordHash<'_> =
-                PasswordHash::new(&expected_password_hash.expose_secret())?;
+                PasswordHash::new(expected_password_hash.expose_secret())?;
 
             Argon2::default()
                 .verify_password(
@@ -145,7 +484,7 @@ async fn verify_password_hash(
     result?
 }
 #[tracing::instrument(name = "Computing password hash", skip_all)]
-async fn compute_password_hash(password: Secret<String>) -> Result<Secret<String>> {
+pub async fn compute_password_hash(password: Secret<String>) -> Result<Secret<String>> {
     let current_span = tracing::Span::current();
 
     let result = tokio::task::spawn_blocking(move || {
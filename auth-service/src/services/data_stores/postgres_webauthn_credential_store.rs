@@ -0,0 +1,149 @@
+use color_eyre::eyre::Report;
+use secrecy::{ExposeSecret, Secret};
+use sqlx::PgPool;
+
+use crate::domain::{
+    CredentialPublicKey, Email, WebauthnCredential, WebauthnCredentialStore,
+    WebauthnCredentialStoreError,
+};
+
+pub struct PostgresWebauthnCredentialStore {
+    pool: PgPool,
+}
+
+impl PostgresWebauthnCredentialStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+struct WebauthnCredentialRow {
+    credential_id: Vec<u8>,
+    email: String,
+    public_key_algorithm: i32,
+    public_key: Vec<u8>,
+    sign_count: i64,
+    name: String,
+}
+
+impl TryFrom<WebauthnCredentialRow> for WebauthnCredential {
+    type Error = Report;
+
+    fn try_from(row: WebauthnCredentialRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            credential_id: row.credential_id,
+            email: Email::parse(Secret::new(row.email))?,
+            public_key: CredentialPublicKey::from_parts(
+                row.public_key_algorithm.into(),
+                row.public_key,
+            )?,
+            sign_count: u32::try_from(row.sign_count)?,
+            name: row.name,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl WebauthnCredentialStore for PostgresWebauthnCredentialStore {
+    #[tracing::instrument(name = "Adding WebAuthn credential to PostgreSQL", skip_all)]
+    async fn add_credential(
+        &mut self,
+        credential: WebauthnCredential,
+    ) -> Result<(), WebauthnCredentialStoreError> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO webauthn_credentials
+                (credential_id, email, public_key_algorithm, public_key, sign_count, name)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (credential_id) DO NOTHING
+            "#,
+            credential.credential_id,
+            credential.email.as_ref().expose_secret(),
+            credential.public_key.algorithm() as i32,
+            credential.public_key.as_bytes(),
+            i64::from(credential.sign_count),
+            credential.name
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| WebauthnCredentialStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(WebauthnCredentialStoreError::CredentialAlreadyExists);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Retrieving WebAuthn credential from PostgreSQL", skip_all)]
+    async fn get_credential(
+        &self,
+        credential_id: &[u8],
+    ) -> Result<WebauthnCredential, WebauthnCredentialStoreError> {
+        sqlx::query_as!(
+            WebauthnCredentialRow,
+            r#"
+            SELECT credential_id, email, public_key_algorithm, public_key, sign_count, name
+            FROM webauthn_credentials
+            WHERE credential_id = $1
+            "#,
+            credential_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| WebauthnCredentialStoreError::UnexpectedError(e.into()))?
+        .ok_or(WebauthnCredentialStoreError::CredentialNotFound)?
+        .try_into()
+        .map_err(WebauthnCredentialStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(name = "Retrieving WebAuthn credentials from PostgreSQL", skip_all)]
+    async fn get_credentials(
+        &self,
+        email: &Email,
+    ) -> Result<Vec<WebauthnCredential>, WebauthnCredentialStoreError> {
+        sqlx::query_as!(
+            WebauthnCredentialRow,
+            r#"
+            SELECT credential_id, email, public_key_algorithm, public_key, sign_count, name
+            FROM webauthn_credentials
+            WHERE email = $1
+            ORDER BY created_at
+            "#,
+            email.as_ref().expose_secret()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| WebauthnCredentialStoreError::UnexpectedError(e.into()))?
+        .into_iter()
+        .map(WebauthnCredential::try_from)
+        .collect::<Result<_, _>>()
+        .map_err(WebauthnCredentialStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(name = "Updating WebAuthn sign count in PostgreSQL", skip_all)]
+    async fn update_sign_count(
+        &mut self,
+        credential_id: &[u8],
+        sign_count: u32,
+    ) -> Result<(), WebauthnCredentialStoreError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE webauthn_credentials
+            SET sign_count = $2
+            WHERE credential_id = $1
+            "#,
+            credential_id,
+            i64::from(sign_count)
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| WebauthnCredentialStoreError::UnexpectedError(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(WebauthnCredentialStoreError::CredentialNotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::Context;
+use redis::{Commands, Connection};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::domain::{
+    data_stores::{
+        AuthorizationCode, AuthorizationCodeData, AuthorizationCodeStore,
+        AuthorizationCodeStoreError,
+    },
+    Email,
+};
+
+pub struct RedisAuthorizationCodeStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisAuthorizationCodeStore {
+    #[tracing::instrument(name = "Create Redis Authorization Code Store", skip_all)]
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct AuthorizationCodeRecord {
+    client_id: String,
+    redirect_uri: String,
+    email: String,
+    scope: String,
+    nonce: Option<String>,
+    code_challenge: String,
+}
+
+// Authorization codes are exchanged right after the redirect, so keep them short-lived
+const AUTHORIZATION_CODE_TTL_SECONDS: u64 = 60;
+const AUTHORIZATION_CODE_KEY_PREFIX: &str = "authorization_code:";
+
+#[tracing::instrument(name = "Make Authorization Code Key", skip_all)]
+fn make_code_key(code: &AuthorizationCode) -> String {
+    format!(
+        "{}{}",
+        AUTHORIZATION_CODE_KEY_PREFIX,
+        code.as_ref().expose_secret()
+    )
+}
+
+#[async_trait::async_trait]
+impl AuthorizationCodeStore for RedisAuthorizationCodeStore {
+    #[tracing::instrument(name = "Add Authorization Code", skip_all)]
+    async fn add_code(
+        &mut self,
+        code: AuthorizationCode,
+        data: AuthorizationCodeData,
+    ) -> Result<(), AuthorizationCodeStoreError> {
+        let record = AuthorizationCodeRecord {
+            client_id: data.client_id,
+            redirect_uri: data.redirect_uri,
+            email: data.email.as_ref().expose_secret().to_owned(),
+            scope: data.scope,
+            nonce: data.nonce,
+            code_challenge: data.code_challenge,
+        };
+        let value = serde_json::to_string(&record)
+            .map_err(|e| AuthorizationCodeStoreError::UnexpectedError(e.into()))?;
+
+        let mut conn = self.conn.write().await;
+        let _: () = conn
+            .set_ex(make_code_key(&code), value, AUTHORIZATION_CODE_TTL_SECONDS)
+            .wrap_err("failed to set authorization code in Redis")
+            .map_err(AuthorizationCodeStoreError::UnexpectedError)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Take Authorization Code", skip_all)]
+    async fn take_code(
+        &mut self,
+        code: &AuthorizationCode,
+    ) -> Result<AuthorizationCodeData, AuthorizationCodeStoreError> {
+        let mut conn = self.conn.write().await;
+
+        // GETDEL so that two concurrent exchanges can't both redeem the code
+        let value: Option<String> = conn
+            .get_del(make_code_key(code))
+            .wrap_err("failed to get authorization code from Redis")
+            .map_err(AuthorizationCodeStoreError::UnexpectedError)?;
+        let value = value.ok_or(AuthorizationCodeStoreError::CodeNotFound)?;
+
+        let record: AuthorizationCodeRecord = serde_json::from_str(&value)
+            .map_err(|e| AuthorizationCodeStoreError::UnexpectedError(e.into()))?;
+
+        let email = Email::parse(Secret::new(record.email))
+            .map_err(AuthorizationCodeStoreError::UnexpectedError)?;
+
+        Ok(AuthorizationCodeData {
+            client_id: record.client_id,
+            redirect_uri: record.redirect_uri,
+            email,
+            scope: record.scope,
+            nonce: record.nonce,
+            code_challenge: record.code_challenge,
+        })
+    }
+}
@@ -51,10 +51,7 @@ impl BannedTokenStore for RedisBannedTokenStore {
     async fn token_exists(&self, token: &Secret<String>) -> bool {
         let key = make_token_key(token.expose_secret());
         let mut conn = self.conn.write().await;
-        match conn.exists::<_, bool>(key) {
-            Ok(exists) => exists,
-            Err(_) => false,
-        }
+        conn.exists::<_, bool>(key).unwrap_or_default()
     }
 }
 
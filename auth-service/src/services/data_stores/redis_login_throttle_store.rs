@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::Context;
+use redis::{Commands, Connection};
+use tokio::sync::RwLock;
+
+use crate::domain::{LoginThrottleStore, LoginThrottleStoreError, ThrottleKey};
+
+pub struct RedisLoginThrottleStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisLoginThrottleStore {
+    #[tracing::instrument(name = "Create Redis Login Throttle Store", skip_all)]
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+const FAILURES_KEY_PREFIX: &str = "login_failures:";
+const LOCKOUT_KEY_PREFIX: &str = "login_lockout:";
+
+#[tracing::instrument(name = "Make Login Failures Key", skip_all)]
+fn make_failures_key(key: &ThrottleKey) -> String {
+    format!("{}{}", FAILURES_KEY_PREFIX, key.id())
+}
+
+#[tracing::instrument(name = "Make Login Lockout Key", skip_all)]
+fn make_lockout_key(key: &ThrottleKey) -> String {
+    format!("{}{}", LOCKOUT_KEY_PREFIX, key.id())
+}
+
+#[async_trait::async_trait]
+impl LoginThrottleStore for RedisLoginThrottleStore {
+    #[tracing::instrument(name = "Get Login Lockout", skip_all)]
+    async fn get_lockout(&self, key: &ThrottleKey) -> Result<Option<u64>, LoginThrottleStoreError> {
+        let mut conn = self.conn.write().await;
+        // TTL is negative if the key doesn't exist or has no expiry
+        let ttl: i64 = conn
+            .ttl(make_lockout_key(key))
+            .wrap_err("failed to get login lockout from Redis")
+            .map_err(LoginThrottleStoreError::UnexpectedError)?;
+        Ok(u64::try_from(ttl).ok().filter(|ttl| *ttl > 0))
+    }
+
+    #[tracing::instrument(name = "Record Login Failure", skip_all)]
+    async fn record_failure(
+        &mut self,
+        key: &ThrottleKey,
+        window_seconds: u64,
+    ) -> Result<u32, LoginThrottleStoreError> {
+        let failures_key = make_failures_key(key);
+        let mut conn = self.conn.write().await;
+        let (failures, _): (u32, ()) = redis::pipe()
+            .atomic()
+            .incr(&failures_key, 1)
+            .expire(&failures_key, window_seconds as i64)
+            .query(&mut *conn)
+            .wrap_err("failed to record login failure in Redis")
+            .map_err(LoginThrottleStoreError::UnexpectedError)?;
+        Ok(failures)
+    }
+
+    #[tracing::instrument(name = "Lock Out Login", skip_all)]
+    async fn lock_out(
+        &mut self,
+        key: &ThrottleKey,
+        seconds: u64,
+    ) -> Result<(), LoginThrottleStoreError> {
+        let mut conn = self.conn.write().await;
+        let _: () = conn
+            .set_ex(make_lockout_key(key), true, seconds)
+            .wrap_err("failed to set login lockout in Redis")
+            .map_err(LoginThrottleStoreError::UnexpectedError)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Reset Login Throttle", skip_all)]
+    async fn reset(&mut self, key: &ThrottleKey) -> Result<(), LoginThrottleStoreError> {
+        let mut conn = self.conn.write().await;
+        let _: () = conn
+            .del(&[make_failures_key(key), make_lockout_key(key)])
+            .wrap_err("failed to reset login throttle in Redis")
+            .map_err(LoginThrottleStoreError::UnexpectedError)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::Context;
+use redis::{Commands, Connection};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::domain::{
+    data_stores::{MagicLinkNonce, MagicLinkToken, MagicLinkTokenStore, MagicLinkTokenStoreError},
+    Email,
+};
+
+pub struct RedisMagicLinkTokenStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisMagicLinkTokenStore {
+    #[tracing::instrument(name = "Create Redis Magic Link Token Store", skip_all)]
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct MagicLinkTokenRecord {
+    email: String,
+    nonce: String,
+}
+
+pub const MAGIC_LINK_TOKEN_TTL_SECONDS: u64 = 60 * 15; // 15 minutes
+const MAGIC_LINK_TOKEN_KEY_PREFIX: &str = "magic_link_token:";
+
+#[tracing::instrument(name = "Make Magic Link Token Key", skip_all)]
+fn make_token_key(token: &MagicLinkToken) -> String {
+    format!(
+        "{}{}",
+        MAGIC_LINK_TOKEN_KEY_PREFIX,
+        token.as_ref().expose_secret()
+    )
+}
+
+#[async_trait::async_trait]
+impl MagicLinkTokenStore for RedisMagicLinkTokenStore {
+    #[tracing::instrument(name = "Add Magic Link Token", skip_all)]
+    async fn add_token(
+        &mut self,
+        token: MagicLinkToken,
+        email: Email,
+        nonce: MagicLinkNonce,
+    ) -> Result<(), MagicLinkTokenStoreError> {
+        let record = MagicLinkTokenRecord {
+            email: email.as_ref().expose_secret().to_owned(),
+            nonce: nonce.as_ref().expose_secret().to_owned(),
+        };
+        let value = serde_json::to_string(&record)
+            .map_err(|e| MagicLinkTokenStoreError::UnexpectedError(e.into()))?;
+
+        let mut conn = self.conn.write().await;
+        let _: () = conn
+            .set_ex(make_token_key(&token), value, MAGIC_LINK_TOKEN_TTL_SECONDS)
+            .wrap_err("failed to set magic link token in Redis")
+            .map_err(MagicLinkTokenStoreError::UnexpectedError)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Take Magic Link Token", skip_all)]
+    async fn take_token(
+        &mut self,
+        token: &MagicLinkToken,
+        nonce: &MagicLinkNonce,
+    ) -> Result<Email, MagicLinkTokenStoreError> {
+        let key = make_token_key(token);
+        let mut conn = self.conn.write().await;
+
+        let value: Option<String> = conn
+            .get(&key)
+            .wrap_err("failed to get magic link token from Redis")
+            .map_err(MagicLinkTokenStoreError::UnexpectedError)?;
+        let value = value.ok_or(MagicLinkTokenStoreError::TokenNotFound)?;
+
+        let record: MagicLinkTokenRecord = serde_json::from_str(&value)
+            .map_err(|e| MagicLinkTokenStoreError::UnexpectedError(e.into()))?;
+        if record.nonce != *nonce.as_ref().expose_secret() {
+            return Err(MagicLinkTokenStoreError::NonceMismatch);
+        }
+
+        // Only the request that deletes the key gets to use the token
+        let deleted: u64 = conn
+            .del(&key)
+            .wrap_err("failed to delete magic link token from Redis")
+            .map_err(MagicLinkTokenStoreError::UnexpectedError)?;
+        if deleted == 0 {
+            return Err(MagicLinkTokenStoreError::TokenNotFound);
+        }
+
+        Email::parse(Secret::new(record.email)).map_err(MagicLinkTokenStoreError::UnexpectedError)
+    }
+}
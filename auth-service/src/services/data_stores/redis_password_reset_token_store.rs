@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::Context;
+use redis::{Commands, Connection};
+use secrecy::{ExposeSecret, Secret};
+use tokio::sync::RwLock;
+
+use crate::domain::{
+    data_stores::{PasswordResetToken, PasswordResetTokenStore, PasswordResetTokenStoreError},
+    Email,
+};
+
+pub struct RedisPasswordResetTokenStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisPasswordResetTokenStore {
+    #[tracing::instrument(name = "Create Redis Password Reset Token Store", skip_all)]
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+const PASSWORD_RESET_TOKEN_TTL_SECONDS: u64 = 60 * 30; // 30 minutes
+const PASSWORD_RESET_TOKEN_KEY_PREFIX: &str = "password_reset_token:";
+
+#[tracing::instrument(name = "Make Password Reset Token Key", skip_all)]
+fn make_token_key(token: &PasswordResetToken) -> String {
+    format!(
+        "{}{}",
+        PASSWORD_RESET_TOKEN_KEY_PREFIX,
+        token.as_ref().expose_secret()
+    )
+}
+
+#[async_trait::async_trait]
+impl PasswordResetTokenStore for RedisPasswordResetTokenStore {
+    #[tracing::instrument(name = "Add Password Reset Token", skip_all)]
+    async fn add_token(
+        &mut self,
+        token: PasswordResetToken,
+        email: Email,
+    ) -> Result<(), PasswordResetTokenStoreError> {
+        let mut conn = self.conn.write().await;
+        let _: () = conn
+            .set_ex(
+                make_token_key(&token),
+                email.as_ref().expose_secret(),
+                PASSWORD_RESET_TOKEN_TTL_SECONDS,
+            )
+            .wrap_err("failed to set password reset token in Redis")
+            .map_err(PasswordResetTokenStoreError::UnexpectedError)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Take Password Reset Token", skip_all)]
+    async fn take_token(
+        &mut self,
+        token: &PasswordResetToken,
+    ) -> Result<Email, PasswordResetTokenStoreError> {
+        let mut conn = self.conn.write().await;
+        let email: Option<String> = conn
+            .get_del(make_token_key(token))
+            .wrap_err("failed to get password reset token from Redis")
+            .map_err(PasswordResetTokenStoreError::UnexpectedError)?;
+        let email = email.ok_or(PasswordResetTokenStoreError::TokenNotFound)?;
+
+        Email::parse(Secret::new(email)).map_err(PasswordResetTokenStoreError::UnexpectedError)
+    }
+}
@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::Context;
+use redis::{Commands, Connection, ErrorKind, RedisError};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::domain::{
+    RateLimit, RateLimitDecision, RateLimitStore, RateLimitStoreError, TokenBucket,
+};
+
+pub struct RedisRateLimitStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisRateLimitStore {
+    #[tracing::instrument(name = "Create Redis Rate Limit Store", skip_all)]
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenBucketRecord {
+    tokens: f64,
+    updated_at_ms: i64,
+}
+
+const KEY_PREFIX: &str = "rate_limit:";
+
+#[tracing::instrument(name = "Make Rate Limit Key", skip_all)]
+fn make_key(key: &str) -> String {
+    format!("{}{}", KEY_PREFIX, key)
+}
+
+// The transaction closure has to fail with a `RedisError`
+fn json_error(e: serde_json::Error) -> RedisError {
+    RedisError::from((
+        ErrorKind::TypeError,
+        "invalid token bucket record",
+        e.to_string(),
+    ))
+}
+
+#[async_trait::async_trait]
+impl RateLimitStore for RedisRateLimitStore {
+    #[tracing::instrument(name = "Take Rate Limit Token", skip_all)]
+    async fn take_token(
+        &mut self,
+        key: &str,
+        limit: &RateLimit,
+    ) -> Result<RateLimitDecision, RateLimitStoreError> {
+        let key = make_key(key);
+        let mut conn = self.conn.write().await;
+
+        // WATCH the bucket so that concurrent requests from other replicas
+        // retry instead of overwriting each other's update
+        redis::transaction(&mut *conn, &[&key], |conn, pipe| {
+            let now_ms = chrono::Utc::now().timestamp_millis();
+            let record: Option<String> = conn.get(&key)?;
+            let mut bucket = match record {
+                Some(record) => {
+                    let record: TokenBucketRecord =
+                        serde_json::from_str(&record).map_err(json_error)?;
+                    TokenBucket {
+                        tokens: record.tokens,
+                        updated_at_ms: record.updated_at_ms,
+                    }
+                }
+                None => TokenBucket::full(limit, now_ms),
+            };
+            let decision = bucket.take(limit, now_ms);
+
+            let record = serde_json::to_string(&TokenBucketRecord {
+                tokens: bucket.tokens,
+                updated_at_ms: bucket.updated_at_ms,
+            })
+            .map_err(json_error)?;
+            // An untouched bucket is full again after one period
+            let result: Option<()> = pipe
+                .set_ex(&key, record, limit.period_seconds)
+                .ignore()
+                .query(conn)?;
+            Ok(result.map(|_| decision))
+        })
+        .wrap_err("failed to take rate limit token in Redis")
+        .map_err(RateLimitStoreError::UnexpectedError)
+    }
+}
@@ -0,0 +1,201 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::Context;
+use redis::{Commands, Connection, ExistenceCheck, SetExpiry, SetOptions};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        data_stores::{RefreshToken, RefreshTokenData, RefreshTokenStore, RefreshTokenStoreError},
+        Email,
+    },
+    utils::auth::REFRESH_TOKEN_TTL_SECONDS,
+};
+
+pub struct RedisRefreshTokenStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisRefreshTokenStore {
+    #[tracing::instrument(name = "Create Redis Refresh Token Store", skip_all)]
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RefreshTokenRecord {
+    email: String,
+    family_id: Uuid,
+    // Missing from records written before tenants existed
+    #[serde(default)]
+    tenant_id: Option<String>,
+}
+
+const REFRESH_TOKEN_KEY_PREFIX: &str = "refresh_token:";
+const USED_TOKEN_KEY_PREFIX: &str = "refresh_token_used:";
+const REVOKED_FAMILY_KEY_PREFIX: &str = "refresh_token_family_revoked:";
+const USER_FAMILIES_KEY_PREFIX: &str = "refresh_token_families:";
+
+#[tracing::instrument(name = "Make Refresh Token Key", skip_all)]
+fn make_token_key(token: &RefreshToken) -> String {
+    format!(
+        "{}{}",
+        REFRESH_TOKEN_KEY_PREFIX,
+        token.as_ref().expose_secret()
+    )
+}
+
+#[tracing::instrument(name = "Make Used Refresh Token Key", skip_all)]
+fn make_used_token_key(token: &RefreshToken) -> String {
+    format!(
+        "{}{}",
+        USED_TOKEN_KEY_PREFIX,
+        token.as_ref().expose_secret()
+    )
+}
+
+#[tracing::instrument(name = "Make Revoked Family Key", skip_all)]
+fn make_revoked_family_key(family_id: &Uuid) -> String {
+    format!("{}{}", REVOKED_FAMILY_KEY_PREFIX, family_id)
+}
+
+#[tracing::instrument(name = "Make User Families Key", skip_all)]
+fn make_user_families_key(email: &Email) -> String {
+    format!(
+        "{}{}",
+        USER_FAMILIES_KEY_PREFIX,
+        email.as_ref().expose_secret()
+    )
+}
+
+#[tracing::instrument(name = "Set Refresh Token Record", skip_all)]
+fn set_record(
+    conn: &mut Connection,
+    token: &RefreshToken,
+    record: &RefreshTokenRecord,
+) -> Result<(), RefreshTokenStoreError> {
+    let value = serde_json::to_string(record)
+        .map_err(|e| RefreshTokenStoreError::UnexpectedError(e.into()))?;
+    let _: () = conn
+        .set_ex(
+            make_token_key(token),
+            value,
+            REFRESH_TOKEN_TTL_SECONDS as u64,
+        )
+        .wrap_err("failed to set refresh token in Redis")
+        .map_err(RefreshTokenStoreError::UnexpectedError)?;
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl RefreshTokenStore for RedisRefreshTokenStore {
+    #[tracing::instrument(name = "Add Refresh Token", skip_all)]
+    async fn add_token(
+        &mut self,
+        token: RefreshToken,
+        data: RefreshTokenData,
+    ) -> Result<(), RefreshTokenStoreError> {
+        let record = RefreshTokenRecord {
+            email: data.email.as_ref().expose_secret().to_owned(),
+            family_id: data.family_id,
+            tenant_id: data.tenant_id.clone(),
+        };
+        let mut conn = self.conn.write().await;
+        set_record(&mut conn, &token, &record)?;
+
+        // Index the family by user so that all of a user's sessions can be revoked at once
+        let user_families_key = make_user_families_key(&data.email);
+        let _: () = conn
+            .sadd(&user_families_key, data.family_id.to_string())
+            .wrap_err("failed to index refresh token family in Redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)?;
+        let _: () = conn
+            .expire(&user_families_key, REFRESH_TOKEN_TTL_SECONDS)
+            .wrap_err("failed to set expiry of refresh token families in Redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Use Refresh Token", skip_all)]
+    async fn use_token(
+        &mut self,
+        token: &RefreshToken,
+    ) -> Result<RefreshTokenData, RefreshTokenStoreError> {
+        let mut conn = self.conn.write().await;
+
+        let value: Option<String> = conn
+            .get(make_token_key(token))
+            .wrap_err("failed to get refresh token from Redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)?;
+        let value = value.ok_or(RefreshTokenStoreError::TokenNotFound)?;
+
+        let record: RefreshTokenRecord = serde_json::from_str(&value)
+            .map_err(|e| RefreshTokenStoreError::UnexpectedError(e.into()))?;
+
+        let revoked: bool = conn
+            .exists(make_revoked_family_key(&record.family_id))
+            .wrap_err("failed to check refresh token family in Redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)?;
+        if revoked {
+            return Err(RefreshTokenStoreError::TokenRevoked);
+        }
+
+        // Mark the token as used with SET NX, so that of two concurrent refreshes
+        // with the same token only one succeeds and the other is seen as reuse.
+        // The record itself is kept around so that a later reuse can be detected.
+        let options = SetOptions::default()
+            .conditional_set(ExistenceCheck::NX)
+            .with_expiration(SetExpiry::EX(REFRESH_TOKEN_TTL_SECONDS as usize));
+        let first_use: bool = conn
+            .set_options(make_used_token_key(token), true, options)
+            .wrap_err("failed to mark refresh token as used in Redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)?;
+        if !first_use {
+            return Err(RefreshTokenStoreError::TokenReused(record.family_id));
+        }
+
+        let email = Email::parse(Secret::new(record.email))
+            .map_err(RefreshTokenStoreError::UnexpectedError)?;
+
+        Ok(RefreshTokenData {
+            email,
+            family_id: record.family_id,
+            tenant_id: record.tenant_id,
+        })
+    }
+
+    #[tracing::instrument(name = "Revoke Refresh Token Family", skip_all)]
+    async fn revoke_family(&mut self, family_id: &Uuid) -> Result<(), RefreshTokenStoreError> {
+        let mut conn = self.conn.write().await;
+        let _: () = conn
+            .set_ex(
+                make_revoked_family_key(family_id),
+                true,
+                REFRESH_TOKEN_TTL_SECONDS as u64,
+            )
+            .wrap_err("failed to revoke refresh token family in Redis")
+            .map_err(RefreshTokenStoreError::UnexpectedError)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Revoke User Refresh Tokens", skip_all)]
+    async fn revoke_user(&mut self, email: &Email) -> Result<(), RefreshTokenStoreError> {
+        let family_ids: Vec<String> = {
+            let mut conn = self.conn.write().await;
+            conn.smembers(make_user_families_key(email))
+                .wrap_err("failed to get refresh token families from Redis")
+                .map_err(RefreshTokenStoreError::UnexpectedError)?
+        };
+
+        for family_id in family_ids {
+            let family_id = Uuid::parse_str(&family_id)
+                .map_err(|e| RefreshTokenStoreError::UnexpectedError(e.into()))?;
+            self.revoke_family(&family_id).await?;
+        }
+        Ok(())
+    }
+}
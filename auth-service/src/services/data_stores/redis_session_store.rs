@@ -0,0 +1,210 @@
+use std::{net::IpAddr, sync::Arc};
+
+use color_eyre::eyre::Context;
+use redis::{Commands, Connection, ExistenceCheck, SetExpiry, SetOptions};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::{
+    domain::{Email, Session, SessionStore, SessionStoreError},
+    utils::auth::REFRESH_TOKEN_TTL_SECONDS,
+};
+
+pub struct RedisSessionStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisSessionStore {
+    #[tracing::instrument(name = "Create Redis Session Store", skip_all)]
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SessionRecord {
+    email: String,
+    device: String,
+    user_agent: Option<String>,
+    ip: IpAddr,
+    created_at: i64,
+    last_seen_at: i64,
+}
+
+impl SessionRecord {
+    fn into_session(self, id: Uuid) -> Result<Session, SessionStoreError> {
+        Ok(Session {
+            id,
+            email: Email::parse(Secret::new(self.email))
+                .map_err(SessionStoreError::UnexpectedError)?,
+            device: self.device,
+            user_agent: self.user_agent,
+            ip: self.ip,
+            created_at: self.created_at,
+            last_seen_at: self.last_seen_at,
+        })
+    }
+}
+
+// A session outlives its refresh tokens by at most the time between refreshes
+const SESSION_TTL_SECONDS: u64 = REFRESH_TOKEN_TTL_SECONDS as u64;
+const SESSION_KEY_PREFIX: &str = "session:";
+const USER_SESSIONS_KEY_PREFIX: &str = "user_sessions:";
+
+#[tracing::instrument(name = "Make Session Key", skip_all)]
+fn make_session_key(id: &Uuid) -> String {
+    format!("{}{}", SESSION_KEY_PREFIX, id)
+}
+
+#[tracing::instrument(name = "Make User Sessions Key", skip_all)]
+fn make_user_sessions_key(email: &Email) -> String {
+    format!(
+        "{}{}",
+        USER_SESSIONS_KEY_PREFIX,
+        email.as_ref().expose_secret()
+    )
+}
+
+#[tracing::instrument(name = "Get Session Record", skip_all)]
+fn get_record(conn: &mut Connection, id: &Uuid) -> Result<SessionRecord, SessionStoreError> {
+    let value: Option<String> = conn
+        .get(make_session_key(id))
+        .wrap_err("failed to get session from Redis")
+        .map_err(SessionStoreError::UnexpectedError)?;
+    let value = value.ok_or(SessionStoreError::SessionNotFound)?;
+    serde_json::from_str(&value)
+        .wrap_err("failed to deserialize session")
+        .map_err(SessionStoreError::UnexpectedError)
+}
+
+#[async_trait::async_trait]
+impl SessionStore for RedisSessionStore {
+    #[tracing::instrument(name = "Add Session", skip_all)]
+    async fn add_session(&mut self, session: Session) -> Result<(), SessionStoreError> {
+        let record = SessionRecord {
+            email: session.email.as_ref().expose_secret().to_owned(),
+            device: session.device,
+            user_agent: session.user_agent,
+            ip: session.ip,
+            created_at: session.created_at,
+            last_seen_at: session.last_seen_at,
+        };
+        let value = serde_json::to_string(&record)
+            .wrap_err("failed to serialize session")
+            .map_err(SessionStoreError::UnexpectedError)?;
+
+        let user_sessions_key = make_user_sessions_key(&session.email);
+        let mut conn = self.conn.write().await;
+        let _: () = redis::pipe()
+            .atomic()
+            .set_ex(make_session_key(&session.id), value, SESSION_TTL_SECONDS)
+            .ignore()
+            .sadd(&user_sessions_key, session.id.to_string())
+            .ignore()
+            .expire(&user_sessions_key, SESSION_TTL_SECONDS as i64)
+            .ignore()
+            .query(&mut *conn)
+            .wrap_err("failed to add session to Redis")
+            .map_err(SessionStoreError::UnexpectedError)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Get Session", skip_all)]
+    async fn get_session(&self, id: &Uuid) -> Result<Session, SessionStoreError> {
+        let mut conn = self.conn.write().await;
+        get_record(&mut conn, id)?.into_session(*id)
+    }
+
+    #[tracing::instrument(name = "Get User Sessions", skip_all)]
+    async fn get_sessions(&self, email: &Email) -> Result<Vec<Session>, SessionStoreError> {
+        let user_sessions_key = make_user_sessions_key(email);
+        let mut conn = self.conn.write().await;
+        let ids: Vec<String> = conn
+            .smembers(&user_sessions_key)
+            .wrap_err("failed to get user sessions from Redis")
+            .map_err(SessionStoreError::UnexpectedError)?;
+
+        let mut sessions = Vec::with_capacity(ids.len());
+        for id in ids {
+            let id = Uuid::parse_str(&id)
+                .wrap_err("invalid session ID in Redis")
+                .map_err(SessionStoreError::UnexpectedError)?;
+            match get_record(&mut conn, &id) {
+                Ok(record) => sessions.push(record.into_session(id)?),
+                // Expired sessions are only dropped from the index here
+                Err(SessionStoreError::SessionNotFound) => {
+                    let _: () = conn
+                        .srem(&user_sessions_key, id.to_string())
+                        .wrap_err("failed to remove expired session from Redis")
+                        .map_err(SessionStoreError::UnexpectedError)?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.last_seen_at));
+        Ok(sessions)
+    }
+
+    #[tracing::instrument(name = "Touch Session", skip_all)]
+    async fn touch_session(
+        &mut self,
+        id: &Uuid,
+        last_seen_at: i64,
+    ) -> Result<(), SessionStoreError> {
+        let mut conn = self.conn.write().await;
+        let mut record = get_record(&mut conn, id)?;
+        record.last_seen_at = last_seen_at;
+        let value = serde_json::to_string(&record)
+            .wrap_err("failed to serialize session")
+            .map_err(SessionStoreError::UnexpectedError)?;
+
+        // XX so that a session removed in the meantime isn't brought back
+        let options = SetOptions::default()
+            .conditional_set(ExistenceCheck::XX)
+            .with_expiration(SetExpiry::EX(SESSION_TTL_SECONDS as usize));
+        let updated: Option<()> = conn
+            .set_options(make_session_key(id), value, options)
+            .wrap_err("failed to update session in Redis")
+            .map_err(SessionStoreError::UnexpectedError)?;
+        updated.ok_or(SessionStoreError::SessionNotFound)
+    }
+
+    #[tracing::instrument(name = "Remove Session", skip_all)]
+    async fn remove_session(&mut self, id: &Uuid) -> Result<(), SessionStoreError> {
+        let mut conn = self.conn.write().await;
+        let session = get_record(&mut conn, id)?.into_session(*id)?;
+        let _: () = redis::pipe()
+            .atomic()
+            .del(make_session_key(id))
+            .ignore()
+            .srem(make_user_sessions_key(&session.email), id.to_string())
+            .ignore()
+            .query(&mut *conn)
+            .wrap_err("failed to remove session from Redis")
+            .map_err(SessionStoreError::UnexpectedError)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Remove User Sessions", skip_all)]
+    async fn remove_sessions(&mut self, email: &Email) -> Result<(), SessionStoreError> {
+        let user_sessions_key = make_user_sessions_key(email);
+        let mut conn = self.conn.write().await;
+        let ids: Vec<String> = conn
+            .smembers(&user_sessions_key)
+            .wrap_err("failed to get user sessions from Redis")
+            .map_err(SessionStoreError::UnexpectedError)?;
+
+        let mut keys: Vec<String> = ids
+            .iter()
+            .map(|id| format!("{}{}", SESSION_KEY_PREFIX, id))
+            .collect();
+        keys.push(user_sessions_key);
+        let _: () = conn
+            .del(keys)
+            .wrap_err("failed to remove user sessions from Redis")
+            .map_err(SessionStoreError::UnexpectedError)?;
+        Ok(())
+    }
+}
@@ -52,7 +52,7 @@ impl TwoFACodeStore for RedisTwoFACodeStore {
         let _: () = conn
             .set_ex(key, value, TEN_MINUTES_IN_SECONDS)
             .wrap_err("failed to set 2FA code in Redis")
-            .map_err(|e| TwoFACodeStoreError::UnexpectedError(e.into()))?;
+            .map_err(TwoFACodeStoreError::UnexpectedError)?;
         Ok(())
     }
 
@@ -63,7 +63,7 @@ impl TwoFACodeStore for RedisTwoFACodeStore {
         let _: () = conn
             .del(key)
             .wrap_err("failed to delete 2FA code from Redis")
-            .map_err(|e| TwoFACodeStoreError::UnexpectedError(e.into()))?;
+            .map_err(TwoFACodeStoreError::UnexpectedError)?;
         Ok(())
     }
 
@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::Context;
+use redis::{Commands, Connection};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::domain::{
+    data_stores::{
+        WebauthnCeremony, WebauthnChallenge, WebauthnChallengeData, WebauthnChallengeStore,
+        WebauthnChallengeStoreError,
+    },
+    Email,
+};
+
+pub struct RedisWebauthnChallengeStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisWebauthnChallengeStore {
+    #[tracing::instrument(name = "Create Redis WebAuthn Challenge Store", skip_all)]
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct WebauthnChallengeRecord {
+    email: Option<String>,
+    registration: bool,
+}
+
+// Matches the timeout the browser is given for the ceremony
+const WEBAUTHN_CHALLENGE_TTL_SECONDS: u64 = 60 * 5;
+const WEBAUTHN_CHALLENGE_KEY_PREFIX: &str = "webauthn_challenge:";
+
+#[tracing::instrument(name = "Make WebAuthn Challenge Key", skip_all)]
+fn make_challenge_key(challenge: &WebauthnChallenge) -> String {
+    format!(
+        "{}{}",
+        WEBAUTHN_CHALLENGE_KEY_PREFIX,
+        challenge.as_ref().expose_secret()
+    )
+}
+
+#[async_trait::async_trait]
+impl WebauthnChallengeStore for RedisWebauthnChallengeStore {
+    #[tracing::instrument(name = "Add WebAuthn Challenge", skip_all)]
+    async fn add_challenge(
+        &mut self,
+        challenge: WebauthnChallenge,
+        data: WebauthnChallengeData,
+    ) -> Result<(), WebauthnChallengeStoreError> {
+        let record = WebauthnChallengeRecord {
+            email: data
+                .email
+                .map(|email| email.as_ref().expose_secret().to_owned()),
+            registration: data.ceremony == WebauthnCeremony::Registration,
+        };
+        let value = serde_json::to_string(&record)
+            .map_err(|e| WebauthnChallengeStoreError::UnexpectedError(e.into()))?;
+
+        let mut conn = self.conn.write().await;
+        let _: () = conn
+            .set_ex(
+                make_challenge_key(&challenge),
+                value,
+                WEBAUTHN_CHALLENGE_TTL_SECONDS,
+            )
+            .wrap_err("failed to set WebAuthn challenge in Redis")
+            .map_err(WebauthnChallengeStoreError::UnexpectedError)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Take WebAuthn Challenge", skip_all)]
+    async fn take_challenge(
+        &mut self,
+        challenge: &WebauthnChallenge,
+    ) -> Result<WebauthnChallengeData, WebauthnChallengeStoreError> {
+        let mut conn = self.conn.write().await;
+        let value: Option<String> = conn
+            .get_del(make_challenge_key(challenge))
+            .wrap_err("failed to get WebAuthn challenge from Redis")
+            .map_err(WebauthnChallengeStoreError::UnexpectedError)?;
+        let value = value.ok_or(WebauthnChallengeStoreError::ChallengeNotFound)?;
+
+        let record: WebauthnChallengeRecord = serde_json::from_str(&value)
+            .map_err(|e| WebauthnChallengeStoreError::UnexpectedError(e.into()))?;
+
+        let email = record
+            .email
+            .map(|email| Email::parse(Secret::new(email)))
+            .transpose()
+            .map_err(WebauthnChallengeStoreError::UnexpectedError)?;
+
+        Ok(WebauthnChallengeData {
+            email,
+            ceremony: if record.registration {
+                WebauthnCeremony::Registration
+            } else {
+                WebauthnCeremony::Authentication
+            },
+        })
+    }
+}
@@ -0,0 +1,123 @@
+use std::path::PathBuf;
+
+use color_eyre::eyre::{Context, Result};
+use secrecy::ExposeSecret;
+use tokio::{
+    fs::{File, OpenOptions},
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    sync::Mutex,
+};
+
+use crate::domain::{AuditRecord, AuditSink, Email};
+
+/// Appends records to a file, one JSON object per line, for log shippers to pick up.
+/// The file stays open, so it has to be rotated in place (e.g. logrotate's
+/// `copytruncate`) rather than moved.
+pub struct JsonLinesAuditSink {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl JsonLinesAuditSink {
+    pub async fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await
+            .wrap_err_with(|| format!("failed to open audit log {}", path.display()))?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl AuditSink for JsonLinesAuditSink {
+    async fn record(&self, record: &AuditRecord) -> Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+
+        // A single write per record keeps concurrent records from interleaving
+        let mut file = self.file.lock().await;
+        file.write_all(&line)
+            .await
+            .wrap_err("failed to write to audit log")?;
+        file.flush().await.wrap_err("failed to flush audit log")?;
+        Ok(())
+    }
+
+    async fn get_records(&self, email: &Email) -> Result<Vec<AuditRecord>> {
+        let file = File::open(&self.path)
+            .await
+            .wrap_err("failed to open audit log")?;
+
+        // Read a line at a time, so only the user's records are held in memory
+        let mut lines = BufReader::new(file).lines();
+        let mut records = Vec::new();
+        while let Some(line) = lines
+            .next_line()
+            .await
+            .wrap_err("failed to read audit log")?
+        {
+            if line.is_empty() {
+                continue;
+            }
+            let record: AuditRecord = serde_json::from_str(&line)?;
+            if record.email.as_deref() == Some(email.as_ref().expose_secret().as_str()) {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::domain::{AuditEvent, LoginMethod};
+
+    fn record(email: &str, event: AuditEvent) -> AuditRecord {
+        AuditRecord {
+            timestamp: 1_760_000_000,
+            request_id: Uuid::new_v4(),
+            ip: "127.0.0.1".parse().unwrap(),
+            email: Some(email.to_owned()),
+            event,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_and_get_records() {
+        let path = std::env::temp_dir().join(format!("audit-{}.jsonl", Uuid::new_v4()));
+        let sink = JsonLinesAuditSink::open(&path).await.unwrap();
+
+        let signup = record("test@example.com", AuditEvent::Signup);
+        let login = record(
+            "test@example.com",
+            AuditEvent::LoginSucceeded {
+                method: LoginMethod::Password,
+            },
+        );
+        let other = record("other@example.com", AuditEvent::Signup);
+        for record in [&signup, &other, &login] {
+            sink.record(record).await.unwrap();
+        }
+
+        let email = Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        assert_eq!(sink.get_records(&email).await.unwrap(), vec![signup, login]);
+
+        // Reopening appends to what is already there
+        let sink = JsonLinesAuditSink::open(&path).await.unwrap();
+        sink.record(&other).await.unwrap();
+        let lines = tokio::fs::read_to_string(&path).await.unwrap();
+        assert_eq!(lines.lines().count(), 4);
+
+        let _ = tokio::fs::remove_file(&path).await;
+    }
+}
@@ -1,3 +1,5 @@
 pub mod data_stores;
+pub mod json_lines_audit_sink;
 pub mod mock_email_client;
+pub mod postgres_audit_sink;
 pub mod postmark_email_client;
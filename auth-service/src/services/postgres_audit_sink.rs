@@ -0,0 +1,76 @@
+use color_eyre::eyre::{Context, Result};
+use secrecy::ExposeSecret;
+use sqlx::PgPool;
+
+use crate::domain::{AuditRecord, AuditSink, Email};
+
+/// Records go into the `audit_events` table, which rejects updates and deletes
+pub struct PostgresAuditSink {
+    pool: PgPool,
+}
+
+impl PostgresAuditSink {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl AuditSink for PostgresAuditSink {
+    #[tracing::instrument(name = "Recording audit event in PostgreSQL", skip_all)]
+    async fn record(&self, record: &AuditRecord) -> Result<()> {
+        let details = serde_json::to_value(&record.event)?;
+        let event = details["event"].as_str().unwrap_or_default().to_owned();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO audit_events (occurred_at, request_id, ip, email, event, details)
+            VALUES (to_timestamp($1), $2::TEXT::UUID, $3::TEXT::INET, $4, $5, $6::TEXT::JSONB)
+            "#,
+            record.timestamp as f64,
+            record.request_id.to_string(),
+            record.ip.to_string(),
+            record.email,
+            event,
+            details.to_string()
+        )
+        .execute(&self.pool)
+        .await
+        .wrap_err("failed to insert audit event")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Retrieving audit events from PostgreSQL", skip_all)]
+    async fn get_records(&self, email: &Email) -> Result<Vec<AuditRecord>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                EXTRACT(EPOCH FROM occurred_at)::BIGINT AS "timestamp!",
+                request_id::TEXT AS "request_id!",
+                host(ip) AS "ip!",
+                email,
+                details::TEXT AS "details!"
+            FROM audit_events
+            WHERE email = $1
+            ORDER BY id
+            "#,
+            email.as_ref().expose_secret()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("failed to fetch audit events")?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(AuditRecord {
+                    timestamp: row.timestamp,
+                    request_id: row.request_id.parse()?,
+                    ip: row.ip.parse()?,
+                    email: row.email,
+                    event: serde_json::from_str(&row.details)?,
+                })
+            })
+            .collect()
+    }
+}
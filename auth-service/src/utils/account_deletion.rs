@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use crate::{
+    app_state::{TokenVersionStoreType, UserStoreType},
+    domain::UserStoreError,
+};
+
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+pub fn grace_period(days: u64) -> Duration {
+    Duration::from_secs(days * 24 * 60 * 60)
+}
+
+// Remove the accounts whose grace period is over for good
+#[tracing::instrument(name = "Purge Deleted Accounts", skip_all)]
+pub async fn purge_deleted_accounts(
+    user_store: &UserStoreType,
+    token_version_store: &TokenVersionStoreType,
+    grace_period: Duration,
+) -> Result<u64, UserStoreError> {
+    let purged = user_store
+        .write()
+        .await
+        .purge_deleted_users(grace_period)
+        .await?;
+
+    // A cached version would outlive the account and be picked up by a new
+    // account with the same address
+    let mut token_version_store = token_version_store.write().await;
+    for email in &purged {
+        if let Err(e) = token_version_store.remove_token_version(email).await {
+            tracing::error!("failed to remove token version: {:?}", e);
+        }
+    }
+
+    if !purged.is_empty() {
+        tracing::info!("purged {} deleted accounts", purged.len());
+    }
+    Ok(purged.len() as u64)
+}
+
+// Purge deleted accounts every hour for as long as the server runs
+pub fn spawn_account_purger(
+    user_store: UserStoreType,
+    token_version_store: TokenVersionStoreType,
+    grace_period: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) =
+                purge_deleted_accounts(&user_store, &token_version_store, grace_period).await
+            {
+                tracing::error!("failed to purge deleted accounts: {:?}", e);
+            }
+        }
+    })
+}
@@ -0,0 +1,49 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use axum_extra::extract::CookieJar;
+use secrecy::Secret;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuthApiError, Email},
+    utils::{
+        auth::{authenticate_principal, bearer_token},
+        constants::JWT_COOKIE_NAME,
+    },
+};
+
+/// The admin a request to the admin API was made by
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdminUser {
+    pub email: Email,
+}
+
+// Let only admins through to the admin API. The access token can come from the
+// `jwt` cookie or an `Authorization: Bearer` header, so that scripts can use it
+// too. API keys are accepted on the Bearer path like everywhere else, but never
+// grant admin access. Handlers get the admin as an `AdminUser` extension.
+#[tracing::instrument(name = "Require Admin", skip_all)]
+pub async fn require_admin(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, AuthApiError> {
+    let token = bearer_token(request.headers())
+        .or_else(|| jar.get(JWT_COOKIE_NAME).map(|cookie| cookie.value()))
+        .ok_or(AuthApiError::MissingToken)?;
+
+    let principal = authenticate_principal(token, &state)
+        .await
+        .map_err(|_| AuthApiError::InvalidToken)?;
+    if !principal.admin {
+        return Err(AuthApiError::Forbidden);
+    }
+
+    let email = Email::parse(Secret::new(principal.sub)).map_err(|_| AuthApiError::InvalidToken)?;
+    request.extensions_mut().insert(AdminUser { email });
+    Ok(next.run(request).await)
+}
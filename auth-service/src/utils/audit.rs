@@ -0,0 +1,54 @@
+use std::net::IpAddr;
+
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use color_eyre::eyre::eyre;
+use secrecy::ExposeSecret;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AuditSinkType,
+    domain::{AuditEvent, AuditRecord, AuthApiError, Email},
+    utils::{client_ip::ClientIp, tracing::RequestId},
+};
+
+/// The request being handled, which the audit events it causes are recorded with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditContext {
+    pub request_id: Uuid,
+    pub ip: IpAddr,
+}
+
+impl AuditContext {
+    // Record `event` about the account of `email`. Failing to record it is
+    // logged rather than failing the request.
+    pub async fn record(&self, sink: &AuditSinkType, email: Option<&Email>, event: AuditEvent) {
+        let record = AuditRecord {
+            timestamp: chrono::Utc::now().timestamp(),
+            request_id: self.request_id,
+            ip: self.ip,
+            email: email.map(|email| email.as_ref().expose_secret().to_owned()),
+            event,
+        };
+        if let Err(e) = sink.record(&record).await {
+            tracing::error!("failed to record audit event: {:?}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AuditContext
+where
+    S: Send + Sync,
+{
+    type Rejection = AuthApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let RequestId(request_id) = parts
+            .extensions
+            .get::<RequestId>()
+            .copied()
+            .ok_or_else(|| AuthApiError::UnexpectedError(eyre!("missing request ID")))?;
+        let ClientIp(ip) = ClientIp::from_request_parts(parts, state).await?;
+        Ok(AuditContext { request_id, ip })
+    }
+}
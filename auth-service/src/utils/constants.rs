@@ -1,21 +1,98 @@
 use core::panic;
 use dotenvy::dotenv;
 use lazy_static::lazy_static;
+use reqwest::Url;
 use secrecy::Secret;
-use std::{collections::HashMap, env as std_env};
+use std::{collections::HashMap, env as std_env, net::IpAddr};
 
 lazy_static! {
     pub static ref ENV: HashMap<String, String> = init_env();
+    // Release builds refuse to sign tokens with a key generated on startup
+    // unless this is set, since every restart or replica would have its own
+    pub static ref ALLOW_EPHEMERAL_JWT_KEY: bool = ENV
+        .get(env::ALLOW_EPHEMERAL_JWT_KEY_ENV_VAR)
+        .map(|value| value == "true")
+        .unwrap_or(false);
+    // Audit events go to this file as JSON lines instead of the database
+    pub static ref AUDIT_LOG_FILE: Option<String> = ENV
+        .get(env::AUDIT_LOG_FILE_ENV_VAR)
+        .filter(|path| !path.is_empty())
+        .cloned();
+    // How long a deleted account can still be restored by logging in
+    pub static ref ACCOUNT_DELETION_GRACE_PERIOD_DAYS: u64 = ENV
+        .get(env::ACCOUNT_DELETION_GRACE_PERIOD_DAYS_ENV_VAR)
+        .filter(|days| !days.is_empty())
+        .map(|days| {
+            days.parse()
+                .expect("ACCOUNT_DELETION_GRACE_PERIOD_DAYS must be a number of days.")
+        })
+        .unwrap_or(DEFAULT_ACCOUNT_DELETION_GRACE_PERIOD_DAYS);
     pub static ref DATABASE_URL: Secret<String> = ENV
         .get(env::DATABASE_URL_ENV_VAR)
         .cloned()
         .map(Secret::new)
         .unwrap_or_else(|| { panic!("DATABASE_URL must be set.") });
-    pub static ref JWT_SECRET: Secret<String> = ENV
-        .get(env::JWT_SECRET_ENV_VAR)
+    pub static ref JWT_KEYS_DIR: Option<String> = ENV
+        .get(env::JWT_KEYS_DIR_ENV_VAR)
+        .filter(|dir| !dir.is_empty())
+        .cloned();
+    pub static ref JWT_SIGNING_KEY_ID: Option<String> = ENV
+        .get(env::JWT_SIGNING_KEY_ID_ENV_VAR)
+        .filter(|kid| !kid.is_empty())
+        .cloned();
+    pub static ref OIDC_ISSUER: String = ENV
+        .get(env::OIDC_ISSUER_ENV_VAR)
+        .filter(|issuer| !issuer.is_empty())
+        .map(|issuer| issuer.trim_end_matches('/').to_owned())
+        .unwrap_or(DEFAULT_OIDC_ISSUER.to_string());
+    pub static ref REQUIRE_VERIFIED_EMAIL: bool = ENV
+        .get(env::REQUIRE_VERIFIED_EMAIL_ENV_VAR)
+        .map(|value| value == "true")
+        .unwrap_or(false);
+    pub static ref TOTP_ENCRYPTION_KEY: Secret<String> = ENV
+        .get(env::TOTP_ENCRYPTION_KEY_ENV_VAR)
         .cloned()
         .map(Secret::new)
-        .unwrap_or_else(|| { panic!("JWT_SECRET must be set.") });
+        .unwrap_or_else(|| { panic!("TOTP_ENCRYPTION_KEY must be set.") });
+    // Passkeys are bound to the site's origin and registrable domain, which
+    // default to those of the public URL
+    pub static ref WEBAUTHN_ORIGIN: String = ENV
+        .get(env::WEBAUTHN_ORIGIN_ENV_VAR)
+        .filter(|origin| !origin.is_empty())
+        .map(|origin| origin.trim_end_matches('/').to_owned())
+        .unwrap_or_else(|| {
+            Url::parse(&OIDC_ISSUER)
+                .expect("OIDC_ISSUER must be a URL.")
+                .origin()
+                .ascii_serialization()
+        });
+    pub static ref WEBAUTHN_RP_ID: String = ENV
+        .get(env::WEBAUTHN_RP_ID_ENV_VAR)
+        .filter(|rp_id| !rp_id.is_empty())
+        .cloned()
+        .unwrap_or_else(|| {
+            Url::parse(&WEBAUTHN_ORIGIN)
+                .ok()
+                .and_then(|origin| origin.host_str().map(str::to_owned))
+                .expect("WEBAUTHN_ORIGIN must be a URL.")
+        });
+    // Proxies whose `X-Forwarded-For` entries are believed when working out
+    // the client address
+    pub static ref TRUSTED_PROXIES: Vec<IpAddr> = ENV
+        .get(env::TRUSTED_PROXIES_ENV_VAR)
+        .map(|proxies| {
+            proxies
+                .split(',')
+                .map(str::trim)
+                .filter(|proxy| !proxy.is_empty())
+                .map(|proxy| {
+                    proxy
+                        .parse()
+                        .expect("TRUSTED_PROXIES must be a comma-separated list of IP addresses.")
+                })
+                .collect()
+        })
+        .unwrap_or_default();
     pub static ref REDIS_HOSTNAME: String = ENV
         .get(env::REDIS_HOSTNAME_ENV_VAR)
         .cloned()
@@ -33,14 +110,30 @@ fn init_env() -> HashMap<String, String> {
 }
 
 pub mod env {
+    pub const ACCOUNT_DELETION_GRACE_PERIOD_DAYS_ENV_VAR: &str =
+        "ACCOUNT_DELETION_GRACE_PERIOD_DAYS";
+    pub const ALLOW_EPHEMERAL_JWT_KEY_ENV_VAR: &str = "ALLOW_EPHEMERAL_JWT_KEY";
+    pub const AUDIT_LOG_FILE_ENV_VAR: &str = "AUDIT_LOG_FILE";
     pub const DATABASE_URL_ENV_VAR: &str = "DATABASE_URL";
-    pub const JWT_SECRET_ENV_VAR: &str = "JWT_SECRET";
+    pub const JWT_KEYS_DIR_ENV_VAR: &str = "JWT_KEYS_DIR";
+    pub const JWT_SIGNING_KEY_ID_ENV_VAR: &str = "JWT_SIGNING_KEY_ID";
+    pub const OIDC_ISSUER_ENV_VAR: &str = "OIDC_ISSUER";
+    pub const REQUIRE_VERIFIED_EMAIL_ENV_VAR: &str = "REQUIRE_VERIFIED_EMAIL";
+    pub const TOTP_ENCRYPTION_KEY_ENV_VAR: &str = "TOTP_ENCRYPTION_KEY";
+    pub const TRUSTED_PROXIES_ENV_VAR: &str = "TRUSTED_PROXIES";
+    pub const WEBAUTHN_ORIGIN_ENV_VAR: &str = "WEBAUTHN_ORIGIN";
+    pub const WEBAUTHN_RP_ID_ENV_VAR: &str = "WEBAUTHN_RP_ID";
     pub const REDIS_HOSTNAME_ENV_VAR: &str = "REDIS_HOSTNAME";
     pub const POSTMARK_AUTH_TOKEN_ENV_VAR: &str = "POSTMARK_AUTH_TOKEN";
 }
 
 pub const JWT_COOKIE_NAME: &str = "jwt";
+pub const REFRESH_TOKEN_COOKIE_NAME: &str = "refresh_token";
+pub const PENDING_2FA_COOKIE_NAME: &str = "pending_2fa";
+pub const MAGIC_LINK_NONCE_COOKIE_NAME: &str = "magic_link_nonce";
 pub const DEFAULT_REDIS_HOSTNAME: &str = "127.0.0.1";
+pub const DEFAULT_OIDC_ISSUER: &str = "http://localhost:3000";
+pub const DEFAULT_ACCOUNT_DELETION_GRACE_PERIOD_DAYS: u64 = 30;
 
 pub mod prod {
     pub const APP_ADDRESS: &str = "0.0.0.0:3000";
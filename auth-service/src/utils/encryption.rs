@@ -0,0 +1,97 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use color_eyre::eyre::{eyre, Context, Result};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use secrecy::{ExposeSecret, Secret};
+
+/// AES-256-GCM key for encrypting secrets at rest. A ciphertext is the random
+/// nonce followed by the encrypted data and the authentication tag.
+#[derive(Clone)]
+pub struct EncryptionKey(LessSafeKey);
+
+impl EncryptionKey {
+    /// `key` is 32 bytes, base64 encoded
+    pub fn parse(key: &Secret<String>) -> Result<Self> {
+        let bytes = STANDARD
+            .decode(key.expose_secret())
+            .wrap_err("encryption key is not valid base64")?;
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn generate_random() -> Self {
+        let mut bytes = [0u8; 32];
+        rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut bytes);
+        Self::from_bytes(&bytes).expect("32 bytes is a valid AES-256 key")
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let key = UnboundKey::new(&AES_256_GCM, bytes)
+            .map_err(|_| eyre!("encryption key must be 32 bytes"))?;
+        Ok(Self(LessSafeKey::new(key)))
+    }
+
+    /// `aad` is authenticated but not encrypted. Binding a ciphertext to the
+    /// row it is stored in keeps it from being copied to another row.
+    pub fn encrypt(&self, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut nonce);
+
+        let mut in_out = plaintext.to_vec();
+        self.0
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(aad),
+                &mut in_out,
+            )
+            .map_err(|_| eyre!("failed to encrypt"))?;
+
+        let mut ciphertext = nonce.to_vec();
+        ciphertext.append(&mut in_out);
+        Ok(ciphertext)
+    }
+
+    pub fn decrypt(&self, ciphertext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        if ciphertext.len() < NONCE_LEN {
+            return Err(eyre!("ciphertext is too short"));
+        }
+        let (nonce, sealed) = ciphertext.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| eyre!("invalid nonce"))?;
+
+        let mut in_out = sealed.to_vec();
+        let plaintext = self
+            .0
+            .open_in_place(nonce, Aad::from(aad), &mut in_out)
+            .map_err(|_| eyre!("failed to decrypt"))?;
+        Ok(plaintext.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_encrypt_decrypt() {
+        let key = EncryptionKey::generate_random();
+        let ciphertext = key.encrypt(b"secret", b"test@example.com").unwrap();
+
+        assert_ne!(&ciphertext[NONCE_LEN..], b"secret");
+        assert_eq!(
+            key.decrypt(&ciphertext, b"test@example.com").unwrap(),
+            b"secret"
+        );
+        assert!(key.decrypt(&ciphertext, b"other@example.com").is_err());
+        assert!(EncryptionKey::generate_random()
+            .decrypt(&ciphertext, b"test@example.com")
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_parse() {
+        let key = Secret::new(STANDARD.encode([7u8; 32]));
+        assert!(EncryptionKey::parse(&key).is_ok());
+
+        let short_key = Secret::new(STANDARD.encode([7u8; 16]));
+        assert!(EncryptionKey::parse(&short_key).is_err());
+        assert!(EncryptionKey::parse(&Secret::new("not base64!".to_owned())).is_err());
+    }
+}
@@ -1,5 +1,13 @@
+pub mod account_deletion;
+pub mod admin;
+pub mod audit;
 pub mod auth;
+pub mod client_ip;
 pub mod constants;
+pub mod encryption;
+pub mod keyring;
+pub mod rate_limit;
+pub mod throttle;
 pub mod tracing;
 
 pub use constants::*;
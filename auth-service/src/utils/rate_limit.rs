@@ -0,0 +1,100 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuthApiError, RateLimit, RateLimitDecision},
+    utils::client_ip::ClientIp,
+};
+
+// Routes that send email or create accounts get the tightest limits, routes
+// that check credentials come next, and token checks made by other services
+// on every one of their requests get the loosest
+const ROUTE_RATE_LIMITS: &[(&str, RateLimit)] = &[
+    ("/signup", RateLimit::per_hour(10)),
+    ("/2fa/code", RateLimit::per_hour(10)),
+    ("/account/email", RateLimit::per_hour(10)),
+    ("/login/magic-link", RateLimit::per_hour(10)),
+    ("/password-reset/request", RateLimit::per_hour(10)),
+    ("/verify-email/resend", RateLimit::per_hour(10)),
+    ("/2fa/disable", RateLimit::per_minute(30)),
+    ("/2fa/enable", RateLimit::per_minute(30)),
+    ("/account", RateLimit::per_minute(30)),
+    ("/account/email/confirm", RateLimit::per_minute(30)),
+    ("/account/password", RateLimit::per_minute(30)),
+    ("/login", RateLimit::per_minute(30)),
+    ("/login/magic-link/callback", RateLimit::per_minute(30)),
+    ("/password-reset/confirm", RateLimit::per_minute(30)),
+    ("/sessions/revoke", RateLimit::per_minute(30)),
+    ("/token", RateLimit::per_minute(30)),
+    ("/verify-2fa", RateLimit::per_minute(30)),
+    ("/webauthn/login/finish", RateLimit::per_minute(30)),
+    ("/webauthn/login/start", RateLimit::per_minute(30)),
+    ("/.well-known/jwks.json", RateLimit::per_second(50)),
+    ("/oauth/introspect", RateLimit::per_second(50)),
+    ("/userinfo", RateLimit::per_second(50)),
+    ("/verify-token", RateLimit::per_second(50)),
+];
+
+const DEFAULT_RATE_LIMIT: RateLimit = RateLimit::per_minute(60);
+
+pub fn route_rate_limit(path: &str) -> RateLimit {
+    ROUTE_RATE_LIMITS
+        .iter()
+        .find(|(route, _)| *route == path)
+        .map(|(_, limit)| *limit)
+        .unwrap_or(DEFAULT_RATE_LIMIT)
+}
+
+// Everyone is limited by address, OAuth clients included. A client ID in the
+// request can't pick the bucket: it is only checked later by the handler, and
+// checking it here would cost a password hash before the bucket is even looked at.
+fn rate_limit_key(path: &str, client_ip: ClientIp) -> String {
+    format!("{}:ip:{}", path, client_ip.0)
+}
+
+#[tracing::instrument(name = "Rate Limit", skip_all)]
+pub async fn rate_limit(
+    State(state): State<AppState>,
+    client_ip: ClientIp,
+    matched_path: MatchedPath,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = matched_path.as_str();
+    let limit = route_rate_limit(path);
+    let key = rate_limit_key(path, client_ip);
+
+    let decision = state
+        .rate_limit_store
+        .write()
+        .await
+        .take_token(&key, &limit)
+        .await;
+    match decision {
+        Ok(RateLimitDecision::Allowed) => next.run(request).await,
+        Ok(RateLimitDecision::Limited {
+            retry_after_seconds,
+        }) => AuthApiError::TooManyRequests(retry_after_seconds).into_response(),
+        // Fail open: an unreachable store shouldn't take the whole service down
+        Err(e) => {
+            tracing::error!("failed to apply rate limit: {:?}", e);
+            next.run(request).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_rate_limit() {
+        assert_eq!(route_rate_limit("/signup"), RateLimit::per_hour(10));
+        assert_eq!(route_rate_limit("/verify-token"), RateLimit::per_second(50));
+        assert_eq!(route_rate_limit("/logout"), DEFAULT_RATE_LIMIT);
+    }
+}
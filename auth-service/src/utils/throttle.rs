@@ -0,0 +1,78 @@
+use crate::{
+    app_state::LoginThrottleStoreType,
+    domain::{AuthApiError, ThrottleKey},
+};
+
+// Fail with `TooManyRequests` if any of `keys` is locked out
+#[tracing::instrument(name = "Check Login Lockout", skip_all)]
+pub async fn check_lockout(
+    store: &LoginThrottleStoreType,
+    keys: &[ThrottleKey],
+) -> Result<(), AuthApiError> {
+    let store = store.read().await;
+    let mut retry_after = None;
+    for key in keys {
+        let lockout = store
+            .get_lockout(key)
+            .await
+            .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+        retry_after = retry_after.max(lockout);
+    }
+
+    match retry_after {
+        Some(seconds) => Err(AuthApiError::TooManyRequests(seconds)),
+        None => Ok(()),
+    }
+}
+
+// Count a failed attempt against `key` and lock it out if that was one too
+// many. Returns the number of failures so far.
+#[tracing::instrument(name = "Record Failed Attempt", skip_all)]
+pub async fn record_failed_attempt(
+    store: &LoginThrottleStoreType,
+    key: &ThrottleKey,
+) -> Result<u32, AuthApiError> {
+    let policy = key.policy();
+    let mut store = store.write().await;
+
+    let failures = store
+        .record_failure(key, policy.window_seconds())
+        .await
+        .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    if let Some(seconds) = policy.lockout_seconds(failures) {
+        tracing::warn!("locking out {} for {} seconds", key.id(), seconds);
+        store
+            .lock_out(key, seconds)
+            .await
+            .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    }
+
+    Ok(failures)
+}
+
+// Count a failed attempt against each of `keys`
+pub async fn record_failed_attempts(
+    store: &LoginThrottleStoreType,
+    keys: &[ThrottleKey],
+) -> Result<(), AuthApiError> {
+    for key in keys {
+        record_failed_attempt(store, key).await?;
+    }
+    Ok(())
+}
+
+// Forget the failed attempts of `keys` after a successful login
+#[tracing::instrument(name = "Reset Failed Attempts", skip_all)]
+pub async fn reset_failed_attempts(
+    store: &LoginThrottleStoreType,
+    keys: &[ThrottleKey],
+) -> Result<(), AuthApiError> {
+    let mut store = store.write().await;
+    for key in keys {
+        store
+            .reset(key)
+            .await
+            .map_err(|e| AuthApiError::UnexpectedError(e.into()))?;
+    }
+    Ok(())
+}
@@ -1,12 +1,13 @@
 use std::time::Duration;
 
-use axum::{body::Body, extract::Request, response::Response};
+use axum::{body::Body, extract::Request, middleware::Next, response::Response};
 use color_eyre::eyre::Result;
 use tracing::{Level, Span};
 use tracing_error::ErrorLayer;
 use tracing_subscriber::filter::EnvFilter;
 use tracing_subscriber::fmt;
 use tracing_subscriber::prelude::*;
+use uuid::Uuid;
 
 pub fn init_tracing() -> Result<()> {
     let fmt_layer = fmt::layer().compact();
@@ -21,8 +22,22 @@ pub fn init_tracing() -> Result<()> {
     Ok(())
 }
 
+/// ID of a request, shared by its log lines and the audit events it causes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestId(pub Uuid);
+
+// Give the request an ID before it reaches the trace layer, so that handlers
+// can read the same ID the request's span is created with
+pub async fn assign_request_id(mut request: Request, next: Next) -> Response {
+    request.extensions_mut().insert(RequestId(Uuid::new_v4()));
+    next.run(request).await
+}
+
 pub fn make_span_with_request_id(request: &Request<Body>) -> Span {
-    let request_id = uuid::Uuid::new_v4();
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map_or_else(Uuid::new_v4, |RequestId(id)| *id);
     tracing::span!(
         Level::INFO,
         "[REQUEST]",
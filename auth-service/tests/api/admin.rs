@@ -0,0 +1,343 @@
+use secrecy::Secret;
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use auth_service::{
+    domain::{AuditEvent, Email},
+    routes::ListUsersResponse,
+    utils::constants::JWT_COOKIE_NAME,
+};
+
+use crate::helpers::{
+    get_audit_events, get_cookie, get_emailed_link_param, get_random_email, login, signup,
+    signup_and_login, signup_and_login_as_admin, TestApp,
+};
+
+const PASSWORD: &str = "P4sSword123!";
+
+// Sign up and log in a user for the admin to act on, returning their access token
+async fn signup_and_login_target(app: &TestApp, email: &str) -> String {
+    let response = signup_and_login(app, email, PASSWORD).await;
+    get_cookie(&response, JWT_COOKIE_NAME)
+}
+
+#[tokio::test]
+async fn should_return_400_if_token_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app.get_admin_users(&[]).await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = app
+        .post_admin_user_action(&get_random_email(), "lock")
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_403_if_user_is_not_admin() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, PASSWORD).await;
+
+    let response = app.get_admin_users(&[]).await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    let response = app.delete_admin_user(&email).await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_accept_admin_bearer_token() {
+    let mut app = TestApp::new().await;
+
+    let admin = signup_and_login_as_admin(&app).await;
+    let response = login(&app, &admin, "Adm1nP4sSword!", false).await;
+    let token = get_cookie(&response, JWT_COOKIE_NAME);
+
+    let response = reqwest::Client::new()
+        .get(format!("{}/admin/users", &app.address))
+        .bearer_auth(token)
+        .send()
+        .await
+        .expect("Failed to execute admin users request.");
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_list_and_search_users_a_page_at_a_time() {
+    let mut app = TestApp::new().await;
+
+    let tag = uuid::Uuid::new_v4().simple().to_string();
+    for name in ["carol", "alice", "bob"] {
+        signup(
+            &app,
+            &format!("{}-{}@example.com", name, tag),
+            PASSWORD,
+            false,
+        )
+        .await;
+    }
+    let admin = signup_and_login_as_admin(&app).await;
+
+    let response = app
+        .get_admin_users(&[
+            ("search", &tag.to_uppercase()),
+            ("page", "2"),
+            ("perPage", "2"),
+        ])
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response
+        .json::<ListUsersResponse>()
+        .await
+        .expect("Could not deserialize response body to ListUsersResponse");
+    assert_eq!(body.total, 3);
+    assert_eq!(body.page, 2);
+    assert_eq!(body.per_page, 2);
+    assert_eq!(body.users.len(), 1);
+    assert_eq!(body.users[0].email, format!("carol-{}@example.com", tag));
+    assert!(!body.users[0].admin);
+
+    let response = app.get_admin_users(&[("search", &admin)]).await;
+    let body = response
+        .json::<ListUsersResponse>()
+        .await
+        .expect("Could not deserialize response body to ListUsersResponse");
+    assert_eq!(body.total, 1);
+    assert!(body.users[0].admin);
+
+    assert!(get_audit_events(&app, &admin)
+        .await
+        .contains(&AuditEvent::AdminUsersListed {
+            search: Some(admin)
+        }));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_lock_and_unlock_user() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let token = signup_and_login_target(&app, &email).await;
+    let admin = signup_and_login_as_admin(&app).await;
+
+    let response = app.post_admin_user_action(&email, "lock").await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_verify_token(&json!({ "token": token })).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let login_body = json!({ "email": email, "password": PASSWORD });
+    let response = app.post_login(&login_body).await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    // Logging in again replaces the admin's cookies
+    login(&app, &admin, "Adm1nP4sSword!", false).await;
+    let response = app.post_admin_user_action(&email, "unlock").await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_login(&login_body).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let events = get_audit_events(&app, &email).await;
+    assert!(events.contains(&AuditEvent::AdminUserLocked {
+        admin: admin.clone()
+    }));
+    assert!(events.contains(&AuditEvent::AdminUserUnlocked { admin }));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_require_password_reset() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let token = signup_and_login_target(&app, &email).await;
+    let admin = signup_and_login_as_admin(&app).await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app.post_admin_user_action(&email, "password-reset").await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_verify_token(&json!({ "token": token })).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app
+        .post_login(&json!({ "email": email, "password": PASSWORD }))
+        .await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    let reset_token = get_emailed_link_param(&app, "password_reset_token").await;
+    let response = app
+        .post_password_reset_confirm(&json!({
+            "token": reset_token,
+            "password": "N3wP4sSword123!",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    login(&app, &email, "N3wP4sSword123!", false).await;
+
+    assert!(get_audit_events(&app, &email)
+        .await
+        .contains(&AuditEvent::AdminPasswordResetRequired { admin }));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_require_2fa() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let token = signup_and_login_target(&app, &email).await;
+    let admin = signup_and_login_as_admin(&app).await;
+
+    let response = app.post_admin_user_action(&email, "2fa").await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_verify_token(&json!({ "token": token })).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app.post_admin_user_action(&email, "2fa").await;
+    assert_eq!(response.status().as_u16(), 409);
+
+    // The 2FA code is emailed
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+    login(&app, &email, PASSWORD, true).await;
+
+    assert!(get_audit_events(&app, &email)
+        .await
+        .contains(&AuditEvent::Admin2FARequired { admin }));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_revoke_user_sessions() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let token = signup_and_login_target(&app, &email).await;
+    let admin = signup_and_login_as_admin(&app).await;
+
+    let response = app.delete_admin_user_sessions(&email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_verify_token(&json!({ "token": token })).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    assert!(get_audit_events(&app, &email)
+        .await
+        .contains(&AuditEvent::AdminSessionsRevoked { admin }));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_grant_and_revoke_admin() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login_target(&app, &email).await;
+    let admin = signup_and_login_as_admin(&app).await;
+
+    let response = app.post_admin_user_action(&email, "admin").await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    // The admin claim comes with the next login
+    let token = get_cookie(&login(&app, &email, PASSWORD, false).await, JWT_COOKIE_NAME);
+    let get_users = || {
+        reqwest::Client::new()
+            .get(format!("{}/admin/users", &app.address))
+            .bearer_auth(&token)
+            .send()
+    };
+    let response = get_users()
+        .await
+        .expect("Failed to execute admin users request.");
+    assert_eq!(response.status().as_u16(), 200);
+
+    // Taking it away ends the sessions that carry the claim
+    login(&app, &admin, "Adm1nP4sSword!", false).await;
+    let response = app.delete_admin_user_action(&email, "admin").await;
+    assert_eq!(response.status().as_u16(), 200);
+    let response = get_users()
+        .await
+        .expect("Failed to execute admin users request.");
+    assert_eq!(response.status().as_u16(), 401);
+
+    login(&app, &email, PASSWORD, false).await;
+    let response = app.get_admin_users(&[]).await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    let events = get_audit_events(&app, &email).await;
+    assert!(events.contains(&AuditEvent::AdminGranted {
+        admin: admin.clone()
+    }));
+    assert!(events.contains(&AuditEvent::AdminRevoked { admin }));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_delete_user_without_grace_period() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let token = signup_and_login_target(&app, &email).await;
+    let admin = signup_and_login_as_admin(&app).await;
+
+    let response = app.delete_admin_user(&email).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_verify_token(&json!({ "token": token })).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app
+        .post_login(&json!({ "email": email, "password": PASSWORD }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    assert!(get_audit_events(&app, &email)
+        .await
+        .contains(&AuditEvent::AdminUserDeleted { admin }));
+
+    let response = app.delete_admin_user(&email).await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    // A new account with the same address doesn't inherit the cached version
+    signup_and_login(&app, &email, PASSWORD).await;
+    let version = app
+        .token_version_store
+        .read()
+        .await
+        .get_token_version(&Email::parse(Secret::new(email.clone())).unwrap())
+        .await;
+    assert_eq!(version, Ok(0));
+
+    app.clean_up().await;
+}
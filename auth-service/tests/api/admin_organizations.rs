@@ -0,0 +1,297 @@
+use serde_json::json;
+
+use auth_service::{
+    domain::{AuditEvent, OrganizationRole},
+    routes::{
+        AccountOrganizationsResponse, ListOrganizationMembersResponse, ListOrganizationsResponse,
+        MembershipResponse, OrganizationMemberResponse, OrganizationResponse,
+    },
+    utils::constants::JWT_COOKIE_NAME,
+};
+
+use crate::helpers::{
+    get_audit_events, get_cookie, get_random_email, login, signup_and_login,
+    signup_and_login_as_admin, TestApp,
+};
+
+const PASSWORD: &str = "P4sSword123!";
+
+async fn create_acme(app: &TestApp) {
+    let response = app
+        .post_admin_organization(&json!({ "id": "acme", "name": "Acme Corp" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+}
+
+async fn login_to_tenant(app: &TestApp, email: &str, tenant: &str) -> reqwest::Response {
+    app.post_login(&json!({ "email": email, "password": PASSWORD, "tenant": tenant }))
+        .await
+}
+
+async fn verify_tenant(app: &TestApp, token: &str, tenant: &str) -> u16 {
+    app.post_verify_token(&json!({ "token": token, "tenant": tenant }))
+        .await
+        .status()
+        .as_u16()
+}
+
+#[tokio::test]
+async fn should_return_403_if_user_is_not_admin() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), PASSWORD).await;
+
+    let response = app.get_admin_organizations().await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    let response = app
+        .post_admin_organization(&json!({ "id": "acme", "name": "Acme Corp" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_create_list_and_delete_organizations() {
+    let mut app = TestApp::new().await;
+
+    let admin = signup_and_login_as_admin(&app).await;
+    create_acme(&app).await;
+
+    let response = app
+        .post_admin_organization(&json!({ "id": "acme", "name": "Another Acme" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 409);
+
+    for (id, name) in [
+        ("", "Empty"),
+        ("Acme", "Upper"),
+        ("ac me", "Space"),
+        ("globex", " "),
+    ] {
+        let response = app
+            .post_admin_organization(&json!({ "id": id, "name": name }))
+            .await;
+        assert_eq!(response.status().as_u16(), 400, "Failed for id {:?}", id);
+    }
+
+    let response = app.get_admin_organizations().await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response
+        .json::<ListOrganizationsResponse>()
+        .await
+        .expect("Could not deserialize response body to ListOrganizationsResponse");
+    assert_eq!(
+        body.organizations,
+        vec![OrganizationResponse {
+            id: "acme".to_owned(),
+            name: "Acme Corp".to_owned(),
+        }]
+    );
+
+    let response = app.delete_admin_organization("acme").await;
+    assert_eq!(response.status().as_u16(), 200);
+    let response = app.delete_admin_organization("acme").await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let events = get_audit_events(&app, &admin).await;
+    assert!(events.contains(&AuditEvent::AdminOrganizationCreated {
+        organization: "acme".to_owned()
+    }));
+    assert!(events.contains(&AuditEvent::AdminOrganizationDeleted {
+        organization: "acme".to_owned()
+    }));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_add_list_and_remove_members() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, PASSWORD).await;
+    let admin = signup_and_login_as_admin(&app).await;
+    create_acme(&app).await;
+
+    let response = app
+        .post_admin_organization_member("acme", &email, "member")
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    // Adding a member again changes their role
+    let response = app
+        .post_admin_organization_member("acme", &email, "owner")
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.get_admin_organization_members("acme").await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response
+        .json::<ListOrganizationMembersResponse>()
+        .await
+        .expect("Could not deserialize response body to ListOrganizationMembersResponse");
+    assert_eq!(
+        body.members,
+        vec![OrganizationMemberResponse {
+            email: email.clone(),
+            role: OrganizationRole::Owner,
+        }]
+    );
+
+    let response = app.delete_admin_organization_member("acme", &email).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let response = app.delete_admin_organization_member("acme", &email).await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let events = get_audit_events(&app, &email).await;
+    assert!(events.contains(&AuditEvent::AdminOrganizationMemberAdded {
+        admin: admin.clone(),
+        organization: "acme".to_owned(),
+        role: OrganizationRole::Owner,
+    }));
+    assert!(
+        events.contains(&AuditEvent::AdminOrganizationMemberRemoved {
+            admin,
+            organization: "acme".to_owned(),
+        })
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_404_if_organization_or_user_not_found() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, PASSWORD).await;
+    signup_and_login_as_admin(&app).await;
+    create_acme(&app).await;
+
+    let response = app
+        .post_admin_organization_member("globex", &email, "member")
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let response = app
+        .post_admin_organization_member("acme", &get_random_email(), "member")
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let response = app.get_admin_organization_members("globex").await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let response = app.delete_admin_organization_member("globex", &email).await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_issue_tenant_tokens_to_members_only() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, PASSWORD).await;
+    signup_and_login_as_admin(&app).await;
+    create_acme(&app).await;
+
+    let response = login_to_tenant(&app, &email, "acme").await;
+    assert_eq!(response.status().as_u16(), 403);
+    let response = login_to_tenant(&app, &email, "globex").await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    let response = app
+        .post_admin_organization_member("acme", &email, "member")
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = login_to_tenant(&app, &email, "acme").await;
+    assert_eq!(response.status().as_u16(), 200);
+    let token = get_cookie(&response, JWT_COOKIE_NAME);
+    assert_eq!(verify_tenant(&app, &token, "acme").await, 200);
+    assert_eq!(verify_tenant(&app, &token, "globex").await, 403);
+
+    // Tokens from a login without a tenant hint aren't scoped to any tenant
+    let response = login(&app, &email, PASSWORD, false).await;
+    let token = get_cookie(&response, JWT_COOKIE_NAME);
+    assert_eq!(verify_tenant(&app, &token, "acme").await, 403);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_not_refresh_tenant_session_after_member_removed() {
+    let mut app = TestApp::new().await;
+
+    let admin = signup_and_login_as_admin(&app).await;
+    let response = login(&app, &admin, "Adm1nP4sSword!", false).await;
+    let admin_token = get_cookie(&response, JWT_COOKIE_NAME);
+    create_acme(&app).await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, PASSWORD).await;
+    let response = reqwest::Client::new()
+        .post(format!("{}/admin/organizations/acme/members", &app.address))
+        .bearer_auth(&admin_token)
+        .json(&json!({ "email": email, "role": "member" }))
+        .send()
+        .await
+        .expect("Failed to execute admin add organization member request.");
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = login_to_tenant(&app, &email, "acme").await;
+    assert_eq!(response.status().as_u16(), 200);
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 200);
+    let token = get_cookie(&response, JWT_COOKIE_NAME);
+    assert_eq!(verify_tenant(&app, &token, "acme").await, 200);
+
+    let response = reqwest::Client::new()
+        .delete(format!(
+            "{}/admin/organizations/acme/members/{}",
+            &app.address, email
+        ))
+        .bearer_auth(&admin_token)
+        .send()
+        .await
+        .expect("Failed to execute admin remove organization member request.");
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_list_account_organizations() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, PASSWORD).await;
+    signup_and_login_as_admin(&app).await;
+    create_acme(&app).await;
+    let response = app
+        .post_admin_organization_member("acme", &email, "admin")
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    login(&app, &email, PASSWORD, false).await;
+    let response = app.get_account_organizations().await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response
+        .json::<AccountOrganizationsResponse>()
+        .await
+        .expect("Could not deserialize response body to AccountOrganizationsResponse");
+    assert_eq!(
+        body.organizations,
+        vec![MembershipResponse {
+            id: "acme".to_owned(),
+            role: OrganizationRole::Admin,
+        }]
+    );
+
+    app.clean_up().await;
+}
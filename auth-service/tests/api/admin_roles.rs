@@ -0,0 +1,221 @@
+use serde_json::json;
+
+use auth_service::{
+    domain::AuditEvent,
+    routes::{RoleResponse, RolesResponse},
+    utils::constants::JWT_COOKIE_NAME,
+};
+
+use crate::helpers::{
+    get_audit_events, get_cookie, get_random_email, login, signup_and_login,
+    signup_and_login_as_admin, TestApp,
+};
+
+const PASSWORD: &str = "P4sSword123!";
+
+async fn create_billing_role(app: &TestApp) {
+    let response = app
+        .post_admin_role(&json!({
+            "name": "billing",
+            "description": "Manages invoices",
+            "permissions": ["invoices:write", "invoices:read"],
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+}
+
+async fn verify_token(app: &TestApp, token: &str, role: Option<&str>, scope: Option<&str>) -> u16 {
+    app.post_verify_token(&json!({ "token": token, "role": role, "scope": scope }))
+        .await
+        .status()
+        .as_u16()
+}
+
+#[tokio::test]
+async fn should_return_403_if_user_is_not_admin() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, PASSWORD).await;
+
+    let response = app.get_admin_roles().await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    let response = app.post_admin_user_role(&email, "billing").await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_create_list_and_delete_roles() {
+    let mut app = TestApp::new().await;
+
+    let admin = signup_and_login_as_admin(&app).await;
+    create_billing_role(&app).await;
+
+    let response = app.post_admin_role(&json!({ "name": "billing" })).await;
+    assert_eq!(response.status().as_u16(), 409);
+
+    for name in ["", "Billing", "bill ing"] {
+        let response = app.post_admin_role(&json!({ "name": name })).await;
+        assert_eq!(
+            response.status().as_u16(),
+            400,
+            "Failed for name {:?}",
+            name
+        );
+    }
+    let response = app
+        .post_admin_role(&json!({ "name": "support", "permissions": ["tickets read"] }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = app.get_admin_roles().await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response
+        .json::<RolesResponse>()
+        .await
+        .expect("Could not deserialize response body to RolesResponse");
+    assert_eq!(
+        body.roles,
+        vec![RoleResponse {
+            name: "billing".to_owned(),
+            description: "Manages invoices".to_owned(),
+            permissions: vec!["invoices:read".to_owned(), "invoices:write".to_owned()],
+        }]
+    );
+
+    let response = app.delete_admin_role("billing").await;
+    assert_eq!(response.status().as_u16(), 200);
+    let response = app.delete_admin_role("billing").await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let events = get_audit_events(&app, &admin).await;
+    assert!(events.contains(&AuditEvent::AdminRoleCreated {
+        role: "billing".to_owned()
+    }));
+    assert!(events.contains(&AuditEvent::AdminRoleDeleted {
+        role: "billing".to_owned()
+    }));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_404_if_user_or_role_not_found() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, PASSWORD).await;
+    signup_and_login_as_admin(&app).await;
+    create_billing_role(&app).await;
+
+    let response = app
+        .post_admin_user_role(&get_random_email(), "billing")
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let response = app.post_admin_user_role(&email, "support").await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let response = app.delete_admin_user_role(&email, "support").await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let response = app.get_admin_user_roles(&get_random_email()).await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_include_assigned_roles_in_tokens() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, PASSWORD).await;
+    let admin = signup_and_login_as_admin(&app).await;
+    create_billing_role(&app).await;
+
+    let response = app.post_admin_user_role(&email, "billing").await;
+    assert_eq!(response.status().as_u16(), 200);
+    // Assigning a role twice changes nothing
+    let response = app.post_admin_user_role(&email, "billing").await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.get_admin_user_roles(&email).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response
+        .json::<RolesResponse>()
+        .await
+        .expect("Could not deserialize response body to RolesResponse");
+    assert_eq!(body.roles.len(), 1);
+    assert_eq!(body.roles[0].name, "billing");
+
+    let response = login(&app, &email, PASSWORD, false).await;
+    let token = get_cookie(&response, JWT_COOKIE_NAME);
+    assert_eq!(verify_token(&app, &token, Some("billing"), None).await, 200);
+    assert_eq!(
+        verify_token(&app, &token, None, Some("invoices:read invoices:write")).await,
+        200
+    );
+    assert_eq!(verify_token(&app, &token, Some("support"), None).await, 403);
+    assert_eq!(
+        verify_token(&app, &token, None, Some("invoices:read invoices:delete")).await,
+        403
+    );
+
+    login(&app, &admin, "Adm1nP4sSword!", false).await;
+    let response = app.delete_admin_user_role(&email, "billing").await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = login(&app, &email, PASSWORD, false).await;
+    let token = get_cookie(&response, JWT_COOKIE_NAME);
+    assert_eq!(verify_token(&app, &token, Some("billing"), None).await, 403);
+    assert_eq!(verify_token(&app, &token, None, None).await, 200);
+
+    let events = get_audit_events(&app, &email).await;
+    assert!(events.contains(&AuditEvent::AdminRoleAssigned {
+        admin: admin.clone(),
+        role: "billing".to_owned()
+    }));
+    assert!(events.contains(&AuditEvent::AdminRoleUnassigned {
+        admin,
+        role: "billing".to_owned()
+    }));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_include_roles_in_refreshed_tokens() {
+    let mut app = TestApp::new().await;
+
+    let admin = signup_and_login_as_admin(&app).await;
+    let response = login(&app, &admin, "Adm1nP4sSword!", false).await;
+    let admin_token = get_cookie(&response, JWT_COOKIE_NAME);
+    create_billing_role(&app).await;
+
+    // The user's session lives in the test client's cookies, so the admin sends
+    // their token as a Bearer token from another client
+    let email = get_random_email();
+    let response = signup_and_login(&app, &email, PASSWORD).await;
+    let token = get_cookie(&response, JWT_COOKIE_NAME);
+    assert_eq!(verify_token(&app, &token, Some("billing"), None).await, 403);
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/admin/users/{}/roles", &app.address, email))
+        .bearer_auth(admin_token)
+        .json(&json!({ "role": "billing" }))
+        .send()
+        .await
+        .expect("Failed to execute admin assign role request.");
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 200);
+    let token = get_cookie(&response, JWT_COOKIE_NAME);
+    assert_eq!(verify_token(&app, &token, Some("billing"), None).await, 200);
+
+    app.clean_up().await;
+}
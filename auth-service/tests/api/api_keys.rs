@@ -0,0 +1,292 @@
+use serde_json::json;
+
+use auth_service::{
+    domain::{AuditEvent, API_KEY_PREFIX},
+    routes::{ApiKeysResponse, CreateApiKeyResponse, UserinfoResponse},
+    utils::{
+        auth::{CredentialType, Principal},
+        constants::JWT_COOKIE_NAME,
+    },
+};
+
+use crate::helpers::{
+    get_audit_events, get_cookie, get_random_email, login, signup_and_login,
+    signup_and_login_as_admin, TestApp,
+};
+
+const PASSWORD: &str = "P4sSword123!";
+
+async fn create_api_key(app: &TestApp, body: serde_json::Value) -> CreateApiKeyResponse {
+    let response = app.post_api_key(&body).await;
+    assert_eq!(response.status().as_u16(), 201);
+    response
+        .json::<CreateApiKeyResponse>()
+        .await
+        .expect("Could not deserialize response body to CreateApiKeyResponse")
+}
+
+async fn verify_api_key(app: &TestApp, key: &str, scope: Option<&str>) -> u16 {
+    app.post_verify_token(&json!({ "token": key, "scope": scope }))
+        .await
+        .status()
+        .as_u16()
+}
+
+// Create a role with `invoices:read` and give it to `email` as the admin
+async fn give_billing_role(app: &TestApp, email: &str) {
+    let response = app
+        .post_admin_role(
+            &json!({ "password": PASSWORD, "name": "billing", "permissions": ["invoices:read"] }),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let response = app.post_admin_user_role(email, "billing").await;
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn should_return_400_if_not_logged_in() {
+    let mut app = TestApp::new().await;
+
+    let response = app.get_api_keys().await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = app
+        .post_api_key(&json!({ "password": PASSWORD, "name": "CI" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_if_invalid_input() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), PASSWORD).await;
+
+    let test_cases = [
+        json!({ "password": PASSWORD, "name": "" }),
+        json!({ "password": PASSWORD, "name": " " }),
+        json!({ "password": PASSWORD, "name": "x".repeat(101) }),
+        json!({ "password": PASSWORD, "name": "CI", "expiresInDays": 0 }),
+        json!({ "password": PASSWORD, "name": "CI", "expiresInDays": 366 }),
+    ];
+    for test_case in test_cases.iter() {
+        let response = app.post_api_key(test_case).await;
+        assert_eq!(
+            response.status().as_u16(),
+            400,
+            "Failed for input: {:?}",
+            test_case
+        );
+    }
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_password_is_wrong() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), PASSWORD).await;
+
+    let response = app
+        .post_api_key(&json!({ "password": "Wr0ngP4sSword!", "name": "CI" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app.get_api_keys().await;
+    let body = response
+        .json::<ApiKeysResponse>()
+        .await
+        .expect("Could not deserialize response body to ApiKeysResponse");
+    assert!(body.api_keys.is_empty());
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_403_if_scope_exceeds_permissions() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), PASSWORD).await;
+
+    let response = app
+        .post_api_key(&json!({ "password": PASSWORD, "name": "CI", "scope": "invoices:read" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_create_list_and_revoke_api_keys() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, PASSWORD).await;
+
+    let created = create_api_key(
+        &app,
+        json!({ "password": PASSWORD, "name": "CI", "expiresInDays": 30 }),
+    )
+    .await;
+    assert!(created.key.starts_with(API_KEY_PREFIX));
+    assert_eq!(created.api_key.name, "CI");
+    assert_eq!(created.api_key.hint, created.key[..8]);
+    assert_eq!(
+        created.api_key.expires_at - created.api_key.created_at,
+        30 * 24 * 60 * 60
+    );
+
+    let response = app.get_api_keys().await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response
+        .json::<ApiKeysResponse>()
+        .await
+        .expect("Could not deserialize response body to ApiKeysResponse");
+    assert_eq!(body.api_keys, vec![created.api_key]);
+
+    // Using the key shows up in the list
+    assert_eq!(verify_api_key(&app, &created.key, None).await, 200);
+    let body = app
+        .get_api_keys()
+        .await
+        .json::<ApiKeysResponse>()
+        .await
+        .expect("Could not deserialize response body to ApiKeysResponse");
+    assert!(body.api_keys[0].last_used_at.is_some());
+
+    let id = body.api_keys[0].id.clone();
+    let response = app.delete_api_key(&id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(verify_api_key(&app, &created.key, None).await, 401);
+    let response = app.delete_api_key(&id).await;
+    assert_eq!(response.status().as_u16(), 404);
+    let response = app.delete_api_key("invalid").await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let events = get_audit_events(&app, &email).await;
+    let id = id.parse().unwrap();
+    assert!(events.contains(&AuditEvent::ApiKeyCreated {
+        id,
+        name: "CI".to_owned()
+    }));
+    assert!(events.contains(&AuditEvent::ApiKeyRevoked { id }));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_not_revoke_other_users_keys() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), PASSWORD).await;
+    let created = create_api_key(&app, json!({ "password": PASSWORD, "name": "CI" })).await;
+
+    signup_and_login(&app, &get_random_email(), PASSWORD).await;
+    let response = app.delete_api_key(&created.api_key.id).await;
+    assert_eq!(response.status().as_u16(), 404);
+    assert_eq!(verify_api_key(&app, &created.key, None).await, 200);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_verify_api_keys_as_principals() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, PASSWORD).await;
+    let admin = signup_and_login_as_admin(&app).await;
+    give_billing_role(&app, &email).await;
+
+    login(&app, &email, PASSWORD, false).await;
+    let created = create_api_key(
+        &app,
+        json!({ "password": PASSWORD, "name": "CI", "scope": "invoices:read" }),
+    )
+    .await;
+
+    let response = app
+        .post_verify_token(&json!({ "token": created.key, "scope": "invoices:read" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let principal = response
+        .json::<Principal>()
+        .await
+        .expect("Could not deserialize response body to Principal");
+    assert_eq!(principal.sub, email);
+    assert_eq!(principal.credential_type, CredentialType::ApiKey);
+    assert_eq!(principal.scope.as_deref(), Some("invoices:read"));
+    assert_eq!(
+        principal.api_key_id.map(|id| id.to_string()),
+        Some(created.api_key.id.clone())
+    );
+    assert!(!principal.admin);
+
+    // Keys carry their scope, not their user's roles
+    let response = app
+        .post_verify_token(&json!({ "token": created.key, "role": "billing" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 403);
+
+    // A key loses the permissions its user loses
+    login(&app, &admin, "Adm1nP4sSword!", false).await;
+    let response = app.delete_admin_user_role(&email, "billing").await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        verify_api_key(&app, &created.key, Some("invoices:read")).await,
+        403
+    );
+    assert_eq!(verify_api_key(&app, &created.key, None).await, 200);
+
+    // ...and stops working while its user is locked
+    let response = app.post_admin_user_action(&email, "lock").await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(verify_api_key(&app, &created.key, None).await, 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_accept_api_keys_as_bearer_tokens() {
+    let mut app = TestApp::new().await;
+
+    let admin = signup_and_login_as_admin(&app).await;
+    let response = login(&app, &admin, "Adm1nP4sSword!", false).await;
+    let admin_token = get_cookie(&response, JWT_COOKIE_NAME);
+    let created = create_api_key(&app, json!({ "password": "Adm1nP4sSword!", "name": "CI" })).await;
+
+    let response = app.get_userinfo(&created.key).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response
+        .json::<UserinfoResponse>()
+        .await
+        .expect("Could not deserialize response body to UserinfoResponse");
+    assert_eq!(body.email, admin);
+
+    // API keys never grant the admin API, even an admin's
+    let response = reqwest::Client::new()
+        .get(format!("{}/admin/users", &app.address))
+        .bearer_auth(&created.key)
+        .send()
+        .await
+        .expect("Failed to execute admin users request.");
+    assert_eq!(response.status().as_u16(), 403);
+    let response = reqwest::Client::new()
+        .get(format!("{}/admin/users", &app.address))
+        .bearer_auth(&admin_token)
+        .send()
+        .await
+        .expect("Failed to execute admin users request.");
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .get_userinfo(&format!("{}invalid", API_KEY_PREFIX))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
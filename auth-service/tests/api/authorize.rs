@@ -0,0 +1,117 @@
+use crate::helpers::{
+    get_authorization_code, get_location, get_pkce_pair, get_query_param, get_random_email,
+    register_oauth_client, signup_and_login, TestApp, TEST_REDIRECT_URI,
+};
+
+#[tokio::test]
+async fn should_return_401_if_client_is_unknown() {
+    let mut app = TestApp::new().await;
+
+    let (_, code_challenge) = get_pkce_pair();
+    let response = app
+        .get_authorize(&[
+            ("response_type", "code"),
+            ("client_id", "unknown"),
+            ("redirect_uri", TEST_REDIRECT_URI),
+            ("scope", "openid"),
+            ("code_challenge", &code_challenge),
+            ("code_challenge_method", "S256"),
+        ])
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_if_redirect_uri_is_not_registered() {
+    let mut app = TestApp::new().await;
+
+    let client_id = register_oauth_client(&app).await;
+    let (_, code_challenge) = get_pkce_pair();
+    let response = app
+        .get_authorize(&[
+            ("response_type", "code"),
+            ("client_id", &client_id),
+            ("redirect_uri", "https://evil.example.com/callback"),
+            ("scope", "openid"),
+            ("code_challenge", &code_challenge),
+            ("code_challenge_method", "S256"),
+        ])
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_redirect_with_error_if_pkce_is_missing() {
+    let mut app = TestApp::new().await;
+
+    let client_id = register_oauth_client(&app).await;
+    let response = app
+        .get_authorize(&[
+            ("response_type", "code"),
+            ("client_id", &client_id),
+            ("redirect_uri", TEST_REDIRECT_URI),
+            ("scope", "openid"),
+            ("state", "xyz"),
+        ])
+        .await;
+
+    assert_eq!(response.status().as_u16(), 303);
+
+    let location = get_location(&response);
+    assert!(location.as_str().starts_with(TEST_REDIRECT_URI));
+    assert_eq!(
+        get_query_param(&location, "error").as_deref(),
+        Some("invalid_request")
+    );
+    assert_eq!(get_query_param(&location, "state").as_deref(), Some("xyz"));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_redirect_to_login_page_if_not_logged_in() {
+    let mut app = TestApp::new().await;
+
+    let client_id = register_oauth_client(&app).await;
+    let (_, code_challenge) = get_pkce_pair();
+    let response = app
+        .get_authorize(&[
+            ("response_type", "code"),
+            ("client_id", &client_id),
+            ("redirect_uri", TEST_REDIRECT_URI),
+            ("scope", "openid"),
+            ("code_challenge", &code_challenge),
+            ("code_challenge_method", "S256"),
+        ])
+        .await;
+
+    assert_eq!(response.status().as_u16(), 303);
+
+    let location = get_location(&response);
+    let return_to = get_query_param(&location, "return_to").expect("No return_to parameter");
+    assert!(return_to.starts_with("/authorize?"));
+    assert!(return_to.contains(&client_id));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_redirect_with_code_if_logged_in() {
+    let mut app = TestApp::new().await;
+
+    let client_id = register_oauth_client(&app).await;
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+
+    let (_, code_challenge) = get_pkce_pair();
+    let code = get_authorization_code(&app, &client_id, &code_challenge).await;
+
+    assert!(!code.is_empty());
+
+    app.clean_up().await;
+}
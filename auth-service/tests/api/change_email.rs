@@ -0,0 +1,132 @@
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use crate::helpers::{get_random_email, signup, signup_and_login, TestApp};
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_account_email(&json!({
+            "newEmail": get_random_email(),
+            "password": "P4sSword123!",
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_if_new_email_is_invalid() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+
+    for new_email in ["", "not-an-email", email.as_str()] {
+        let response = app
+            .post_account_email(&json!({
+                "newEmail": new_email,
+                "password": "P4sSword123!",
+            }))
+            .await;
+
+        assert_eq!(
+            response.status().as_u16(),
+            400,
+            "Failed for new email: {}",
+            new_email
+        );
+    }
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_password_is_incorrect() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+
+    let response = app
+        .post_account_email(&json!({
+            "newEmail": get_random_email(),
+            "password": "Wr0ngP4sSword!",
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_409_if_new_email_is_taken() {
+    let mut app = TestApp::new().await;
+
+    let other_email = get_random_email();
+    signup(&app, &other_email, "P4sSword123!", false).await;
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+
+    let response = app
+        .post_account_email(&json!({
+            "newEmail": other_email,
+            "password": "P4sSword123!",
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 409);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_email_both_addresses() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let new_email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(2)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app
+        .post_account_email(&json!({
+            "newEmail": new_email,
+            "password": "P4sSword123!",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let requests = app
+        .email_server
+        .received_requests()
+        .await
+        .expect("Request recording is disabled");
+    let recipients: Vec<String> = requests
+        .iter()
+        .rev()
+        .take(2)
+        .map(|request| {
+            let body: serde_json::Value = request.body_json().expect("Email body is not JSON");
+            body["To"]
+                .as_str()
+                .expect("Email has no recipient")
+                .to_owned()
+        })
+        .collect();
+    assert_eq!(recipients, [new_email, email]);
+
+    app.clean_up().await;
+}
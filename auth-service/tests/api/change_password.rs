@@ -0,0 +1,131 @@
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use auth_service::{
+    domain::AuditEvent, routes::SessionsResponse, utils::constants::JWT_COOKIE_NAME, ErrorResponse,
+};
+
+use crate::helpers::{
+    get_audit_events, get_cookie, get_random_email, login, signup_and_login, TestApp,
+};
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_account_password(&json!({
+            "currentPassword": "P4sSword123!",
+            "newPassword": "N3wP4sSword123!",
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_if_new_password_is_weak() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+
+    let response = app
+        .post_account_password(&json!({
+            "currentPassword": "P4sSword123!",
+            "newPassword": "short",
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_current_password_is_incorrect() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+
+    let response = app
+        .post_account_password(&json!({
+            "currentPassword": "Wr0ngP4sSword!",
+            "newPassword": "N3wP4sSword123!",
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+    assert_eq!(
+        response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialize response body to ErrorResponse")
+            .error,
+        "Incorrect credentials".to_owned()
+    );
+
+    // The password is unchanged
+    login(&app, &email, "P4sSword123!", false).await;
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_change_password_and_log_out_other_sessions() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let response = signup_and_login(&app, &email, "P4sSword123!").await;
+    let other_token = get_cookie(&response, JWT_COOKIE_NAME);
+    login(&app, &email, "P4sSword123!", false).await;
+
+    // A notice is sent to the user
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app
+        .post_account_password(&json!({
+            "currentPassword": "P4sSword123!",
+            "newPassword": "N3wP4sSword123!",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let new_token = get_cookie(&response, JWT_COOKIE_NAME);
+    let events = get_audit_events(&app, &email).await;
+    assert_eq!(events.last(), Some(&AuditEvent::PasswordChanged));
+
+    let response = app
+        .post_verify_token(&json!({ "token": other_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    // This device stays logged in with a new session
+    let response = app.post_verify_token(&json!({ "token": new_token })).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let sessions = app
+        .get_sessions()
+        .await
+        .json::<SessionsResponse>()
+        .await
+        .expect("Could not deserialize response body to SessionsResponse")
+        .sessions;
+    assert_eq!(sessions.len(), 1);
+
+    let response = app
+        .post_login(&json!({ "email": email, "password": "P4sSword123!" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+    login(&app, &email, "N3wP4sSword123!", false).await;
+
+    app.clean_up().await;
+}
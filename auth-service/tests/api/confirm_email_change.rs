@@ -0,0 +1,111 @@
+use secrecy::ExposeSecret;
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use auth_service::{domain::TotpSecret, utils::constants::JWT_COOKIE_NAME};
+
+use crate::helpers::{
+    enroll_totp, get_cookie, get_emailed_link_param, get_random_email, login, login_with_2fa,
+    signup_and_login, TestApp,
+};
+
+// Ask to move the logged-in user to `new_email` and return the token from the
+// link sent there
+async fn request_email_change(app: &TestApp, new_email: &str) -> String {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let response = app
+        .post_account_email(&json!({
+            "newEmail": new_email,
+            "password": "P4sSword123!",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    get_emailed_link_param(app, "email_change_token").await
+}
+
+#[tokio::test]
+async fn should_return_401_if_token_is_invalid() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_account_email_confirm(&json!({ "token": "invalid" }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_change_email_and_revoke_tokens() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let new_email = get_random_email();
+    let password = "P4sSword123!";
+    let response = signup_and_login(&app, &email, password).await;
+    let old_token = get_cookie(&response, JWT_COOKIE_NAME);
+
+    let token = request_email_change(&app, &new_email).await;
+
+    let response = app
+        .post_account_email_confirm(&json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_verify_token(&json!({ "token": old_token })).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app
+        .post_login(&json!({ "email": email, "password": password }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+    login(&app, &new_email, password, false).await;
+
+    // The link can only be used once
+    let response = app
+        .post_account_email_confirm(&json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_keep_authenticator_app() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let new_email = get_random_email();
+    let password = "P4sSword123!";
+    signup_and_login(&app, &email, password).await;
+    let secret = enroll_totp(&app).await;
+
+    let token = request_email_change(&app, &new_email).await;
+    let response = app
+        .post_account_email_confirm(&json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let login_attempt_id = login_with_2fa(&app, &new_email, password).await;
+    let code = secret.code_at(TotpSecret::current_step());
+    let response = app
+        .post_verify_2fa(&json!({
+            "email": new_email,
+            "loginAttemptId": login_attempt_id,
+            "2FACode": code.as_ref().expose_secret(),
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
@@ -0,0 +1,161 @@
+use std::time::Duration;
+
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use auth_service::{
+    utils::{account_deletion::purge_deleted_accounts, constants::JWT_COOKIE_NAME},
+    ErrorResponse,
+};
+
+use crate::helpers::{get_cookie, get_random_email, login, signup, signup_and_login, TestApp};
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .delete_account(&json!({ "password": "P4sSword123!" }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_password_is_incorrect() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+
+    let response = app
+        .delete_account(&json!({ "password": "Wr0ngP4sSword!" }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+    assert_eq!(
+        response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialize response body to ErrorResponse")
+            .error,
+        "Incorrect credentials".to_owned()
+    );
+
+    // The account is untouched
+    assert_eq!(
+        purge_deleted_accounts(&app.user_store, &app.token_version_store, Duration::ZERO).await,
+        Ok(0)
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_revoke_tokens_and_send_notice() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let response = signup_and_login(&app, &email, "P4sSword123!").await;
+    let token = get_cookie(&response, JWT_COOKIE_NAME);
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app
+        .delete_account(&json!({ "password": "P4sSword123!" }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_verify_token(&json!({ "token": token })).await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_cancel_deletion_when_logging_in() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+
+    // The deletion notice, then the cancellation notice
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(2)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app
+        .delete_account(&json!({ "password": "P4sSword123!" }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    login(&app, &email, "P4sSword123!", false).await;
+
+    assert_eq!(
+        purge_deleted_accounts(&app.user_store, &app.token_version_store, Duration::ZERO).await,
+        Ok(0)
+    );
+    login(&app, &email, "P4sSword123!", false).await;
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_purge_account_after_grace_period() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+
+    let response = app
+        .delete_account(&json!({ "password": "P4sSword123!" }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    // Still within the grace period
+    assert_eq!(
+        purge_deleted_accounts(
+            &app.user_store,
+            &app.token_version_store,
+            Duration::from_secs(3600)
+        )
+        .await,
+        Ok(0)
+    );
+
+    assert_eq!(
+        purge_deleted_accounts(&app.user_store, &app.token_version_store, Duration::ZERO).await,
+        Ok(1)
+    );
+
+    let response = app
+        .post_login(&json!({
+            "email": email,
+            "password": "P4sSword123!",
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    // The address is free again
+    signup(&app, &email, "P4sSword123!", false).await;
+
+    app.clean_up().await;
+}
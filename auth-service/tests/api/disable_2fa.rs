@@ -0,0 +1,185 @@
+use secrecy::ExposeSecret;
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use auth_service::{domain::TotpSecret, ErrorResponse};
+
+use crate::helpers::{
+    enroll_totp, get_random_email, get_random_two_fa_code, login, login_with_emailed_2fa_code,
+    request_2fa_code, signup, signup_and_login, TestApp,
+};
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_2fa_disable(&json!({
+            "password": "P4sSword123!",
+            "2FACode": get_random_two_fa_code(),
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_password_is_incorrect() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+    let secret = enroll_totp(&app).await;
+    let code = secret.code_at(TotpSecret::current_step());
+
+    let response = app
+        .post_2fa_disable(&json!({
+            "password": "Wr0ngP4sSword!",
+            "2FACode": code.as_ref().expose_secret(),
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    // The authenticator app is still required
+    login(&app, &email, "P4sSword123!", true).await;
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_code_is_incorrect() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup(&app, &email, "P4sSword123!", true).await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    login_with_emailed_2fa_code(&app, &email, "P4sSword123!").await;
+    let code = request_2fa_code(&app, &email).await;
+    let wrong_code = if code == "000000" { "111111" } else { "000000" };
+
+    let response = app
+        .post_2fa_disable(&json!({
+            "password": "P4sSword123!",
+            "2FACode": wrong_code,
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+    assert_eq!(
+        response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialize response body to ErrorResponse")
+            .error,
+        "Incorrect credentials".to_owned()
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_409_if_2fa_not_enabled() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let code = request_2fa_code(&app, &email).await;
+
+    let response = app
+        .post_2fa_disable(&json!({
+            "password": "P4sSword123!",
+            "2FACode": code,
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 409);
+    assert_eq!(
+        response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialize response body to ErrorResponse")
+            .error,
+        "2FA not enabled".to_owned()
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_disable_emailed_2fa_and_send_notice() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup(&app, &email, "P4sSword123!", true).await;
+
+    // The login code, the code for disabling 2FA and the notice
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(3)
+        .mount(&app.email_server)
+        .await;
+
+    login_with_emailed_2fa_code(&app, &email, "P4sSword123!").await;
+    let code = request_2fa_code(&app, &email).await;
+
+    let response = app
+        .post_2fa_disable(&json!({
+            "password": "P4sSword123!",
+            "2FACode": code,
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    login(&app, &email, "P4sSword123!", false).await;
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_disable_authenticator_app() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+    let secret = enroll_totp(&app).await;
+    let code = secret.code_at(TotpSecret::current_step());
+
+    let response = app
+        .post_2fa_disable(&json!({
+            "password": "P4sSword123!",
+            "2FACode": code.as_ref().expose_secret(),
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    login(&app, &email, "P4sSword123!", false).await;
+
+    // The authenticator app can be enrolled again from scratch
+    let response = app.post_totp_enroll().await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
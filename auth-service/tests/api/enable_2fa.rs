@@ -0,0 +1,148 @@
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use auth_service::ErrorResponse;
+
+use crate::helpers::{
+    enroll_totp, get_random_email, get_random_two_fa_code, login, login_with_emailed_2fa_code,
+    request_2fa_code, signup, signup_and_login, TestApp,
+};
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_2fa_enable(&json!({ "2FACode": get_random_two_fa_code() }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_if_code_is_malformed() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+
+    let response = app.post_2fa_enable(&json!({ "2FACode": "12ab" })).await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_code_is_incorrect() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let code = request_2fa_code(&app, &email).await;
+    let wrong_code = if code == "000000" { "111111" } else { "000000" };
+
+    let response = app.post_2fa_enable(&json!({ "2FACode": wrong_code })).await;
+
+    assert_eq!(response.status().as_u16(), 401);
+    assert_eq!(
+        response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialize response body to ErrorResponse")
+            .error,
+        "Incorrect credentials".to_owned()
+    );
+
+    // 2FA is still off
+    login(&app, &email, "P4sSword123!", false).await;
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_409_if_2fa_already_enabled() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup(&app, &email, "P4sSword123!", true).await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    login_with_emailed_2fa_code(&app, &email, "P4sSword123!").await;
+    let code = request_2fa_code(&app, &email).await;
+
+    let response = app.post_2fa_enable(&json!({ "2FACode": code })).await;
+
+    assert_eq!(response.status().as_u16(), 409);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_409_if_authenticator_app_enabled() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+    enroll_totp(&app).await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let code = request_2fa_code(&app, &email).await;
+
+    let response = app.post_2fa_enable(&json!({ "2FACode": code })).await;
+
+    assert_eq!(response.status().as_u16(), 409);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_enable_2fa_and_send_notice() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+
+    // The code, the notice and the code for the next login
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(3)
+        .mount(&app.email_server)
+        .await;
+
+    let code = request_2fa_code(&app, &email).await;
+
+    let response = app.post_2fa_enable(&json!({ "2FACode": code })).await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_2fa_enable(&json!({ "2FACode": code })).await;
+
+    assert_eq!(response.status().as_u16(), 409);
+
+    login(&app, &email, "P4sSword123!", true).await;
+
+    app.clean_up().await;
+}
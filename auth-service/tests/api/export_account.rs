@@ -0,0 +1,97 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+use auth_service::{
+    domain::{AuditEvent, LoginMethod},
+    routes::{AccountExportResponse, TwoFAMethod},
+};
+
+use crate::helpers::{
+    enroll_totp, get_random_email, login, register_passkey, signup_and_login, TestApp,
+};
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app.get_account_export().await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_export_user_sessions_and_passkeys() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+    login(&app, &email, "P4sSword123!", false).await;
+    let authenticator = register_passkey(&app).await;
+
+    let response = app.get_account_export().await;
+
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response
+        .json::<AccountExportResponse>()
+        .await
+        .expect("Could not deserialize response body to AccountExportResponse");
+    assert_eq!(body.user.email, email);
+    assert!(!body.user.verified);
+    assert!(!body.user.requires_2fa);
+    assert!(!body.user.pending_deletion);
+    assert_eq!(body.two_fa_method, None);
+    assert_eq!(body.passkeys.len(), 1);
+    assert_eq!(
+        body.passkeys[0].credential_id,
+        URL_SAFE_NO_PAD.encode(&authenticator.credential_id)
+    );
+    assert_eq!(body.sessions.len(), 2);
+    assert_eq!(body.logins.len(), 2);
+    assert_eq!(
+        body.sessions
+            .iter()
+            .filter(|session| session.current)
+            .count(),
+        1
+    );
+    let events: Vec<_> = body
+        .audit_events
+        .into_iter()
+        .map(|record| record.event)
+        .collect();
+    assert_eq!(
+        events[..3],
+        [
+            AuditEvent::Signup,
+            AuditEvent::LoginSucceeded {
+                method: LoginMethod::Password
+            },
+            AuditEvent::LoginSucceeded {
+                method: LoginMethod::Password
+            },
+        ]
+    );
+    assert_eq!(events.last(), Some(&AuditEvent::PasskeyRegistered));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_export_authenticator_app_as_2fa_method() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+    enroll_totp(&app).await;
+
+    let response = app.get_account_export().await;
+
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response
+        .json::<AccountExportResponse>()
+        .await
+        .expect("Could not deserialize response body to AccountExportResponse");
+    assert_eq!(body.two_fa_method, Some(TwoFAMethod::Totp));
+
+    app.clean_up().await;
+}
@@ -1,6 +1,13 @@
 use std::{str::FromStr, sync::Arc};
 
-use reqwest::{cookie::Jar, Client};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ciborium::value::Value;
+use reqwest::{cookie::Jar, redirect::Policy, Client, Url};
+use ring::{
+    digest::{digest, SHA256},
+    rand::SystemRandom,
+    signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING},
+};
 use secrecy::{ExposeSecret, Secret};
 use serde_json::json;
 use sqlx::{
@@ -10,41 +17,97 @@ use sqlx::{
 use tokio::sync::RwLock;
 
 use auth_service::{
-    app_state::{AppState, BannedTokenStoreType, TwoFACodeStoreType},
-    domain::{Email, LoginAttemptId, TwoFACode},
+    app_state::{
+        AppState, AuditSinkType, BannedTokenStoreType, OAuthClientStoreType, RefreshTokenStoreType,
+        TokenVersionStoreType, TwoFACodeStoreType, UserStoreType,
+    },
+    domain::{
+        AssertionResponse, AttestationResponse, AuditEvent, AuditRecord, AuthenticationCredential,
+        Email, LoginAttemptId, OAuthClient, PublicKeyCredentialCreationOptions,
+        PublicKeyCredentialRequestOptions, RegistrationCredential, TotpSecret, TwoFACode,
+    },
     get_postgres_pool, get_redis_client,
+    routes::{RecoveryCodesResponse, TotpEnrollResponse, TwoFactorAuthResponse},
     services::{
         data_stores::{
+            postgres_api_key_store::PostgresApiKeyStore,
+            postgres_login_history_store::PostgresLoginHistoryStore,
+            postgres_oauth_client_store::PostgresOAuthClientStore,
+            postgres_organization_store::PostgresOrganizationStore,
+            postgres_recovery_code_store::PostgresRecoveryCodeStore,
+            postgres_role_store::PostgresRoleStore,
+            postgres_token_version_store::PostgresTokenVersionStore,
+            postgres_totp_secret_store::PostgresTotpSecretStore,
             postgres_user_store::PostgresUserStore,
+            postgres_webauthn_credential_store::PostgresWebauthnCredentialStore,
+            redis_authorization_code_store::RedisAuthorizationCodeStore,
             redis_banned_token_store::RedisBannedTokenStore,
-            redis_two_fa_code_store::RedisTwoFACodeStore,
+            redis_magic_link_token_store::RedisMagicLinkTokenStore,
+            redis_password_reset_token_store::RedisPasswordResetTokenStore,
+            redis_refresh_token_store::RedisRefreshTokenStore,
+            redis_session_store::RedisSessionStore, redis_two_fa_code_store::RedisTwoFACodeStore,
+            redis_webauthn_challenge_store::RedisWebauthnChallengeStore, HashmapLoginThrottleStore,
+            HashmapRateLimitStore,
         },
+        postgres_audit_sink::PostgresAuditSink,
         postmark_email_client::PostmarkEmailClient,
     },
     utils::{
-        constants::{test, DATABASE_URL},
+        constants::{test, DATABASE_URL, WEBAUTHN_ORIGIN},
+        encryption::EncryptionKey,
         REDIS_HOSTNAME,
     },
     Application,
 };
 use uuid::Uuid;
-use wiremock::MockServer;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
 
 pub struct TestApp {
     pub address: String,
+    pub audit_sink: AuditSinkType,
     pub banned_token_store: BannedTokenStoreType,
     pub cookie_jar: Arc<Jar>,
     pub clean_up_called: bool,
     pub db_name: String,
     pub email_server: MockServer,
     pub http_client: reqwest::Client,
+    pub oauth_client_store: OAuthClientStoreType,
+    pub refresh_token_store: RefreshTokenStoreType,
+    pub token_version_store: TokenVersionStoreType,
     pub two_fa_code_store: TwoFACodeStoreType,
+    pub user_store: UserStoreType,
 }
 
 impl TestApp {
     pub async fn new() -> Self {
         let (pg_pool, db_name) = configure_postgresql().await;
-        let user_store = Arc::new(RwLock::new(PostgresUserStore::new(pg_pool)));
+        let totp_encryption_key = EncryptionKey::generate_random();
+        let user_store = Arc::new(RwLock::new(PostgresUserStore::new(
+            pg_pool.clone(),
+            totp_encryption_key.clone(),
+        )));
+        let oauth_client_store =
+            Arc::new(RwLock::new(PostgresOAuthClientStore::new(pg_pool.clone())));
+        let recovery_code_store =
+            Arc::new(RwLock::new(PostgresRecoveryCodeStore::new(pg_pool.clone())));
+        let webauthn_credential_store = Arc::new(RwLock::new(
+            PostgresWebauthnCredentialStore::new(pg_pool.clone()),
+        ));
+        let totp_secret_store = Arc::new(RwLock::new(PostgresTotpSecretStore::new(
+            pg_pool.clone(),
+            totp_encryption_key,
+        )));
+
+        let audit_sink: AuditSinkType = Arc::new(PostgresAuditSink::new(pg_pool.clone()));
+        let login_history_store =
+            Arc::new(RwLock::new(PostgresLoginHistoryStore::new(pg_pool.clone())));
+        let role_store = Arc::new(RwLock::new(PostgresRoleStore::new(pg_pool.clone())));
+        let organization_store =
+            Arc::new(RwLock::new(PostgresOrganizationStore::new(pg_pool.clone())));
+        let api_key_store = Arc::new(RwLock::new(PostgresApiKeyStore::new(pg_pool.clone())));
 
         let redis_conn = Arc::new(RwLock::new(
             get_redis_client(REDIS_HOSTNAME.to_owned())
@@ -56,7 +119,35 @@ impl TestApp {
         let banned_token_store =
             Arc::new(RwLock::new(RedisBannedTokenStore::new(redis_conn.clone())));
 
-        let two_fa_code_store = Arc::new(RwLock::new(RedisTwoFACodeStore::new(redis_conn)));
+        let refresh_token_store =
+            Arc::new(RwLock::new(RedisRefreshTokenStore::new(redis_conn.clone())));
+
+        let two_fa_code_store = Arc::new(RwLock::new(RedisTwoFACodeStore::new(redis_conn.clone())));
+
+        let authorization_code_store = Arc::new(RwLock::new(RedisAuthorizationCodeStore::new(
+            redis_conn.clone(),
+        )));
+
+        let password_reset_token_store = Arc::new(RwLock::new(RedisPasswordResetTokenStore::new(
+            redis_conn.clone(),
+        )));
+
+        let webauthn_challenge_store = Arc::new(RwLock::new(RedisWebauthnChallengeStore::new(
+            redis_conn.clone(),
+        )));
+
+        let magic_link_token_store = Arc::new(RwLock::new(RedisMagicLinkTokenStore::new(
+            redis_conn.clone(),
+        )));
+        let session_store = Arc::new(RwLock::new(RedisSessionStore::new(redis_conn.clone())));
+        let token_version_store = Arc::new(RwLock::new(PostgresTokenVersionStore::new(
+            pg_pool, redis_conn,
+        )));
+
+        // Every test connects from 127.0.0.1, so failures and requests counted
+        // in the shared Redis would lock tests out of each other
+        let login_throttle_store = Arc::new(RwLock::new(HashmapLoginThrottleStore::default()));
+        let rate_limit_store = Arc::new(RwLock::new(HashmapRateLimitStore::default()));
 
         // Set up a mock email server
         let email_server = MockServer::start().await;
@@ -64,10 +155,28 @@ impl TestApp {
         let email_client = Arc::new(configure_postmark_email_client(base_url)); // Updated!
 
         let app_state = AppState::new(
-            user_store,
+            user_store.clone(),
             banned_token_store.clone(),
+            refresh_token_store.clone(),
             two_fa_code_store.clone(),
             email_client,
+            oauth_client_store.clone(),
+            authorization_code_store,
+            password_reset_token_store,
+            totp_secret_store,
+            recovery_code_store,
+            webauthn_credential_store,
+            webauthn_challenge_store,
+            magic_link_token_store,
+            login_throttle_store,
+            rate_limit_store,
+            session_store,
+            token_version_store.clone(),
+            audit_sink.clone(),
+            login_history_store,
+            role_store,
+            organization_store,
+            api_key_store,
         );
 
         let app = Application::build(app_state, test::APP_ADDRESS)
@@ -81,32 +190,365 @@ impl TestApp {
 
         let cookie_jar = Arc::new(Jar::default());
 
+        // Redirects are not followed so that tests can inspect the `Location` header
         let http_client = Client::builder()
             .cookie_provider(cookie_jar.clone())
+            .redirect(Policy::none())
             .build()
             .unwrap();
 
         TestApp {
             address,
+            audit_sink,
             banned_token_store,
             cookie_jar,
             clean_up_called: false,
             db_name,
             email_server,
             http_client,
+            oauth_client_store,
+            refresh_token_store,
+            token_version_store,
             two_fa_code_store,
+            user_store,
         }
     }
 
     pub async fn get_path(&self, path: &str) -> reqwest::Response {
         self.http_client
-            .get(&format!("{}", &self.address))
+            .get(format!("{}{}", &self.address, path))
+            .send()
+            .await
+            .unwrap_or_else(|_| panic!("[auth_service::TestApp] Failed to get '{}' path.", path))
+    }
+
+    pub async fn get_authorize(&self, query: &[(&str, &str)]) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/authorize", &self.address))
+            .query(query)
+            .send()
+            .await
+            .expect("Failed to execute authorize request.")
+    }
+
+    pub async fn get_userinfo(&self, access_token: &str) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/userinfo", &self.address))
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .expect("Failed to execute userinfo request.")
+    }
+
+    pub async fn get_admin_users(&self, query: &[(&str, &str)]) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/admin/users", &self.address))
+            .query(query)
+            .send()
+            .await
+            .expect("Failed to execute admin users request.")
+    }
+
+    // Take one of the admin actions that are posted to /admin/users/:email/<action>
+    pub async fn post_admin_user_action(&self, email: &str, action: &str) -> reqwest::Response {
+        self.http_client
+            .post(format!(
+                "{}/admin/users/{}/{}",
+                &self.address, email, action
+            ))
+            .send()
+            .await
+            .expect("Failed to execute admin user request.")
+    }
+
+    pub async fn delete_admin_user_action(&self, email: &str, action: &str) -> reqwest::Response {
+        self.http_client
+            .delete(format!(
+                "{}/admin/users/{}/{}",
+                &self.address, email, action
+            ))
+            .send()
+            .await
+            .expect("Failed to execute admin user request.")
+    }
+
+    pub async fn delete_admin_user(&self, email: &str) -> reqwest::Response {
+        self.http_client
+            .delete(format!("{}/admin/users/{}", &self.address, email))
+            .send()
+            .await
+            .expect("Failed to execute admin delete user request.")
+    }
+
+    pub async fn delete_admin_user_sessions(&self, email: &str) -> reqwest::Response {
+        self.http_client
+            .delete(format!("{}/admin/users/{}/sessions", &self.address, email))
+            .send()
+            .await
+            .expect("Failed to execute admin delete sessions request.")
+    }
+
+    pub async fn get_admin_roles(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/admin/roles", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute admin roles request.")
+    }
+
+    pub async fn post_admin_role<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/admin/roles", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute admin create role request.")
+    }
+
+    pub async fn delete_admin_role(&self, name: &str) -> reqwest::Response {
+        self.http_client
+            .delete(format!("{}/admin/roles/{}", &self.address, name))
+            .send()
+            .await
+            .expect("Failed to execute admin delete role request.")
+    }
+
+    pub async fn get_admin_user_roles(&self, email: &str) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/admin/users/{}/roles", &self.address, email))
+            .send()
+            .await
+            .expect("Failed to execute admin user roles request.")
+    }
+
+    pub async fn post_admin_user_role(&self, email: &str, role: &str) -> reqwest::Response {
+        self.http_client
+            .post(format!("{}/admin/users/{}/roles", &self.address, email))
+            .json(&json!({ "role": role }))
+            .send()
+            .await
+            .expect("Failed to execute admin assign role request.")
+    }
+
+    pub async fn delete_admin_user_role(&self, email: &str, role: &str) -> reqwest::Response {
+        self.http_client
+            .delete(format!(
+                "{}/admin/users/{}/roles/{}",
+                &self.address, email, role
+            ))
+            .send()
+            .await
+            .expect("Failed to execute admin unassign role request.")
+    }
+
+    pub async fn get_admin_organizations(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/admin/organizations", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute admin organizations request.")
+    }
+
+    pub async fn post_admin_organization<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/admin/organizations", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute admin create organization request.")
+    }
+
+    pub async fn delete_admin_organization(&self, id: &str) -> reqwest::Response {
+        self.http_client
+            .delete(format!("{}/admin/organizations/{}", &self.address, id))
             .send()
             .await
-            .expect(&format!(
-                "[auth_service::TestApp] Failed to get '{}' path.",
-                path
+            .expect("Failed to execute admin delete organization request.")
+    }
+
+    pub async fn get_admin_organization_members(&self, id: &str) -> reqwest::Response {
+        self.http_client
+            .get(format!(
+                "{}/admin/organizations/{}/members",
+                &self.address, id
+            ))
+            .send()
+            .await
+            .expect("Failed to execute admin organization members request.")
+    }
+
+    pub async fn post_admin_organization_member(
+        &self,
+        id: &str,
+        email: &str,
+        role: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .post(format!(
+                "{}/admin/organizations/{}/members",
+                &self.address, id
+            ))
+            .json(&json!({ "email": email, "role": role }))
+            .send()
+            .await
+            .expect("Failed to execute admin add organization member request.")
+    }
+
+    pub async fn delete_admin_organization_member(
+        &self,
+        id: &str,
+        email: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .delete(format!(
+                "{}/admin/organizations/{}/members/{}",
+                &self.address, id, email
             ))
+            .send()
+            .await
+            .expect("Failed to execute admin remove organization member request.")
+    }
+
+    pub async fn get_account_export(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/account/export", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_account_logins(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/account/logins", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute account logins request.")
+    }
+
+    pub async fn get_account_organizations(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/account/organizations", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute account organizations request.")
+    }
+
+    pub async fn get_api_keys(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/account/api-keys", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute API keys request.")
+    }
+
+    pub async fn post_api_key<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/account/api-keys", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute create API key request.")
+    }
+
+    pub async fn delete_api_key(&self, id: &str) -> reqwest::Response {
+        self.http_client
+            .delete(format!("{}/account/api-keys/{}", &self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute revoke API key request.")
+    }
+
+    pub async fn get_sessions(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/sessions", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute sessions request.")
+    }
+
+    pub async fn delete_session(&self, id: &str) -> reqwest::Response {
+        self.http_client
+            .delete(format!("{}/sessions/{}", &self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute delete session request.")
+    }
+
+    pub async fn delete_sessions(&self) -> reqwest::Response {
+        self.http_client
+            .delete(format!("{}/sessions", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute delete sessions request.")
+    }
+
+    pub async fn delete_account<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .delete(format!("{}/account", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_account_email<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/account/email", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute change email request.")
+    }
+
+    pub async fn post_account_email_confirm<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/account/email/confirm", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute confirm email change request.")
+    }
+
+    pub async fn post_account_password<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/account/password", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute change password request.")
+    }
+
+    pub async fn post_sessions_revoke<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/sessions/revoke", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute revoke session request.")
     }
 
     pub async fn post_signup<Body>(&self, body: &Body) -> reqwest::Response
@@ -114,7 +556,7 @@ impl TestApp {
         Body: serde::Serialize,
     {
         self.http_client
-            .post(&format!("{}/signup", &self.address))
+            .post(format!("{}/signup", &self.address))
             .json(body)
             .send()
             .await
@@ -128,7 +570,7 @@ impl TestApp {
         println!("---------------> [post_login] 1");
         let ret = self
             .http_client
-            .post(&format!("{}/login", &self.address))
+            .post(format!("{}/login", &self.address))
             .json(body)
             .send()
             .await
@@ -137,14 +579,112 @@ impl TestApp {
         ret
     }
 
+    pub async fn post_login_magic_link<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/login/magic-link", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute magic link request.")
+    }
+
+    pub async fn post_login_magic_link_callback<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/login/magic-link/callback", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute magic link callback request.")
+    }
+
+    pub async fn post_introspect(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+        token: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .post(format!("{}/oauth/introspect", &self.address))
+            .basic_auth(client_id, Some(client_secret))
+            .form(&[("token", token)])
+            .send()
+            .await
+            .expect("Failed to execute introspect request.")
+    }
+
+    pub async fn post_revoke(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+        token: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .post(format!("{}/oauth/revoke", &self.address))
+            .basic_auth(client_id, Some(client_secret))
+            .form(&[("token", token)])
+            .send()
+            .await
+            .expect("Failed to execute revoke request.")
+    }
+
     pub async fn post_logout(&self) -> reqwest::Response {
         self.http_client
-            .post(&format!("{}/logout", &self.address))
+            .post(format!("{}/logout", &self.address))
             .send()
             .await
             .expect("Failed to execute logout request.")
     }
 
+    pub async fn post_password_reset_request<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/password-reset/request", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute password reset request.")
+    }
+
+    pub async fn post_password_reset_confirm<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/password-reset/confirm", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute password reset confirmation.")
+    }
+
+    pub async fn post_refresh_token(&self) -> reqwest::Response {
+        self.http_client
+            .post(format!("{}/token/refresh", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute refresh token request.")
+    }
+
+    pub async fn post_token<Body>(&self, form: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/token", &self.address))
+            .form(form)
+            .send()
+            .await
+            .expect("Failed to execute token request.")
+    }
+
     pub async fn post_verify_token<Body>(&self, body: &Body) -> reqwest::Response
     where
         Body: serde::Serialize,
@@ -157,6 +697,90 @@ impl TestApp {
             .expect("Failed to execute request.")
     }
 
+    pub async fn post_verify_email<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/verify-email", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_resend_verification_email<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/verify-email/resend", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_2fa_code(&self) -> reqwest::Response {
+        self.http_client
+            .post(format!("{}/2fa/code", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_2fa_disable<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/2fa/disable", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_2fa_enable<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/2fa/enable", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_recovery_codes(&self) -> reqwest::Response {
+        self.http_client
+            .post(format!("{}/2fa/recovery-codes", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_totp_enroll(&self) -> reqwest::Response {
+        self.http_client
+            .post(format!("{}/2fa/totp/enroll", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_totp_confirm<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/2fa/totp/confirm", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
     pub async fn post_verify_2fa<Body>(&self, body: &Body) -> reqwest::Response
     where
         Body: serde::Serialize,
@@ -169,6 +793,50 @@ impl TestApp {
             .expect("Failed to execute request.")
     }
 
+    pub async fn post_webauthn_register_start(&self) -> reqwest::Response {
+        self.http_client
+            .post(format!("{}/webauthn/register/start", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_webauthn_register_finish<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/webauthn/register/finish", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_webauthn_login_start<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/webauthn/login/start", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_webauthn_login_finish<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/webauthn/login/finish", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
     pub async fn clean_up(&mut self) {
         if self.clean_up_called {
             return;
@@ -226,25 +894,410 @@ pub async fn login(
     login_response
 }
 
+pub const FIREFOX_USER_AGENT: &str =
+    "Mozilla/5.0 (X11; Linux x86_64; rv:131.0) Gecko/20100101 Firefox/131.0";
+
+// Log in from a browser rather than the test client, which looks like another device
+pub async fn login_from_browser(app: &TestApp, email: &str, password: &str) -> reqwest::Response {
+    let response = app
+        .http_client
+        .post(format!("{}/login", &app.address))
+        .header(reqwest::header::USER_AGENT, FIREFOX_USER_AGENT)
+        .json(&json!({ "email": email, "password": password }))
+        .send()
+        .await
+        .expect("Failed to execute login request.");
+    assert_eq!(response.status().as_u16(), 200);
+    response
+}
+
 pub async fn signup_and_login(app: &TestApp, email: &str, password: &str) -> reqwest::Response {
     signup(app, email, password, false).await;
     login(app, email, password, false).await
 }
 
+// Sign up a new user, make them an admin and log them in. Returns their email.
+pub async fn signup_and_login_as_admin(app: &TestApp) -> String {
+    let email = get_random_email();
+    let password = "Adm1nP4sSword!";
+    signup(app, &email, password, false).await;
+    app.user_store
+        .write()
+        .await
+        .set_admin(&Email::parse(Secret::new(email.clone())).unwrap(), true)
+        .await
+        .expect("Failed to make user an admin");
+    login(app, &email, password, false).await;
+    email
+}
+
+pub const TEST_CLIENT_SECRET: &str = "client-secret";
+pub const TEST_REDIRECT_URI: &str = "https://app.example.com/callback";
+
+// Register an OpenID Connect client and return its client ID
+pub async fn register_oauth_client(app: &TestApp) -> String {
+    let client_id = Uuid::new_v4().to_string();
+    app.oauth_client_store
+        .write()
+        .await
+        .add_client(OAuthClient::new(
+            client_id.clone(),
+            Secret::new(TEST_CLIENT_SECRET.to_owned()),
+            vec![TEST_REDIRECT_URI.to_owned()],
+        ))
+        .await
+        .expect("Failed to register OAuth client");
+    client_id
+}
+
+// Return a PKCE (code_verifier, code_challenge) pair
+pub fn get_pkce_pair() -> (String, String) {
+    let code_verifier = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    let digest = ring::digest::digest(&ring::digest::SHA256, code_verifier.as_bytes());
+    (code_verifier, URL_SAFE_NO_PAD.encode(digest))
+}
+
+// Run /authorize for a logged-in user and return the issued authorization code
+pub async fn get_authorization_code(
+    app: &TestApp,
+    client_id: &str,
+    code_challenge: &str,
+) -> String {
+    let response = app
+        .get_authorize(&[
+            ("response_type", "code"),
+            ("client_id", client_id),
+            ("redirect_uri", TEST_REDIRECT_URI),
+            ("scope", "openid email"),
+            ("state", "state"),
+            ("nonce", "nonce"),
+            ("code_challenge", code_challenge),
+            ("code_challenge_method", "S256"),
+        ])
+        .await;
+    assert_eq!(response.status().as_u16(), 303);
+
+    get_query_param(&get_location(&response), "code").expect("No code in redirect")
+}
+
+pub fn get_cookie(response: &reqwest::Response, name: &str) -> String {
+    response
+        .cookies()
+        .find(|cookie| cookie.name() == name)
+        .unwrap_or_else(|| panic!("No {} cookie found", name))
+        .value()
+        .to_owned()
+}
+
+pub fn get_location(response: &reqwest::Response) -> Url {
+    let location = response
+        .headers()
+        .get(reqwest::header::LOCATION)
+        .expect("No Location header")
+        .to_str()
+        .unwrap();
+    Url::parse(location).expect("Failed to parse Location header")
+}
+
+pub fn get_query_param(url: &Url, name: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+// Extract the value of the `name` query parameter from the link in the last email sent
+pub async fn get_emailed_link_param(app: &TestApp, name: &str) -> String {
+    let requests = app
+        .email_server
+        .received_requests()
+        .await
+        .expect("Request recording is disabled");
+    let request = requests.last().expect("No email was sent");
+    let body: serde_json::Value = request.body_json().expect("Email body is not JSON");
+    let text = body["TextBody"].as_str().expect("Email has no text body");
+
+    let link = text
+        .split_whitespace()
+        .find(|word| word.starts_with("http"))
+        .expect("Email contains no link");
+    get_query_param(&Url::parse(link).expect("Failed to parse link"), name)
+        .unwrap_or_else(|| panic!("Link has no {} parameter", name))
+}
+
+// Ask for a magic link for `email` and return the token from the emailed link
+pub async fn get_magic_link_token(app: &TestApp, email: &str) -> String {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let response = app.post_login_magic_link(&json!({ "email": email })).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    get_emailed_link_param(app, "magic_link_token").await
+}
+
+// Enroll an authenticator app for the logged-in user and return its secret. The
+// enrollment is confirmed with the previous step's code so that the current one
+// is still unused.
+pub async fn enroll_totp(app: &TestApp) -> TotpSecret {
+    let response = app.post_totp_enroll().await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response
+        .json::<TotpEnrollResponse>()
+        .await
+        .expect("Could not deserialize response body to TotpEnrollResponse");
+    let secret = TotpSecret::parse_base32(&body.secret).expect("Invalid TOTP secret");
+
+    let code = secret.code_at(TotpSecret::current_step() - 1);
+    let response = app
+        .post_totp_confirm(&json!({ "code": code.as_ref().expose_secret() }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    secret
+}
+
+// Log in a user that has 2FA enabled and return the login attempt ID
+pub async fn login_with_2fa(app: &TestApp, email: &str, password: &str) -> String {
+    login(app, email, password, true)
+        .await
+        .json::<TwoFactorAuthResponse>()
+        .await
+        .expect("Could not deserialize response body to TwoFactorAuthResponse")
+        .login_attempt_id
+}
+
+// Log in a user that gets 2FA codes by email, entering the code that was sent
+pub async fn login_with_emailed_2fa_code(
+    app: &TestApp,
+    email: &str,
+    password: &str,
+) -> reqwest::Response {
+    let login_attempt_id = login_with_2fa(app, email, password).await;
+    let (_, code) = app
+        .two_fa_code_store
+        .read()
+        .await
+        .get_code(&Email::parse(Secret::new(email.to_owned())).unwrap())
+        .await
+        .expect("Failed to get 2FA code");
+
+    let response = app
+        .post_verify_2fa(&json!({
+            "email": email,
+            "loginAttemptId": login_attempt_id,
+            "2FACode": code.as_ref().expose_secret(),
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    response
+}
+
+// Have a 2FA code emailed to the logged-in user and return it
+pub async fn request_2fa_code(app: &TestApp, email: &str) -> String {
+    let response = app.post_2fa_code().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let (_, code) = app
+        .two_fa_code_store
+        .read()
+        .await
+        .get_code(&Email::parse(Secret::new(email.to_owned())).unwrap())
+        .await
+        .expect("Failed to get 2FA code");
+    code.as_ref().expose_secret().to_owned()
+}
+
+// The audit log of `email`, oldest first
+pub async fn get_audit_records(app: &TestApp, email: &str) -> Vec<AuditRecord> {
+    app.audit_sink
+        .get_records(&Email::parse(Secret::new(email.to_owned())).unwrap())
+        .await
+        .expect("Failed to get audit records")
+}
+
+pub async fn get_audit_events(app: &TestApp, email: &str) -> Vec<AuditEvent> {
+    get_audit_records(app, email)
+        .await
+        .into_iter()
+        .map(|record| record.event)
+        .collect()
+}
+
+// Generate a new set of recovery codes for the logged-in user
+pub async fn get_recovery_codes(app: &TestApp) -> Vec<String> {
+    let response = app.post_recovery_codes().await;
+    assert_eq!(response.status().as_u16(), 200);
+    response
+        .json::<RecoveryCodesResponse>()
+        .await
+        .expect("Could not deserialize response body to RecoveryCodesResponse")
+        .recovery_codes
+}
+
+// Authenticator data flags
+pub const USER_PRESENT: u8 = 0x01;
+pub const USER_VERIFIED: u8 = 0x04;
+const ATTESTED_CREDENTIAL_DATA: u8 = 0x40;
+
+/// Passkey with an ES256 key that answers WebAuthn ceremonies the way a
+/// browser and authenticator would, for the app's origin
+pub struct SoftwareAuthenticator {
+    key_pair: EcdsaKeyPair,
+    pub credential_id: Vec<u8>,
+    sign_count: u32,
+}
+
+impl SoftwareAuthenticator {
+    pub fn new() -> Self {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng)
+            .expect("Failed to generate key");
+        let key_pair =
+            EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng)
+                .expect("Failed to parse key");
+        Self {
+            key_pair,
+            credential_id: Uuid::new_v4().as_bytes().to_vec(),
+            sign_count: 0,
+        }
+    }
+
+    fn client_data(ceremony_type: &str, challenge: &str) -> Vec<u8> {
+        json!({
+            "type": ceremony_type,
+            "challenge": challenge,
+            "origin": WEBAUTHN_ORIGIN.as_str(),
+            "crossOrigin": false,
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    fn authenticator_data(&self, rp_id: &str, flags: u8) -> Vec<u8> {
+        let mut data = digest(&SHA256, rp_id.as_bytes()).as_ref().to_vec();
+        data.push(flags);
+        data.extend_from_slice(&self.sign_count.to_be_bytes());
+        data
+    }
+
+    pub fn register(&self, options: &PublicKeyCredentialCreationOptions) -> RegistrationCredential {
+        let point = self.key_pair.public_key().as_ref();
+        let cose_key = Value::Map(vec![
+            (1.into(), 2.into()),
+            (3.into(), (-7).into()),
+            ((-1).into(), 1.into()),
+            ((-2).into(), Value::Bytes(point[1..33].to_vec())),
+            ((-3).into(), Value::Bytes(point[33..].to_vec())),
+        ]);
+
+        let mut authenticator_data = self.authenticator_data(
+            &options.rp.id,
+            USER_PRESENT | USER_VERIFIED | ATTESTED_CREDENTIAL_DATA,
+        );
+        authenticator_data.extend_from_slice(&[0; 16]);
+        authenticator_data.extend_from_slice(&(self.credential_id.len() as u16).to_be_bytes());
+        authenticator_data.extend_from_slice(&self.credential_id);
+        ciborium::into_writer(&cose_key, &mut authenticator_data).unwrap();
+
+        let attestation_object = Value::Map(vec![
+            ("fmt".into(), "none".into()),
+            ("attStmt".into(), Value::Map(vec![])),
+            ("authData".into(), Value::Bytes(authenticator_data)),
+        ]);
+        let mut encoded_attestation_object = Vec::new();
+        ciborium::into_writer(&attestation_object, &mut encoded_attestation_object).unwrap();
+
+        RegistrationCredential {
+            id: URL_SAFE_NO_PAD.encode(&self.credential_id),
+            credential_type: "public-key".to_owned(),
+            response: AttestationResponse {
+                client_data_json: URL_SAFE_NO_PAD
+                    .encode(Self::client_data("webauthn.create", &options.challenge)),
+                attestation_object: URL_SAFE_NO_PAD.encode(encoded_attestation_object),
+            },
+        }
+    }
+
+    pub fn authenticate(
+        &mut self,
+        options: &PublicKeyCredentialRequestOptions,
+        flags: u8,
+    ) -> AuthenticationCredential {
+        self.sign_count += 1;
+        let authenticator_data = self.authenticator_data(&options.rp_id, flags);
+        let client_data = Self::client_data("webauthn.get", &options.challenge);
+
+        let mut signed_data = authenticator_data.clone();
+        signed_data.extend_from_slice(digest(&SHA256, &client_data).as_ref());
+        let signature = self
+            .key_pair
+            .sign(&SystemRandom::new(), &signed_data)
+            .expect("Failed to sign");
+
+        AuthenticationCredential {
+            id: URL_SAFE_NO_PAD.encode(&self.credential_id),
+            credential_type: "public-key".to_owned(),
+            response: AssertionResponse {
+                client_data_json: URL_SAFE_NO_PAD.encode(client_data),
+                authenticator_data: URL_SAFE_NO_PAD.encode(authenticator_data),
+                signature: URL_SAFE_NO_PAD.encode(signature.as_ref()),
+                user_handle: None,
+            },
+        }
+    }
+}
+
+pub async fn get_webauthn_registration_options(
+    app: &TestApp,
+) -> PublicKeyCredentialCreationOptions {
+    let response = app.post_webauthn_register_start().await;
+    assert_eq!(response.status().as_u16(), 200);
+    response
+        .json::<PublicKeyCredentialCreationOptions>()
+        .await
+        .expect("Could not deserialize response body to PublicKeyCredentialCreationOptions")
+}
+
+// Register a new passkey for the logged-in user
+pub async fn register_passkey(app: &TestApp) -> SoftwareAuthenticator {
+    let authenticator = SoftwareAuthenticator::new();
+    let options = get_webauthn_registration_options(app).await;
+    let response = app
+        .post_webauthn_register_finish(&json!({ "credential": authenticator.register(&options) }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    authenticator
+}
+
+pub async fn get_webauthn_login_options(
+    app: &TestApp,
+    email: Option<&str>,
+) -> PublicKeyCredentialRequestOptions {
+    let response = app
+        .post_webauthn_login_start(&json!({ "email": email }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    response
+        .json::<PublicKeyCredentialRequestOptions>()
+        .await
+        .expect("Could not deserialize response body to PublicKeyCredentialRequestOptions")
+}
+
 pub fn get_random_login_attempt_id() -> String {
-    let id = LoginAttemptId::generate_random()
+    LoginAttemptId::generate_random()
         .as_ref()
         .expose_secret()
-        .to_string();
-    format!("{id}")
+        .to_string()
 }
 
 pub fn get_random_two_fa_code() -> String {
-    let code = TwoFACode::generate_random()
+    TwoFACode::generate_random()
         .as_ref()
         .expose_secret()
-        .to_string();
-    format!("{code}")
+        .to_string()
 }
 
 async fn configure_postgresql() -> (PgPool, String) {
@@ -0,0 +1,65 @@
+use auth_service::{routes::IntrospectResponse, utils::constants::JWT_COOKIE_NAME};
+
+use crate::helpers::{
+    get_cookie, get_random_email, register_oauth_client, signup_and_login, TestApp,
+    TEST_CLIENT_SECRET,
+};
+
+#[tokio::test]
+async fn should_return_active_token_claims() {
+    let mut app = TestApp::new().await;
+
+    let client_id = register_oauth_client(&app).await;
+    let email = get_random_email();
+    let login_response = signup_and_login(&app, &email, "P4sSword123!").await;
+    let token = get_cookie(&login_response, JWT_COOKIE_NAME);
+
+    let response = app
+        .post_introspect(&client_id, TEST_CLIENT_SECRET, &token)
+        .await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    let introspection = response.json::<IntrospectResponse>().await.unwrap();
+    assert!(introspection.active);
+    assert_eq!(introspection.sub.as_deref(), Some(email.as_str()));
+    assert!(introspection.exp.is_some());
+    assert!(introspection.iat.is_some());
+    assert!(introspection.jti.is_some());
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_inactive_for_invalid_token() {
+    let mut app = TestApp::new().await;
+
+    let client_id = register_oauth_client(&app).await;
+
+    let response = app
+        .post_introspect(&client_id, TEST_CLIENT_SECRET, "invalid")
+        .await;
+
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        response.json::<IntrospectResponse>().await.unwrap(),
+        IntrospectResponse::default()
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_client_credentials_are_invalid() {
+    let mut app = TestApp::new().await;
+
+    let client_id = register_oauth_client(&app).await;
+
+    let response = app
+        .post_introspect(&client_id, "wrong-secret", "invalid")
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
@@ -0,0 +1,61 @@
+use jsonwebtoken::{decode, decode_header, jwk::JwkSet, DecodingKey, Validation};
+
+use crate::helpers::{get_random_email, signup_and_login, TestApp};
+use auth_service::utils::{auth::Claims, constants::JWT_COOKIE_NAME};
+
+#[tokio::test]
+async fn should_return_200_with_public_keys() {
+    let mut app = TestApp::new().await;
+
+    let response = app.get_path("/.well-known/jwks.json").await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    let jwks = response
+        .json::<JwkSet>()
+        .await
+        .expect("Failed to parse response body as JWK set.");
+
+    assert!(!jwks.keys.is_empty());
+    assert!(jwks.keys.iter().all(|jwk| jwk.common.key_id.is_some()));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_verify_issued_token_with_published_key() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let login_response = signup_and_login(&app, &email, "P4sSword123!").await;
+
+    let token = login_response
+        .cookies()
+        .find(|cookie| cookie.name() == JWT_COOKIE_NAME)
+        .expect("No auth cookie found")
+        .value()
+        .to_owned();
+
+    let jwks = app
+        .get_path("/.well-known/jwks.json")
+        .await
+        .json::<JwkSet>()
+        .await
+        .expect("Failed to parse response body as JWK set.");
+
+    let header = decode_header(&token).expect("Failed to decode token header");
+    let kid = header.kid.expect("Token has no kid");
+    let jwk = jwks.find(&kid).expect("Signing key is not published");
+
+    let claims = decode::<Claims>(
+        &token,
+        &DecodingKey::from_jwk(jwk).unwrap(),
+        &Validation::new(header.alg),
+    )
+    .expect("Failed to verify token with published key")
+    .claims;
+
+    assert_eq!(claims.sub, email);
+
+    app.clean_up().await;
+}
@@ -5,9 +5,15 @@ use wiremock::{
     Mock, ResponseTemplate,
 };
 
-use crate::helpers::{get_random_email, TestApp};
+use crate::helpers::{
+    enroll_totp, get_audit_events, get_cookie, get_random_email, login, signup, signup_and_login,
+    TestApp,
+};
 use auth_service::{
-    domain::Email, routes::TwoFactorAuthResponse, utils::constants::JWT_COOKIE_NAME,
+    domain::{AuditEvent, Email, LoginMethod},
+    routes::{TwoFAMethod, TwoFactorAuthResponse},
+    utils::constants::{JWT_COOKIE_NAME, PENDING_2FA_COOKIE_NAME},
+    ErrorResponse,
 };
 // TODO: add api_test macro
 // use test_helpers::api_test;
@@ -131,6 +137,7 @@ async fn should_return_206_if_valid_credentials_and_2fa_enabled() {
         .expect("Failed to parse response body as JSON.");
 
     assert_eq!(json_body.message, "2FA required".to_owned());
+    assert_eq!(json_body.method, TwoFAMethod::Email);
 
     let email = Email::parse(Secret::new(random_email.clone())).unwrap();
     let (login_attempt_id, code) = {
@@ -146,3 +153,206 @@ async fn should_return_206_if_valid_credentials_and_2fa_enabled() {
 
     app.clean_up().await;
 }
+
+#[tokio::test]
+async fn should_return_206_without_email_if_authenticator_app_enrolled() {
+    let mut app = TestApp::new().await;
+
+    let random_email = get_random_email();
+    let password = "P4sSword123!";
+    signup_and_login(&app, &random_email, password).await;
+    enroll_totp(&app).await;
+
+    // The code comes from the authenticator app, so no email is sent
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    let login_body = serde_json::json!({
+        "email": &random_email,
+        "password": &password,
+    });
+
+    let login_response = app.post_login(&login_body).await;
+
+    assert_eq!(login_response.status().as_u16(), 206);
+
+    let json_body = login_response
+        .json::<TwoFactorAuthResponse>()
+        .await
+        .expect("Failed to parse response body as JSON.");
+
+    assert_eq!(json_body.message, "2FA required".to_owned());
+    assert_eq!(json_body.method, TwoFAMethod::Totp);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_not_grant_access_before_2fa_is_verified() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, true).await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let login_response = login(&app, &email, password, true).await;
+
+    assert!(login_response
+        .cookies()
+        .all(|cookie| cookie.name() != JWT_COOKIE_NAME));
+    let pending_token = get_cookie(&login_response, PENDING_2FA_COOKIE_NAME);
+
+    // The pending token is not an access token
+    let response = app
+        .post_verify_token(&json!({ "token": pending_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app.get_userinfo(&pending_token).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    // Protected routes don't get a usable cookie, even when it is sent as one
+    let response = app.post_totp_enroll().await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/2fa/totp/enroll", &app.address))
+        .header(
+            reqwest::header::COOKIE,
+            format!("{}={}", JWT_COOKIE_NAME, pending_token),
+        )
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_429_after_too_many_failed_attempts() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, false).await;
+
+    let wrong_password = json!({ "email": email, "password": "Wr0ngPassword!" });
+    for _ in 0..6 {
+        let response = app.post_login(&wrong_password).await;
+        assert_eq!(response.status().as_u16(), 401);
+    }
+
+    // Locked out, even with the right password
+    let response = app
+        .post_login(&json!({ "email": email, "password": password }))
+        .await;
+    assert_eq!(response.status().as_u16(), 429);
+
+    let retry_after: u64 = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .expect("No Retry-After header")
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=30).contains(&retry_after));
+    assert_eq!(
+        response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialize response body to ErrorResponse")
+            .error,
+        "Too many requests".to_owned()
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_429_after_too_many_failed_attempts_from_one_client() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, false).await;
+
+    // Few guesses per account, but many in total
+    for _ in 0..21 {
+        let response = app
+            .post_login(&json!({ "email": get_random_email(), "password": password }))
+            .await;
+        assert_eq!(response.status().as_u16(), 401);
+    }
+
+    let response = app
+        .post_login(&json!({ "email": email, "password": password }))
+        .await;
+    assert_eq!(response.status().as_u16(), 429);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_reset_failed_attempts_after_successful_login() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, false).await;
+
+    let wrong_password = json!({ "email": email, "password": "Wr0ngPassword!" });
+    for _ in 0..2 {
+        for _ in 0..5 {
+            let response = app.post_login(&wrong_password).await;
+            assert_eq!(response.status().as_u16(), 401);
+        }
+        login(&app, &email, password, false).await;
+    }
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_record_failed_and_successful_logins_in_audit_log() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, false).await;
+
+    let response = app
+        .post_login(&json!({
+            "email": &email,
+            "password": "Wr0ngPassword!",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+    login(&app, &email, password, false).await;
+
+    assert_eq!(
+        get_audit_events(&app, &email).await,
+        [
+            AuditEvent::Signup,
+            AuditEvent::LoginFailed {
+                method: LoginMethod::Password
+            },
+            AuditEvent::LoginSucceeded {
+                method: LoginMethod::Password
+            },
+        ]
+    );
+
+    app.clean_up().await;
+}
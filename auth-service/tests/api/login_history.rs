@@ -0,0 +1,130 @@
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use auth_service::{domain::LoginMethod, routes::LoginHistoryResponse};
+
+use crate::helpers::{
+    get_random_email, login, login_from_browser, login_with_emailed_2fa_code, signup,
+    signup_and_login, TestApp,
+};
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app.get_account_logins().await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_list_logins_most_recent_first() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, false).await;
+    login_from_browser(&app, &email, password).await;
+    login(&app, &email, password, false).await;
+
+    let response = app.get_account_logins().await;
+    assert_eq!(response.status().as_u16(), 200);
+    let logins = response
+        .json::<LoginHistoryResponse>()
+        .await
+        .expect("Could not deserialize response body to LoginHistoryResponse")
+        .logins;
+    assert_eq!(logins.len(), 2);
+
+    assert_eq!(logins[0].method, LoginMethod::Password);
+    assert_eq!(logins[0].device, "Unknown device");
+    assert!(logins[0].current);
+
+    assert_eq!(logins[1].method, LoginMethod::Password);
+    assert_eq!(logins[1].device, "Firefox on Linux");
+    assert_eq!(logins[1].ip, "127.0.0.1");
+    assert!(!logins[1].current);
+    assert!(logins[1].logged_in_at <= logins[0].logged_in_at);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_email_notice_when_logging_in_from_new_device() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup_and_login(&app, &email, password).await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    login_from_browser(&app, &email, password).await;
+
+    let requests = app.email_server.received_requests().await.unwrap();
+    let body: serde_json::Value = requests.last().unwrap().body_json().unwrap();
+    let text = body["TextBody"].as_str().unwrap();
+    assert!(text.contains("Firefox on Linux"));
+    assert!(text.contains("revoke_session_token="));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_not_email_notice_when_logging_in_from_known_device() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, false).await;
+
+    // The first login has nothing to compare with, so it isn't new either
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    login(&app, &email, password, false).await;
+    login(&app, &email, password, false).await;
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_record_2fa_login_as_finished_with_second_factor() {
+    let mut app = TestApp::new().await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, true).await;
+    login_with_emailed_2fa_code(&app, &email, password).await;
+
+    let logins = app
+        .get_account_logins()
+        .await
+        .json::<LoginHistoryResponse>()
+        .await
+        .expect("Could not deserialize response body to LoginHistoryResponse")
+        .logins;
+    assert_eq!(logins.len(), 1);
+    assert_eq!(logins[0].method, LoginMethod::SecondFactor);
+
+    app.clean_up().await;
+}
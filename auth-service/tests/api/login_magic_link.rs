@@ -0,0 +1,108 @@
+use auth_service::{routes::MagicLinkResponse, utils::constants::MAGIC_LINK_NONCE_COOKIE_NAME};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use crate::helpers::{get_cookie, get_random_email, signup, TestApp};
+
+#[tokio::test]
+async fn should_send_magic_link_if_user_exists() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup(&app, &email, "P4sSword123!", false).await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app.post_login_magic_link(&json!({ "email": email })).await;
+
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(!get_cookie(&response, MAGIC_LINK_NONCE_COOKIE_NAME).is_empty());
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_same_response_if_user_does_not_exist() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup(&app, &email, "P4sSword123!", false).await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    let existing = app.post_login_magic_link(&json!({ "email": email })).await;
+    let unknown = app
+        .post_login_magic_link(&json!({ "email": get_random_email() }))
+        .await;
+
+    assert_eq!(existing.status(), unknown.status());
+    // Both responses set a nonce cookie
+    get_cookie(&unknown, MAGIC_LINK_NONCE_COOKIE_NAME);
+    assert_eq!(
+        existing.json::<MagicLinkResponse>().await.unwrap(),
+        unknown.json::<MagicLinkResponse>().await.unwrap()
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_keep_nonce_for_repeated_requests() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup(&app, &email, "P4sSword123!", false).await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let first = app.post_login_magic_link(&json!({ "email": email })).await;
+    let second = app.post_login_magic_link(&json!({ "email": email })).await;
+
+    assert_eq!(
+        get_cookie(&first, MAGIC_LINK_NONCE_COOKIE_NAME),
+        get_cookie(&second, MAGIC_LINK_NONCE_COOKIE_NAME)
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_if_email_is_malformed() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_login_magic_link(&json!({ "email": "not-an-email" }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_422_if_malformed_input() {
+    let mut app = TestApp::new().await;
+
+    let response = app.post_login_magic_link(&json!({})).await;
+
+    assert_eq!(response.status().as_u16(), 422);
+
+    app.clean_up().await;
+}
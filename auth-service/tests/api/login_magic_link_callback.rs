@@ -0,0 +1,157 @@
+use auth_service::{
+    routes::{TwoFAMethod, TwoFactorAuthResponse},
+    utils::constants::JWT_COOKIE_NAME,
+};
+use reqwest::{cookie::Jar, Client};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::helpers::{get_cookie, get_magic_link_token, get_random_email, signup, TestApp};
+
+#[tokio::test]
+async fn should_return_200_and_set_auth_cookie() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup(&app, &email, "P4sSword123!", false).await;
+    let token = get_magic_link_token(&app, &email).await;
+
+    let response = app
+        .post_login_magic_link_callback(&json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let auth_token = get_cookie(&response, JWT_COOKIE_NAME);
+    let response = app.post_verify_token(&json!({ "token": auth_token })).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_206_if_user_has_2fa() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup(&app, &email, "P4sSword123!", true).await;
+    let token = get_magic_link_token(&app, &email).await;
+
+    let response = app
+        .post_login_magic_link_callback(&json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 206);
+
+    let body = response
+        .json::<TwoFactorAuthResponse>()
+        .await
+        .expect("Could not deserialize response body to TwoFactorAuthResponse");
+    assert_eq!(body.method, TwoFAMethod::Email);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_link_is_opened_in_another_browser() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup(&app, &email, "P4sSword123!", false).await;
+    let token = get_magic_link_token(&app, &email).await;
+
+    // A browser without any nonce cookie
+    let response = Client::new()
+        .post(format!("{}/login/magic-link/callback", &app.address))
+        .json(&json!({ "token": token }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 401);
+
+    // A browser that asked for a link of its own
+    let other_browser = Client::builder()
+        .cookie_provider(Arc::new(Jar::default()))
+        .build()
+        .unwrap();
+    let response = other_browser
+        .post(format!("{}/login/magic-link", &app.address))
+        .json(&json!({ "email": email }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let response = other_browser
+        .post(format!("{}/login/magic-link/callback", &app.address))
+        .json(&json!({ "token": token }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 401);
+
+    // The link still works in the browser that asked for it
+    let response = app
+        .post_login_magic_link_callback(&json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_link_is_reused() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup(&app, &email, "P4sSword123!", false).await;
+    let token = get_magic_link_token(&app, &email).await;
+
+    let response = app
+        .post_login_magic_link_callback(&json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    // Ask for another link so that the browser has a nonce cookie again
+    get_magic_link_token(&app, &email).await;
+
+    let response = app
+        .post_login_magic_link_callback(&json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_token_is_invalid() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup(&app, &email, "P4sSword123!", false).await;
+    get_magic_link_token(&app, &email).await;
+
+    let test_cases = ["invalid".to_owned(), "x".repeat(43), String::new()];
+
+    for token in test_cases {
+        let response = app
+            .post_login_magic_link_callback(&json!({ "token": token }))
+            .await;
+        assert_eq!(
+            response.status().as_u16(),
+            401,
+            "Failed for token: {:?}",
+            token
+        );
+    }
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_422_if_malformed_input() {
+    let mut app = TestApp::new().await;
+
+    let response = app.post_login_magic_link_callback(&json!({})).await;
+
+    assert_eq!(response.status().as_u16(), 422);
+
+    app.clean_up().await;
+}
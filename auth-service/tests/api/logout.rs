@@ -1,8 +1,11 @@
 use reqwest::Url;
 use secrecy::Secret;
 
-use crate::helpers::{get_random_email, signup_and_login, TestApp};
-use auth_service::utils::constants::JWT_COOKIE_NAME;
+use crate::helpers::{get_audit_events, get_random_email, signup_and_login, TestApp};
+use auth_service::{
+    domain::{AuditEvent, RefreshToken},
+    utils::constants::{JWT_COOKIE_NAME, REFRESH_TOKEN_COOKIE_NAME},
+};
 
 #[tokio::test]
 async fn should_return_400_if_jwt_cookie_missing() {
@@ -42,7 +45,7 @@ async fn should_return_200_if_valid_jwt_cookie() {
     let email = get_random_email();
     let password = "P4sSword123!";
 
-    let login_response = signup_and_login(&app, &email, &password).await;
+    let login_response = signup_and_login(&app, &email, password).await;
 
     let auth_cookie = login_response
         .cookies()
@@ -74,7 +77,7 @@ async fn should_return_400_if_logout_called_twice_in_a_row() {
     let email = get_random_email();
     let password = "P4sSword123!";
 
-    signup_and_login(&app, &email, &password).await;
+    signup_and_login(&app, &email, password).await;
 
     let logout_response1 = app.post_logout().await;
     assert_eq!(logout_response1.status().as_u16(), 200);
@@ -84,3 +87,49 @@ async fn should_return_400_if_logout_called_twice_in_a_row() {
 
     app.clean_up().await;
 }
+
+#[tokio::test]
+async fn should_revoke_refresh_token_on_logout() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+
+    let login_response = signup_and_login(&app, &email, password).await;
+
+    let refresh_cookie = login_response
+        .cookies()
+        .find(|cookie| cookie.name() == REFRESH_TOKEN_COOKIE_NAME)
+        .expect("Couldn't find refresh cookie");
+    let refresh_token = RefreshToken::parse(refresh_cookie.value().to_owned()).unwrap();
+
+    let logout_response = app.post_logout().await;
+    assert_eq!(logout_response.status().as_u16(), 200);
+
+    {
+        let mut refresh_token_store = app.refresh_token_store.write().await;
+        let result = refresh_token_store.use_token(&refresh_token).await;
+        assert!(result.is_err());
+    }
+
+    let refresh_response = app.post_refresh_token().await;
+    assert_eq!(refresh_response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_record_logout_in_audit_log() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+
+    let response = app.post_logout().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let events = get_audit_events(&app, &email).await;
+    assert_eq!(events.last(), Some(&AuditEvent::Logout));
+
+    app.clean_up().await;
+}
@@ -1,7 +1,46 @@
-mod helpers; 
-mod login; 
-mod logout; 
+mod admin;
+mod admin_organizations;
+mod admin_roles;
+mod api_keys;
+mod authorize;
+mod change_email;
+mod change_password;
+mod confirm_email_change;
+mod delete_account;
+mod disable_2fa;
+mod enable_2fa;
+mod export_account;
+mod helpers;
+mod introspect;
+mod jwks;
+mod login;
+mod login_history;
+mod login_magic_link;
+mod login_magic_link_callback;
+mod logout;
+mod openid_configuration;
+mod password_reset_confirm;
+mod password_reset_request;
+mod rate_limit;
+mod recovery_codes;
+mod refresh_token;
+mod resend_verification_email;
+mod revoke;
+mod revoke_all_sessions;
+mod revoke_session;
+mod revoke_session_link;
 mod root;
-mod signup; 
-mod verify_2fa; 
-mod verify_token;
\ No newline at end of file
+mod send_2fa_code;
+mod sessions;
+mod signup;
+mod token;
+mod totp_confirm;
+mod totp_enroll;
+mod userinfo;
+mod verify_2fa;
+mod verify_email;
+mod verify_token;
+mod webauthn_login_finish;
+mod webauthn_login_start;
+mod webauthn_register_finish;
+mod webauthn_register_start;
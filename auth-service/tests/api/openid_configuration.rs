@@ -0,0 +1,28 @@
+use serde_json::Value;
+
+use crate::helpers::TestApp;
+use auth_service::utils::constants::OIDC_ISSUER;
+
+#[tokio::test]
+async fn should_return_200_with_provider_metadata() {
+    let mut app = TestApp::new().await;
+
+    let response = app.get_path("/.well-known/openid-configuration").await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    let metadata = response
+        .json::<Value>()
+        .await
+        .expect("Failed to parse response body as JSON.");
+
+    assert_eq!(metadata["issuer"], OIDC_ISSUER.as_str());
+    assert_eq!(
+        metadata["jwks_uri"],
+        format!("{}/.well-known/jwks.json", *OIDC_ISSUER)
+    );
+    assert_eq!(metadata["response_types_supported"][0], "code");
+    assert_eq!(metadata["code_challenge_methods_supported"][0], "S256");
+
+    app.clean_up().await;
+}
@@ -0,0 +1,132 @@
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use auth_service::utils::constants::JWT_COOKIE_NAME;
+
+use crate::helpers::{
+    get_cookie, get_emailed_link_param, get_random_email, signup_and_login, TestApp,
+};
+
+async fn request_reset_token(app: &TestApp, email: &str) -> String {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let response = app
+        .post_password_reset_request(&json!({ "email": email }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    get_emailed_link_param(app, "password_reset_token").await
+}
+
+#[tokio::test]
+async fn should_reset_password_and_revoke_tokens() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let login_response = signup_and_login(&app, &email, "P4sSword123!").await;
+    let old_token = get_cookie(&login_response, JWT_COOKIE_NAME);
+
+    let reset_token = request_reset_token(&app, &email).await;
+
+    let response = app
+        .post_password_reset_confirm(&json!({
+            "token": reset_token,
+            "password": "N3wP4sSword123!",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_verify_token(&json!({ "token": old_token })).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app
+        .post_login(&json!({ "email": email, "password": "P4sSword123!" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app
+        .post_login(&json!({ "email": email, "password": "N3wP4sSword123!" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_token_is_reused() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+    let reset_token = request_reset_token(&app, &email).await;
+
+    let body = json!({
+        "token": reset_token,
+        "password": "N3wP4sSword123!",
+    });
+    assert_eq!(
+        app.post_password_reset_confirm(&body)
+            .await
+            .status()
+            .as_u16(),
+        200
+    );
+    assert_eq!(
+        app.post_password_reset_confirm(&body)
+            .await
+            .status()
+            .as_u16(),
+        401
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_if_password_is_weak_without_using_token() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+    let reset_token = request_reset_token(&app, &email).await;
+
+    let response = app
+        .post_password_reset_confirm(&json!({ "token": reset_token, "password": "weak" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = app
+        .post_password_reset_confirm(&json!({
+            "token": reset_token,
+            "password": "N3wP4sSword123!",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_token_is_invalid() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_password_reset_confirm(&json!({
+            "token": "invalid",
+            "password": "N3wP4sSword123!",
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
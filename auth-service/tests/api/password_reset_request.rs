@@ -0,0 +1,79 @@
+use auth_service::routes::PasswordResetRequestResponse;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use crate::helpers::{get_random_email, signup, TestApp};
+
+#[tokio::test]
+async fn should_send_reset_email_if_user_exists() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup(&app, &email, "P4sSword123!", false).await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app
+        .post_password_reset_request(&serde_json::json!({ "email": email }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_same_response_if_user_does_not_exist() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup(&app, &email, "P4sSword123!", false).await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    let existing = app
+        .post_password_reset_request(&serde_json::json!({ "email": email }))
+        .await;
+    let unknown = app
+        .post_password_reset_request(&serde_json::json!({ "email": get_random_email() }))
+        .await;
+
+    assert_eq!(existing.status(), unknown.status());
+    assert_eq!(
+        existing
+            .json::<PasswordResetRequestResponse>()
+            .await
+            .unwrap(),
+        unknown
+            .json::<PasswordResetRequestResponse>()
+            .await
+            .unwrap()
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_if_email_is_malformed() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_password_reset_request(&serde_json::json!({ "email": "not-an-email" }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
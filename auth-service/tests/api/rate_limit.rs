@@ -0,0 +1,105 @@
+use serde_json::json;
+
+use crate::helpers::{get_random_email, TestApp};
+use auth_service::ErrorResponse;
+
+fn signup_body() -> serde_json::Value {
+    json!({
+        "email": get_random_email(),
+        "password": "P4SS!W0rd",
+        "requires2FA": false,
+    })
+}
+
+#[tokio::test]
+async fn should_return_429_after_too_many_signups() {
+    let mut app = TestApp::new().await;
+
+    for _ in 0..10 {
+        let response = app.post_signup(&signup_body()).await;
+        assert_eq!(response.status().as_u16(), 201);
+    }
+
+    let response = app.post_signup(&signup_body()).await;
+    assert_eq!(response.status().as_u16(), 429);
+
+    let retry_after: u64 = response
+        .headers()
+        .get("retry-after")
+        .expect("Retry-After header is missing")
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=360).contains(&retry_after));
+
+    assert_eq!(
+        response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialize response body to ErrorResponse")
+            .error,
+        "Too many requests".to_owned()
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_limit_each_route_separately() {
+    let mut app = TestApp::new().await;
+
+    for _ in 0..11 {
+        app.post_signup(&signup_body()).await;
+    }
+
+    let response = app
+        .post_login(&json!({ "email": get_random_email(), "password": "P4SS!W0rd" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_ignore_x_forwarded_for_from_untrusted_client() {
+    let mut app = TestApp::new().await;
+
+    for i in 0..11 {
+        let response = app
+            .http_client
+            .post(format!("{}/signup", &app.address))
+            .header("X-Forwarded-For", format!("203.0.113.{}", i))
+            .json(&signup_body())
+            .send()
+            .await
+            .expect("Failed to post signup request.");
+
+        let expected = if i < 10 { 201 } else { 429 };
+        assert_eq!(response.status().as_u16(), expected);
+    }
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_not_give_made_up_client_ids_their_own_bucket() {
+    let mut app = TestApp::new().await;
+
+    for i in 0..31 {
+        let response = app
+            .http_client
+            .post(format!("{}/login", &app.address))
+            .basic_auth(format!("made-up-client-{}", i), Some("x"))
+            // A malformed email is rejected before failed logins are counted
+            .json(&json!({ "email": "not-an-email", "password": "P4SS!W0rd" }))
+            .send()
+            .await
+            .expect("Failed to post login request.");
+
+        let expected = if i < 30 { 400 } else { 429 };
+        assert_eq!(response.status().as_u16(), expected);
+    }
+
+    app.clean_up().await;
+}
@@ -0,0 +1,72 @@
+use std::collections::HashSet;
+
+use serde_json::json;
+
+use auth_service::domain::RecoveryCode;
+
+use crate::helpers::{
+    enroll_totp, get_random_email, get_recovery_codes, login_with_2fa, signup_and_login, TestApp,
+};
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app.post_recovery_codes().await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_200_with_ten_distinct_codes() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+
+    let recovery_codes = get_recovery_codes(&app).await;
+
+    assert_eq!(recovery_codes.len(), 10);
+    assert_eq!(recovery_codes.iter().collect::<HashSet<_>>().len(), 10);
+    for code in recovery_codes {
+        assert!(RecoveryCode::parse(code).is_ok());
+    }
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_invalidate_previous_codes() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup_and_login(&app, &email, password).await;
+    enroll_totp(&app).await;
+
+    let old_codes = get_recovery_codes(&app).await;
+    let new_codes = get_recovery_codes(&app).await;
+
+    let login_attempt_id = login_with_2fa(&app, &email, password).await;
+    let response = app
+        .post_verify_2fa(&json!({
+            "email": email,
+            "loginAttemptId": login_attempt_id,
+            "2FACode": old_codes[0],
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let login_attempt_id = login_with_2fa(&app, &email, password).await;
+    let response = app
+        .post_verify_2fa(&json!({
+            "email": email,
+            "loginAttemptId": login_attempt_id,
+            "2FACode": new_codes[0],
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
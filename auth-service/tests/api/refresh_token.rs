@@ -0,0 +1,120 @@
+use reqwest::Url;
+use serde_json::json;
+
+use crate::helpers::{get_cookie, get_random_email, signup_and_login, TestApp};
+use auth_service::utils::constants::{JWT_COOKIE_NAME, REFRESH_TOKEN_COOKIE_NAME};
+
+fn set_refresh_cookie(app: &TestApp, token: &str) {
+    app.cookie_jar.add_cookie_str(
+        &format!(
+            "{}={}; HttpOnly; SameSite=Lax; Path=/",
+            REFRESH_TOKEN_COOKIE_NAME, token
+        ),
+        &Url::parse(&app.address).expect("Failed to parse URL"),
+    );
+}
+
+#[tokio::test]
+async fn should_return_400_if_refresh_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app.post_refresh_token().await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_invalid_refresh_token() {
+    let mut app = TestApp::new().await;
+
+    set_refresh_cookie(&app, "invalid");
+
+    let response = app.post_refresh_token().await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_200_and_rotate_tokens_if_valid_refresh_token() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let login_response = signup_and_login(&app, &email, "P4sSword123!").await;
+    let old_refresh_token = get_cookie(&login_response, REFRESH_TOKEN_COOKIE_NAME);
+
+    let response = app.post_refresh_token().await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    let auth_token = get_cookie(&response, JWT_COOKIE_NAME);
+    let new_refresh_token = get_cookie(&response, REFRESH_TOKEN_COOKIE_NAME);
+    assert_ne!(old_refresh_token, new_refresh_token);
+
+    let response = app.post_verify_token(&json!({ "token": auth_token })).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_revoke_token_family_if_refresh_token_reused() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let login_response = signup_and_login(&app, &email, "P4sSword123!").await;
+    let old_refresh_token = get_cookie(&login_response, REFRESH_TOKEN_COOKIE_NAME);
+
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 200);
+    let new_refresh_token = get_cookie(&response, REFRESH_TOKEN_COOKIE_NAME);
+
+    // Replaying the old refresh token is treated as theft
+    set_refresh_cookie(&app, &old_refresh_token);
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    // ...and the newer token from the same family no longer works either
+    set_refresh_cookie(&app, &new_refresh_token);
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_accept_only_one_of_concurrent_refreshes_with_same_token() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+
+    let (first, second) = tokio::join!(app.post_refresh_token(), app.post_refresh_token());
+    let mut statuses = [first.status().as_u16(), second.status().as_u16()];
+    statuses.sort();
+    assert_eq!(statuses, [200, 401]);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_session_was_revoked() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let login_response = signup_and_login(&app, &email, "P4sSword123!").await;
+    let refresh_token = get_cookie(&login_response, REFRESH_TOKEN_COOKIE_NAME);
+
+    let response = app.delete_sessions().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    set_refresh_cookie(&app, &refresh_token);
+    let response = app.post_refresh_token().await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
@@ -0,0 +1,72 @@
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use auth_service::routes::ResendVerificationEmailResponse;
+
+use crate::helpers::{get_emailed_link_param, get_random_email, signup, TestApp};
+
+#[tokio::test]
+async fn should_resend_email_if_unverified() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup(&app, &email, "P4sSword123!", false).await;
+
+    // One email for the signup, one for the resend
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    let response = app
+        .post_resend_verification_email(&json!({ "email": email }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_not_send_email_if_already_verified() {
+    let mut app = TestApp::new().await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    let email = get_random_email();
+    signup(&app, &email, "P4sSword123!", false).await;
+    let token = get_emailed_link_param(&app, "email_verification_token").await;
+    let response = app.post_verify_email(&json!({ "token": token })).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let verified = app
+        .post_resend_verification_email(&json!({ "email": email }))
+        .await;
+    let unknown = app
+        .post_resend_verification_email(&json!({ "email": get_random_email() }))
+        .await;
+
+    assert_eq!(verified.status().as_u16(), 200);
+    assert_eq!(
+        verified
+            .json::<ResendVerificationEmailResponse>()
+            .await
+            .unwrap(),
+        unknown
+            .json::<ResendVerificationEmailResponse>()
+            .await
+            .unwrap()
+    );
+
+    app.clean_up().await;
+}
@@ -0,0 +1,86 @@
+use auth_service::{
+    routes::IntrospectResponse,
+    utils::constants::{JWT_COOKIE_NAME, REFRESH_TOKEN_COOKIE_NAME},
+};
+
+use crate::helpers::{
+    get_cookie, get_random_email, register_oauth_client, signup_and_login, TestApp,
+    TEST_CLIENT_SECRET,
+};
+
+#[tokio::test]
+async fn should_revoke_access_token() {
+    let mut app = TestApp::new().await;
+
+    let client_id = register_oauth_client(&app).await;
+    let login_response = signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+    let token = get_cookie(&login_response, JWT_COOKIE_NAME);
+
+    let response = app
+        .post_revoke(&client_id, TEST_CLIENT_SECRET, &token)
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let introspection = app
+        .post_introspect(&client_id, TEST_CLIENT_SECRET, &token)
+        .await
+        .json::<IntrospectResponse>()
+        .await
+        .unwrap();
+    assert!(!introspection.active);
+
+    let response = app
+        .post_verify_token(&serde_json::json!({ "token": token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_revoke_refresh_token() {
+    let mut app = TestApp::new().await;
+
+    let client_id = register_oauth_client(&app).await;
+    let login_response = signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+    let refresh_token = get_cookie(&login_response, REFRESH_TOKEN_COOKIE_NAME);
+
+    let response = app
+        .post_revoke(&client_id, TEST_CLIENT_SECRET, &refresh_token)
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    // The refresh cookie from the login is still in the jar
+    let response = app.post_refresh_token().await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_200_for_invalid_token() {
+    let mut app = TestApp::new().await;
+
+    let client_id = register_oauth_client(&app).await;
+
+    let response = app
+        .post_revoke(&client_id, TEST_CLIENT_SECRET, "invalid")
+        .await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_client_credentials_are_invalid() {
+    let mut app = TestApp::new().await;
+
+    let client_id = register_oauth_client(&app).await;
+
+    let response = app.post_revoke(&client_id, "wrong-secret", "invalid").await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
@@ -0,0 +1,49 @@
+use serde_json::json;
+
+use crate::helpers::{get_cookie, get_random_email, login, signup, TestApp};
+use auth_service::{routes::SessionsResponse, utils::constants::JWT_COOKIE_NAME};
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app.delete_sessions().await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_revoke_all_sessions() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, false).await;
+    let tokens = [
+        get_cookie(&login(&app, &email, password, false).await, JWT_COOKIE_NAME),
+        get_cookie(&login(&app, &email, password, false).await, JWT_COOKIE_NAME),
+    ];
+
+    let response = app.delete_sessions().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    for token in tokens {
+        let response = app.post_verify_token(&json!({ "token": token })).await;
+        assert_eq!(response.status().as_u16(), 401);
+    }
+
+    // Logging in again starts a new session
+    login(&app, &email, password, false).await;
+    let response = app.get_sessions().await;
+    assert_eq!(response.status().as_u16(), 200);
+    let sessions = response
+        .json::<SessionsResponse>()
+        .await
+        .expect("Could not deserialize response body to SessionsResponse")
+        .sessions;
+    assert_eq!(sessions.len(), 1);
+
+    app.clean_up().await;
+}
@@ -0,0 +1,123 @@
+use serde_json::json;
+
+use crate::helpers::{get_cookie, get_random_email, login, signup, TestApp};
+use auth_service::{
+    routes::{SessionResponse, SessionsResponse},
+    utils::constants::JWT_COOKIE_NAME,
+    ErrorResponse,
+};
+
+async fn get_sessions(app: &TestApp) -> Vec<SessionResponse> {
+    let response = app.get_sessions().await;
+    assert_eq!(response.status().as_u16(), 200);
+    response
+        .json::<SessionsResponse>()
+        .await
+        .expect("Could not deserialize response body to SessionsResponse")
+        .sessions
+}
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app.delete_session(&uuid::Uuid::new_v4().to_string()).await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_revoke_other_session() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, false).await;
+    let other_device_token =
+        get_cookie(&login(&app, &email, password, false).await, JWT_COOKIE_NAME);
+    let token = get_cookie(&login(&app, &email, password, false).await, JWT_COOKIE_NAME);
+
+    let other_session = get_sessions(&app)
+        .await
+        .into_iter()
+        .find(|session| !session.current)
+        .expect("Other session is missing");
+
+    let response = app.delete_session(&other_session.id).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_verify_token(&json!({ "token": other_device_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app.post_verify_token(&json!({ "token": token })).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let sessions = get_sessions(&app).await;
+    assert_eq!(sessions.len(), 1);
+    assert!(sessions[0].current);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_log_out_when_revoking_current_session() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, false).await;
+    let token = get_cookie(&login(&app, &email, password, false).await, JWT_COOKIE_NAME);
+
+    let current_session = get_sessions(&app).await.remove(0);
+    let response = app.delete_session(&current_session.id).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_verify_token(&json!({ "token": token })).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    // The auth cookie was removed
+    let response = app.get_sessions().await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_404_if_session_not_found() {
+    let mut app = TestApp::new().await;
+
+    let password = "P4sSword123!";
+    let other_email = get_random_email();
+    signup(&app, &other_email, password, false).await;
+    login(&app, &other_email, password, false).await;
+    let other_session = get_sessions(&app).await.remove(0);
+
+    let email = get_random_email();
+    signup(&app, &email, password, false).await;
+    login(&app, &email, password, false).await;
+
+    // Sessions of other users are hidden too
+    let ids = [
+        other_session.id,
+        uuid::Uuid::new_v4().to_string(),
+        "not-a-session".to_owned(),
+    ];
+    for id in ids.iter() {
+        let response = app.delete_session(id).await;
+        assert_eq!(response.status().as_u16(), 404, "Failed for id: {}", id);
+        assert_eq!(
+            response
+                .json::<ErrorResponse>()
+                .await
+                .expect("Could not deserialize response body to ErrorResponse")
+                .error,
+            "Session not found".to_owned()
+        );
+    }
+
+    app.clean_up().await;
+}
@@ -0,0 +1,73 @@
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use auth_service::utils::constants::JWT_COOKIE_NAME;
+
+use crate::helpers::{
+    get_cookie, get_emailed_link_param, get_random_email, login_from_browser, signup_and_login,
+    TestApp,
+};
+
+#[tokio::test]
+async fn should_return_422_if_malformed_input() {
+    let mut app = TestApp::new().await;
+
+    let response = app.post_sessions_revoke(&json!({})).await;
+
+    assert_eq!(response.status().as_u16(), 422);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_invalid_token() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_sessions_revoke(&json!({ "token": "invalid" }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_end_session_named_in_new_sign_in_notice() {
+    let mut app = TestApp::new().await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    let response = signup_and_login(&app, &email, password).await;
+    let known_device_token = get_cookie(&response, JWT_COOKIE_NAME);
+    let response = login_from_browser(&app, &email, password).await;
+    let new_device_token = get_cookie(&response, JWT_COOKIE_NAME);
+    let token = get_emailed_link_param(&app, "revoke_session_token").await;
+
+    let response = app.post_sessions_revoke(&json!({ "token": token })).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_verify_token(&json!({ "token": new_device_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+    let response = app
+        .post_verify_token(&json!({ "token": known_device_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    // Opening the link again does nothing
+    let response = app.post_sessions_revoke(&json!({ "token": token })).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
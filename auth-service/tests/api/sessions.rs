@@ -0,0 +1,56 @@
+use crate::helpers::{get_random_email, login, login_from_browser, signup, TestApp};
+use auth_service::routes::SessionsResponse;
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app.get_sessions().await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_list_sessions_of_user() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, false).await;
+
+    // Log in from a browser, then from another client that takes over the cookies
+    login_from_browser(&app, &email, password).await;
+    login(&app, &email, password, false).await;
+
+    // Sessions of other users aren't listed
+    let other_email = get_random_email();
+    signup(&app, &other_email, password, false).await;
+
+    let response = app.get_sessions().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let sessions = response
+        .json::<SessionsResponse>()
+        .await
+        .expect("Could not deserialize response body to SessionsResponse")
+        .sessions;
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(sessions.iter().filter(|session| session.current).count(), 1);
+
+    let browser_session = sessions
+        .iter()
+        .find(|session| session.device == "Firefox on Linux")
+        .expect("Browser session is missing");
+    assert!(!browser_session.current);
+    assert_eq!(browser_session.ip, "127.0.0.1");
+    assert!(browser_session.created_at > 0);
+    assert!(browser_session.last_seen_at >= browser_session.created_at);
+
+    let current_session = sessions.iter().find(|session| session.current).unwrap();
+    assert_eq!(current_session.device, "Unknown device");
+    assert_eq!(current_session.user_agent, None);
+
+    app.clean_up().await;
+}
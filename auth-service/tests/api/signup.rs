@@ -1,7 +1,7 @@
 use serde_json::json;
 
-use crate::helpers::{get_random_email, TestApp};
-use auth_service::{routes::SignupResponse, ErrorResponse};
+use crate::helpers::{get_audit_events, get_audit_records, get_random_email, TestApp};
+use auth_service::{domain::AuditEvent, routes::SignupResponse, ErrorResponse};
 
 #[tokio::test]
 async fn should_return_422_if_malformed_input() {
@@ -134,3 +134,25 @@ async fn should_return_409_if_email_already_exists() {
 
     app.clean_up().await;
 }
+
+#[tokio::test]
+async fn should_record_signup_in_audit_log() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let response = app
+        .post_signup(&json!({
+            "email": &email,
+            "password": "P4SS!W0rd",
+            "requires2FA": false,
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    assert_eq!(get_audit_events(&app, &email).await, [AuditEvent::Signup]);
+    let records = get_audit_records(&app, &email).await;
+    assert_eq!(records[0].email.as_deref(), Some(email.as_str()));
+    assert!(!records[0].request_id.is_nil());
+
+    app.clean_up().await;
+}
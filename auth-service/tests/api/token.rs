@@ -0,0 +1,236 @@
+use jsonwebtoken::{decode, decode_header, Validation};
+use reqwest::Url;
+
+use crate::helpers::{
+    get_authorization_code, get_location, get_pkce_pair, get_query_param, get_random_email,
+    register_oauth_client, signup_and_login, TestApp, TEST_CLIENT_SECRET, TEST_REDIRECT_URI,
+};
+use auth_service::{
+    routes::{IntrospectResponse, TokenResponse},
+    utils::{
+        auth::Claims,
+        constants::{JWT_COOKIE_NAME, OIDC_ISSUER},
+        keyring::KEYRING,
+    },
+    ErrorResponse,
+};
+
+#[tokio::test]
+async fn should_return_200_with_tokens_for_valid_code() {
+    let mut app = TestApp::new().await;
+
+    let client_id = register_oauth_client(&app).await;
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+
+    let (code_verifier, code_challenge) = get_pkce_pair();
+    let code = get_authorization_code(&app, &client_id, &code_challenge).await;
+
+    let response = app
+        .post_token(&[
+            ("grant_type", "authorization_code"),
+            ("code", &code),
+            ("redirect_uri", TEST_REDIRECT_URI),
+            ("code_verifier", &code_verifier),
+            ("client_id", &client_id),
+            ("client_secret", TEST_CLIENT_SECRET),
+        ])
+        .await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    let tokens = response
+        .json::<TokenResponse>()
+        .await
+        .expect("Failed to parse response body as token response.");
+    assert_eq!(tokens.token_type, "Bearer");
+
+    let header = decode_header(&tokens.id_token).unwrap();
+    let key = KEYRING.get(&header.kid.unwrap()).unwrap();
+    let mut validation = Validation::new(key.algorithm());
+    validation.set_audience(&[&client_id]);
+    validation.set_issuer(&[OIDC_ISSUER.as_str()]);
+    let claims = decode::<Claims>(&tokens.id_token, key.decoding_key(), &validation)
+        .expect("Failed to verify ID token")
+        .claims;
+
+    assert_eq!(claims.sub, email);
+    assert_eq!(claims.nonce.as_deref(), Some("nonce"));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_if_code_verifier_is_wrong() {
+    let mut app = TestApp::new().await;
+
+    let client_id = register_oauth_client(&app).await;
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+
+    let (_, code_challenge) = get_pkce_pair();
+    let (other_verifier, _) = get_pkce_pair();
+    let code = get_authorization_code(&app, &client_id, &code_challenge).await;
+
+    let response = app
+        .post_token(&[
+            ("grant_type", "authorization_code"),
+            ("code", &code),
+            ("redirect_uri", TEST_REDIRECT_URI),
+            ("code_verifier", &other_verifier),
+            ("client_id", &client_id),
+            ("client_secret", TEST_CLIENT_SECRET),
+        ])
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        "invalid_grant"
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_if_code_is_reused() {
+    let mut app = TestApp::new().await;
+
+    let client_id = register_oauth_client(&app).await;
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+
+    let (code_verifier, code_challenge) = get_pkce_pair();
+    let code = get_authorization_code(&app, &client_id, &code_challenge).await;
+
+    let form = [
+        ("grant_type", "authorization_code"),
+        ("code", &code),
+        ("redirect_uri", TEST_REDIRECT_URI),
+        ("code_verifier", &code_verifier),
+        ("client_id", &client_id),
+        ("client_secret", TEST_CLIENT_SECRET),
+    ];
+
+    assert_eq!(app.post_token(&form).await.status().as_u16(), 200);
+    assert_eq!(app.post_token(&form).await.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_client_secret_is_wrong() {
+    let mut app = TestApp::new().await;
+
+    let client_id = register_oauth_client(&app).await;
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+
+    let (code_verifier, code_challenge) = get_pkce_pair();
+    let code = get_authorization_code(&app, &client_id, &code_challenge).await;
+
+    let response = app
+        .post_token(&[
+            ("grant_type", "authorization_code"),
+            ("code", &code),
+            ("redirect_uri", TEST_REDIRECT_URI),
+            ("code_verifier", &code_verifier),
+            ("client_id", &client_id),
+            ("client_secret", "wrong-secret"),
+        ])
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_not_grant_requested_permissions_the_user_lacks() {
+    let mut app = TestApp::new().await;
+
+    let client_id = register_oauth_client(&app).await;
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+
+    let (code_verifier, code_challenge) = get_pkce_pair();
+    let response = app
+        .get_authorize(&[
+            ("response_type", "code"),
+            ("client_id", &client_id),
+            ("redirect_uri", TEST_REDIRECT_URI),
+            ("scope", "openid users:write"),
+            ("state", "state"),
+            ("code_challenge", &code_challenge),
+            ("code_challenge_method", "S256"),
+        ])
+        .await;
+    assert_eq!(response.status().as_u16(), 303);
+    let code = get_query_param(&get_location(&response), "code").expect("No code in redirect");
+
+    let response = app
+        .post_token(&[
+            ("grant_type", "authorization_code"),
+            ("code", &code),
+            ("redirect_uri", TEST_REDIRECT_URI),
+            ("code_verifier", &code_verifier),
+            ("client_id", &client_id),
+            ("client_secret", TEST_CLIENT_SECRET),
+        ])
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let tokens = response
+        .json::<TokenResponse>()
+        .await
+        .expect("Failed to parse response body as token response.");
+    assert_eq!(tokens.scope, "openid");
+
+    let response = app
+        .post_introspect(&client_id, TEST_CLIENT_SECRET, &tokens.access_token)
+        .await;
+    let introspection = response.json::<IntrospectResponse>().await.unwrap();
+    assert!(introspection.active);
+    assert_eq!(introspection.scope.as_deref(), Some("openid"));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_not_accept_access_token_as_session_cookie() {
+    let mut app = TestApp::new().await;
+
+    let client_id = register_oauth_client(&app).await;
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+
+    let (code_verifier, code_challenge) = get_pkce_pair();
+    let code = get_authorization_code(&app, &client_id, &code_challenge).await;
+    let response = app
+        .post_token(&[
+            ("grant_type", "authorization_code"),
+            ("code", &code),
+            ("redirect_uri", TEST_REDIRECT_URI),
+            ("code_verifier", &code_verifier),
+            ("client_id", &client_id),
+            ("client_secret", TEST_CLIENT_SECRET),
+        ])
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let tokens = response
+        .json::<TokenResponse>()
+        .await
+        .expect("Failed to parse response body as token response.");
+
+    // The client can use its token where clients are meant to...
+    let response = app.get_userinfo(&tokens.access_token).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    // ...but not in place of the user's login session
+    app.cookie_jar.add_cookie_str(
+        &format!(
+            "{}={}; HttpOnly; SameSite=Lax; Path=/",
+            JWT_COOKIE_NAME, tokens.access_token
+        ),
+        &Url::parse(&app.address).expect("Failed to parse URL"),
+    );
+    let response = app.get_sessions().await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
@@ -0,0 +1,165 @@
+use secrecy::ExposeSecret;
+use serde_json::json;
+
+use auth_service::{
+    domain::TotpSecret,
+    routes::{RecoveryCodesResponse, SessionsResponse, TotpEnrollResponse},
+    utils::constants::JWT_COOKIE_NAME,
+    ErrorResponse,
+};
+
+use crate::helpers::{get_cookie, get_random_email, login, signup_and_login, TestApp};
+
+// Start an enrollment for a newly signed up user and return the secret
+async fn start_enrollment(app: &TestApp) -> TotpSecret {
+    signup_and_login(app, &get_random_email(), "P4sSword123!").await;
+
+    let body = app
+        .post_totp_enroll()
+        .await
+        .json::<TotpEnrollResponse>()
+        .await
+        .expect("Could not deserialize response body to TotpEnrollResponse");
+    TotpSecret::parse_base32(&body.secret).expect("Invalid TOTP secret")
+}
+
+#[tokio::test]
+async fn should_return_422_if_malformed_input() {
+    let mut app = TestApp::new().await;
+
+    start_enrollment(&app).await;
+
+    let response = app.post_totp_confirm(&json!({ "token": "123456" })).await;
+
+    assert_eq!(response.status().as_u16(), 422);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_if_invalid_code() {
+    let mut app = TestApp::new().await;
+
+    start_enrollment(&app).await;
+
+    for code in ["", "12345", "1234567", "abcdef"] {
+        let response = app.post_totp_confirm(&json!({ "code": code })).await;
+
+        assert_eq!(response.status().as_u16(), 400, "Failed for code: {}", code);
+    }
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_incorrect_code() {
+    let mut app = TestApp::new().await;
+
+    let secret = start_enrollment(&app).await;
+
+    // A code from outside the allowed clock drift
+    let code = secret.code_at(TotpSecret::current_step() - 3);
+    let response = app
+        .post_totp_confirm(&json!({ "code": code.as_ref().expose_secret() }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+    assert_eq!(
+        response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialize response body to ErrorResponse")
+            .error,
+        "Incorrect credentials".to_owned()
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_not_enrolled() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+
+    let response = app.post_totp_confirm(&json!({ "code": "123456" })).await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_200_if_correct_code() {
+    let mut app = TestApp::new().await;
+
+    let secret = start_enrollment(&app).await;
+
+    let code = secret.code_at(TotpSecret::current_step());
+    let response = app
+        .post_totp_confirm(&json!({ "code": code.as_ref().expose_secret() }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    // The first set of recovery codes is shown on confirmation
+    let body = response
+        .json::<RecoveryCodesResponse>()
+        .await
+        .expect("Could not deserialize response body to RecoveryCodesResponse");
+    assert_eq!(body.recovery_codes.len(), 10);
+
+    // Confirming again is rejected
+    let code = secret.code_at(TotpSecret::current_step() + 1);
+    let response = app
+        .post_totp_confirm(&json!({ "code": code.as_ref().expose_secret() }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 409);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_log_out_other_sessions() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    let response = signup_and_login(&app, &email, password).await;
+    let other_token = get_cookie(&response, JWT_COOKIE_NAME);
+    login(&app, &email, password, false).await;
+    let body = app
+        .post_totp_enroll()
+        .await
+        .json::<TotpEnrollResponse>()
+        .await
+        .expect("Could not deserialize response body to TotpEnrollResponse");
+    let secret = TotpSecret::parse_base32(&body.secret).expect("Invalid TOTP secret");
+
+    let code = secret.code_at(TotpSecret::current_step());
+    let response = app
+        .post_totp_confirm(&json!({ "code": code.as_ref().expose_secret() }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let new_token = get_cookie(&response, JWT_COOKIE_NAME);
+
+    let response = app
+        .post_verify_token(&json!({ "token": other_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    // This device stays logged in with a new session
+    let response = app.post_verify_token(&json!({ "token": new_token })).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let sessions = app
+        .get_sessions()
+        .await
+        .json::<SessionsResponse>()
+        .await
+        .expect("Could not deserialize response body to SessionsResponse")
+        .sessions;
+    assert_eq!(sessions.len(), 1);
+
+    app.clean_up().await;
+}
@@ -0,0 +1,102 @@
+use reqwest::Url;
+use secrecy::ExposeSecret;
+
+use auth_service::{
+    domain::{AuditEvent, TotpSecret},
+    routes::TotpEnrollResponse,
+    utils::constants::JWT_COOKIE_NAME,
+    ErrorResponse,
+};
+
+use crate::helpers::{enroll_totp, get_audit_events, get_random_email, signup_and_login, TestApp};
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app.post_totp_enroll().await;
+
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialize response body to ErrorResponse")
+            .error,
+        "Missing token".to_owned()
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_invalid_token() {
+    let mut app = TestApp::new().await;
+
+    app.cookie_jar.add_cookie_str(
+        &format!(
+            "{}=invalid; HttpOnly; SameSite=Lax; Secure; Path=/",
+            JWT_COOKIE_NAME
+        ),
+        &Url::parse("http://127.0.0.1").expect("Failed to parse URL"),
+    );
+
+    let response = app.post_totp_enroll().await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_200_with_secret_and_otpauth_uri() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+
+    let response = app.post_totp_enroll().await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = response
+        .json::<TotpEnrollResponse>()
+        .await
+        .expect("Could not deserialize response body to TotpEnrollResponse");
+
+    let secret = TotpSecret::parse_base32(&body.secret).expect("Invalid TOTP secret");
+    assert_eq!(secret.as_ref().expose_secret().len(), 20);
+    assert!(body.otpauth_uri.starts_with("otpauth://totp/"));
+    assert!(body
+        .otpauth_uri
+        .contains(&format!("secret={}&", body.secret)));
+    assert!(body.otpauth_uri.contains(&email.replace('@', "%40")));
+
+    assert!(get_audit_events(&app, &email)
+        .await
+        .contains(&AuditEvent::TotpEnrollmentStarted));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_409_if_already_enrolled() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+    enroll_totp(&app).await;
+
+    let response = app.post_totp_enroll().await;
+
+    assert_eq!(response.status().as_u16(), 409);
+    assert_eq!(
+        response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialize response body to ErrorResponse")
+            .error,
+        "2FA already enabled".to_owned()
+    );
+
+    app.clean_up().await;
+}
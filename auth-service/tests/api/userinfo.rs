@@ -0,0 +1,39 @@
+use serde_json::Value;
+
+use crate::helpers::{get_random_email, signup_and_login, TestApp};
+use auth_service::utils::constants::JWT_COOKIE_NAME;
+
+#[tokio::test]
+async fn should_return_200_with_user_claims() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let login_response = signup_and_login(&app, &email, "P4sSword123!").await;
+    let token = login_response
+        .cookies()
+        .find(|cookie| cookie.name() == JWT_COOKIE_NAME)
+        .expect("No auth cookie found")
+        .value()
+        .to_owned();
+
+    let response = app.get_userinfo(&token).await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    let userinfo = response.json::<Value>().await.unwrap();
+    assert_eq!(userinfo["sub"], email.as_str());
+    assert_eq!(userinfo["email"], email.as_str());
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_invalid_token() {
+    let mut app = TestApp::new().await;
+
+    let response = app.get_userinfo("invalid").await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
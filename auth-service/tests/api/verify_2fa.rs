@@ -1,5 +1,3 @@
-use auth_service::domain::{Email, LoginAttemptId, TwoFACode};
-use auth_service::utils::JWT_COOKIE_NAME;
 use secrecy::{ExposeSecret, Secret};
 use serde_json::json;
 use wiremock::{
@@ -7,11 +5,17 @@ use wiremock::{
     Mock, ResponseTemplate,
 };
 
-use crate::helpers;
 use crate::helpers::{
-    get_random_email, get_random_login_attempt_id, get_random_two_fa_code, TestApp,
+    enroll_totp, get_audit_records, get_cookie, get_random_email, get_random_login_attempt_id,
+    get_random_two_fa_code, get_recovery_codes, get_webauthn_login_options, login_with_2fa,
+    login_with_emailed_2fa_code, register_passkey, signup, signup_and_login, TestApp, USER_PRESENT,
+    USER_VERIFIED,
+};
+use auth_service::{
+    domain::{AuditEvent, Email, LoginMethod, TotpSecret},
+    utils::constants::{JWT_COOKIE_NAME, PENDING_2FA_COOKIE_NAME},
+    ErrorResponse,
 };
-use auth_service::{routes::TwoFactorAuthResponse, ErrorResponse};
 
 #[tokio::test]
 async fn should_return_422_if_malformed_input() {
@@ -79,6 +83,441 @@ async fn should_return_400_if_invalid_input() {
     app.clean_up().await;
 }
 
+fn verify_2fa_body(email: &str, login_attempt_id: &str, code: &str) -> serde_json::Value {
+    json!({
+        "email": email,
+        "loginAttemptId": login_attempt_id,
+        "2FACode": code,
+    })
+}
+
+async fn get_emailed_2fa_code(app: &TestApp, email: &str) -> String {
+    let (_, code) = app
+        .two_fa_code_store
+        .read()
+        .await
+        .get_code(&Email::parse(Secret::new(email.to_owned())).unwrap())
+        .await
+        .expect("Failed to get 2FA code");
+    code.as_ref().expose_secret().to_owned()
+}
+
+// A valid code that is certainly not `code`
+fn get_wrong_two_fa_code(code: &str) -> String {
+    let code: u32 = code.parse().unwrap();
+    format!("{:06}", (code + 1) % 1_000_000)
+}
+
+#[tokio::test]
+async fn should_accept_code_after_a_wrong_guess() {
+    let mut app = TestApp::new().await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, true).await;
+
+    let login_attempt_id = login_with_2fa(&app, &email, password).await;
+    let code = get_emailed_2fa_code(&app, &email).await;
+
+    let response = app
+        .post_verify_2fa(&verify_2fa_body(
+            &email,
+            &login_attempt_id,
+            &get_wrong_two_fa_code(&code),
+        ))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app
+        .post_verify_2fa(&verify_2fa_body(&email, &login_attempt_id, &code))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_invalidate_code_after_too_many_wrong_guesses() {
+    let mut app = TestApp::new().await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, true).await;
+
+    let login_attempt_id = login_with_2fa(&app, &email, password).await;
+    let code = get_emailed_2fa_code(&app, &email).await;
+
+    for _ in 0..5 {
+        let response = app
+            .post_verify_2fa(&verify_2fa_body(
+                &email,
+                &login_attempt_id,
+                &get_wrong_two_fa_code(&code),
+            ))
+            .await;
+        assert_eq!(response.status().as_u16(), 401);
+    }
+
+    let response = app
+        .post_verify_2fa(&verify_2fa_body(&email, &login_attempt_id, &code))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_429_after_too_many_wrong_guesses_for_account() {
+    let mut app = TestApp::new().await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, true).await;
+
+    // Logging in again doesn't start the count over
+    for _ in 0..2 {
+        let login_attempt_id = login_with_2fa(&app, &email, password).await;
+        let code = get_emailed_2fa_code(&app, &email).await;
+        for _ in 0..3 {
+            let response = app
+                .post_verify_2fa(&verify_2fa_body(
+                    &email,
+                    &login_attempt_id,
+                    &get_wrong_two_fa_code(&code),
+                ))
+                .await;
+            assert_eq!(response.status().as_u16(), 401);
+        }
+    }
+
+    let login_body = json!({ "email": email, "password": password });
+    let response = app.post_login(&login_body).await;
+    assert_eq!(response.status().as_u16(), 429);
+    assert!(response
+        .headers()
+        .contains_key(reqwest::header::RETRY_AFTER));
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_replace_pending_2fa_cookie_with_auth_cookie() {
+    let mut app = TestApp::new().await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, true).await;
+
+    let response = login_with_emailed_2fa_code(&app, &email, password).await;
+
+    assert!(get_cookie(&response, PENDING_2FA_COOKIE_NAME).is_empty());
+    let auth_token = get_cookie(&response, JWT_COOKIE_NAME);
+    let response = app.post_verify_token(&json!({ "token": auth_token })).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    // Only now can protected routes be reached
+    let response = app.post_totp_enroll().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_without_pending_2fa_cookie() {
+    let mut app = TestApp::new().await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, true).await;
+
+    let login_attempt_id = login_with_2fa(&app, &email, password).await;
+    let code = get_emailed_2fa_code(&app, &email).await;
+    let body = verify_2fa_body(&email, &login_attempt_id, &code);
+
+    // The login attempt ID and code alone aren't enough
+    let response = reqwest::Client::new()
+        .post(format!("{}/verify-2fa", &app.address))
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 400);
+
+    // ...and trying doesn't use up the code
+    let response = app.post_verify_2fa(&body).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_pending_2fa_cookie_of_another_user() {
+    let mut app = TestApp::new().await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, true).await;
+    let login_attempt_id = login_with_2fa(&app, &email, password).await;
+    let code = get_emailed_2fa_code(&app, &email).await;
+
+    // Logging in as someone else replaces the pending cookie
+    let other_email = get_random_email();
+    signup(&app, &other_email, password, true).await;
+    login_with_2fa(&app, &other_email, password).await;
+
+    let response = app
+        .post_verify_2fa(&verify_2fa_body(&email, &login_attempt_id, &code))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_200_if_correct_totp_code() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup_and_login(&app, &email, password).await;
+    let secret = enroll_totp(&app).await;
+
+    let login_attempt_id = login_with_2fa(&app, &email, password).await;
+    let code = secret.code_at(TotpSecret::current_step());
+
+    let response = app
+        .post_verify_2fa(&verify_2fa_body(
+            &email,
+            &login_attempt_id,
+            code.as_ref().expose_secret(),
+        ))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(!get_cookie(&response, JWT_COOKIE_NAME).is_empty());
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_totp_code_replayed() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup_and_login(&app, &email, password).await;
+    let secret = enroll_totp(&app).await;
+    let code = secret.code_at(TotpSecret::current_step());
+
+    let login_attempt_id = login_with_2fa(&app, &email, password).await;
+    let response = app
+        .post_verify_2fa(&verify_2fa_body(
+            &email,
+            &login_attempt_id,
+            code.as_ref().expose_secret(),
+        ))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let login_attempt_id = login_with_2fa(&app, &email, password).await;
+    let response = app
+        .post_verify_2fa(&verify_2fa_body(
+            &email,
+            &login_attempt_id,
+            code.as_ref().expose_secret(),
+        ))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    // The code of the step used to confirm the enrollment can't be replayed either
+    let login_attempt_id = login_with_2fa(&app, &email, password).await;
+    let code = secret.code_at(TotpSecret::current_step() - 1);
+    let response = app
+        .post_verify_2fa(&verify_2fa_body(
+            &email,
+            &login_attempt_id,
+            code.as_ref().expose_secret(),
+        ))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_incorrect_totp_code() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup_and_login(&app, &email, password).await;
+    let secret = enroll_totp(&app).await;
+
+    let login_attempt_id = login_with_2fa(&app, &email, password).await;
+    let code = secret.code_at(TotpSecret::current_step() + 3);
+
+    let response = app
+        .post_verify_2fa(&verify_2fa_body(
+            &email,
+            &login_attempt_id,
+            code.as_ref().expose_secret(),
+        ))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_200_if_recovery_code_used_once() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup_and_login(&app, &email, password).await;
+    enroll_totp(&app).await;
+    let recovery_codes = get_recovery_codes(&app).await;
+
+    let login_attempt_id = login_with_2fa(&app, &email, password).await;
+    let response = app
+        .post_verify_2fa(&verify_2fa_body(
+            &email,
+            &login_attempt_id,
+            &recovery_codes[0],
+        ))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(!get_cookie(&response, JWT_COOKIE_NAME).is_empty());
+
+    // Each code is consumed on use
+    let login_attempt_id = login_with_2fa(&app, &email, password).await;
+    let response = app
+        .post_verify_2fa(&verify_2fa_body(
+            &email,
+            &login_attempt_id,
+            &recovery_codes[0],
+        ))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    // Codes are typed in by hand, so case doesn't matter
+    let login_attempt_id = login_with_2fa(&app, &email, password).await;
+    let response = app
+        .post_verify_2fa(&verify_2fa_body(
+            &email,
+            &login_attempt_id,
+            &recovery_codes[1].to_uppercase(),
+        ))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_200_if_passkey_used_as_second_factor() {
+    let mut app = TestApp::new().await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, true).await;
+    login_with_emailed_2fa_code(&app, &email, password).await;
+    let mut authenticator = register_passkey(&app).await;
+
+    // User presence is enough, since the password was the first factor
+    let login_attempt_id = login_with_2fa(&app, &email, password).await;
+    let options = get_webauthn_login_options(&app, Some(&email)).await;
+    let response = app
+        .post_verify_2fa(&json!({
+            "email": email,
+            "loginAttemptId": login_attempt_id,
+            "passkey": authenticator.authenticate(&options, USER_PRESENT),
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(!get_cookie(&response, JWT_COOKIE_NAME).is_empty());
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_passkey_of_another_user() {
+    let mut app = TestApp::new().await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+    let mut authenticator = register_passkey(&app).await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, true).await;
+
+    for user in [None, Some(email.as_str())] {
+        let login_attempt_id = login_with_2fa(&app, &email, password).await;
+        let options = get_webauthn_login_options(&app, user).await;
+        let response = app
+            .post_verify_2fa(&json!({
+                "email": email,
+                "loginAttemptId": login_attempt_id,
+                "passkey": authenticator.authenticate(&options, USER_PRESENT | USER_VERIFIED),
+            }))
+            .await;
+
+        assert_eq!(response.status().as_u16(), 401);
+    }
+
+    app.clean_up().await;
+}
+
 /*
 #[tokio::test]
 async fn should_return_401_if_incorrect_credentials() {
@@ -411,3 +850,54 @@ async fn should_return_401_if_old_code() {
     app.clean_up().await;
 }
   */
+
+#[tokio::test]
+async fn should_record_2fa_events_in_audit_log() {
+    let mut app = TestApp::new().await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, true).await;
+
+    let login_attempt_id = login_with_2fa(&app, &email, password).await;
+    let code = get_emailed_2fa_code(&app, &email).await;
+    let response = app
+        .post_verify_2fa(&verify_2fa_body(
+            &email,
+            &login_attempt_id,
+            &get_wrong_two_fa_code(&code),
+        ))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+    let response = app
+        .post_verify_2fa(&verify_2fa_body(&email, &login_attempt_id, &code))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let records = get_audit_records(&app, &email).await;
+    let events: Vec<_> = records.iter().map(|record| record.event.clone()).collect();
+    assert_eq!(
+        events,
+        [
+            AuditEvent::Signup,
+            AuditEvent::TwoFACodeSent,
+            AuditEvent::TwoFAFailed,
+            AuditEvent::TwoFAVerified,
+            AuditEvent::LoginSucceeded {
+                method: LoginMethod::SecondFactor
+            },
+        ]
+    );
+
+    // Events are tied to the request that caused them
+    assert_eq!(records[3].request_id, records[4].request_id);
+    assert_ne!(records[2].request_id, records[3].request_id);
+
+    app.clean_up().await;
+}
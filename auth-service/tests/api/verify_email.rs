@@ -0,0 +1,75 @@
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use auth_service::utils::constants::JWT_COOKIE_NAME;
+
+use crate::helpers::{
+    get_cookie, get_emailed_link_param, get_random_email, signup, signup_and_login, TestApp,
+};
+
+#[tokio::test]
+async fn should_send_verification_email_on_signup() {
+    let mut app = TestApp::new().await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    signup(&app, &get_random_email(), "P4sSword123!", false).await;
+
+    let token = get_emailed_link_param(&app, "email_verification_token").await;
+    assert!(!token.is_empty());
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_200_if_valid_token() {
+    let mut app = TestApp::new().await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    signup(&app, &get_random_email(), "P4sSword123!", false).await;
+    let token = get_emailed_link_param(&app, "email_verification_token").await;
+
+    let response = app.post_verify_email(&json!({ "token": token })).await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_invalid_token() {
+    let mut app = TestApp::new().await;
+
+    let response = app.post_verify_email(&json!({ "token": "invalid" })).await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_auth_token_is_used() {
+    let mut app = TestApp::new().await;
+
+    let login_response = signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+    let auth_token = get_cookie(&login_response, JWT_COOKIE_NAME);
+
+    let response = app.post_verify_email(&json!({ "token": auth_token })).await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
@@ -1,7 +1,10 @@
 use serde_json::json;
 
-use crate::helpers::{get_random_email, signup_and_login, TestApp};
-use auth_service::utils::constants::JWT_COOKIE_NAME;
+use crate::helpers::{get_cookie, get_random_email, signup_and_login, TestApp};
+use auth_service::utils::{
+    auth::{CredentialType, Principal},
+    constants::JWT_COOKIE_NAME,
+};
 
 #[tokio::test]
 async fn should_return_422_if_malformed_input() {
@@ -30,7 +33,7 @@ async fn should_return_200_valid_token() {
     let email = get_random_email();
     let password = "P4sSword123!";
 
-    let login_response = signup_and_login(&app, &email, &password).await;
+    let login_response = signup_and_login(&app, &email, password).await;
 
     let auth_cookie = login_response
         .cookies()
@@ -45,6 +48,14 @@ async fn should_return_200_valid_token() {
 
     assert_eq!(response.status().as_u16(), 200);
 
+    let principal = response
+        .json::<Principal>()
+        .await
+        .expect("Could not deserialize response body to Principal");
+    assert_eq!(principal.sub, email);
+    assert_eq!(principal.credential_type, CredentialType::AccessToken);
+    assert!(!principal.admin);
+
     app.clean_up().await;
 }
 
@@ -86,3 +97,30 @@ async fn should_return_401_if_banned_token() {
 
     app.clean_up().await;
 }
+
+#[tokio::test]
+async fn should_return_403_if_token_lacks_role_or_scope() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    let login_response = signup_and_login(&app, &email, "P4sSword123!").await;
+    let token = get_cookie(&login_response, JWT_COOKIE_NAME);
+
+    let test_cases = [
+        json!({ "token": token, "role": "billing" }),
+        json!({ "token": token, "scope": "invoices:read" }),
+    ];
+
+    for test_case in test_cases.iter() {
+        let response = app.post_verify_token(test_case).await;
+
+        assert_eq!(
+            response.status().as_u16(),
+            403,
+            "Failed for input: {:?}",
+            test_case
+        );
+    }
+
+    app.clean_up().await;
+}
@@ -0,0 +1,166 @@
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use auth_service::{utils::constants::JWT_COOKIE_NAME, ErrorResponse};
+
+use crate::helpers::{
+    get_cookie, get_random_email, get_webauthn_login_options, login_with_emailed_2fa_code,
+    register_passkey, signup, signup_and_login, SoftwareAuthenticator, TestApp, USER_PRESENT,
+    USER_VERIFIED,
+};
+
+#[tokio::test]
+async fn should_return_422_if_malformed_input() {
+    let mut app = TestApp::new().await;
+
+    let test_cases = [json!({}), json!({ "id": "abc", "type": "public-key" })];
+
+    for test_case in test_cases {
+        let response = app.post_webauthn_login_finish(&test_case).await;
+
+        assert_eq!(
+            response.status().as_u16(),
+            422,
+            "Failed for input: {:?}",
+            test_case
+        );
+    }
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_200_and_set_auth_cookie_with_verified_passkey() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+    let mut authenticator = register_passkey(&app).await;
+    app.post_logout().await;
+
+    // Discoverable credentials are found without an email
+    for email in [Some(email.as_str()), None] {
+        let options = get_webauthn_login_options(&app, email).await;
+        let response = app
+            .post_webauthn_login_finish(
+                &authenticator.authenticate(&options, USER_PRESENT | USER_VERIFIED),
+            )
+            .await;
+
+        assert_eq!(response.status().as_u16(), 200);
+        assert!(!get_cookie(&response, JWT_COOKIE_NAME).is_empty());
+    }
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_skip_2fa_for_accounts_that_require_it() {
+    let mut app = TestApp::new().await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let email = get_random_email();
+    let password = "P4sSword123!";
+    signup(&app, &email, password, true).await;
+    login_with_emailed_2fa_code(&app, &email, password).await;
+    let mut authenticator = register_passkey(&app).await;
+
+    let options = get_webauthn_login_options(&app, Some(&email)).await;
+    let response = app
+        .post_webauthn_login_finish(
+            &authenticator.authenticate(&options, USER_PRESENT | USER_VERIFIED),
+        )
+        .await;
+
+    assert_eq!(response.status().as_u16(), 200);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_without_user_verification() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+    let mut authenticator = register_passkey(&app).await;
+
+    let options = get_webauthn_login_options(&app, Some(&email)).await;
+    let response = app
+        .post_webauthn_login_finish(&authenticator.authenticate(&options, USER_PRESENT))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+    assert_eq!(
+        response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialize response body to ErrorResponse")
+            .error,
+        "Incorrect credentials".to_owned()
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_challenge_is_reused() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+    let mut authenticator = register_passkey(&app).await;
+
+    let options = get_webauthn_login_options(&app, Some(&email)).await;
+    let credential = authenticator.authenticate(&options, USER_PRESENT | USER_VERIFIED);
+    let response = app.post_webauthn_login_finish(&credential).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_webauthn_login_finish(&credential).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_challenge_was_issued_for_another_user() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+    let mut authenticator = register_passkey(&app).await;
+
+    let options = get_webauthn_login_options(&app, Some(&get_random_email())).await;
+    let response = app
+        .post_webauthn_login_finish(
+            &authenticator.authenticate(&options, USER_PRESENT | USER_VERIFIED),
+        )
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_passkey_is_not_registered() {
+    let mut app = TestApp::new().await;
+
+    let options = get_webauthn_login_options(&app, None).await;
+    let response = app
+        .post_webauthn_login_finish(
+            &SoftwareAuthenticator::new().authenticate(&options, USER_PRESENT | USER_VERIFIED),
+        )
+        .await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
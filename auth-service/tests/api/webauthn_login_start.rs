@@ -0,0 +1,69 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde_json::json;
+
+use auth_service::{utils::constants::WEBAUTHN_RP_ID, ErrorResponse};
+
+use crate::helpers::{
+    get_random_email, get_webauthn_login_options, register_passkey, signup_and_login, TestApp,
+};
+
+#[tokio::test]
+async fn should_return_400_if_invalid_email() {
+    let mut app = TestApp::new().await;
+
+    let response = app
+        .post_webauthn_login_start(&json!({ "email": "invalid" }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialize response body to ErrorResponse")
+            .error,
+        "Invalid credentials".to_owned()
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_200_without_allowed_credentials_if_no_email() {
+    let mut app = TestApp::new().await;
+
+    let options = get_webauthn_login_options(&app, None).await;
+
+    assert_eq!(options.rp_id, *WEBAUTHN_RP_ID);
+    assert_eq!(
+        URL_SAFE_NO_PAD.decode(&options.challenge).unwrap().len(),
+        32
+    );
+    assert!(options.allow_credentials.is_empty());
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_200_with_passkeys_of_user() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+    let authenticator = register_passkey(&app).await;
+
+    let options = get_webauthn_login_options(&app, Some(&email)).await;
+
+    assert_eq!(options.allow_credentials.len(), 1);
+    assert_eq!(
+        URL_SAFE_NO_PAD
+            .decode(&options.allow_credentials[0].id)
+            .unwrap(),
+        authenticator.credential_id
+    );
+
+    let options = get_webauthn_login_options(&app, Some(&get_random_email())).await;
+    assert!(options.allow_credentials.is_empty());
+
+    app.clean_up().await;
+}
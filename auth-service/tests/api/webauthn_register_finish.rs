@@ -0,0 +1,195 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde_json::json;
+
+use auth_service::{domain::WebauthnChallenge, routes::WebauthnCredentialResponse, ErrorResponse};
+use secrecy::ExposeSecret;
+
+use crate::helpers::{
+    get_random_email, get_webauthn_registration_options, signup_and_login, SoftwareAuthenticator,
+    TestApp,
+};
+
+#[tokio::test]
+async fn should_return_422_if_malformed_input() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+
+    let test_cases = [json!({}), json!({ "credential": { "id": "abc" } })];
+
+    for test_case in test_cases {
+        let response = app.post_webauthn_register_finish(&test_case).await;
+
+        assert_eq!(
+            response.status().as_u16(),
+            422,
+            "Failed for input: {:?}",
+            test_case
+        );
+    }
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+    let options = get_webauthn_registration_options(&app).await;
+    let credential = SoftwareAuthenticator::new().register(&options);
+    app.post_logout().await;
+
+    let response = app
+        .post_webauthn_register_finish(&json!({ "credential": credential }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_201_if_valid_credential() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+    let authenticator = SoftwareAuthenticator::new();
+    let options = get_webauthn_registration_options(&app).await;
+
+    let response = app
+        .post_webauthn_register_finish(&json!({
+            "name": "  Work laptop ",
+            "credential": authenticator.register(&options),
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 201);
+    let body = response
+        .json::<WebauthnCredentialResponse>()
+        .await
+        .expect("Could not deserialize response body to WebauthnCredentialResponse");
+    assert_eq!(
+        body,
+        WebauthnCredentialResponse {
+            credential_id: URL_SAFE_NO_PAD.encode(&authenticator.credential_id),
+            name: "Work laptop".to_owned(),
+        }
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_if_challenge_was_not_issued() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+    let mut options = get_webauthn_registration_options(&app).await;
+    options.challenge = WebauthnChallenge::generate_random()
+        .as_ref()
+        .expose_secret()
+        .to_owned();
+
+    let response = app
+        .post_webauthn_register_finish(&json!({
+            "credential": SoftwareAuthenticator::new().register(&options),
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialize response body to ErrorResponse")
+            .error,
+        "Invalid credentials".to_owned()
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_if_challenge_is_reused() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+    let options = get_webauthn_registration_options(&app).await;
+
+    let response = app
+        .post_webauthn_register_finish(&json!({
+            "credential": SoftwareAuthenticator::new().register(&options),
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app
+        .post_webauthn_register_finish(&json!({
+            "credential": SoftwareAuthenticator::new().register(&options),
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_if_challenge_was_issued_to_another_user() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+    let options = get_webauthn_registration_options(&app).await;
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+
+    let response = app
+        .post_webauthn_register_finish(&json!({
+            "credential": SoftwareAuthenticator::new().register(&options),
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_if_signed_for_another_relying_party() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+    let mut options = get_webauthn_registration_options(&app).await;
+    options.rp.id = "evil.example".to_owned();
+
+    let response = app
+        .post_webauthn_register_finish(&json!({
+            "credential": SoftwareAuthenticator::new().register(&options),
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_400_if_credential_already_registered() {
+    let mut app = TestApp::new().await;
+
+    let authenticator = SoftwareAuthenticator::new();
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+    let options = get_webauthn_registration_options(&app).await;
+    let response = app
+        .post_webauthn_register_finish(&json!({ "credential": authenticator.register(&options) }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    // The browser normally prevents this through `excludeCredentials`
+    let options = get_webauthn_registration_options(&app).await;
+    let response = app
+        .post_webauthn_register_finish(&json!({ "credential": authenticator.register(&options) }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.clean_up().await;
+}
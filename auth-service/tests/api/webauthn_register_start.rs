@@ -0,0 +1,99 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use reqwest::Url;
+
+use auth_service::{
+    utils::constants::{JWT_COOKIE_NAME, WEBAUTHN_RP_ID},
+    ErrorResponse,
+};
+
+use crate::helpers::{
+    get_random_email, get_webauthn_registration_options, register_passkey, signup_and_login,
+    TestApp,
+};
+
+#[tokio::test]
+async fn should_return_400_if_jwt_cookie_missing() {
+    let mut app = TestApp::new().await;
+
+    let response = app.post_webauthn_register_start().await;
+
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialize response body to ErrorResponse")
+            .error,
+        "Missing token".to_owned()
+    );
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_401_if_invalid_token() {
+    let mut app = TestApp::new().await;
+
+    app.cookie_jar.add_cookie_str(
+        &format!(
+            "{}=invalid; HttpOnly; SameSite=Lax; Secure; Path=/",
+            JWT_COOKIE_NAME
+        ),
+        &Url::parse("http://127.0.0.1").expect("Failed to parse URL"),
+    );
+
+    let response = app.post_webauthn_register_start().await;
+
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_return_200_with_creation_options() {
+    let mut app = TestApp::new().await;
+
+    let email = get_random_email();
+    signup_and_login(&app, &email, "P4sSword123!").await;
+
+    let options = get_webauthn_registration_options(&app).await;
+
+    assert_eq!(options.rp.id, *WEBAUTHN_RP_ID);
+    assert_eq!(options.user.name, email);
+    assert_ne!(options.user.id, email);
+    assert_eq!(
+        URL_SAFE_NO_PAD.decode(&options.challenge).unwrap().len(),
+        32
+    );
+    assert!(options.pub_key_cred_params.iter().any(|p| p.alg == -7));
+    assert!(options.exclude_credentials.is_empty());
+
+    // Every ceremony gets a new challenge
+    let other_options = get_webauthn_registration_options(&app).await;
+    assert_ne!(options.challenge, other_options.challenge);
+
+    app.clean_up().await;
+}
+
+#[tokio::test]
+async fn should_exclude_registered_passkeys() {
+    let mut app = TestApp::new().await;
+
+    signup_and_login(&app, &get_random_email(), "P4sSword123!").await;
+    let first = register_passkey(&app).await;
+    let second = register_passkey(&app).await;
+
+    let options = get_webauthn_registration_options(&app).await;
+
+    let mut excluded: Vec<_> = options
+        .exclude_credentials
+        .iter()
+        .map(|credential| URL_SAFE_NO_PAD.decode(&credential.id).unwrap())
+        .collect();
+    excluded.sort();
+    let mut expected = vec![first.credential_id, second.credential_id];
+    expected.sort();
+    assert_eq!(excluded, expected);
+
+    app.clean_up().await;
+}